@@ -93,8 +93,10 @@ pub struct ItemDto {
 	#[sqlxo(field = "name")]
 	pub different_name: String,
 	pub description:    String,
+	#[sqlxo(facet(buckets = "10, 50, 100"))]
 	pub price:          f32,
 	pub amount:         i32,
+	#[sqlxo(facet)]
 	pub active:         bool,
 	pub due_date:       DateTime<Utc>,
 
@@ -135,6 +137,20 @@ fn sqlxo_items(_query: WebReadFilter<ItemDto>) -> Vec<ItemDto> {
 	Vec::new()
 }
 
+#[allow(dead_code)]
+#[utoipa::path(
+    get,
+    path = "/items/sqlxo/aggregate",
+    params(WebReadFilter<ItemDto>),
+    responses((status = 200, description = "Grouped item rows", body = [ItemDtoAggregateRow])),
+    tag = "items"
+)]
+fn sqlxo_items_aggregate(
+	_query: WebReadFilter<ItemDto>,
+) -> Vec<ItemDtoAggregateRow> {
+	Vec::new()
+}
+
 #[allow(dead_code)]
 #[utoipa::path(
     patch,
@@ -162,7 +178,12 @@ fn sqlxo_items_delete(_query: WebDeleteFilter<ItemDto>) -> u64 {
 #[derive(OpenApi)]
 #[openapi(
     info(title = "SQL Filter API", version = "1.0.0"),
-    paths(sqlxo_items, sqlxo_items_update, sqlxo_items_delete),
+    paths(
+        sqlxo_items,
+        sqlxo_items_aggregate,
+        sqlxo_items_update,
+        sqlxo_items_delete
+    ),
     components(
         schemas(
             ItemDto,
@@ -175,6 +196,9 @@ fn sqlxo_items_delete(_query: WebDeleteFilter<ItemDto>) -> u64 {
             ItemDtoSortField,
             ItemDtoAggregateLeaf,
             ItemDtoJoinPath,
+            ItemDtoGroupField,
+            ItemDtoAggregateField,
+            ItemDtoAggregateRow,
 
             MaterialDtoLeaf,
             MaterialDtoSortField,
@@ -183,7 +207,12 @@ fn sqlxo_items_delete(_query: WebDeleteFilter<ItemDto>) -> u64 {
             SupplierDtoJoinPath,
 
             sqlxo::WebSortDirection,
+            sqlxo::facet::Facets,
+            sqlxo::facet::FacetCounts,
+            sqlxo::facet::FacetValueCount,
+            sqlxo::facet::FacetBucketCount,
             sqlxo::web::WebPagination,
+            sqlxo::web::WebAggregateRow,
             sqlxo::web::WebSearchPayload<ItemDto>,
             sqlxo::web::GenericWebSearch<ItemDtoJoinPath>,
             sqlxo::web::JoinPayload<ItemDtoJoinPath>,
@@ -0,0 +1,15 @@
+CREATE TABLE tenant_supplier (
+    tenant_id UUID NOT NULL,
+    id UUID NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (tenant_id, id)
+);
+
+CREATE TABLE tenant_material (
+    tenant_id UUID NOT NULL,
+    id UUID NOT NULL,
+    name TEXT NOT NULL,
+    supplier_id UUID,
+    PRIMARY KEY (tenant_id, id),
+    FOREIGN KEY (tenant_id, supplier_id) REFERENCES tenant_supplier(tenant_id, id)
+);
@@ -0,0 +1,5 @@
+CREATE TABLE category (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    parent_id UUID REFERENCES category(id)
+);
@@ -0,0 +1,25 @@
+CREATE TABLE author (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TABLE post (
+    id UUID PRIMARY KEY,
+    author_id UUID NOT NULL REFERENCES author(id),
+    editor_id UUID REFERENCES author(id),
+    title TEXT NOT NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TABLE comment (
+    id UUID PRIMARY KEY,
+    post_id UUID NOT NULL REFERENCES post(id),
+    body TEXT NOT NULL
+);
+
+CREATE TABLE award (
+    id UUID PRIMARY KEY,
+    author_id UUID NOT NULL REFERENCES author(id),
+    title TEXT NOT NULL
+);
@@ -0,0 +1,12 @@
+CREATE TABLE project (
+    id UUID PRIMARY KEY,
+    tenant_id UUID NOT NULL,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE task (
+    id UUID PRIMARY KEY,
+    tenant_id UUID NOT NULL,
+    project_id UUID NOT NULL REFERENCES project(id),
+    title TEXT NOT NULL
+);
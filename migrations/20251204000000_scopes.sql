@@ -0,0 +1,6 @@
+CREATE TABLE note (
+    id UUID PRIMARY KEY,
+    title TEXT NOT NULL,
+    archived BOOLEAN NOT NULL DEFAULT FALSE,
+    published_at TIMESTAMPTZ
+);
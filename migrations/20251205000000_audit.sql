@@ -0,0 +1,16 @@
+CREATE TABLE sqlxo_audit (
+    id BIGSERIAL PRIMARY KEY,
+    table_name TEXT NOT NULL,
+    action TEXT NOT NULL,
+    entity JSONB NOT NULL,
+    before JSONB,
+    after JSONB,
+    actor TEXT,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE invoice (
+    id UUID PRIMARY KEY,
+    number TEXT NOT NULL,
+    amount BIGINT NOT NULL
+);
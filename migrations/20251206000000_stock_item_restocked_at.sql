@@ -0,0 +1 @@
+ALTER TABLE stock_item ADD COLUMN restocked_at TIMESTAMPTZ;
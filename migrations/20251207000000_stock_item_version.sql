@@ -0,0 +1 @@
+ALTER TABLE stock_item ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+CREATE TABLE stock_booking (
+    id UUID PRIMARY KEY,
+    stock_item_id UUID NOT NULL REFERENCES stock_item(id),
+    name TEXT NOT NULL,
+    amount INTEGER NOT NULL
+);
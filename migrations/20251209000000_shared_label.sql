@@ -0,0 +1,10 @@
+CREATE TABLE label (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE task_label (
+    id UUID PRIMARY KEY,
+    tenant_id UUID NOT NULL,
+    label_id UUID NOT NULL REFERENCES label(id)
+);
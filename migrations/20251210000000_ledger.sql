@@ -0,0 +1,16 @@
+CREATE TABLE ledger (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE ledger_entry (
+    id UUID PRIMARY KEY,
+    ledger_id UUID NOT NULL REFERENCES ledger(id),
+    amount BIGINT NOT NULL
+);
+
+CREATE TABLE ledger_tag (
+    id UUID PRIMARY KEY,
+    ledger_id UUID REFERENCES ledger(id),
+    name TEXT NOT NULL
+);
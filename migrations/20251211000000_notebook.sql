@@ -0,0 +1,6 @@
+CREATE TABLE notebook (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+ALTER TABLE note ADD COLUMN notebook_id UUID REFERENCES notebook(id);
@@ -0,0 +1,261 @@
+use std::future::Future;
+
+use chrono::{
+	DateTime,
+	Utc,
+};
+use serde_json::Value;
+use sqlx::{
+	Executor,
+	Postgres,
+};
+use sqlxo_traits::{
+	Audited,
+	Error,
+	HasPrimaryKey,
+	PrimaryKey,
+	QueryContext,
+	SqlWrite,
+};
+
+use crate::{
+	blocks::{
+		ReadHead,
+		SelectType,
+		SqlWriter,
+	},
+	select::{
+		SelectionColumn,
+		SelectionList,
+	},
+	PrimaryKeyOf,
+};
+
+/// Table the changes of `#[sqlxo(audited)]` models are written to:
+///
+/// ```sql
+/// CREATE TABLE sqlxo_audit (
+///     id         BIGSERIAL PRIMARY KEY,
+///     table_name TEXT NOT NULL,
+///     action     TEXT NOT NULL,
+///     entity     JSONB NOT NULL,
+///     before     JSONB,
+///     after      JSONB,
+///     actor      TEXT,
+///     changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+/// );
+/// ```
+pub const AUDIT_TABLE: &str = "sqlxo_audit";
+
+/// CTE an audited mutation runs in, returning the changed rows.
+pub(crate) const CHANGED_CTE: &str = "sqlxo_changed";
+
+tokio::task_local! {
+	static ACTOR: String;
+}
+
+/// Runs `f` with `actor` recorded as the author of the audited changes made
+/// by the plans built inside.
+pub async fn scope<F: Future>(actor: impl Into<String>, f: F) -> F::Output {
+	ACTOR.scope(actor.into(), f).await
+}
+
+/// Runs `f` with `actor` as the author of the audited changes.
+pub fn sync_scope<R>(actor: impl Into<String>, f: impl FnOnce() -> R) -> R {
+	ACTOR.sync_scope(actor.into(), f)
+}
+
+/// The actor of the surrounding [`scope`], if any.
+pub fn current() -> Option<String> {
+	ACTOR.try_with(String::clone).ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum AuditAction {
+	Insert,
+	Update,
+	Delete,
+}
+
+impl AuditAction {
+	fn as_str(self) -> &'static str {
+		match self {
+			Self::Insert => "insert",
+			Self::Update => "update",
+			Self::Delete => "delete",
+		}
+	}
+}
+
+/// A change recorded in the [`AUDIT_TABLE`].
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct AuditEntry {
+	pub id:         i64,
+	pub table_name: String,
+	pub action:     AuditAction,
+	/// The primary key columns of the row, e.g. `{"id": "..."}`.
+	pub entity:     Value,
+	/// The row before the change; `None` for inserts.
+	pub before:     Option<Value>,
+	/// The row after the change; `None` for hard deletes.
+	pub after:      Option<Value>,
+	pub actor:      Option<String>,
+	pub changed_at: DateTime<Utc>,
+}
+
+/// The recorded changes of the row with primary key `key`, oldest first.
+pub async fn history<'e, C, E>(
+	key: PrimaryKeyOf<C>,
+	exec: E,
+) -> Result<Vec<AuditEntry>, Error>
+where
+	C: QueryContext,
+	C::Model: HasPrimaryKey,
+	E: Executor<'e, Database = Postgres>,
+{
+	let mut w = SqlWriter::new(ReadHead::new(AUDIT_TABLE, SelectType::Star));
+	// The key is matched on the entity read back into a row of the table.
+	w.push(&format!(
+		r#" CROSS JOIN LATERAL jsonb_populate_record(NULL::{table}, "{AUDIT_TABLE}"."entity") AS "{table}""#,
+		table = C::TABLE,
+	));
+	w.push_where_raw(|writer| {
+		writer.push(&format!(r#""{AUDIT_TABLE}"."table_name" = "#));
+		writer.bind(C::TABLE);
+	});
+	w.push_where_raw(|writer| {
+		<C::Model as PrimaryKey>::write_key_in(
+			std::slice::from_ref(&key),
+			writer,
+		)
+	});
+	w.push(&format!(r#" ORDER BY "{AUDIT_TABLE}"."id""#));
+
+	Ok(w.into_builder()
+		.build_query_as::<AuditEntry>()
+		.fetch_all(exec)
+		.await?)
+}
+
+/// Records the rows a plan of an audited model changes, resolved when the
+/// plan is built.
+///
+/// The mutation runs in the [`CHANGED_CTE`] returning every changed row and
+/// a second CTE inserts them into the [`AUDIT_TABLE`]. Both see the table as
+/// it was before the statement, so joining it by primary key yields the
+/// before image.
+pub(crate) struct AuditTrail {
+	action:      AuditAction,
+	/// Whether the changed row remains, i.e. has an after image.
+	keeps_row:   bool,
+	primary_key: &'static [&'static str],
+	actor:       Option<String>,
+}
+
+impl AuditTrail {
+	pub(crate) fn resolve<M: Audited + PrimaryKey>(
+		action: AuditAction,
+		keeps_row: bool,
+	) -> Option<Self> {
+		M::AUDITED
+			.then(|| Self::new(action, keeps_row, M::PRIMARY_KEY, current()))
+	}
+
+	/// The trail of the rows of a table keyed by `primary_key` that a plan
+	/// changes on behalf of another, e.g. in a delete cascade.
+	pub(crate) fn new(
+		action: AuditAction,
+		keeps_row: bool,
+		primary_key: &'static [&'static str],
+		actor: Option<String>,
+	) -> Self {
+		Self {
+			action,
+			keeps_row,
+			primary_key,
+			actor,
+		}
+	}
+
+	/// Ends the statement so that it still reports the changed rows as
+	/// affected.
+	pub(crate) fn push_count(
+		&self,
+		qb: &mut sqlx::QueryBuilder<'static, Postgres>,
+		table: &str,
+	) {
+		self.push_changes(qb, table);
+		qb.push(format!(r#" SELECT 1 FROM "{CHANGED_CTE}""#));
+	}
+
+	/// Ends the statement returning `selection` of the changed rows.
+	pub(crate) fn push_returning<Output>(
+		&self,
+		qb: &mut sqlx::QueryBuilder<'static, Postgres>,
+		table: &str,
+		selection: Option<&SelectionList<Output, SelectionColumn>>,
+	) {
+		self.push_changes(qb, table);
+		qb.push(" SELECT ");
+		match selection {
+			Some(selection) => selection.push_columns(qb, table),
+			None => {
+				qb.push(format!(r#""{table}".*"#));
+			}
+		}
+		qb.push(format!(r#" FROM "{CHANGED_CTE}" AS "{table}""#));
+	}
+
+	fn push_changes(
+		&self,
+		qb: &mut sqlx::QueryBuilder<'static, Postgres>,
+		table: &str,
+	) {
+		let column = |alias: &str, col: &str| format!(r#""{alias}"."{col}""#);
+		let entity = self
+			.primary_key
+			.iter()
+			.map(|col| format!("'{col}', {}", column(CHANGED_CTE, col)))
+			.collect::<Vec<_>>()
+			.join(", ");
+		let matches = self
+			.primary_key
+			.iter()
+			.map(|col| {
+				format!(
+					"{} = {}",
+					column("sqlxo_before", col),
+					column(CHANGED_CTE, col)
+				)
+			})
+			.collect::<Vec<_>>()
+			.join(" AND ");
+		// An insert that hit `ON CONFLICT ... DO UPDATE` changed an existing
+		// row.
+		let action = match self.action {
+			AuditAction::Insert => format!(
+				"CASE WHEN {} IS NULL THEN 'insert' ELSE 'update' END",
+				column("sqlxo_before", self.primary_key[0])
+			),
+			action => format!("'{}'", action.as_str()),
+		};
+		let after = if self.keeps_row {
+			format!(r#"to_jsonb("{CHANGED_CTE}")"#)
+		} else {
+			"NULL".to_string()
+		};
+
+		qb.push(format!(
+			r#" RETURNING "{table}".*), "sqlxo_audit_rows" AS (INSERT INTO {AUDIT_TABLE} (table_name, action, entity, before, after, actor) SELECT "#
+		));
+		qb.push_bind(table.to_string());
+		qb.push(format!(
+			r#", {action}, jsonb_build_object({entity}), to_jsonb("sqlxo_before"), {after}, "#
+		));
+		qb.push_bind(self.actor.clone());
+		qb.push(format!(
+			r#" FROM "{CHANGED_CTE}" LEFT JOIN {table} AS "sqlxo_before" ON {matches})"#
+		));
+	}
+}
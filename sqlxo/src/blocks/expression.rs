@@ -1,4 +1,3 @@
-use crate::blocks::SqlWriter;
 use sqlxo_traits::{
 	Filterable,
 	SqlWrite,
@@ -39,7 +38,7 @@ where
 }
 
 impl<T: Filterable> Expression<T> {
-	pub fn write(&self, w: &mut SqlWriter) {
+	pub fn write<W: SqlWrite>(&self, w: &mut W) {
 		match self {
 			Expression::Leaf(q) => q.write(w),
 			Expression::And(xs) => {
@@ -65,3 +64,25 @@ impl<T: Filterable> Expression<T> {
 		}
 	}
 }
+
+/// Filter every read of the model is restricted to unless `.unscoped()` is
+/// called, set with `#[sqlxo(default_scope = "...")]`.
+pub trait DefaultScope: Filterable + Sized {
+	fn default_scope() -> Expression<Self>;
+}
+
+pub trait GetDefaultScope: Filterable + Sized {
+	fn get_default_scope() -> Option<Expression<Self>>;
+}
+
+impl<T: Filterable> GetDefaultScope for T {
+	default fn get_default_scope() -> Option<Expression<Self>> {
+		None
+	}
+}
+
+impl<T: DefaultScope> GetDefaultScope for T {
+	fn get_default_scope() -> Option<Expression<Self>> {
+		Some(T::default_scope())
+	}
+}
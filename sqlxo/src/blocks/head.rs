@@ -26,10 +26,18 @@ pub enum SelectType {
 	Projection(Vec<SelectProjection>),
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DistinctClause {
+	Rows,
+	On(SmallVec<[QualifiedColumn; 4]>),
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum DeleteType {
 	Hard,
 	Soft,
+	/// Clears the soft-delete marker again.
+	Restore,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -43,13 +51,90 @@ pub trait ToHead {
 }
 
 pub struct ReadHead<'a> {
-	r#type: SelectType,
-	table:  &'a str,
+	r#type:      SelectType,
+	table:       &'a str,
+	source:      Option<&'a str>,
+	projections: Vec<SelectProjection>,
+	distinct:    Option<DistinctClause>,
 }
 
 impl<'a> ReadHead<'a> {
 	pub fn new(table: &'a str, r#type: SelectType) -> Self {
-		Self { r#type, table }
+		Self {
+			r#type,
+			table,
+			source: None,
+			projections: Vec::new(),
+			distinct: None,
+		}
+	}
+
+	fn write_count(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, r#""{}".*, COUNT(*) OVER() AS total_count"#, self.table)
+	}
+
+	/// Renders `SELECT DISTINCT`/`SELECT DISTINCT ON (...)`. `EXISTS` reads
+	/// ignore it.
+	pub fn with_distinct(mut self, distinct: Option<DistinctClause>) -> Self {
+		self.distinct = distinct;
+		self
+	}
+
+	fn write_select(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, "SELECT ")?;
+		match &self.distinct {
+			None => Ok(()),
+			Some(DistinctClause::Rows) => write!(f, "DISTINCT "),
+			Some(DistinctClause::On(cols)) => {
+				write!(f, "DISTINCT ON (")?;
+				for (idx, col) in cols.iter().enumerate() {
+					if idx > 0 {
+						write!(f, ", ")?;
+					}
+					write!(f, r#""{}"."{}""#, col.table_alias, col.column)?;
+				}
+				write!(f, ") ")
+			}
+		}
+	}
+
+	/// Appends computed columns to `SELECT "table".*` reads. Other select
+	/// types ignore them.
+	pub fn with_projections(
+		mut self,
+		projections: Vec<SelectProjection>,
+	) -> Self {
+		self.projections = projections;
+		self
+	}
+
+	fn write_star_tail(
+		&self,
+		cols: &[AliasedColumn],
+		f: &mut Formatter<'_>,
+	) -> fmt::Result {
+		write_extras(cols, f)?;
+		for projection in &self.projections {
+			write!(f, ", {}", projection.expression)?;
+			if let Some(alias) = &projection.alias {
+				write!(f, r#" AS "{}""#, alias)?;
+			}
+		}
+		write!(f, " FROM {}", self.source_clause())
+	}
+
+	/// Reads from a named CTE aliased as the table, so columns stay
+	/// qualified with the table name.
+	pub fn from_cte(mut self, cte: &'a str) -> Self {
+		self.source = Some(cte);
+		self
+	}
+
+	fn source_clause(&self) -> String {
+		match self.source {
+			Some(cte) => format!(r#""{}" AS "{}""#, cte, self.table),
+			None => self.table.to_string(),
+		}
 	}
 }
 
@@ -63,34 +148,34 @@ impl<'a> Display for ReadHead<'a> {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		match &self.r#type {
 			SelectType::Star => {
-				write!(f, r#"SELECT "{}".* FROM {}"#, self.table, self.table)
+				self.write_select(f)?;
+				write!(f, r#""{}".*"#, self.table)?;
+				self.write_star_tail(&[], f)
 			}
 			SelectType::StarWithExtras(cols) => {
-				write!(f, r#"SELECT "{}".*"#, self.table)?;
-				write_extras(cols, f)?;
-				write!(f, " FROM {}", self.table)
+				self.write_select(f)?;
+				write!(f, r#""{}".*"#, self.table)?;
+				self.write_star_tail(cols, f)
 			}
 			SelectType::StarAndCount => {
-				write!(
-					f,
-					r#"SELECT "{}".*, COUNT(*) OVER() AS total_count FROM {}"#,
-					self.table, self.table
-				)
+				self.write_select(f)?;
+				self.write_count(f)?;
+				self.write_star_tail(&[], f)
 			}
 			SelectType::StarAndCountExtras(cols) => {
-				write!(
-					f,
-					r#"SELECT "{}".*, COUNT(*) OVER() AS total_count"#,
-					self.table
-				)?;
-				write_extras(cols, f)?;
-				write!(f, " FROM {}", self.table)
+				self.write_select(f)?;
+				self.write_count(f)?;
+				self.write_star_tail(cols, f)
 			}
 			SelectType::Exists => {
-				write!(f, "SELECT EXISTS(SELECT 1 FROM {}", self.table)
+				write!(
+					f,
+					"SELECT EXISTS(SELECT 1 FROM {}",
+					self.source_clause()
+				)
 			}
 			SelectType::Projection(exprs) => {
-				write!(f, "SELECT ")?;
+				self.write_select(f)?;
 				for (idx, expr) in exprs.iter().enumerate() {
 					if idx > 0 {
 						write!(f, ", ")?;
@@ -100,11 +185,11 @@ impl<'a> Display for ReadHead<'a> {
 						write!(f, r#" AS "{}""#, alias)?;
 					}
 				}
-				write!(f, " FROM {}", self.table)
+				write!(f, " FROM {}", self.source_clause())
 			}
 			SelectType::Columns(cols) => {
 				let mut first = true;
-				write!(f, "SELECT ")?;
+				self.write_select(f)?;
 				for col in cols {
 					if !first {
 						write!(f, ", ")?;
@@ -112,7 +197,7 @@ impl<'a> Display for ReadHead<'a> {
 					first = false;
 					write!(f, r#""{}"."{}""#, col.table_alias, col.column)?;
 				}
-				write!(f, " FROM {}", self.table)
+				write!(f, " FROM {}", self.source_clause())
 			} /* #[cfg(any(test, feature = "test-utils"))]
 			   * BuildType::Raw => write!(f, ""), */
 		}
@@ -130,6 +215,86 @@ fn write_extras(cols: &[AliasedColumn], f: &mut Formatter<'_>) -> fmt::Result {
 	Ok(())
 }
 
+/// Opens a `WITH RECURSIVE` CTE and writes its anchor member up to the
+/// `FROM` clause. Every row carries its depth and the path of primary keys
+/// leading to it.
+pub struct RecursiveHead<'a> {
+	cte:         &'a str,
+	table:       &'a str,
+	key_columns: &'a [&'a str],
+}
+
+impl<'a> RecursiveHead<'a> {
+	pub fn new(
+		cte: &'a str,
+		table: &'a str,
+		key_columns: &'a [&'a str],
+	) -> Self {
+		Self {
+			cte,
+			table,
+			key_columns,
+		}
+	}
+}
+
+impl<'a> ToHead for RecursiveHead<'a> {
+	fn to_head(self) -> Cow<'static, str> {
+		self.to_string().into()
+	}
+}
+
+impl<'a> Display for RecursiveHead<'a> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			r#"WITH RECURSIVE "{}" AS (SELECT "{}".*, 0 AS "{}", ARRAY[{}] AS "{}" FROM {}"#,
+			self.cte,
+			self.table,
+			crate::tree::DEPTH_COLUMN,
+			path_key(self.table, self.key_columns),
+			crate::tree::PATH_COLUMN,
+			self.table,
+		)
+	}
+}
+
+/// Opens the CTE of the rows a faceted read matches; `base` projects the
+/// row key and every faceted column.
+pub struct FacetHead<'a> {
+	cte:  &'a str,
+	base: ReadHead<'a>,
+}
+
+impl<'a> FacetHead<'a> {
+	pub fn new(cte: &'a str, base: ReadHead<'a>) -> Self {
+		Self { cte, base }
+	}
+}
+
+impl<'a> ToHead for FacetHead<'a> {
+	fn to_head(self) -> Cow<'static, str> {
+		self.to_string().into()
+	}
+}
+
+impl<'a> Display for FacetHead<'a> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, r#"WITH "{}" AS ({}"#, self.cte, self.base)
+	}
+}
+
+/// Renders the primary key of `alias` as a single text value, e.g.
+/// `ROW("category"."id")::text`.
+pub(crate) fn path_key(alias: &str, key_columns: &[&str]) -> String {
+	let columns = key_columns
+		.iter()
+		.map(|col| format!(r#""{}"."{}""#, alias, col))
+		.collect::<Vec<_>>()
+		.join(", ");
+	format!("ROW({})::text", columns)
+}
+
 pub struct DeleteHead<'a> {
 	r#type:              DeleteType,
 	table:               &'a str,
@@ -152,6 +317,14 @@ impl<'a> DeleteHead<'a> {
 			delete_marker_field,
 		}
 	}
+
+	pub fn restore(table: &'a str, delete_marker_field: &'a str) -> Self {
+		Self {
+			r#type: DeleteType::Restore,
+			table,
+			delete_marker_field: Some(delete_marker_field),
+		}
+	}
 }
 
 impl<'a> ToHead for DeleteHead<'a> {
@@ -172,6 +345,12 @@ impl<'a> Display for DeleteHead<'a> {
 					.expect("Soft delete requires delete_marker_field");
 				write!(f, "UPDATE {} SET {} = NOW()", self.table, field)
 			}
+			DeleteType::Restore => {
+				let field = self
+					.delete_marker_field
+					.expect("Restore requires delete_marker_field");
+				write!(f, "UPDATE {} SET {} = NULL", self.table, field)
+			}
 		}
 	}
 }
@@ -207,3 +386,30 @@ impl<'a> ToHead for InsertHead<'a> {
 		format!("INSERT INTO {} ", self.table).into()
 	}
 }
+
+/// Opens the CTE an audited mutation runs in, see [`crate::audit`]. Renders
+/// `head` unchanged when the mutation is not audited.
+pub struct AuditedHead<H> {
+	head:    H,
+	audited: bool,
+}
+
+impl<H: ToHead> AuditedHead<H> {
+	pub fn new(head: H, audited: bool) -> Self {
+		Self { head, audited }
+	}
+}
+
+impl<H: ToHead> ToHead for AuditedHead<H> {
+	fn to_head(self) -> Cow<'static, str> {
+		if !self.audited {
+			return self.head.to_head();
+		}
+		format!(
+			r#"WITH "{}" AS ({}"#,
+			crate::audit::CHANGED_CTE,
+			self.head.to_head()
+		)
+		.into()
+	}
+}
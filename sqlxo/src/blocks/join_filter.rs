@@ -0,0 +1,78 @@
+use sqlx::{
+	Postgres,
+	Type,
+};
+use sqlxo_traits::{
+	Filterable,
+	JoinPath,
+	SqlWrite,
+};
+
+use crate::blocks::{
+	Expression,
+	SqlWriter,
+};
+
+type WriteFilter = Box<dyn Fn(&mut AliasedWriter<'_>) + Send + Sync>;
+
+/// A filter on the table a join path ends at, written against the alias the
+/// path gives that table.
+pub struct JoinFilter {
+	path:  JoinPath,
+	table: &'static str,
+	write: WriteFilter,
+}
+
+impl JoinFilter {
+	pub fn new<F>(path: JoinPath, expr: Expression<F>) -> Self
+	where
+		F: Filterable + Send + Sync + 'static,
+	{
+		let table = path
+			.segments()
+			.last()
+			.map(|segment| segment.descriptor.right_table)
+			.expect("join filters need a non-empty join path");
+
+		Self {
+			path,
+			table,
+			write: Box::new(move |w| expr.write(w)),
+		}
+	}
+
+	pub fn path(&self) -> &JoinPath {
+		&self.path
+	}
+
+	pub(crate) fn write(&self, w: &mut SqlWriter) {
+		let mut aliased = AliasedWriter {
+			inner: w,
+			from:  format!(r#""{}"."#, self.table),
+			to:    format!(r#""{}"."#, self.path.alias()),
+		};
+		(self.write)(&mut aliased);
+	}
+}
+
+/// Rewrites the columns a filter qualifies with its table name to a join
+/// alias.
+pub(crate) struct AliasedWriter<'w> {
+	inner: &'w mut SqlWriter,
+	from:  String,
+	to:    String,
+}
+
+impl SqlWrite for AliasedWriter<'_> {
+	fn push(&mut self, s: &str) {
+		self.inner.push(&s.replace(&self.from, &self.to));
+	}
+
+	fn bind<T>(&mut self, value: T)
+	where
+		T: sqlx::Encode<'static, Postgres> + Send + 'static,
+		T: Type<Postgres>,
+	{
+		self.inner.bind(value);
+	}
+}
@@ -1,4 +1,6 @@
 use sqlx::{
+	postgres::PgArguments,
+	Arguments,
 	Postgres,
 	Type,
 };
@@ -11,31 +13,42 @@ use sqlxo_traits::{
 use sqlxo_traits::{
 	QueryContext,
 	SqlWrite,
+	TenantId,
 };
 
 mod expression;
 mod head;
+mod join_filter;
 mod pagination;
 mod sort;
 
-pub use expression::Expression;
+pub use expression::{
+	DefaultScope,
+	Expression,
+	GetDefaultScope,
+};
+pub(crate) use head::path_key;
 pub use head::{
+	AuditedHead,
 	DeleteHead,
+	DistinctClause,
+	FacetHead,
 	InsertHead,
 	QualifiedColumn,
 	ReadHead,
+	RecursiveHead,
 	SelectProjection,
 	SelectType,
+	ToHead,
 	UpdateHead,
 };
+pub use join_filter::JoinFilter;
 pub use pagination::{
 	Page,
 	Pagination,
 };
 pub use sort::SortOrder;
 
-use crate::blocks::head::ToHead;
-
 /// TODO: add modifier traits
 /// and()
 /// or()
@@ -50,6 +63,12 @@ pub trait BuildableJoin<C: QueryContext> {
 	fn join_path(self, path: JoinPath) -> Self;
 }
 
+pub trait BuildableJoinFilter<C: QueryContext>: BuildableJoin<C> {
+	/// Keeps the rows whose joined row matches `filter`, joining its path
+	/// unless it already is.
+	fn join_filter(self, filter: JoinFilter) -> Self;
+}
+
 pub trait BuildableSort<C: QueryContext> {
 	fn order_by(self, s: SortOrder<C::Sort>) -> Self;
 }
@@ -66,13 +85,24 @@ pub struct SqlWriter {
 	has_group_by:   bool,
 	has_having:     bool,
 	has_pagination: bool,
+	tenant:         Option<TenantId>,
 }
 
 impl SqlWriter {
 	pub fn new(head: impl ToHead) -> Self {
-		let qb =
-			sqlx::QueryBuilder::<Postgres>::new(head.to_head().to_string());
+		Self::from_builder(sqlx::QueryBuilder::new(head.to_head().to_string()))
+	}
 
+	/// Like [`new`](Self::new), with `params` bound ahead of every bind
+	/// written later, for heads that compare against them.
+	pub(crate) fn with_params(head: impl ToHead, params: PgArguments) -> Self {
+		Self::from_builder(sqlx::QueryBuilder::with_arguments(
+			head.to_head().to_string(),
+			params,
+		))
+	}
+
+	fn from_builder(qb: sqlx::QueryBuilder<'static, Postgres>) -> Self {
 		Self {
 			qb,
 			has_join: false,
@@ -81,9 +111,43 @@ impl SqlWriter {
 			has_group_by: false,
 			has_having: false,
 			has_pagination: false,
+			tenant: None,
 		}
 	}
 
+	/// Restricts the tenant-scoped tables the writer joins to `tenant`.
+	pub(crate) fn with_tenant(mut self, tenant: Option<&TenantId>) -> Self {
+		self.tenant = tenant.cloned();
+		self
+	}
+
+	pub(crate) fn tenant(&self) -> Option<&TenantId> {
+		self.tenant.as_ref()
+	}
+
+	/// Adds `"alias"."field" = <tenant>` to the `WHERE` clause.
+	pub(crate) fn push_tenant_where(
+		&mut self,
+		alias: &str,
+		field: Option<&str>,
+	) {
+		let (Some(field), Some(tenant)) = (field, self.tenant.clone()) else {
+			return;
+		};
+		self.push_where_raw(|w| w.push_tenant_condition(&tenant, alias, field));
+	}
+
+	/// Writes `"alias"."field" = <tenant>`.
+	pub(crate) fn push_tenant_condition(
+		&mut self,
+		tenant: &TenantId,
+		alias: &str,
+		field: &str,
+	) {
+		self.qb.push(format!(r#""{alias}"."{field}" = "#));
+		tenant.push_bind(&mut self.qb);
+	}
+
 	pub fn into_builder(self) -> sqlx::QueryBuilder<'static, Postgres> {
 		self.qb
 	}
@@ -108,52 +172,44 @@ impl SqlWriter {
 		self.has_join = true;
 	}
 
-	fn push_join_path(&mut self, path: &JoinPath, base_table: &str) {
-		if path.is_empty() {
+	pub(crate) fn push_join_path(&mut self, path: &JoinPath, base_table: &str) {
+		push_join_path_sql(
+			&mut self.qb,
+			path,
+			base_table,
+			self.tenant.as_ref(),
+		);
+	}
+
+	/// Adds `joins` as the extra tables of an `UPDATE ... FROM` or
+	/// `DELETE ... USING` (`keyword`) and keeps only the rows they and
+	/// `filters` match. Call before any other `WHERE` condition.
+	pub fn push_mutation_joins(
+		&mut self,
+		keyword: &str,
+		joins: &[JoinPath],
+		filters: &[JoinFilter],
+		base_table: &str,
+	) {
+		if self.has_join || joins.is_empty() {
 			return;
 		}
 
-		let mut left_alias = base_table.to_string();
-		let mut alias_prefix = String::new();
-
-		for segment in path.segments() {
-			let join_word = match segment.kind {
-				JoinKind::Inner => " INNER JOIN ",
-				JoinKind::Left => " LEFT JOIN ",
-			};
-
-			if let Some(through) = segment.descriptor.through {
-				let mut through_alias = alias_prefix.clone();
-				through_alias.push_str(through.alias_segment);
-				let clause = format!(
-					r#"{join}{table} AS "{alias}" ON "{left}"."{left_field}" = "{alias}"."{right_field}""#,
-					join = join_word,
-					table = through.table,
-					alias = &through_alias,
-					left = &left_alias,
-					left_field = through.left_field,
-					right_field = through.right_field,
-				);
-				self.qb.push(clause);
-				left_alias = through_alias;
-			}
-
-			alias_prefix.push_str(segment.descriptor.alias_segment);
-			let right_alias = alias_prefix.clone();
-
-			let clause = format!(
-				r#"{join}{table} AS "{alias}" ON "{left}"."{left_field}" = "{alias}"."{right_field}""#,
-				join = join_word,
-				table = segment.descriptor.right_table,
-				alias = &right_alias,
-				left = &left_alias,
-				left_field = segment.descriptor.left_field,
-				right_field = segment.descriptor.right_field,
-			);
-
-			self.qb.push(clause);
-			left_alias = right_alias;
+		let conditions = push_mutation_join_tables(
+			&mut self.qb,
+			keyword,
+			joins,
+			base_table,
+			self.tenant.as_ref(),
+		);
+		for condition in &conditions {
+			self.push_where_raw(|w| w.push(condition));
 		}
+		for filter in filters {
+			self.push_where_raw(|w| filter.write(w));
+		}
+
+		self.has_join = true;
 	}
 
 	pub fn push_where<F: Filterable>(&mut self, expr: &Expression<F>) {
@@ -227,6 +283,223 @@ impl SqlWriter {
 	}
 }
 
+pub(crate) fn push_join_path_sql(
+	qb: &mut sqlx::QueryBuilder<'static, Postgres>,
+	path: &JoinPath,
+	base_table: &str,
+	tenant: Option<&TenantId>,
+) {
+	for hop in join_hops(path, base_table) {
+		hop.push(qb, tenant);
+	}
+}
+
+/// Pushes the tables of `joins` as the extra `FROM` (`UPDATE`) or `USING`
+/// (`DELETE`) items of a mutation. The first hop of a path can't be joined
+/// onto the target table there, so its condition is returned for the
+/// `WHERE` clause; the hops after it render as regular joins.
+pub(crate) fn push_mutation_join_tables(
+	qb: &mut sqlx::QueryBuilder<'static, Postgres>,
+	keyword: &str,
+	joins: &[JoinPath],
+	base_table: &str,
+	tenant: Option<&TenantId>,
+) -> Vec<String> {
+	let mut conditions = Vec::new();
+	let mut first = true;
+
+	for path in joins {
+		let mut hops = join_hops(path, base_table).into_iter();
+		let Some(head) = hops.next() else {
+			continue;
+		};
+
+		qb.push(if first { keyword } else { ", " });
+		head.push_table(qb, tenant);
+		conditions.extend(head.on);
+		first = false;
+
+		for hop in hops {
+			hop.push(qb, tenant);
+		}
+	}
+
+	conditions
+}
+
+struct JoinHop {
+	kind:         JoinKind,
+	table:        &'static str,
+	alias:        String,
+	on:           Option<String>,
+	tenant_field: Option<&'static str>,
+}
+
+impl JoinHop {
+	fn push(
+		&self,
+		qb: &mut sqlx::QueryBuilder<'static, Postgres>,
+		tenant: Option<&TenantId>,
+	) {
+		qb.push(self.kind.keyword());
+		let Some(on) = &self.on else {
+			self.push_table(qb, tenant);
+			return;
+		};
+
+		// A tenant check in `ON` only blanks out the columns of a preserved
+		// right side, so those rows are narrowed before the join instead.
+		if self.kind.preserves_right() {
+			self.push_table(qb, tenant);
+			qb.push(format!(" ON {on}"));
+			return;
+		}
+
+		qb.push(format!(r#"{} AS "{}" ON {on}"#, self.table, self.alias));
+		if let (Some(field), Some(tenant)) = (self.tenant_field, tenant) {
+			qb.push(format!(r#" AND "{}"."{field}" = "#, self.alias));
+			tenant.push_bind(qb);
+		}
+	}
+
+	/// Pushes the table without a condition to restrict, narrowing it to
+	/// the tenant's rows in a subquery.
+	fn push_table(
+		&self,
+		qb: &mut sqlx::QueryBuilder<'static, Postgres>,
+		tenant: Option<&TenantId>,
+	) {
+		match (self.tenant_field, tenant) {
+			(Some(field), Some(tenant)) => {
+				qb.push(format!(
+					r#"(SELECT * FROM {table} WHERE "{table}"."{field}" = "#,
+					table = self.table,
+				));
+				tenant.push_bind(qb);
+				qb.push(format!(r#") AS "{}""#, self.alias));
+			}
+			_ => {
+				qb.push(format!(r#"{} AS "{}""#, self.table, self.alias));
+			}
+		}
+	}
+}
+
+/// The tables `path` joins in order, a many-to-many hop contributing its
+/// `through` table first.
+fn join_hops(path: &JoinPath, base_table: &str) -> Vec<JoinHop> {
+	let mut hops = Vec::new();
+	let mut left_alias = base_table.to_string();
+	let mut alias_prefix = String::new();
+
+	for segment in path.segments() {
+		let kind = segment.kind;
+
+		if !segment.kind.has_condition() {
+			alias_prefix.push_str(segment.descriptor.alias_segment);
+			hops.push(JoinHop {
+				kind,
+				table: segment.descriptor.right_table,
+				alias: alias_prefix.clone(),
+				on: None,
+				tenant_field: segment.descriptor.tenant_field,
+			});
+			left_alias = alias_prefix.clone();
+			continue;
+		}
+
+		if let Some(through) = segment.descriptor.through {
+			let mut through_alias = alias_prefix.clone();
+			through_alias.push_str(through.alias_segment);
+			hops.push(JoinHop {
+				kind,
+				table: through.table,
+				on: Some(join_condition(
+					&left_alias,
+					through.left_fields,
+					&through_alias,
+					through.right_fields,
+				)),
+				alias: through_alias.clone(),
+				tenant_field: through.tenant_field,
+			});
+			left_alias = through_alias;
+		}
+
+		alias_prefix.push_str(segment.descriptor.alias_segment);
+		let right_alias = alias_prefix.clone();
+		hops.push(JoinHop {
+			kind,
+			table: segment.descriptor.right_table,
+			on: Some(join_condition(
+				&left_alias,
+				segment.descriptor.left_fields,
+				&right_alias,
+				segment.descriptor.right_fields,
+			)),
+			alias: right_alias.clone(),
+			tenant_field: segment.descriptor.tenant_field,
+		});
+		left_alias = right_alias;
+	}
+
+	hops
+}
+
+pub(crate) fn assert_join_start(path: &JoinPath, table: &str) {
+	if let Some(expected) = path.first_table() {
+		assert_eq!(
+			expected, table,
+			"join path must start at base table `{}` but started at `{}`",
+			table, expected,
+		);
+	}
+}
+
+/// Whether `path` or a path continuing it is already joined.
+pub(crate) fn is_joined(joins: Option<&[JoinPath]>, path: &JoinPath) -> bool {
+	joins.unwrap_or_default().iter().any(|joined| {
+		joined.len() >= path.len() &&
+			joined
+				.segments()
+				.iter()
+				.zip(path.segments())
+				.all(|(a, b)| a.descriptor == b.descriptor)
+	})
+}
+
+pub(crate) fn join_condition(
+	left_alias: &str,
+	left_fields: &[&str],
+	right_alias: &str,
+	right_fields: &[&str],
+) -> String {
+	assert_eq!(
+		left_fields.len(),
+		right_fields.len(),
+		"join condition needs the same number of columns on both sides",
+	);
+
+	left_fields
+		.iter()
+		.zip(right_fields)
+		.map(|(left, right)| {
+			format!(r#""{left_alias}"."{left}" = "{right_alias}"."{right}""#)
+		})
+		.collect::<Vec<_>>()
+		.join(" AND ")
+}
+
+/// Binds `tenant` to the next placeholder of `args`.
+pub(crate) fn bind_tenant(args: &mut PgArguments, tenant: &TenantId) {
+	match tenant {
+		TenantId::Int(id) => args.add(*id),
+		TenantId::Uuid(id) => args.add(*id),
+		TenantId::Text(id) => args.add(id.clone()),
+	}
+	.expect("tenant ids always encode");
+}
+
 impl SqlWrite for SqlWriter {
 	fn push(&mut self, s: &str) {
 		self.qb.push(s);
@@ -23,6 +23,7 @@ impl Pagination {
 }
 
 /// Standard pagination response.
+#[derive(Clone)]
 pub struct Page<T> {
 	pub items:       Vec<T>,
 	pub page_size:   i64,
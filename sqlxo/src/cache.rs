@@ -0,0 +1,517 @@
+use std::{
+	any::{
+		Any,
+		TypeId,
+	},
+	collections::HashMap,
+	sync::{
+		Arc,
+		Mutex,
+	},
+	time::{
+		Duration,
+		Instant,
+	},
+};
+
+use hashlink::LruCache;
+use sqlx::{
+	Execute,
+	PgPool,
+};
+use sqlxo_traits::{
+	Creatable,
+	CreateModel,
+	DeleteCascade,
+	Error,
+	JoinNavigationModel,
+	QueryContext,
+	Updatable,
+};
+
+use crate::{
+	audit::AUDIT_TABLE,
+	blocks::{
+		Page,
+		SelectType,
+	},
+	select::{
+		HavingExpr,
+		SelectionColumn,
+		SelectionEntry,
+	},
+	DeleteQueryPlan,
+	FetchablePlan,
+	InsertQueryPlan,
+	ReadQueryPlan,
+	UpdateQueryPlan,
+};
+
+/// Identifies a cached read: the generated SQL, the bound values as sqlx
+/// encodes them and the type of the cached result.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+	sql:    String,
+	binds:  String,
+	result: TypeId,
+}
+
+pub type CachedValue = Arc<dyn Any + Send + Sync>;
+
+/// Storage of a [`CachedExecutor`].
+pub trait CacheBackend: Send + Sync {
+	fn get(&self, key: &CacheKey) -> Option<CachedValue>;
+
+	/// Stores `value` until it expires after `ttl` or a mutation of one of
+	/// `tables` invalidates it.
+	fn insert(
+		&self,
+		key: CacheKey,
+		value: CachedValue,
+		tables: &[&str],
+		ttl: Option<Duration>,
+	);
+
+	/// Drops every entry read from `table`.
+	fn invalidate(&self, table: &str);
+}
+
+struct MemoryEntry {
+	value:      CachedValue,
+	tables:     Vec<String>,
+	expires_at: Option<Instant>,
+}
+
+/// In-memory [`CacheBackend`] evicting the least recently used entry once
+/// `capacity` entries are stored.
+pub struct MemoryCache {
+	entries: Mutex<LruCache<CacheKey, MemoryEntry>>,
+}
+
+impl MemoryCache {
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			entries: Mutex::new(LruCache::new(capacity)),
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.entries.lock().expect("cache lock poisoned").len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl CacheBackend for MemoryCache {
+	fn get(&self, key: &CacheKey) -> Option<CachedValue> {
+		let mut entries = self.entries.lock().expect("cache lock poisoned");
+		let entry = entries.get(key)?;
+		if entry.expires_at.is_some_and(|at| at <= Instant::now()) {
+			entries.remove(key);
+			return None;
+		}
+		Some(entry.value.clone())
+	}
+
+	fn insert(
+		&self,
+		key: CacheKey,
+		value: CachedValue,
+		tables: &[&str],
+		ttl: Option<Duration>,
+	) {
+		let entry = MemoryEntry {
+			value,
+			tables: tables.iter().map(|t| t.to_string()).collect(),
+			expires_at: ttl.map(|ttl| Instant::now() + ttl),
+		};
+		self.entries
+			.lock()
+			.expect("cache lock poisoned")
+			.insert(key, entry);
+	}
+
+	fn invalidate(&self, table: &str) {
+		let mut entries = self.entries.lock().expect("cache lock poisoned");
+		let stale: Vec<CacheKey> = entries
+			.iter()
+			.filter(|(_, entry)| entry.tables.iter().any(|t| t == table))
+			.map(|(key, _)| key.clone())
+			.collect();
+		for key in stale {
+			entries.remove(&key);
+		}
+	}
+}
+
+/// Runs plans on a pool, serving repeated reads from a [`CacheBackend`].
+///
+/// Reads are cached per SQL and bound values, so tenants and other bound
+/// filters never share entries. Mutations run through
+/// [`execute`](Self::execute) drop the cached reads of every table they
+/// write; changes made past the executor are only picked up once the
+/// entries expire.
+pub struct CachedExecutor<B: CacheBackend = MemoryCache> {
+	pool:        PgPool,
+	backend:     B,
+	ttl:         Option<Duration>,
+	/// Bumped whenever a table is invalidated, so reads that ran while it
+	/// was aren't stored.
+	generations: Mutex<HashMap<String, u64>>,
+}
+
+impl CachedExecutor<MemoryCache> {
+	/// Caches up to `capacity` reads in memory.
+	pub fn new(pool: PgPool, capacity: usize) -> Self {
+		Self::with_backend(pool, MemoryCache::new(capacity))
+	}
+}
+
+impl<B: CacheBackend> CachedExecutor<B> {
+	pub fn with_backend(pool: PgPool, backend: B) -> Self {
+		Self {
+			pool,
+			backend,
+			ttl: None,
+			generations: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Expires cached reads after `ttl`.
+	pub fn with_ttl(mut self, ttl: Duration) -> Self {
+		self.ttl = Some(ttl);
+		self
+	}
+
+	pub fn pool(&self) -> &PgPool {
+		&self.pool
+	}
+
+	pub fn backend(&self) -> &B {
+		&self.backend
+	}
+
+	pub async fn fetch_all<C, Row>(
+		&self,
+		plan: &ReadQueryPlan<'_, C, Row>,
+	) -> Result<Vec<Row>, Error>
+	where
+		C: QueryContext,
+		C::Model: JoinNavigationModel,
+		Row: Clone
+			+ Send
+			+ Sync
+			+ Unpin
+			+ 'static
+			+ for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+	{
+		self.cached(plan, SelectType::Star, plan.fetch_all(&self.pool))
+			.await
+	}
+
+	pub async fn fetch_optional<C, Row>(
+		&self,
+		plan: &ReadQueryPlan<'_, C, Row>,
+	) -> Result<Option<Row>, Error>
+	where
+		C: QueryContext,
+		C::Model: JoinNavigationModel,
+		Row: Clone
+			+ Send
+			+ Sync
+			+ Unpin
+			+ 'static
+			+ for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+	{
+		self.cached(plan, SelectType::Star, plan.fetch_optional(&self.pool))
+			.await
+	}
+
+	/// Like [`fetch_optional`](Self::fetch_optional) but fails with
+	/// [`sqlx::Error::RowNotFound`] when there is no row.
+	pub async fn fetch_one<C, Row>(
+		&self,
+		plan: &ReadQueryPlan<'_, C, Row>,
+	) -> Result<Row, Error>
+	where
+		C: QueryContext,
+		C::Model: JoinNavigationModel,
+		Row: Clone
+			+ Send
+			+ Sync
+			+ Unpin
+			+ 'static
+			+ for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+	{
+		self.fetch_optional(plan)
+			.await?
+			.ok_or(Error::Sqlx(sqlx::Error::RowNotFound))
+	}
+
+	pub async fn fetch_page<C>(
+		&self,
+		plan: &ReadQueryPlan<'_, C>,
+	) -> Result<Page<C::Model>, Error>
+	where
+		C: QueryContext,
+		C::Model: JoinNavigationModel + Clone + 'static,
+	{
+		self.cached(plan, SelectType::StarAndCount, plan.fetch_page(&self.pool))
+			.await
+	}
+
+	/// Runs `plan` and invalidates the cached reads of the tables it
+	/// writes, once it succeeded.
+	pub async fn execute<P>(&self, plan: &P) -> Result<u64, Error>
+	where
+		P: CachedMutation,
+	{
+		let rows = plan.execute_on(&self.pool).await?;
+		for table in plan.written_tables() {
+			self.invalidate(table);
+		}
+		Ok(rows)
+	}
+
+	/// Drops the cached reads of `table`, e.g. after changing it outside of
+	/// the executor. Reads of `table` still running aren't cached.
+	pub fn invalidate(&self, table: &str) {
+		let mut generations =
+			self.generations.lock().expect("cache lock poisoned");
+		*generations.entry(table.to_string()).or_default() += 1;
+		self.backend.invalidate(table);
+	}
+
+	fn generations(
+		generations: &HashMap<String, u64>,
+		tables: &[&str],
+	) -> Vec<u64> {
+		tables
+			.iter()
+			.map(|table| generations.get(*table).copied().unwrap_or_default())
+			.collect()
+	}
+
+	async fn cached<C, Row, T>(
+		&self,
+		plan: &ReadQueryPlan<'_, C, Row>,
+		select_type: SelectType,
+		fetch: impl std::future::Future<Output = Result<T, Error>>,
+	) -> Result<T, Error>
+	where
+		C: QueryContext,
+		C::Model: JoinNavigationModel,
+		T: Clone + Send + Sync + 'static,
+	{
+		let key = read_key::<C, Row, T>(plan, select_type)?;
+		if let Some(value) = self.backend.get(&key) {
+			if let Some(value) = value.downcast_ref::<T>() {
+				return Ok(value.clone());
+			}
+		}
+
+		let tables = read_tables(plan);
+		let read_at = Self::generations(
+			&self.generations.lock().expect("cache lock poisoned"),
+			&tables,
+		);
+		let value = fetch.await?;
+
+		// Held while inserting so no invalidation slips in after the check.
+		let generations = self.generations.lock().expect("cache lock poisoned");
+		if Self::generations(&generations, &tables) == read_at {
+			self.backend.insert(
+				key,
+				Arc::new(value.clone()),
+				&tables,
+				self.ttl,
+			);
+		}
+		Ok(value)
+	}
+}
+
+fn read_key<C, Row, T>(
+	plan: &ReadQueryPlan<'_, C, Row>,
+	select_type: SelectType,
+) -> Result<CacheKey, Error>
+where
+	C: QueryContext,
+	C::Model: JoinNavigationModel,
+	T: 'static,
+{
+	let mut qb = plan.to_query_builder(select_type);
+	let mut query = qb.build();
+	let sql = query.sql().to_string();
+	let binds = query.take_arguments().map_err(sqlx::Error::Encode)?;
+	Ok(CacheKey {
+		sql,
+		binds: format!("{binds:?}"),
+		result: TypeId::of::<T>(),
+	})
+}
+
+/// The base table and every table `plan` joins, counts, selects, groups,
+/// aggregates or counts facets of.
+fn read_tables<'p, C, Row>(plan: &'p ReadQueryPlan<'_, C, Row>) -> Vec<&'p str>
+where
+	C: QueryContext,
+{
+	let descriptors = plan
+		.joins
+		.iter()
+		.flatten()
+		.flat_map(|path| path.segments())
+		.map(|segment| &segment.descriptor)
+		.chain(plan.counts.iter().flatten());
+
+	let mut tables = vec![plan.table];
+	for descriptor in descriptors {
+		push_table(&mut tables, descriptor.right_table);
+		if let Some(through) = &descriptor.through {
+			push_table(&mut tables, through.table);
+		}
+	}
+
+	let mut columns: Vec<SelectionColumn> = Vec::new();
+	for entry in plan.selection.iter().flat_map(|s| &s.entries) {
+		match entry {
+			SelectionEntry::Column(column) => columns.push(*column),
+			SelectionEntry::Aggregate(aggregate) => {
+				columns.extend(aggregate.column)
+			}
+			SelectionEntry::Window(window) => {
+				columns.extend(window.column);
+				columns.extend(window.partition_by.iter().copied());
+			}
+		}
+	}
+	columns.extend(plan.group_by.iter().flatten().copied());
+	columns.extend(plan.facets.iter().flatten().map(|facet| facet.column));
+	let having = plan
+		.having
+		.iter()
+		.chain(plan.aggregate_filter.iter().map(|filter| &filter.having));
+	for expr in having {
+		having_columns(expr, &mut columns);
+	}
+
+	for column in columns {
+		push_table(&mut tables, column.table);
+	}
+	tables
+}
+
+fn having_columns(expr: &HavingExpr, columns: &mut Vec<SelectionColumn>) {
+	match expr {
+		HavingExpr::And(xs) | HavingExpr::Or(xs) => {
+			for x in xs {
+				having_columns(x, columns);
+			}
+		}
+		HavingExpr::Not(inner) => having_columns(inner, columns),
+		HavingExpr::Leaf(predicate) => {
+			columns.extend(predicate.selection.column)
+		}
+	}
+}
+
+fn push_table<'t>(tables: &mut Vec<&'t str>, table: &'t str) {
+	if !tables.contains(&table) {
+		tables.push(table);
+	}
+}
+
+/// A plan a [`CachedExecutor`] can run as a mutation.
+#[async_trait::async_trait]
+pub trait CachedMutation: Sync {
+	/// Tables whose cached reads are stale once the plan ran, the
+	/// [`AUDIT_TABLE`] included when the plan records changes there.
+	fn written_tables(&self) -> Vec<&str>;
+
+	async fn execute_on(&self, pool: &PgPool) -> Result<u64, Error>;
+}
+
+#[async_trait::async_trait]
+impl<C, Row> CachedMutation for InsertQueryPlan<'_, C, Row>
+where
+	C: QueryContext,
+	C::Model: Creatable,
+	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+{
+	fn written_tables(&self) -> Vec<&str> {
+		let mut children = Vec::new();
+		for model in &self.create_models {
+			model.child_tables(&mut children);
+		}
+		let mut tables = vec![self.table];
+		if self.audit.is_some() {
+			tables.push(AUDIT_TABLE);
+		}
+		for table in children {
+			push_table(&mut tables, table);
+		}
+		tables
+	}
+
+	async fn execute_on(&self, pool: &PgPool) -> Result<u64, Error> {
+		self.execute_atomic(pool).await
+	}
+}
+
+#[async_trait::async_trait]
+impl<C, Row> CachedMutation for UpdateQueryPlan<'_, C, Row>
+where
+	C: QueryContext,
+	C::Model: Updatable,
+	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+{
+	fn written_tables(&self) -> Vec<&str> {
+		let mut tables = vec![self.table];
+		if self.audit.is_some() {
+			tables.push(AUDIT_TABLE);
+		}
+		tables
+	}
+
+	async fn execute_on(&self, pool: &PgPool) -> Result<u64, Error> {
+		self.execute_atomic(pool).await
+	}
+}
+
+#[async_trait::async_trait]
+impl<C, Row> CachedMutation for DeleteQueryPlan<'_, C, Row>
+where
+	C: QueryContext,
+	C::Model: crate::Deletable,
+	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+{
+	/// Cascades may delete or update the rows of related tables.
+	fn written_tables(&self) -> Vec<&str> {
+		let mut tables = vec![self.table];
+		if self.audit.is_some() {
+			tables.push(AUDIT_TABLE);
+		}
+		push_cascade_tables(&mut tables, self.cascades);
+		tables
+	}
+
+	async fn execute_on(&self, pool: &PgPool) -> Result<u64, Error> {
+		self.execute_atomic(pool).await
+	}
+}
+
+fn push_cascade_tables(tables: &mut Vec<&str>, cascades: &[DeleteCascade]) {
+	for cascade in cascades {
+		if tables.contains(&cascade.table) {
+			continue;
+		}
+		tables.push(cascade.table);
+		if cascade.audited_key.is_some() {
+			push_table(tables, AUDIT_TABLE);
+		}
+		push_cascade_tables(tables, (cascade.children)());
+	}
+}
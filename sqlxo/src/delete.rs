@@ -1,33 +1,113 @@
 use std::marker::PhantomData;
 
+use chrono::{
+	DateTime,
+	Duration,
+	Utc,
+};
 use sqlx::{
+	Acquire,
 	Executor,
+	PgConnection,
 	Postgres,
 };
-use sqlxo_traits::QueryContext;
+use sqlxo_traits::{
+	CascadeCycle,
+	CascadeType,
+	DeleteCascade,
+	Error,
+	Filterable,
+	JoinKind,
+	JoinPath,
+	QueryContext,
+	RestrictedDelete,
+	SqlWrite,
+	TenantScoped,
+};
 
 use crate::{
 	and,
+	audit::{
+		self,
+		AuditAction,
+		AuditTrail,
+	},
 	blocks::{
+		assert_join_start,
+		is_joined,
+		push_mutation_join_tables,
+		AuditedHead,
 		BuildableFilter,
+		BuildableJoin,
+		BuildableJoinFilter,
 		DeleteHead,
 		Expression,
+		JoinFilter,
+		ReadHead,
+		SelectType,
 		SqlWriter,
+		UpdateHead,
 	},
+	guard,
 	select::{
 		self,
 		SelectionList,
 	},
+	tenant::TenantScope,
 	Buildable,
 	ExecutablePlan,
 	FetchablePlan,
 	Planable,
 };
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum DeleteMode {
+	Delete,
+	/// Clears the delete marker of soft-deleted rows.
+	Restore,
+	/// Hard-deletes soft-deleted rows.
+	Purge,
+}
+
+/// Purges only rows soft-deleted before this point.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum PurgeThreshold {
+	Before(DateTime<Utc>),
+	OlderThan(Duration),
+}
+
+/// A statement run ahead of the delete on behalf of a `cascade_type(...)`.
+enum CascadeStatement {
+	/// Fails the delete when the `EXISTS` query finds a row.
+	Restrict {
+		child: &'static str,
+		query: sqlx::QueryBuilder<'static, Postgres>,
+	},
+	Apply(sqlx::QueryBuilder<'static, Postgres>),
+}
+
+impl CascadeStatement {
+	/// Ends the statement changing the rows of `relation`, recording them in
+	/// `audit`.
+	fn apply(
+		w: SqlWriter,
+		relation: &DeleteCascade,
+		audit: Option<AuditTrail>,
+	) -> Self {
+		let mut query = w.into_builder();
+		if let Some(audit) = audit {
+			audit.push_count(&mut query, relation.table);
+		}
+		Self::Apply(query)
+	}
+}
+
 /// TODO: this will be useful once multiple sql dialects will be supported
 #[allow(dead_code)]
 pub trait BuildableDeleteQuery<C, Row = <C as QueryContext>::Model>:
-	Buildable<C, Row = Row, Plan: Planable<C, Row>> + BuildableFilter<C>
+	Buildable<C, Row = Row, Plan: Planable<C, Row>>
+	+ BuildableFilter<C>
+	+ BuildableJoin<C>
 where
 	C: QueryContext,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
@@ -39,10 +119,21 @@ pub struct DeleteQueryPlan<
 	C: QueryContext,
 	Row = <C as QueryContext>::Model,
 > {
+	pub(crate) joins: Option<Vec<JoinPath>>,
+	pub(crate) join_filters: Vec<JoinFilter>,
 	pub(crate) where_expr: Option<Expression<C::Query>>,
 	pub(crate) table: &'a str,
 	pub(crate) is_soft: bool,
 	pub(crate) delete_marker_field: Option<&'a str>,
+	pub(crate) mode: DeleteMode,
+	pub(crate) purge_threshold: Option<PurgeThreshold>,
+	pub(crate) cascades: &'static [DeleteCascade],
+	pub(crate) all_rows: bool,
+	pub(crate) max_affected_rows: Option<u64>,
+	pub(crate) tenant: TenantScope,
+	pub(crate) audit: Option<AuditTrail>,
+	/// Author of the audited changes of the cascades.
+	pub(crate) actor: Option<String>,
 	pub(crate) selection: Option<SelectionList<Row, select::SelectionColumn>>,
 	row: PhantomData<Row>,
 }
@@ -52,9 +143,37 @@ where
 	C: QueryContext,
 {
 	fn to_query_builder(&self) -> sqlx::QueryBuilder<'static, Postgres> {
-		let head =
-			DeleteHead::new(self.table, self.is_soft, self.delete_marker_field);
-		let mut w = SqlWriter::new(head);
+		let head = match (self.mode, self.delete_marker_field) {
+			(DeleteMode::Delete, marker) => {
+				DeleteHead::new(self.table, self.is_soft, marker)
+			}
+			(DeleteMode::Restore, Some(marker)) => {
+				DeleteHead::restore(self.table, marker)
+			}
+			(DeleteMode::Purge, Some(_)) => {
+				DeleteHead::new(self.table, false, None)
+			}
+			(_, None) => unreachable!("restore and purge require soft deletes"),
+		};
+		let mut w =
+			SqlWriter::new(AuditedHead::new(head, self.audit.is_some()))
+				.with_tenant(self.tenant.tenant());
+
+		if let Some(js) = &self.joins {
+			// Soft deletes and restores render as an `UPDATE`.
+			let keyword = match (self.mode, self.is_soft) {
+				(DeleteMode::Delete, true) | (DeleteMode::Restore, _) => {
+					" FROM "
+				}
+				_ => " USING ",
+			};
+			w.push_mutation_joins(keyword, js, &self.join_filters, self.table);
+		}
+		w.push_tenant_where(self.table, C::Model::TENANT_FIELD);
+
+		if self.trash_marker().is_some() {
+			w.push_where_raw(|writer| self.push_trash_condition(writer));
+		}
 
 		if let Some(e) = &self.where_expr {
 			w.push_where(e);
@@ -63,14 +182,460 @@ where
 		w.into_builder()
 	}
 
+	/// The delete marker restores and purges select soft-deleted rows by.
+	fn trash_marker(&self) -> Option<&str> {
+		match self.mode {
+			DeleteMode::Delete => None,
+			DeleteMode::Restore | DeleteMode::Purge => self.delete_marker_field,
+		}
+	}
+
+	/// Writes the condition matching soft-deleted rows, before the purge
+	/// threshold if there is one.
+	fn push_trash_condition(&self, w: &mut SqlWriter) {
+		let Some(marker) = self.trash_marker() else {
+			return;
+		};
+		let qualified = format!(r#""{}"."{}""#, self.table, marker);
+		w.push(&qualified);
+		w.push(" IS NOT NULL");
+		match self.purge_threshold {
+			Some(PurgeThreshold::Before(before)) => {
+				w.push(&format!(" AND {qualified} < "));
+				w.bind(before);
+			}
+			Some(PurgeThreshold::OlderThan(age)) => {
+				w.push(&format!(" AND {qualified} < NOW() - "));
+				w.bind(age);
+			}
+			None => {}
+		}
+	}
+
+	/// The statement without `RETURNING`.
+	fn to_execute_builder(&self) -> sqlx::QueryBuilder<'static, Postgres> {
+		let mut qb = self.to_query_builder();
+		if let Some(audit) = &self.audit {
+			audit.push_count(&mut qb, self.table);
+		}
+		qb
+	}
+
 	fn push_returning(&self, qb: &mut sqlx::QueryBuilder<'static, Postgres>) {
-		select::push_returning(qb, self.table, self.selection.as_ref());
+		if let Some(audit) = &self.audit {
+			audit.push_returning(qb, self.table, self.selection.as_ref());
+			return;
+		}
+		select::push_mutation_returning(
+			qb,
+			self.table,
+			self.joins.is_some(),
+			self.selection.as_ref(),
+		);
+	}
+
+	/// Statements enforcing the `cascade_type(...)` of the relations, children
+	/// before their parents. Purges apply them like hard deletes; restores
+	/// don't cascade.
+	fn cascade_statements(&self) -> Result<Vec<CascadeStatement>, Error> {
+		let mut out = Vec::new();
+		if self.mode != DeleteMode::Restore {
+			self.push_cascade_statements(
+				self.table,
+				self.cascades,
+				&mut Vec::new(),
+				&mut out,
+			)?;
+		}
+		Ok(out)
+	}
+
+	fn push_cascade_statements(
+		&self,
+		parent_table: &str,
+		relations: &'static [DeleteCascade],
+		path: &mut Vec<&'static DeleteCascade>,
+		out: &mut Vec<CascadeStatement>,
+	) -> Result<(), Error> {
+		for relation in relations {
+			let Some(cascade) = relation.cascade_type(parent_table) else {
+				continue;
+			};
+			// Only deleting children recurses; clearing or checking them
+			// also works on the table being deleted from.
+			if cascade == CascadeType::Cascade &&
+				(relation.table == self.table ||
+					path.iter().any(|step| step.table == relation.table))
+			{
+				return Err(CascadeCycle {
+					table: self.table.to_string(),
+					child: relation.table.to_string(),
+				}
+				.into());
+			}
+
+			path.push(relation);
+			let marker = (relation.delete_marker)();
+			match cascade {
+				CascadeType::Restrict => {
+					let mut w = SqlWriter::new(ReadHead::new(
+						relation.table,
+						SelectType::Exists,
+					));
+					w.push_where_raw(|w| {
+						self.push_cascade_scope(w, path, true)
+					});
+					w.push(")");
+					out.push(CascadeStatement::Restrict {
+						child: relation.table,
+						query: w.into_builder(),
+					});
+				}
+				CascadeType::SetNull => {
+					let audit =
+						self.cascade_audit(relation, AuditAction::Update, true);
+					let mut w = SqlWriter::new(AuditedHead::new(
+						UpdateHead::new(relation.table),
+						audit.is_some(),
+					));
+					// `SET` targets can't be qualified with the table.
+					for (idx, column) in relation.fk.iter().enumerate() {
+						if idx > 0 {
+							w.push(", ");
+						}
+						w.push(&format!(r#""{column}" = NULL"#));
+					}
+					w.push_where_raw(|w| {
+						self.push_cascade_scope(w, path, false)
+					});
+					out.push(CascadeStatement::apply(w, relation, audit));
+				}
+				CascadeType::Cascade => {
+					self.push_cascade_statements(
+						relation.table,
+						(relation.children)(),
+						path,
+						out,
+					)?;
+					// Purged rows are gone for good, so are their children.
+					let marker =
+						marker.filter(|_| self.mode == DeleteMode::Delete);
+					let audit = self.cascade_audit(
+						relation,
+						AuditAction::Delete,
+						marker.is_some(),
+					);
+					let mut w = SqlWriter::new(AuditedHead::new(
+						DeleteHead::new(
+							relation.table,
+							marker.is_some(),
+							marker,
+						),
+						audit.is_some(),
+					));
+					w.push_where_raw(|w| {
+						self.push_cascade_scope(w, path, true)
+					});
+					out.push(CascadeStatement::apply(w, relation, audit));
+				}
+			}
+			path.pop();
+		}
+
+		Ok(())
+	}
+
+	/// Records the rows of `relation` a cascade changes when it is audited.
+	fn cascade_audit(
+		&self,
+		relation: &DeleteCascade,
+		action: AuditAction,
+		keeps_row: bool,
+	) -> Option<AuditTrail> {
+		relation.audited_key.map(|primary_key| {
+			AuditTrail::new(action, keeps_row, primary_key, self.actor.clone())
+		})
+	}
+
+	/// Writes the condition matching the rows of the last table on `path`
+	/// that reference the rows being deleted. `live` skips rows already
+	/// soft-deleted, unless purging, which removes the rows they reference.
+	fn push_cascade_scope(
+		&self,
+		w: &mut SqlWriter,
+		path: &[&DeleteCascade],
+		live: bool,
+	) {
+		let Some((relation, parents)) = path.split_last() else {
+			return;
+		};
+		let parent_table = parents.last().map_or(self.table, |p| p.table);
+		w.push(&format!(
+			"({}) IN (SELECT {} FROM {}",
+			qualified_columns(relation.table, relation.fk),
+			qualified_columns(parent_table, relation.parent_key),
+			parent_table
+		));
+		if !parents.is_empty() {
+			w.push(" WHERE ");
+			self.push_cascade_scope(w, parents, true);
+		} else {
+			self.push_root_scope(w);
+		}
+		w.push(")");
+		let live = live && self.mode == DeleteMode::Delete;
+		if let (true, Some(marker)) = (live, (relation.delete_marker)()) {
+			w.push(&format!(
+				r#" AND "{}"."{}" IS NULL"#,
+				relation.table, marker
+			));
+		}
+	}
+
+	/// Writes the joins and filters selecting the rows being deleted into the
+	/// subquery over the target table.
+	fn push_root_scope(&self, w: &mut SqlWriter) {
+		let conditions = match &self.joins {
+			Some(js) => push_mutation_join_tables(
+				w.query_builder_mut(),
+				", ",
+				js,
+				self.table,
+				self.tenant.tenant(),
+			),
+			None => Vec::new(),
+		};
+
+		let mut keyword = " WHERE ";
+		for condition in &conditions {
+			w.push(keyword);
+			w.push(condition);
+			keyword = " AND ";
+		}
+		if let (Some(field), Some(tenant)) =
+			(C::Model::TENANT_FIELD, self.tenant.tenant())
+		{
+			w.push(keyword);
+			w.push_tenant_condition(tenant, self.table, field);
+			keyword = " AND ";
+		}
+		for filter in &self.join_filters {
+			w.push(keyword);
+			filter.write(w);
+			keyword = " AND ";
+		}
+		if self.trash_marker().is_some() {
+			w.push(keyword);
+			self.push_trash_condition(w);
+			keyword = " AND ";
+		}
+		if let Some(e) = &self.where_expr {
+			w.push(keyword);
+			e.write(w);
+		}
+	}
+
+	fn check_affected(&self, affected: u64) -> Result<(), Error> {
+		match self.max_affected_rows {
+			Some(max) => guard::check_affected(self.table, max, affected),
+			None => Ok(()),
+		}
+	}
+
+	/// Runs the cascade statements; the caller owns the transaction.
+	async fn run_cascades(
+		&self,
+		statements: Vec<CascadeStatement>,
+		conn: &mut PgConnection,
+	) -> Result<(), Error> {
+		for statement in statements {
+			match statement {
+				CascadeStatement::Restrict { child, mut query } => {
+					let referenced: bool = query
+						.build_query_scalar()
+						.fetch_one(&mut *conn)
+						.await?;
+					if referenced {
+						return Err(RestrictedDelete {
+							table: self.table.to_string(),
+							child: child.to_string(),
+						}
+						.into());
+					}
+				}
+				CascadeStatement::Apply(mut query) => {
+					query.build().execute(&mut *conn).await?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	fn check_tenant(&self) -> Result<(), Error> {
+		self.tenant.check(self.table, C::Model::TENANT_FIELD)
+	}
+
+	/// A purge threshold scopes the rows like a filter does.
+	fn check_filtered(&self) -> Result<(), Error> {
+		guard::check_filtered(
+			self.table,
+			self.all_rows ||
+				self.where_expr.is_some() ||
+				!self.join_filters.is_empty() ||
+				self.purge_threshold.is_some(),
+		)
+	}
+
+	/// Fails when relations need cascading or a row limit is set, which both
+	/// need a transaction.
+	fn single_statement(&self) -> Result<(), Error> {
+		self.check_tenant()?;
+		self.check_filtered()?;
+		guard::single_statement(self.table, self.max_affected_rows)?;
+		if self.cascade_statements()?.is_empty() {
+			return Ok(());
+		}
+		Err(guard::needs_transaction(
+			self.table,
+			"it cascades to related tables",
+		))
+	}
+
+	#[cfg(any(test, feature = "test-utils"))]
+	pub fn cascade_sql(&self) -> Vec<String> {
+		use sqlx::Execute;
+		self.cascade_statements()
+			.expect("cascade statements")
+			.into_iter()
+			.map(|statement| match statement {
+				CascadeStatement::Restrict { mut query, .. } |
+				CascadeStatement::Apply(mut query) => query.build().sql().to_string(),
+			})
+			.collect()
 	}
 
 	#[cfg(any(test, feature = "test-utils"))]
 	pub fn sql(&self) -> String {
 		use sqlx::Execute;
-		self.to_query_builder().build().sql().to_string()
+		self.to_execute_builder().build().sql().to_string()
+	}
+}
+
+impl<'a, C, Row> DeleteQueryPlan<'a, C, Row>
+where
+	C: QueryContext,
+	C::Model: crate::Deletable,
+	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+{
+	/// Deletes the rows, first applying the `cascade_type(...)` of the
+	/// relations inside a transaction when there are any. With a
+	/// `max_affected_rows` the transaction is rolled back when more rows are
+	/// deleted.
+	pub async fn execute_atomic<'c, A>(&self, conn: A) -> Result<u64, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		let statements = self.cascade_statements()?;
+		if statements.is_empty() && self.max_affected_rows.is_none() {
+			let mut conn = conn.acquire().await?;
+			return ExecutablePlan::execute(self, &mut *conn).await;
+		}
+
+		self.check_tenant()?;
+		self.check_filtered()?;
+		let mut tx = conn.begin().await?;
+		self.run_cascades(statements, &mut tx).await?;
+		let rows = self
+			.to_execute_builder()
+			.build()
+			.execute(&mut *tx)
+			.await?
+			.rows_affected();
+		self.check_affected(rows)?;
+		tx.commit().await?;
+
+		Ok(rows)
+	}
+
+	/// See [`execute_atomic`](Self::execute_atomic).
+	pub async fn fetch_one_atomic<'c, A>(&self, conn: A) -> Result<Row, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		let statements = self.cascade_statements()?;
+		if statements.is_empty() && self.max_affected_rows.is_none() {
+			let mut conn = conn.acquire().await?;
+			return FetchablePlan::fetch_one(self, &mut *conn).await;
+		}
+
+		self.check_tenant()?;
+		self.check_filtered()?;
+		let mut tx = conn.begin().await?;
+		self.run_cascades(statements, &mut tx).await?;
+		let mut qb = self.to_query_builder();
+		self.push_returning(&mut qb);
+		let rows = qb.build_query_as::<Row>().fetch_all(&mut *tx).await?;
+		self.check_affected(rows.len() as u64)?;
+		tx.commit().await?;
+
+		rows.into_iter()
+			.next()
+			.ok_or(Error::Sqlx(sqlx::Error::RowNotFound))
+	}
+
+	/// See [`execute_atomic`](Self::execute_atomic).
+	pub async fn fetch_all_atomic<'c, A>(
+		&self,
+		conn: A,
+	) -> Result<Vec<Row>, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		let statements = self.cascade_statements()?;
+		if statements.is_empty() && self.max_affected_rows.is_none() {
+			let mut conn = conn.acquire().await?;
+			return FetchablePlan::fetch_all(self, &mut *conn).await;
+		}
+
+		self.check_tenant()?;
+		self.check_filtered()?;
+		let mut tx = conn.begin().await?;
+		self.run_cascades(statements, &mut tx).await?;
+		let mut qb = self.to_query_builder();
+		self.push_returning(&mut qb);
+		let rows = qb.build_query_as::<Row>().fetch_all(&mut *tx).await?;
+		self.check_affected(rows.len() as u64)?;
+		tx.commit().await?;
+
+		Ok(rows)
+	}
+
+	/// See [`execute_atomic`](Self::execute_atomic).
+	pub async fn fetch_optional_atomic<'c, A>(
+		&self,
+		conn: A,
+	) -> Result<Option<Row>, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		let statements = self.cascade_statements()?;
+		if statements.is_empty() && self.max_affected_rows.is_none() {
+			let mut conn = conn.acquire().await?;
+			return FetchablePlan::fetch_optional(self, &mut *conn).await;
+		}
+
+		self.check_tenant()?;
+		self.check_filtered()?;
+		let mut tx = conn.begin().await?;
+		self.run_cascades(statements, &mut tx).await?;
+		let mut qb = self.to_query_builder();
+		self.push_returning(&mut qb);
+		let rows = qb.build_query_as::<Row>().fetch_all(&mut *tx).await?;
+		self.check_affected(rows.len() as u64)?;
+		tx.commit().await?;
+
+		Ok(rows.into_iter().next())
 	}
 }
 
@@ -81,12 +646,13 @@ where
 	C::Model: crate::Deletable,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
 {
-	async fn execute<'e, E>(&self, exec: E) -> Result<u64, sqlx::Error>
+	async fn execute<'e, E>(&self, exec: E) -> Result<u64, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.single_statement()?;
 		let rows = self
-			.to_query_builder()
+			.to_execute_builder()
 			.build()
 			.execute(exec)
 			.await?
@@ -103,34 +669,34 @@ where
 	C::Model: crate::Deletable,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
 {
-	async fn fetch_one<'e, E>(&self, exec: E) -> Result<Row, sqlx::Error>
+	async fn fetch_one<'e, E>(&self, exec: E) -> Result<Row, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.single_statement()?;
 		let mut qb = self.to_query_builder();
 		self.push_returning(&mut qb);
-		qb.build_query_as::<Row>().fetch_one(exec).await
+		Ok(qb.build_query_as::<Row>().fetch_one(exec).await?)
 	}
 
-	async fn fetch_all<'e, E>(&self, exec: E) -> Result<Vec<Row>, sqlx::Error>
+	async fn fetch_all<'e, E>(&self, exec: E) -> Result<Vec<Row>, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.single_statement()?;
 		let mut qb = self.to_query_builder();
 		self.push_returning(&mut qb);
-		qb.build_query_as::<Row>().fetch_all(exec).await
+		Ok(qb.build_query_as::<Row>().fetch_all(exec).await?)
 	}
 
-	async fn fetch_optional<'e, E>(
-		&self,
-		exec: E,
-	) -> Result<Option<Row>, sqlx::Error>
+	async fn fetch_optional<'e, E>(&self, exec: E) -> Result<Option<Row>, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.single_statement()?;
 		let mut qb = self.to_query_builder();
 		self.push_returning(&mut qb);
-		qb.build_query_as::<Row>().fetch_optional(exec).await
+		Ok(qb.build_query_as::<Row>().fetch_optional(exec).await?)
 	}
 }
 
@@ -142,17 +708,49 @@ where
 {
 }
 
+/// State of a [`DeleteQueryBuilder`] made by `delete()` or `restore()`.
+pub struct Deleting;
+
+/// State of a [`DeleteQueryBuilder`] made by `purge()`, the only one taking
+/// [`deleted_before`](DeleteQueryBuilder::deleted_before) and
+/// [`older_than`](DeleteQueryBuilder::older_than).
+pub struct Purging;
+
+/// The state a [`DeleteQueryBuilder`] is in, [`Deleting`] or [`Purging`].
+pub trait DeleteState: Send + Sync + 'static {
+	#[doc(hidden)]
+	const PURGE: bool;
+}
+
+impl DeleteState for Deleting {
+	const PURGE: bool = false;
+}
+
+impl DeleteState for Purging {
+	const PURGE: bool = true;
+}
+
 pub struct DeleteQueryBuilder<
 	'a,
 	C: QueryContext,
 	Row = <C as QueryContext>::Model,
+	S = Deleting,
 > {
 	pub(crate) table: &'a str,
+	pub(crate) joins: Option<Vec<JoinPath>>,
+	pub(crate) join_filters: Vec<JoinFilter>,
 	pub(crate) where_expr: Option<Expression<C::Query>>,
 	pub(crate) is_soft: bool,
 	pub(crate) delete_marker_field: Option<&'a str>,
+	pub(crate) mode: DeleteMode,
+	pub(crate) purge_threshold: Option<PurgeThreshold>,
+	pub(crate) cascades: &'static [DeleteCascade],
+	pub(crate) all_rows: bool,
+	pub(crate) max_affected_rows: Option<u64>,
+	pub(crate) all_tenants: bool,
 	pub(crate) selection: Option<SelectionList<Row, select::SelectionColumn>>,
 	row: PhantomData<Row>,
+	state: PhantomData<S>,
 }
 
 impl<'a, C, Row> DeleteQueryBuilder<'a, C, Row>
@@ -162,31 +760,66 @@ where
 	pub fn new_soft(table: &'a str, delete_marker_field: &'a str) -> Self {
 		Self {
 			table,
+			joins: None,
+			join_filters: Vec::new(),
 			where_expr: None,
 			is_soft: true,
 			delete_marker_field: Some(delete_marker_field),
+			mode: DeleteMode::Delete,
+			purge_threshold: None,
+			cascades: &[],
+			all_rows: false,
+			max_affected_rows: None,
+			all_tenants: false,
 			selection: None,
 			row: PhantomData,
+			state: PhantomData,
 		}
 	}
 
 	pub fn new_hard(table: &'a str) -> Self {
 		Self {
 			table,
+			joins: None,
+			join_filters: Vec::new(),
 			where_expr: None,
 			is_soft: false,
 			delete_marker_field: None,
+			mode: DeleteMode::Delete,
+			purge_threshold: None,
+			cascades: &[],
+			all_rows: false,
+			max_affected_rows: None,
+			all_tenants: false,
 			selection: None,
 			row: PhantomData,
+			state: PhantomData,
 		}
 	}
 }
 
-impl<'a, C, Row> Buildable<C> for DeleteQueryBuilder<'a, C, Row>
+impl<'a, C, Row, S> DeleteQueryBuilder<'a, C, Row, S>
+where
+	C: QueryContext,
+{
+	/// Soft deletes keep the row and restores bring it back, so both record
+	/// the row after the change.
+	fn audit_trail(&self) -> Option<AuditTrail> {
+		let (action, keeps_row) = match self.mode {
+			DeleteMode::Delete => (AuditAction::Delete, self.is_soft),
+			DeleteMode::Restore => (AuditAction::Update, true),
+			DeleteMode::Purge => (AuditAction::Delete, false),
+		};
+		AuditTrail::resolve::<C::Model>(action, keeps_row)
+	}
+}
+
+impl<'a, C, Row, S> Buildable<C> for DeleteQueryBuilder<'a, C, Row, S>
 where
 	C: QueryContext,
 	C::Model: crate::Deletable,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+	S: DeleteState,
 {
 	type Row = Row;
 	type Plan = DeleteQueryPlan<'a, C, Row>;
@@ -194,28 +827,53 @@ where
 	fn from_ctx() -> Self {
 		Self {
 			table:               C::TABLE,
+			joins:               None,
+			join_filters:        Vec::new(),
 			where_expr:          None,
 			is_soft:             <C::Model as crate::Deletable>::IS_SOFT_DELETE,
 			delete_marker_field:
 				<C::Model as crate::Deletable>::DELETE_MARKER_FIELD,
+			mode:                if S::PURGE {
+				DeleteMode::Purge
+			} else {
+				DeleteMode::Delete
+			},
+			purge_threshold:     None,
+			cascades:
+				<C::Model as crate::Deletable>::DELETE_CASCADES,
+			all_rows:            false,
+			max_affected_rows:   None,
+			all_tenants:         false,
 			selection:           None,
 			row:                 PhantomData,
+			state:               PhantomData,
 		}
 	}
 
 	fn build(self) -> Self::Plan {
+		let audit = self.audit_trail();
 		DeleteQueryPlan {
-			where_expr:          self.where_expr,
-			table:               self.table,
-			is_soft:             self.is_soft,
+			joins: self.joins,
+			join_filters: self.join_filters,
+			where_expr: self.where_expr,
+			table: self.table,
+			is_soft: self.is_soft,
 			delete_marker_field: self.delete_marker_field,
-			selection:           self.selection,
-			row:                 PhantomData,
+			mode: self.mode,
+			purge_threshold: self.purge_threshold,
+			cascades: self.cascades,
+			all_rows: self.all_rows,
+			max_affected_rows: self.max_affected_rows,
+			tenant: TenantScope::resolve(self.all_tenants),
+			audit,
+			actor: audit::current(),
+			selection: self.selection,
+			row: PhantomData,
 		}
 	}
 }
 
-impl<'a, C, Row> DeleteQueryBuilder<'a, C, Row>
+impl<'a, C, Row, S> DeleteQueryBuilder<'a, C, Row, S>
 where
 	C: QueryContext,
 	C::Model: crate::Deletable,
@@ -224,7 +882,7 @@ where
 	pub fn take<NewRow>(
 		self,
 		selection: SelectionList<NewRow, select::SelectionEntry>,
-	) -> DeleteQueryBuilder<'a, C, NewRow>
+	) -> DeleteQueryBuilder<'a, C, NewRow, S>
 	where
 		NewRow: Send
 			+ Sync
@@ -233,16 +891,88 @@ where
 	{
 		DeleteQueryBuilder {
 			table:               self.table,
+			joins:               self.joins,
+			join_filters:        self.join_filters,
 			where_expr:          self.where_expr,
 			is_soft:             self.is_soft,
 			delete_marker_field: self.delete_marker_field,
+			mode:                self.mode,
+			purge_threshold:     self.purge_threshold,
+			cascades:            self.cascades,
+			all_rows:            self.all_rows,
+			max_affected_rows:   self.max_affected_rows,
+			all_tenants:         self.all_tenants,
 			selection:           Some(selection.expect_columns()),
 			row:                 PhantomData,
+			state:               PhantomData,
+		}
+	}
+}
+
+impl<'a, C, Row, S> DeleteQueryBuilder<'a, C, Row, S>
+where
+	C: QueryContext,
+	C::Model: crate::Deletable,
+	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+	S: DeleteState,
+{
+	pub(crate) fn trash(mode: DeleteMode) -> Self
+	where
+		C::Model: crate::SoftDeletable,
+	{
+		Self {
+			mode,
+			..Self::from_ctx()
 		}
 	}
+
+	/// Allows the delete to run without a filter, affecting every row.
+	pub fn all_rows(mut self) -> Self {
+		self.all_rows = true;
+		self
+	}
+
+	/// Deletes the rows of every tenant, e.g. for admin queries.
+	pub fn all_tenants(mut self) -> Self {
+		self.all_tenants = true;
+		self
+	}
+
+	/// Fails and rolls back the delete when it would affect more than `max`
+	/// rows. Rows changed by cascades do not count.
+	pub fn max_affected_rows(mut self, max: u64) -> Self {
+		self.max_affected_rows = Some(max);
+		self
+	}
+
+	/// Only deletes rows whose row at the end of `path` matches `e`. The path
+	/// is joined unless it already is.
+	pub fn where_join<F>(self, path: JoinPath, e: Expression<F>) -> Self
+	where
+		F: Filterable + Send + Sync + 'static,
+	{
+		self.join_filter(JoinFilter::new(path, e))
+	}
+}
+
+impl<'a, C, Row> DeleteQueryBuilder<'a, C, Row, Purging>
+where
+	C: QueryContext,
+{
+	/// Purges only rows soft-deleted before `before`.
+	pub fn deleted_before(mut self, before: DateTime<Utc>) -> Self {
+		self.purge_threshold = Some(PurgeThreshold::Before(before));
+		self
+	}
+
+	/// Purges only rows soft-deleted longer than `age` ago.
+	pub fn older_than(mut self, age: Duration) -> Self {
+		self.purge_threshold = Some(PurgeThreshold::OlderThan(age));
+		self
+	}
 }
 
-impl<'a, C, Row> BuildableFilter<C> for DeleteQueryBuilder<'a, C, Row>
+impl<'a, C, Row, S> BuildableFilter<C> for DeleteQueryBuilder<'a, C, Row, S>
 where
 	C: QueryContext,
 	C::Model: crate::Deletable,
@@ -257,10 +987,54 @@ where
 	}
 }
 
-impl<'a, C, Row> BuildableDeleteQuery<C, Row> for DeleteQueryBuilder<'a, C, Row>
+impl<'a, C, Row, S> BuildableJoin<C> for DeleteQueryBuilder<'a, C, Row, S>
+where
+	C: QueryContext,
+	C::Model: crate::Deletable,
+{
+	fn join(self, join: <C as QueryContext>::Join, kind: JoinKind) -> Self {
+		self.join_path(JoinPath::from_join(join, kind))
+	}
+
+	fn join_path(mut self, path: JoinPath) -> Self {
+		assert_join_start(&path, self.table);
+		match &mut self.joins {
+			Some(existing) => existing.push(path),
+			None => self.joins = Some(vec![path]),
+		};
+
+		self
+	}
+}
+
+impl<'a, C, Row, S> BuildableJoinFilter<C> for DeleteQueryBuilder<'a, C, Row, S>
+where
+	C: QueryContext,
+	C::Model: crate::Deletable,
+{
+	fn join_filter(mut self, filter: JoinFilter) -> Self {
+		if !is_joined(self.joins.as_deref(), filter.path()) {
+			self = self.join_path(filter.path().clone());
+		}
+		self.join_filters.push(filter);
+		self
+	}
+}
+
+impl<'a, C, Row, S> BuildableDeleteQuery<C, Row>
+	for DeleteQueryBuilder<'a, C, Row, S>
 where
 	C: QueryContext,
 	C::Model: crate::Deletable,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+	S: DeleteState,
 {
 }
+
+fn qualified_columns(table: &str, columns: &[&str]) -> String {
+	columns
+		.iter()
+		.map(|column| format!(r#""{}"."{}""#, table, column))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
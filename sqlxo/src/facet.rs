@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::select::{
+	Column,
+	SelectionColumn,
+};
+
+/// Name of the CTE holding the filtered rows facets are counted over.
+pub(crate) const FACET_CTE: &str = "__sqlxo_facet_base";
+/// Column carrying the facet object when facets ride along a page read.
+pub(crate) const FACET_COLUMN: &str = "__sqlxo_facets";
+pub(crate) const FACET_KEY_COLUMN: &str = "__sqlxo_key";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FacetKind {
+	/// One count per distinct value.
+	Values,
+	/// Counts per range between ascending bounds. Values below the first
+	/// and above the last bound land in open-ended buckets.
+	Buckets(&'static [f64]),
+}
+
+/// A column whose values are counted over the rows a read matches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Facet {
+	pub name:   &'static str,
+	pub column: SelectionColumn,
+	pub kind:   FacetKind,
+}
+
+impl Facet {
+	pub const fn new(
+		name: &'static str,
+		column: SelectionColumn,
+		kind: FacetKind,
+	) -> Self {
+		Self { name, column, kind }
+	}
+
+	pub fn values<C: Column>(name: &'static str, _: C) -> Self {
+		Self::new(
+			name,
+			SelectionColumn::new(C::TABLE, C::NAME),
+			FacetKind::Values,
+		)
+	}
+
+	pub fn buckets<C: Column>(
+		name: &'static str,
+		_: C,
+		bounds: &'static [f64],
+	) -> Self {
+		assert!(
+			!bounds.is_empty() && bounds.windows(2).all(|w| w[0] < w[1]),
+			"facet `{name}` requires strictly ascending bucket bounds",
+		);
+		Self::new(
+			name,
+			SelectionColumn::new(C::TABLE, C::NAME),
+			FacetKind::Buckets(bounds),
+		)
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FacetValueCount {
+	pub value: Value,
+	pub count: i64,
+}
+
+/// `from` is inclusive, `to` exclusive; a missing bound is unbounded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FacetBucketCount {
+	pub from:  Option<f64>,
+	pub to:    Option<f64>,
+	pub count: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum FacetCounts {
+	Values(Vec<FacetValueCount>),
+	Buckets(Vec<FacetBucketCount>),
+}
+
+/// Facet counts keyed by facet name.
+#[derive(
+	Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(transparent)]
+pub struct Facets(pub BTreeMap<String, FacetCounts>);
+
+impl Facets {
+	pub fn get(&self, name: &str) -> Option<&FacetCounts> {
+		self.0.get(name)
+	}
+
+	pub fn values(&self, name: &str) -> Option<&[FacetValueCount]> {
+		match self.get(name)? {
+			FacetCounts::Values(values) => Some(values),
+			FacetCounts::Buckets(_) => None,
+		}
+	}
+
+	pub fn buckets(&self, name: &str) -> Option<&[FacetBucketCount]> {
+		match self.get(name)? {
+			FacetCounts::Buckets(buckets) => Some(buckets),
+			FacetCounts::Values(_) => None,
+		}
+	}
+
+	/// Maps the JSON object the facet query produced. Every bucket range is
+	/// reported, including the empty ones the query leaves out.
+	pub(crate) fn decode(
+		facets: &[Facet],
+		raw: Option<Value>,
+	) -> Result<Self, sqlx::Error> {
+		#[derive(Deserialize)]
+		struct RawBucket {
+			bucket: usize,
+			count:  i64,
+		}
+
+		let mut raw = match raw {
+			Some(Value::Object(map)) => map,
+			_ => Default::default(),
+		};
+		let decode_err = |err: serde_json::Error| sqlx::Error::ColumnDecode {
+			index:  FACET_COLUMN.to_string(),
+			source: Box::new(err),
+		};
+
+		let mut out = BTreeMap::new();
+		for facet in facets {
+			let value = raw.remove(facet.name).unwrap_or(Value::Array(vec![]));
+			let counts = match facet.kind {
+				FacetKind::Values => FacetCounts::Values(
+					serde_json::from_value(value).map_err(decode_err)?,
+				),
+				FacetKind::Buckets(bounds) => {
+					let raw: Vec<RawBucket> =
+						serde_json::from_value(value).map_err(decode_err)?;
+					let buckets = (0..=bounds.len())
+						.map(|idx| FacetBucketCount {
+							from:  idx.checked_sub(1).map(|prev| bounds[prev]),
+							to:    bounds.get(idx).copied(),
+							count: raw
+								.iter()
+								.filter(|b| b.bucket == idx)
+								.map(|b| b.count)
+								.sum(),
+						})
+						.collect();
+					FacetCounts::Buckets(buckets)
+				}
+			};
+			out.insert(facet.name.to_string(), counts);
+		}
+
+		Ok(Self(out))
+	}
+}
@@ -0,0 +1,57 @@
+use crate::{
+	Error,
+	NeedsTransaction,
+	TooManyRowsAffected,
+	UnfilteredMutation,
+};
+
+pub(crate) fn check_filtered(table: &str, filtered: bool) -> Result<(), Error> {
+	if filtered {
+		return Ok(());
+	}
+	Err(UnfilteredMutation {
+		table: table.to_string(),
+	}
+	.into())
+}
+
+pub(crate) fn check_affected(
+	table: &str,
+	max: u64,
+	affected: u64,
+) -> Result<(), Error> {
+	if affected <= max {
+		return Ok(());
+	}
+	Err(TooManyRowsAffected {
+		table: table.to_string(),
+		max,
+		affected,
+	}
+	.into())
+}
+
+pub(crate) fn needs_transaction(
+	table: &str,
+	reason: impl Into<String>,
+) -> Error {
+	NeedsTransaction {
+		table:  table.to_string(),
+		reason: reason.into(),
+	}
+	.into()
+}
+
+/// Fails when a row limit is set, which needs a transaction to roll back.
+pub(crate) fn single_statement(
+	table: &str,
+	max_affected_rows: Option<u64>,
+) -> Result<(), Error> {
+	if max_affected_rows.is_none() {
+		return Ok(());
+	}
+	Err(needs_transaction(
+		table,
+		"it is capped by `max_affected_rows`",
+	))
+}
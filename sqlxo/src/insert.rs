@@ -1,30 +1,242 @@
-use std::marker::PhantomData;
+use std::{
+	marker::PhantomData,
+	sync::Arc,
+};
 
+use smallvec::SmallVec;
 use sqlx::{
+	Acquire,
 	Executor,
 	Postgres,
 };
 use sqlxo_traits::{
 	Creatable,
 	CreateModel,
+	Error,
+	Filterable,
+	GetUpdateFields,
+	PrimaryKey,
 	QueryContext,
+	SqlWrite,
+	TenantScoped,
+	UnsupportedInsert,
+	Updatable,
+	UpdateModel,
 };
 
 use crate::{
+	and,
+	audit::{
+		AuditAction,
+		AuditTrail,
+	},
 	blocks::{
+		AuditedHead,
+		Expression,
 		InsertHead,
 		SqlWriter,
 	},
+	guard,
 	select::{
 		self,
+		ColumnList,
 		SelectionList,
 	},
+	tenant::TenantScope,
 	Buildable,
 	ExecutablePlan,
 	FetchablePlan,
 	Planable,
 };
 
+/// Postgres accepts at most this many binds per statement.
+const MAX_BINDS: usize = u16::MAX as usize;
+
+/// The unique index an `ON CONFLICT` clause arbitrates on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ConflictTarget {
+	Columns(SmallVec<[&'static str; 4]>),
+	Constraint(&'static str),
+}
+
+type UpdateWriter = Arc<
+	dyn Fn(
+			&mut sqlx::QueryBuilder<'static, Postgres>,
+			bool,
+		) -> Vec<&'static str>
+		+ Send
+		+ Sync,
+>;
+
+#[derive(Clone)]
+pub(crate) enum ConflictAction {
+	Nothing,
+	Update { apply: UpdateWriter, binds: usize },
+	Excluded(SmallVec<[&'static str; 4]>),
+}
+
+/// Counts the binds a filter writes without rendering it.
+struct BindCounter(usize);
+
+impl SqlWrite for BindCounter {
+	fn push(&mut self, _: &str) {}
+
+	fn bind<T>(&mut self, _: T)
+	where
+		T: sqlx::Encode<'static, Postgres> + Send + 'static,
+		T: sqlx::Type<Postgres>,
+	{
+		self.0 += 1;
+	}
+}
+
+pub(crate) struct OnConflict<Q: Filterable> {
+	target:     Option<ConflictTarget>,
+	action:     Option<ConflictAction>,
+	where_expr: Option<Expression<Q>>,
+}
+
+impl<Q: Filterable> Default for OnConflict<Q> {
+	fn default() -> Self {
+		Self {
+			target:     None,
+			action:     None,
+			where_expr: None,
+		}
+	}
+}
+
+impl<Q: Filterable> OnConflict<Q> {
+	fn is_set(&self) -> bool {
+		self.target.is_some() ||
+			self.action.is_some() ||
+			self.where_expr.is_some()
+	}
+
+	/// Fails when the clause can't be written: it needs an action,
+	/// `DO NOTHING` takes no `WHERE` and `DO UPDATE` needs a target.
+	fn validate(&self, has_primary_key: bool) -> Result<(), &'static str> {
+		if !self.is_set() {
+			return Ok(());
+		}
+		match &self.action {
+			None => {
+				Err("on_conflict requires .do_nothing() or .do_update(...)")
+			}
+			Some(ConflictAction::Nothing) if self.where_expr.is_some() => {
+				Err("conflict_where only applies to .do_update(...)")
+			}
+			Some(
+				ConflictAction::Update { .. } | ConflictAction::Excluded(_),
+			) if self.target.is_none() && !has_primary_key => {
+				Err("do_update requires .on_conflict(...) on a table without \
+				     primary key")
+			}
+			Some(_) => Ok(()),
+		}
+	}
+
+	/// Whether `DO UPDATE` would leave `SET` empty.
+	fn sets_nothing<M: GetUpdateFields>(&self) -> bool {
+		if M::update_marker_field().is_some() || M::version_field().is_some() {
+			return false;
+		}
+		match &self.action {
+			Some(ConflictAction::Update { binds, .. }) => *binds == 0,
+			Some(ConflictAction::Excluded(columns)) => columns.is_empty(),
+			_ => false,
+		}
+	}
+
+	/// Binds the clause adds to every statement.
+	fn bind_count<M: TenantScoped>(&self, tenant: bool) -> usize {
+		let update_binds = match &self.action {
+			Some(ConflictAction::Update { binds, .. }) => *binds,
+			Some(ConflictAction::Excluded(_)) => 0,
+			_ => return 0,
+		};
+		let tenant_binds = usize::from(tenant && M::TENANT_FIELD.is_some());
+		let mut counter = BindCounter(0);
+		if let Some(expr) = &self.where_expr {
+			expr.write(&mut counter);
+		}
+		update_binds + tenant_binds + counter.0
+	}
+
+	fn write<M: GetUpdateFields + TenantScoped>(
+		&self,
+		w: &mut SqlWriter,
+		table: &str,
+	) {
+		let Some(action) = &self.action else {
+			return;
+		};
+
+		w.push(" ON CONFLICT");
+		match &self.target {
+			Some(ConflictTarget::Columns(columns)) => {
+				w.push(" (");
+				w.push(&columns.join(", "));
+				w.push(")");
+			}
+			Some(ConflictTarget::Constraint(name)) => {
+				w.push(" ON CONSTRAINT ");
+				w.push(name);
+			}
+			None => {}
+		}
+
+		if matches!(action, ConflictAction::Nothing) {
+			w.push(" DO NOTHING");
+			return;
+		}
+
+		w.push(" DO UPDATE SET ");
+		let mut has_previous = false;
+		if let Some(marker) = M::update_marker_field() {
+			w.push(marker);
+			w.push(" = NOW()");
+			has_previous = true;
+		}
+		// Unqualified columns are ambiguous next to `EXCLUDED`.
+		if let Some(version) = M::version_field() {
+			if has_previous {
+				w.push(", ");
+			}
+			w.push(&format!(r#"{version} = "{table}"."{version}" + 1"#));
+			has_previous = true;
+		}
+		match action {
+			ConflictAction::Update { apply, .. } => {
+				apply(w.query_builder_mut(), has_previous);
+			}
+			ConflictAction::Excluded(columns) => {
+				for (idx, column) in columns.iter().enumerate() {
+					if idx > 0 || has_previous {
+						w.push(", ");
+					}
+					w.push(&format!("{column} = EXCLUDED.{column}"));
+				}
+			}
+			ConflictAction::Nothing => unreachable!(),
+		}
+
+		// The conflicting row may belong to another tenant.
+		let mut keyword = " WHERE ";
+		if let (Some(field), Some(tenant)) =
+			(M::TENANT_FIELD, w.tenant().cloned())
+		{
+			w.push(keyword);
+			w.push_tenant_condition(&tenant, table, field);
+			keyword = " AND ";
+		}
+		if let Some(expr) = &self.where_expr {
+			w.push(keyword);
+			expr.write(w);
+		}
+	}
+}
+
 #[allow(dead_code)]
 pub trait BuildableInsertQuery<C, Row = <C as QueryContext>::Model>:
 	Buildable<C, Row = Row, Plan: Planable<C, Row>>
@@ -42,9 +254,13 @@ pub struct InsertQueryPlan<
 	C::Model: Creatable,
 {
 	pub(crate) table: &'a str,
-	pub(crate) create_model: <C::Model as Creatable>::CreateModel,
+	pub(crate) create_models: Vec<<C::Model as Creatable>::CreateModel>,
 	pub(crate) insert_marker_field: Option<&'static str>,
+	pub(crate) conflict: Option<OnConflict<C::Query>>,
 	pub(crate) selection: Option<SelectionList<Row, select::SelectionColumn>>,
+	pub(crate) tenant: TenantScope,
+	pub(crate) audit: Option<AuditTrail>,
+	pub(crate) unsupported: Option<UnsupportedInsert>,
 	row: PhantomData<Row>,
 }
 
@@ -53,24 +269,270 @@ where
 	C: QueryContext,
 	C::Model: Creatable,
 {
-	fn to_query_builder(&self) -> sqlx::QueryBuilder<'static, Postgres> {
-		let head = InsertHead::new(self.table);
-		let mut w = SqlWriter::new(head);
+	/// Splits the rows into statements that stay below the bind limit.
+	fn chunks(
+		&self,
+	) -> std::slice::Chunks<'_, <C::Model as Creatable>::CreateModel> {
+		let binds =
+			<<C::Model as Creatable>::CreateModel as CreateModel>::BIND_COUNT;
+		// Audited statements also bind the table and the actor.
+		let audit_binds = if self.audit.is_some() { 2 } else { 0 };
+		let available = MAX_BINDS
+			.saturating_sub(self.conflict_bind_count())
+			.saturating_sub(audit_binds);
+		self.create_models.chunks((available / binds.max(1)).max(1))
+	}
 
-		self.create_model
-			.apply_inserts(w.query_builder_mut(), self.insert_marker_field);
+	/// Binds the `ON CONFLICT` clause adds to every statement.
+	fn conflict_bind_count(&self) -> usize {
+		self.conflict.as_ref().map_or(0, |conflict| {
+			conflict.bind_count::<C::Model>(self.tenant.tenant().is_some())
+		})
+	}
+
+	fn chunk_query_builder(
+		&self,
+		models: &[<C::Model as Creatable>::CreateModel],
+	) -> sqlx::QueryBuilder<'static, Postgres> {
+		let head =
+			AuditedHead::new(InsertHead::new(self.table), self.audit.is_some());
+		let mut w = SqlWriter::new(head).with_tenant(self.tenant.tenant());
+		let qb = w.query_builder_mut();
+
+		<<C::Model as Creatable>::CreateModel as CreateModel>::push_columns(
+			qb,
+			self.insert_marker_field,
+		);
+		qb.push(" VALUES ");
+		for (idx, model) in models.iter().enumerate() {
+			if idx > 0 {
+				qb.push(", ");
+			}
+			model.push_values(
+				qb,
+				self.insert_marker_field,
+				self.tenant.tenant(),
+			);
+		}
+
+		if let Some(conflict) = &self.conflict {
+			conflict.write::<C::Model>(&mut w, self.table);
+		}
 
 		w.into_builder()
 	}
 
+	/// The generic `Executor` plan methods run a single statement, so
+	/// inserts spanning several chunks fail with
+	/// [`NeedsTransaction`](crate::NeedsTransaction). `None` when there are
+	/// no rows to insert.
+	fn to_query_builder(
+		&self,
+	) -> Result<Option<sqlx::QueryBuilder<'static, Postgres>>, Error> {
+		self.check()?;
+		self.check_flat()?;
+		let mut chunks = self.chunks();
+		match (chunks.next(), chunks.next()) {
+			(None, _) => Ok(None),
+			(Some(models), None) => Ok(Some(self.chunk_query_builder(models))),
+			(Some(_), Some(_)) => Err(guard::needs_transaction(
+				self.table,
+				format!(
+					"its {} rows need several statements",
+					self.create_models.len()
+				),
+			)),
+		}
+	}
+
+	/// Fails with the [`UnsupportedInsert`] found when the plan was built or
+	/// when there is no tenant to fill in.
+	fn check(&self) -> Result<(), Error> {
+		if let Some(unsupported) = &self.unsupported {
+			return Err(unsupported.clone().into());
+		}
+		self.tenant.check(self.table, C::Model::TENANT_FIELD)
+	}
+
+	fn unsupported(&self, reason: &str) -> Error {
+		UnsupportedInsert {
+			table:  self.table.to_string(),
+			reason: reason.to_string(),
+		}
+		.into()
+	}
+
+	fn has_children(&self) -> bool {
+		self.create_models.iter().any(CreateModel::has_children)
+	}
+
+	/// Fails when the rows carry nested children, which only
+	/// [`fetch_graph`](Self::fetch_graph) inserts.
+	fn check_flat(&self) -> Result<(), Error> {
+		if !self.has_children() {
+			return Ok(());
+		}
+		Err(self.unsupported(
+			"its rows carry nested children, which only \
+			 `InsertQueryPlan::fetch_graph` inserts",
+		))
+	}
+
+	/// Inserts the rows together with their `nested_create` children in one
+	/// transaction. Each child gets the key of its parent, overwriting the
+	/// foreign key set on the child, which can be left at any value such as
+	/// `Uuid::nil()`. The returned rows have the children loaded.
+	pub async fn fetch_graph<'c, A>(
+		&self,
+		conn: A,
+	) -> Result<Vec<C::Model>, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		self.check()?;
+		if self.conflict.is_some() && self.has_children() {
+			return Err(self.unsupported(
+				"nested children cannot be inserted with an ON CONFLICT clause",
+			));
+		}
+
+		let mut tx = conn.begin().await?;
+		let mut rows = Vec::with_capacity(self.create_models.len());
+		// `RETURNING` does not promise the order of `VALUES`, so rows with
+		// children get a statement of their own to pair them with their row.
+		let batched =
+			|a: &<C::Model as Creatable>::CreateModel,
+			 b: &<C::Model as Creatable>::CreateModel| {
+				!a.has_children() && !b.has_children()
+			};
+		for models in self.chunks().flat_map(|chunk| chunk.chunk_by(batched)) {
+			let mut qb = self.chunk_query_builder(models);
+			match &self.audit {
+				Some(audit) => {
+					audit.push_returning::<C::Model>(&mut qb, self.table, None)
+				}
+				None => {
+					qb.push(" RETURNING *");
+				}
+			}
+			let mut inserted =
+				qb.build_query_as::<C::Model>().fetch_all(&mut *tx).await?;
+			if let ([model], [row]) = (models, inserted.as_mut_slice()) {
+				model.insert_children(row, &mut tx).await?;
+			}
+			rows.extend(inserted);
+		}
+		tx.commit().await?;
+
+		Ok(rows)
+	}
+
+	/// Inserts every row, running one statement per chunk inside a
+	/// transaction when the rows exceed the bind limit.
+	pub async fn execute_atomic<'c, A>(&self, conn: A) -> Result<u64, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		self.check()?;
+		if self.has_children() {
+			return Ok(self.fetch_graph(conn).await?.len() as u64);
+		}
+		match self.to_query_builder() {
+			Ok(Some(mut qb)) => {
+				let mut conn = conn.acquire().await?;
+				self.push_count(&mut qb);
+				return Ok(qb
+					.build()
+					.execute(&mut *conn)
+					.await?
+					.rows_affected());
+			}
+			Ok(None) => return Ok(0),
+			Err(_) => {}
+		}
+
+		let mut tx = conn.begin().await?;
+		let mut rows = 0;
+		for models in self.chunks() {
+			let mut qb = self.chunk_query_builder(models);
+			self.push_count(&mut qb);
+			rows += qb.build().execute(&mut *tx).await?.rows_affected();
+		}
+		tx.commit().await?;
+
+		Ok(rows)
+	}
+
+	/// Inserts every row and returns them; see
+	/// [`execute_atomic`](Self::execute_atomic). `RETURNING` does not
+	/// promise the order of `VALUES`, so match rows by key, not position.
+	pub async fn fetch_all_atomic<'c, A>(
+		&self,
+		conn: A,
+	) -> Result<Vec<Row>, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+		Row: Send + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+	{
+		self.check()?;
+		self.check_flat()?;
+		match self.to_query_builder() {
+			Ok(Some(mut qb)) => {
+				let mut conn = conn.acquire().await?;
+				self.push_returning(&mut qb);
+				return Ok(qb
+					.build_query_as::<Row>()
+					.fetch_all(&mut *conn)
+					.await?);
+			}
+			Ok(None) => return Ok(Vec::new()),
+			Err(_) => {}
+		}
+
+		let mut tx = conn.begin().await?;
+		let mut rows = Vec::with_capacity(self.create_models.len());
+		for models in self.chunks() {
+			let mut qb = self.chunk_query_builder(models);
+			self.push_returning(&mut qb);
+			rows.extend(qb.build_query_as::<Row>().fetch_all(&mut *tx).await?);
+		}
+		tx.commit().await?;
+
+		Ok(rows)
+	}
+
+	/// Ends a statement without `RETURNING`.
+	fn push_count(&self, qb: &mut sqlx::QueryBuilder<'static, Postgres>) {
+		if let Some(audit) = &self.audit {
+			audit.push_count(qb, self.table);
+		}
+	}
+
 	fn push_returning(&self, qb: &mut sqlx::QueryBuilder<'static, Postgres>) {
+		if let Some(audit) = &self.audit {
+			audit.push_returning(qb, self.table, self.selection.as_ref());
+			return;
+		}
 		select::push_returning(qb, self.table, self.selection.as_ref());
 	}
 
+	/// Renders the first statement.
 	#[cfg(any(test, feature = "test-utils"))]
 	pub fn sql(&self) -> String {
 		use sqlx::Execute;
-		self.to_query_builder().build().sql().to_string()
+		self.chunks()
+			.next()
+			.map(|models| {
+				let mut qb = self.chunk_query_builder(models);
+				self.push_count(&mut qb);
+				qb.build().sql().to_string()
+			})
+			.unwrap_or_default()
+	}
+
+	#[cfg(any(test, feature = "test-utils"))]
+	pub fn statement_count(&self) -> usize {
+		self.chunks().len()
 	}
 }
 
@@ -81,16 +543,15 @@ where
 	C::Model: Creatable,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
 {
-	async fn execute<'e, E>(&self, exec: E) -> Result<u64, sqlx::Error>
+	async fn execute<'e, E>(&self, exec: E) -> Result<u64, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
-		let rows = self
-			.to_query_builder()
-			.build()
-			.execute(exec)
-			.await?
-			.rows_affected();
+		let Some(mut qb) = self.to_query_builder()? else {
+			return Ok(0);
+		};
+		self.push_count(&mut qb);
+		let rows = qb.build().execute(exec).await?.rows_affected();
 
 		Ok(rows)
 	}
@@ -103,34 +564,37 @@ where
 	C::Model: Creatable,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
 {
-	async fn fetch_one<'e, E>(&self, exec: E) -> Result<Row, sqlx::Error>
+	async fn fetch_one<'e, E>(&self, exec: E) -> Result<Row, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
-		let mut qb = self.to_query_builder();
+		let Some(mut qb) = self.to_query_builder()? else {
+			return Err(Error::Sqlx(sqlx::Error::RowNotFound));
+		};
 		self.push_returning(&mut qb);
-		qb.build_query_as::<Row>().fetch_one(exec).await
+		Ok(qb.build_query_as::<Row>().fetch_one(exec).await?)
 	}
 
-	async fn fetch_all<'e, E>(&self, exec: E) -> Result<Vec<Row>, sqlx::Error>
+	async fn fetch_all<'e, E>(&self, exec: E) -> Result<Vec<Row>, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
-		let mut qb = self.to_query_builder();
+		let Some(mut qb) = self.to_query_builder()? else {
+			return Ok(Vec::new());
+		};
 		self.push_returning(&mut qb);
-		qb.build_query_as::<Row>().fetch_all(exec).await
+		Ok(qb.build_query_as::<Row>().fetch_all(exec).await?)
 	}
 
-	async fn fetch_optional<'e, E>(
-		&self,
-		exec: E,
-	) -> Result<Option<Row>, sqlx::Error>
+	async fn fetch_optional<'e, E>(&self, exec: E) -> Result<Option<Row>, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
-		let mut qb = self.to_query_builder();
+		let Some(mut qb) = self.to_query_builder()? else {
+			return Ok(None);
+		};
 		self.push_returning(&mut qb);
-		qb.build_query_as::<Row>().fetch_optional(exec).await
+		Ok(qb.build_query_as::<Row>().fetch_optional(exec).await?)
 	}
 }
 
@@ -150,8 +614,9 @@ pub struct InsertQueryBuilder<
 	C::Model: Creatable,
 {
 	pub(crate) table: &'a str,
-	pub(crate) create_model: Option<<C::Model as Creatable>::CreateModel>,
+	pub(crate) create_models: Vec<<C::Model as Creatable>::CreateModel>,
 	pub(crate) insert_marker_field: Option<&'static str>,
+	pub(crate) conflict: OnConflict<C::Query>,
 	pub(crate) selection: Option<SelectionList<Row, select::SelectionColumn>>,
 	row: PhantomData<Row>,
 }
@@ -161,11 +626,84 @@ where
 	C: QueryContext,
 	C::Model: Creatable,
 {
+	/// Adds a row to insert.
 	pub fn model(
 		mut self,
 		model: <C::Model as Creatable>::CreateModel,
 	) -> Self {
-		self.create_model = Some(model);
+		self.create_models.push(model);
+		self
+	}
+
+	/// Adds rows to insert in one multi-row `VALUES` statement, split into
+	/// several statements when the binds would exceed what Postgres accepts.
+	/// Inserting no rows runs no statement and returns nothing.
+	pub fn models(
+		mut self,
+		models: impl IntoIterator<Item = <C::Model as Creatable>::CreateModel>,
+	) -> Self {
+		self.create_models.extend(models);
+		self
+	}
+
+	/// Arbitrates conflicts on the unique index over `columns` instead of
+	/// the primary key.
+	pub fn on_conflict(mut self, columns: impl ColumnList) -> Self {
+		let columns = columns.into_columns().iter().map(|c| c.column).collect();
+		self.conflict.target = Some(ConflictTarget::Columns(columns));
+		self
+	}
+
+	pub fn on_conflict_constraint(mut self, constraint: &'static str) -> Self {
+		self.conflict.target = Some(ConflictTarget::Constraint(constraint));
+		self
+	}
+
+	/// Skips rows that conflict; `RETURNING` only yields inserted rows.
+	pub fn do_nothing(mut self) -> Self {
+		self.conflict.action = Some(ConflictAction::Nothing);
+		self
+	}
+
+	/// Overwrites the conflicting row's columns with the values this insert
+	/// proposed for them. Without columns, update marker or version the
+	/// conflicting row is skipped like [`do_nothing`](Self::do_nothing).
+	pub fn do_update_excluded(mut self, columns: impl ColumnList) -> Self {
+		let columns = columns.into_columns().iter().map(|c| c.column).collect();
+		self.conflict.action = Some(ConflictAction::Excluded(columns));
+		self
+	}
+
+	/// Only updates conflicting rows matching `e`; unqualified columns refer
+	/// to the existing row.
+	pub fn conflict_where(mut self, e: Expression<C::Query>) -> Self {
+		self.conflict.where_expr = match self.conflict.where_expr.take() {
+			Some(existing) => Some(and![existing, e]),
+			None => Some(e),
+		};
+		self
+	}
+}
+
+impl<'a, C, Row> InsertQueryBuilder<'a, C, Row>
+where
+	C: QueryContext,
+	C::Model: Creatable + Updatable,
+{
+	/// Applies `model` to the conflicting row. A model without set fields
+	/// on a table without update marker or version skips the conflicting row
+	/// like [`do_nothing`](Self::do_nothing).
+	pub fn do_update(
+		mut self,
+		model: <C::Model as Updatable>::UpdateModel,
+	) -> Self {
+		let binds = model.bind_count();
+		self.conflict.action = Some(ConflictAction::Update {
+			apply: Arc::new(move |qb, has_previous| {
+				model.apply_updates(qb, has_previous)
+			}),
+			binds,
+		});
 		self
 	}
 }
@@ -182,23 +720,46 @@ where
 	fn from_ctx() -> Self {
 		Self {
 			table:               C::TABLE,
-			create_model:        None,
+			create_models:       Vec::new(),
 			insert_marker_field: <C::Model as Creatable>::INSERT_MARKER_FIELD,
+			conflict:            OnConflict::default(),
 			selection:           None,
 			row:                 PhantomData,
 		}
 	}
 
-	fn build(self) -> Self::Plan {
-		let create_model = self
-			.create_model
-			.expect("create model must be set with .model()");
+	fn build(mut self) -> Self::Plan {
+		let primary_key = <C::Model as PrimaryKey>::PRIMARY_KEY;
+		let unsupported =
+			self.conflict.validate(!primary_key.is_empty()).err().map(
+				|reason| UnsupportedInsert {
+					table:  self.table.to_string(),
+					reason: reason.to_string(),
+				},
+			);
+		let conflict = self.conflict.is_set().then(|| {
+			// `DO UPDATE SET` needs at least one assignment.
+			if self.conflict.sets_nothing::<C::Model>() {
+				self.conflict.action = Some(ConflictAction::Nothing);
+				self.conflict.where_expr = None;
+			}
+			if self.conflict.target.is_none() && !primary_key.is_empty() {
+				self.conflict.target = Some(ConflictTarget::Columns(
+					primary_key.iter().copied().collect(),
+				));
+			}
+			self.conflict
+		});
 
 		InsertQueryPlan {
 			table: self.table,
-			create_model,
+			create_models: self.create_models,
 			insert_marker_field: self.insert_marker_field,
+			conflict,
 			selection: self.selection,
+			tenant: TenantScope::resolve(false),
+			audit: AuditTrail::resolve::<C::Model>(AuditAction::Insert, true),
+			unsupported,
 			row: PhantomData,
 		}
 	}
@@ -222,8 +783,9 @@ where
 	{
 		InsertQueryBuilder {
 			table:               self.table,
-			create_model:        self.create_model,
+			create_models:       self.create_models,
 			insert_marker_field: self.insert_marker_field,
+			conflict:            self.conflict,
 			selection:           Some(selection.expect_columns()),
 			row:                 PhantomData,
 		}
@@ -20,18 +20,27 @@ pub mod prelude {
 	};
 }
 
+pub mod audit;
 pub mod blocks;
+pub mod cache;
+pub mod facet;
 pub mod fts;
 pub mod select;
+pub mod tenant;
+pub mod tree;
 pub mod web;
 
 mod delete;
+mod guard;
 mod insert;
 mod read;
 mod update;
 pub use delete::{
 	DeleteQueryBuilder,
 	DeleteQueryPlan,
+	DeleteState,
+	Deleting,
+	Purging,
 };
 pub use insert::{
 	InsertQueryBuilder,
@@ -54,12 +63,15 @@ pub use web::{
 	WebDeleteFilter,
 	WebExpression,
 	WebFilter,
+	WebPurgeFilter,
 	WebQueryError,
 	WebReadFilter,
+	WebRestoreFilter,
 	WebSort,
 	WebUpdateFilter,
 };
 
+use blocks::BuildableFilter;
 use sqlx::{
 	Executor,
 	Postgres,
@@ -84,6 +96,22 @@ where
 		DeleteQueryBuilder::from_ctx()
 	}
 
+	/// Clears the delete marker of soft-deleted rows.
+	pub fn restore() -> DeleteQueryBuilder<'a, C>
+	where
+		C::Model: crate::SoftDeletable,
+	{
+		DeleteQueryBuilder::trash(delete::DeleteMode::Restore)
+	}
+
+	/// Permanently deletes soft-deleted rows.
+	pub fn purge() -> DeleteQueryBuilder<'a, C, C::Model, Purging>
+	where
+		C::Model: crate::SoftDeletable,
+	{
+		DeleteQueryBuilder::trash(delete::DeleteMode::Purge)
+	}
+
 	pub fn update() -> UpdateQueryBuilder<'a, C>
 	where
 		C::Model: crate::Updatable,
@@ -97,29 +125,77 @@ where
 	{
 		InsertQueryBuilder::from_ctx()
 	}
+
+	/// Reads the row with the given primary key.
+	pub fn find(key: PrimaryKeyOf<C>) -> ReadQueryBuilder<'a, C>
+	where
+		C::Model: crate::HasPrimaryKey,
+	{
+		Self::read().r#where(C::primary_key_in(vec![key]).into())
+	}
+
+	/// Reads the rows with the given primary keys, in the order of `keys`
+	/// unless the query is sorted otherwise.
+	pub fn find_many(keys: &[PrimaryKeyOf<C>]) -> ReadQueryBuilder<'a, C>
+	where
+		C::Model: crate::HasPrimaryKey,
+	{
+		let mut read =
+			Self::read().r#where(C::primary_key_in(keys.to_vec()).into());
+		read.key_order = Some(keys.to_vec());
+		read
+	}
+
+	pub async fn exists<'e, E>(
+		key: PrimaryKeyOf<C>,
+		exec: E,
+	) -> Result<bool, Error>
+	where
+		C::Model: crate::HasPrimaryKey,
+		E: Executor<'e, Database = Postgres>,
+	{
+		Self::find(key).build().exists(exec).await
+	}
+
+	pub fn update_by_id(key: PrimaryKeyOf<C>) -> UpdateQueryBuilder<'a, C>
+	where
+		C::Model: crate::Updatable + crate::HasPrimaryKey,
+	{
+		Self::update().r#where(C::primary_key_in(vec![key]).into())
+	}
+
+	pub fn delete_by_id(key: PrimaryKeyOf<C>) -> DeleteQueryBuilder<'a, C>
+	where
+		C::Model: crate::Deletable + crate::HasPrimaryKey,
+	{
+		Self::delete().r#where(C::primary_key_in(vec![key]).into())
+	}
 }
 
+/// The primary key type of the model behind `C`.
+pub type PrimaryKeyOf<C> = <<C as QueryContext>::Model as PrimaryKey>::Key;
+
 #[async_trait::async_trait]
 pub trait ExecutablePlan<C: QueryContext> {
-	async fn execute<'e, E>(&self, exec: E) -> Result<u64, sqlx::Error>
+	async fn execute<'e, E>(&self, exec: E) -> Result<u64, Error>
 	where
 		E: Executor<'e, Database = Postgres>;
 }
 
 #[async_trait::async_trait]
 pub trait FetchablePlan<C: QueryContext, Row> {
-	async fn fetch_one<'e, E>(&self, exec: E) -> Result<Row, sqlx::Error>
+	async fn fetch_one<'e, E>(&self, exec: E) -> Result<Row, Error>
 	where
 		E: Executor<'e, Database = Postgres>;
 
-	async fn fetch_all<'e, E>(&self, exec: E) -> Result<Vec<Row>, sqlx::Error>
+	async fn fetch_all<'e, E>(&self, exec: E) -> Result<Vec<Row>, Error>
 	where
 		E: Executor<'e, Database = Postgres>;
 
 	async fn fetch_optional<'e, E>(
 		&self,
 		exec: E,
-	) -> Result<Option<Row>, sqlx::Error>
+	) -> Result<Option<Row>, Error>
 	where
 		E: Executor<'e, Database = Postgres>;
 }
@@ -2,51 +2,93 @@ use smallvec::SmallVec;
 use std::marker::PhantomData;
 
 use sqlx::{
-	postgres::PgRow,
+	postgres::{
+		PgArguments,
+		PgRow,
+	},
+	Execute as _,
 	Executor,
 	FromRow,
 	Postgres,
+	Row as _,
+	ValueRef,
 };
 use sqlxo_traits::{
 	AliasedColumn,
+	Error,
 	FullTextSearchConfig,
 	FullTextSearchable,
 	GetDeleteMarker,
+	JoinDescriptor,
 	JoinKind,
 	JoinNavigationModel,
 	JoinPath,
 	PrimaryKey,
 	QueryContext,
+	Sortable,
+	SqlJoin,
 	SqlWrite,
+	TenantId,
+	TenantScoped,
+	UnsupportedRead,
 };
 
 use crate::{
 	and,
 	blocks::{
+		bind_tenant,
+		join_condition,
+		path_key,
 		BuildableFilter,
 		BuildableJoin,
 		BuildablePage,
 		BuildableSort,
+		DistinctClause,
 		Expression,
+		FacetHead,
+		GetDefaultScope,
 		Page,
 		Pagination,
 		QualifiedColumn,
 		ReadHead,
+		RecursiveHead,
 		SelectProjection,
 		SelectType,
 		SortOrder,
 		SqlWriter,
+		ToHead,
+	},
+	facet::{
+		Facet,
+		FacetKind,
+		Facets,
+		FACET_COLUMN,
+		FACET_CTE,
+		FACET_KEY_COLUMN,
 	},
 	order_by,
 	select::{
 		AggregateFunction,
 		AggregateSelection,
+		ColumnList,
+		Distinct,
+		Fraction,
 		GroupByList,
+		HavingExpr,
 		HavingList,
 		HavingPredicate,
 		SelectionColumn,
 		SelectionEntry,
 		SelectionList,
+		WindowFunction,
+		WindowSelection,
+	},
+	tenant::TenantScope,
+	tree::{
+		self,
+		RecursiveQuery,
+		TreeDirection,
+		TreeNode,
 	},
 	Buildable,
 	ExecutablePlan,
@@ -156,20 +198,218 @@ pub struct ReadQueryPlan<'a, C: QueryContext, Row = <C as QueryContext>::Model>
 	pub(crate) sort_expr: Option<SortOrder<C::Sort>>,
 	pub(crate) pagination: Option<Pagination>,
 	pub(crate) table: &'a str,
-	pub(crate) include_deleted: bool,
+	pub(crate) deleted: DeletedRows,
 	pub(crate) delete_marker_field: Option<&'a str>,
 	pub(crate) selection: Option<SelectionList<Row, SelectionEntry>>,
 	pub(crate) group_by: Option<Vec<SelectionColumn>>,
-	pub(crate) having: Option<Vec<HavingPredicate>>,
+	pub(crate) having: Option<HavingExpr>,
 	pub(crate) full_text_search: Option<Box<dyn DynFullTextSearchPlan>>,
 	pub(crate) aggregate_filter: Option<AggregateFilter>,
+	pub(crate) recursive: Option<RecursiveQuery<C::Query>>,
+	pub(crate) counts: Option<Vec<JoinDescriptor>>,
+	pub(crate) facets: Option<Vec<Facet>>,
+	pub(crate) distinct: Option<Distinct>,
+	pub(crate) key_order: Option<Vec<<C::Model as PrimaryKey>::Key>>,
+	pub(crate) tenant: TenantScope,
+	pub(crate) default_scope: Option<Expression<C::Query>>,
+	pub(crate) unsupported: Option<UnsupportedRead>,
+	/// DTO keys [`fetch_web_page`](Self::fetch_web_page) leaves out.
+	pub(crate) hidden_fields: Vec<&'static str>,
 	row: PhantomData<Row>,
 }
 
 #[derive(Clone)]
 pub struct AggregateFilter {
-	pub columns:    SmallVec<[&'static str; 2]>,
-	pub predicates: Vec<HavingPredicate>,
+	pub columns: SmallVec<[&'static str; 2]>,
+	pub having:  HavingExpr,
+}
+
+/// Placeholder the tenant of tenant-scoped counts is bound to, see
+/// [`SqlWriter::with_tenant_param`].
+const TENANT_PARAM: &str = "$1";
+
+/// Counts the related rows that are not soft-deleted, are within the default
+/// scope of the related model and, within a tenant scope, belong to the
+/// tenant. Many-to-many counts join the target table only when its rows carry
+/// a delete marker, a default scope or a tenant column to check.
+///
+/// The default scope is written to `params`, whose binds come before every
+/// bind of the rest of the query.
+fn count_projection(
+	table: &str,
+	descriptor: &JoinDescriptor,
+	tenant: &TenantScope,
+	params: &mut sqlx::QueryBuilder<'static, Postgres>,
+) -> SelectProjection {
+	let scoped = |field: Option<&'static str>| {
+		field.filter(|_| tenant.tenant().is_some())
+	};
+	let alias = format!("{}count", descriptor.alias_segment);
+	let mut conditions = Vec::new();
+	let source = match descriptor.through {
+		Some(through) => {
+			conditions.push(join_condition(
+				table,
+				through.left_fields,
+				&alias,
+				through.right_fields,
+			));
+			push_not_deleted(&mut conditions, &alias, through.delete_marker);
+			push_tenant(&mut conditions, &alias, scoped(through.tenant_field));
+			let target_tenant = scoped(descriptor.tenant_field);
+			let target_scope = scope_condition(descriptor, params);
+			if descriptor.delete_marker.is_none() &&
+				target_scope.is_none() &&
+				target_tenant.is_none()
+			{
+				format!(r#"{} AS "{}""#, through.table, alias)
+			} else {
+				let target = format!("{alias}_target");
+				push_not_deleted(
+					&mut conditions,
+					&target,
+					descriptor.delete_marker,
+				);
+				push_tenant(&mut conditions, &target, target_tenant);
+				format!(
+					r#"{} AS "{}" JOIN {} AS "{}" ON {}"#,
+					through.table,
+					alias,
+					scoped_source(descriptor.right_table, target_scope),
+					target,
+					join_condition(
+						&alias,
+						descriptor.left_fields,
+						&target,
+						descriptor.right_fields,
+					),
+				)
+			}
+		}
+		None => {
+			conditions.push(join_condition(
+				table,
+				descriptor.left_fields,
+				&alias,
+				descriptor.right_fields,
+			));
+			push_not_deleted(&mut conditions, &alias, descriptor.delete_marker);
+			push_tenant(
+				&mut conditions,
+				&alias,
+				scoped(descriptor.tenant_field),
+			);
+			let scope = scope_condition(descriptor, params);
+			format!(
+				r#"{} AS "{}""#,
+				scoped_source(descriptor.right_table, scope),
+				alias,
+			)
+		}
+	};
+
+	SelectProjection {
+		expression: format!(
+			"(SELECT COUNT(*) FROM {} WHERE {})",
+			source,
+			conditions.join(" AND "),
+		),
+		alias:      Some(format!("__sqlxo_count_{}", descriptor.identifier)),
+	}
+}
+
+/// The default scope of the counted model, written to `params`.
+fn scope_condition(
+	descriptor: &JoinDescriptor,
+	params: &mut sqlx::QueryBuilder<'static, Postgres>,
+) -> Option<String> {
+	let write = descriptor.default_scope?;
+	let start = params.sql().len();
+	write(params);
+	Some(params.sql()[start..].to_string())
+}
+
+/// `table`, restricted to `scope` in a subquery so the scope's columns,
+/// qualified by the table name, resolve under the count alias.
+fn scoped_source(table: &str, scope: Option<String>) -> String {
+	match scope {
+		Some(scope) => format!("(SELECT * FROM {table} WHERE {scope})"),
+		None => table.to_string(),
+	}
+}
+
+fn push_not_deleted(
+	conditions: &mut Vec<String>,
+	alias: &str,
+	delete_marker: Option<&str>,
+) {
+	if let Some(marker) = delete_marker {
+		conditions.push(format!(r#""{alias}"."{marker}" IS NULL"#));
+	}
+}
+
+fn push_tenant(
+	conditions: &mut Vec<String>,
+	alias: &str,
+	tenant_field: Option<&str>,
+) {
+	if let Some(field) = tenant_field {
+		conditions.push(format!(r#""{alias}"."{field}" = {TENANT_PARAM}"#));
+	}
+}
+
+const DISTINCT_TOTAL: &str = "__sqlxo_total";
+
+/// Which rows a read sees with respect to the soft-delete marker.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum DeletedRows {
+	#[default]
+	Exclude,
+	Include,
+	Only,
+}
+
+impl DeletedRows {
+	fn marker_condition(self) -> Option<&'static str> {
+		match self {
+			Self::Exclude => Some(" IS NULL"),
+			Self::Include => None,
+			Self::Only => Some(" IS NOT NULL"),
+		}
+	}
+}
+
+fn facet_column(idx: usize) -> String {
+	format!("__sqlxo_facet_{idx}")
+}
+
+fn format_facet_counts(facet: &Facet, idx: usize) -> String {
+	let column = facet_column(idx);
+	let (entry, order, grouped, filter) = match facet.kind {
+		FacetKind::Values => (
+			"'value', \"value\"",
+			r#" ORDER BY "count" DESC, "value""#.to_string(),
+			format!(r#""{column}" AS "value""#),
+			String::new(),
+		),
+		FacetKind::Buckets(bounds) => {
+			let bounds: Vec<String> =
+				bounds.iter().map(|bound| bound.to_string()).collect();
+			(
+				"'bucket', \"bucket\"",
+				String::new(),
+				format!(
+					r#"width_bucket("{column}"::float8, ARRAY[{}]::float8[]) AS "bucket""#,
+					bounds.join(", ")
+				),
+				format!(r#" WHERE "{column}" IS NOT NULL"#),
+			)
+		}
+	};
+
+	format!(
+		r#"(SELECT COALESCE(json_agg(json_build_object({entry}, 'count', "count"){order}), '[]'::json) FROM (SELECT {grouped}, COUNT(DISTINCT "{FACET_KEY_COLUMN}") AS "count" FROM "{FACET_CTE}"{filter} GROUP BY 1) AS "__sqlxo_facet")"#
+	)
 }
 
 fn build_alias_lookup(
@@ -265,24 +505,88 @@ fn format_aggregate_expression(
 	base_table: &str,
 	aliases: &[(&'static str, String)],
 ) -> String {
-	match selection.column {
-		Some(col) => {
-			let qualified = resolve_selection_column(&col, base_table, aliases);
-			match selection.function {
-				AggregateFunction::CountDistinct => format!(
-					r#"COUNT(DISTINCT "{}"."{}")"#,
-					qualified.table_alias, qualified.column
-				),
-				_ => format!(
-					r#"{}("{}"."{}")"#,
-					selection.function.sql_name(),
-					qualified.table_alias,
-					qualified.column
-				),
-			}
+	let Some(col) = selection.column else {
+		return format!("{}(*)", selection.function.sql_name());
+	};
+
+	let qualified = resolve_selection_column(&col, base_table, aliases);
+	let column =
+		format!(r#""{}"."{}""#, qualified.table_alias, qualified.column);
+	let options = &selection.options;
+	let order = options
+		.order
+		.map(|order| {
+			let target =
+				resolve_selection_column(&order.column, base_table, aliases);
+			format!(
+				r#" ORDER BY "{}"."{}" {}"#,
+				target.table_alias,
+				target.column,
+				if order.descending { "DESC" } else { "ASC" }
+			)
+		})
+		.unwrap_or_default();
+
+	match selection.function {
+		AggregateFunction::CountDistinct => {
+			format!("COUNT(DISTINCT {column})")
+		}
+		AggregateFunction::StringAgg => format!(
+			"STRING_AGG({column}::text, '{}'{order})",
+			options.separator.unwrap_or_default().replace('\'', "''"),
+		),
+		AggregateFunction::ArrayAgg => format!("ARRAY_AGG({column}{order})"),
+		AggregateFunction::StdDev | AggregateFunction::Variance => {
+			format!("{}({column})::float8", selection.function.sql_name())
+		}
+		AggregateFunction::PercentileCont => format!(
+			"PERCENTILE_CONT({}) WITHIN GROUP (ORDER BY {column})::float8",
+			options.fraction.map_or(0.5, Fraction::get),
+		),
+		_ => format!("{}({column})", selection.function.sql_name()),
+	}
+}
+
+fn format_window_expression(
+	selection: &WindowSelection,
+	base_table: &str,
+	aliases: &[(&'static str, String)],
+) -> String {
+	let qualify = |col: &SelectionColumn| {
+		let qualified = resolve_selection_column(col, base_table, aliases);
+		format!(r#""{}"."{}""#, qualified.table_alias, qualified.column)
+	};
+
+	let arguments = match (selection.function, &selection.column) {
+		(
+			WindowFunction::Lag(offset) | WindowFunction::Lead(offset),
+			Some(col),
+		) => {
+			format!("{}, {}", qualify(col), offset)
 		}
-		None => format!("{}(*)", selection.function.sql_name()),
+		(_, Some(col)) => qualify(col),
+		(_, None) => String::new(),
+	};
+
+	let mut over = Vec::new();
+	if !selection.partition_by.is_empty() {
+		let columns: Vec<String> =
+			selection.partition_by.iter().map(qualify).collect();
+		over.push(format!("PARTITION BY {}", columns.join(", ")));
+	}
+	if let Some(order) = &selection.order_by {
+		over.push(format!("ORDER BY {order}"));
 	}
+	if selection.function == WindowFunction::RunningSum {
+		over.push("ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW".into());
+	}
+
+	format!(
+		"{}({}) OVER ({})",
+		selection.function.sql_name(),
+		arguments,
+		over.join(" ")
+	)
 }
 
 fn write_having_predicate(
@@ -300,11 +604,91 @@ fn write_having_predicate(
 	predicate.bind_value(writer);
 }
 
+/// Writes the top-level group without surrounding parentheses; nested
+/// groups are parenthesized.
+fn write_having_expr(
+	expr: &HavingExpr,
+	writer: &mut SqlWriter,
+	base_table: &str,
+	aliases: &[(&'static str, String)],
+) {
+	match expr {
+		HavingExpr::And(xs) => {
+			write_having_group(xs, " AND ", writer, base_table, aliases)
+		}
+		HavingExpr::Or(xs) => {
+			write_having_group(xs, " OR ", writer, base_table, aliases)
+		}
+		other => write_nested_having_expr(other, writer, base_table, aliases),
+	}
+}
+
+fn write_nested_having_expr(
+	expr: &HavingExpr,
+	writer: &mut SqlWriter,
+	base_table: &str,
+	aliases: &[(&'static str, String)],
+) {
+	match expr {
+		HavingExpr::Leaf(predicate) => {
+			write_having_predicate(predicate, writer, base_table, aliases)
+		}
+		HavingExpr::Not(inner) => {
+			writer.push("NOT ");
+			write_nested_having_expr(inner, writer, base_table, aliases);
+		}
+		HavingExpr::And(xs) | HavingExpr::Or(xs) if xs.is_empty() => {
+			writer.push(if matches!(expr, HavingExpr::And(_)) {
+				"TRUE"
+			} else {
+				"FALSE"
+			});
+		}
+		group => {
+			writer.push("(");
+			write_having_expr(group, writer, base_table, aliases);
+			writer.push(")");
+		}
+	}
+}
+
+fn write_having_group(
+	xs: &[HavingExpr],
+	separator: &str,
+	writer: &mut SqlWriter,
+	base_table: &str,
+	aliases: &[(&'static str, String)],
+) {
+	for (idx, x) in xs.iter().enumerate() {
+		if idx > 0 {
+			writer.push(separator);
+		}
+		write_nested_having_expr(x, writer, base_table, aliases);
+	}
+}
+
 impl<'a, C, Row> ReadQueryPlan<'a, C, Row>
 where
 	C: QueryContext,
 	C::Model: JoinNavigationModel,
 {
+	/// Fails with the [`UnsupportedRead`] found when the plan was built or
+	/// when a tenant-scoped read runs without a tenant.
+	fn check(&self) -> Result<(), Error> {
+		if let Some(unsupported) = &self.unsupported {
+			return Err(unsupported.clone().into());
+		}
+		self.tenant.check(self.table, C::Model::TENANT_FIELD)
+	}
+
+	fn unsupported(&self, reason: &str) -> Error {
+		UnsupportedRead {
+			table:  self.table.to_string(),
+			reason: reason.to_string(),
+		}
+		.into()
+	}
+
 	fn compute_aggregate_filter(&mut self) {
 		if self.having.is_none() ||
 			self.selection.is_some() ||
@@ -318,18 +702,15 @@ where
 			return;
 		}
 
-		let Some(predicates) = self.having.take() else {
+		let Some(having) = self.having.take() else {
 			return;
 		};
-		if predicates.is_empty() {
+		if having.is_empty() {
 			return;
 		}
 
 		let columns = SmallVec::<[&'static str; 2]>::from_slice(pk_columns);
-		self.aggregate_filter = Some(AggregateFilter {
-			columns,
-			predicates,
-		});
+		self.aggregate_filter = Some(AggregateFilter { columns, having });
 	}
 
 	fn push_aggregate_filter_clause(
@@ -338,23 +719,31 @@ where
 		filter: &AggregateFilter,
 	) {
 		writer.push_where_raw(|w| {
-			if filter.columns.len() == 1 {
-				let col = filter.columns[0];
-				w.push(&format!(r#""{}"."{}""#, self.table, col));
-			} else {
-				w.push("(");
-				for (idx, col) in filter.columns.iter().enumerate() {
-					if idx > 0 {
-						w.push(", ");
-					}
-					w.push(&format!(r#""{}"."{}""#, self.table, col));
+			self.write_aggregate_filter_condition(w, filter)
+		});
+	}
+
+	fn write_aggregate_filter_condition(
+		&self,
+		w: &mut SqlWriter,
+		filter: &AggregateFilter,
+	) {
+		if filter.columns.len() == 1 {
+			let col = filter.columns[0];
+			w.push(&format!(r#""{}"."{}""#, self.table, col));
+		} else {
+			w.push("(");
+			for (idx, col) in filter.columns.iter().enumerate() {
+				if idx > 0 {
+					w.push(", ");
 				}
-				w.push(")");
+				w.push(&format!(r#""{}"."{}""#, self.table, col));
 			}
-			w.push(" IN (");
-			self.write_aggregate_subquery(w, filter);
 			w.push(")");
-		});
+		}
+		w.push(" IN (");
+		self.write_aggregate_subquery(w, filter);
+		w.push(")");
 	}
 
 	fn write_aggregate_subquery(
@@ -374,11 +763,7 @@ where
 
 		if let Some(js) = &self.joins {
 			for path in js {
-				push_join_path_inline(
-					writer.query_builder_mut(),
-					path,
-					self.table,
-				);
+				writer.push_join_path(path, self.table);
 			}
 		}
 
@@ -387,18 +772,43 @@ where
 		self.write_subquery_having(writer, filter);
 	}
 
-	fn write_subquery_filters(&self, writer: &mut SqlWriter) {
+	/// The filters of a subquery over the rows the read matches, `having`
+	/// included.
+	fn write_filtered_rows(&self, w: &mut SqlWriter) -> bool {
+		let has_clause = self.write_subquery_filters(w);
+		let Some(filter) = &self.aggregate_filter else {
+			return has_clause;
+		};
+		w.push(if has_clause { " AND " } else { " WHERE " });
+		self.write_aggregate_filter_condition(w, filter);
+		true
+	}
+
+	fn write_subquery_filters(&self, writer: &mut SqlWriter) -> bool {
 		let mut has_clause = false;
 
-		if !self.include_deleted {
-			if let Some(delete_field) = self.delete_marker_field {
-				writer.push(" WHERE ");
-				writer.push(&format!(
-					r#""{}"."{}" IS NULL"#,
-					self.table, delete_field
-				));
-				has_clause = true;
-			}
+		if let (Some(field), Some(tenant)) =
+			(C::Model::TENANT_FIELD, self.tenant.tenant())
+		{
+			writer.push(" WHERE ");
+			writer.push_tenant_condition(tenant, self.table, field);
+			has_clause = true;
+		}
+
+		if let (Some(delete_field), Some(condition)) =
+			(self.delete_marker_field, self.deleted.marker_condition())
+		{
+			writer.push(if has_clause { " AND " } else { " WHERE " });
+			writer.push(&format!(r#""{}"."{}""#, self.table, delete_field));
+			writer.push(condition);
+			has_clause = true;
+		}
+
+		if let Some(scope) = &self.default_scope {
+			writer.push(if has_clause { " AND (" } else { " WHERE (" });
+			scope.write(writer);
+			writer.push(")");
+			has_clause = true;
 		}
 
 		if let Some(expr) = &self.where_expr {
@@ -420,7 +830,10 @@ where
 			}
 			fts.write_condition(writer, self.table, self.joins.as_deref());
 			writer.push(")");
+			has_clause = true;
 		}
+
+		has_clause
 	}
 
 	fn write_subquery_group_by(
@@ -442,33 +855,90 @@ where
 		writer: &mut SqlWriter,
 		filter: &AggregateFilter,
 	) {
-		if filter.predicates.is_empty() {
+		if filter.having.is_empty() {
 			return;
 		}
 
 		let aliases = build_alias_lookup(self.joins.as_deref());
 		writer.push(" HAVING ");
-		for (idx, predicate) in filter.predicates.iter().enumerate() {
-			if idx > 0 {
-				writer.push(" AND ");
-			}
-			write_having_predicate(predicate, writer, self.table, &aliases);
-		}
+		write_having_expr(&filter.having, writer, self.table, &aliases);
 	}
 
-	fn to_query_builder(
+	pub(crate) fn to_query_builder(
 		&self,
 		select_type: SelectType,
 	) -> sqlx::QueryBuilder<'static, Postgres> {
-		let effective_select = self.select_type_for(select_type.clone());
-		let head = ReadHead::new(self.table, effective_select);
-		let mut w = SqlWriter::new(head);
+		self.build_query(select_type, None)
+	}
+
+	/// `facets` are computed in an uncorrelated subquery joined onto every
+	/// row, so they come back in the same round trip.
+	fn build_query(
+		&self,
+		select_type: SelectType,
+		facets: Option<&[Facet]>,
+	) -> sqlx::QueryBuilder<'static, Postgres> {
+		let mut effective_select = self.select_type_for(select_type.clone());
+		let (mut projections, params) = self.count_projections();
+		if facets.is_some() {
+			projections.push(SelectProjection {
+				expression: format!(r#""{FACET_COLUMN}"."facets""#),
+				alias:      Some(FACET_COLUMN.to_string()),
+			});
+		}
+		// `COUNT(*) OVER()` runs before `DISTINCT`, so distinct pages count
+		// their rows in a joined subquery instead.
+		let distinct_total = self.distinct.is_some() &&
+			matches!(
+				effective_select,
+				SelectType::StarAndCount | SelectType::StarAndCountExtras(_)
+			);
+		if distinct_total {
+			effective_select = match effective_select {
+				SelectType::StarAndCountExtras(extras) => {
+					SelectType::StarWithExtras(extras)
+				}
+				_ => SelectType::Star,
+			};
+			projections.push(SelectProjection {
+				expression: format!(r#""{DISTINCT_TOTAL}"."total_count""#),
+				alias:      Some("total_count".to_string()),
+			});
+		}
+		let head = ReadHead::new(self.table, effective_select)
+			.with_projections(projections)
+			.with_distinct(self.distinct_clause());
+		let mut w = match &self.recursive {
+			Some(recursive) => self.recursive_writer(recursive, head, params),
+			None => self.writer(head, params),
+		};
+
+		if let Some(facets) = facets {
+			w.push(" CROSS JOIN (");
+			w.push(&self.facet_head(facets).to_string());
+			self.write_facet_body(&mut w, facets);
+			w.push(&format!(r#") AS "{FACET_COLUMN}""#));
+		}
+
+		if distinct_total {
+			self.write_distinct_total(&mut w);
+		}
 
 		if let Some(js) = &self.joins {
 			w.push_joins(js, self.table);
 		}
 
 		self.push_where_clause(&mut w);
+		// Pages leave out rows without a base row before they are limited,
+		// so they stay full and match `total_count`.
+		if matches!(
+			select_type,
+			SelectType::StarAndCount | SelectType::StarAndCountExtras(_)
+		) {
+			if let Some(condition) = self.base_row_condition() {
+				w.push_where_raw(|writer| writer.push(&condition));
+			}
+		}
 		if let Some(filter) = &self.aggregate_filter {
 			self.push_aggregate_filter_clause(&mut w, filter);
 		} else {
@@ -491,6 +961,18 @@ where
 					});
 				}
 			}
+			if self.recursive.is_some() {
+				let path =
+					format!(r#""{}"."{}""#, self.table, tree::PATH_COLUMN);
+				w.push_order_by_raw(|writer| writer.push(&path));
+			}
+			if let Some(keys) =
+				self.key_order.as_deref().filter(|k| !k.is_empty())
+			{
+				w.push_order_by_raw(|writer| {
+					C::Model::write_key_position(keys, writer)
+				});
+			}
 		}
 
 		if let SelectType::Exists = select_type {
@@ -509,6 +991,271 @@ where
 		w.into_builder()
 	}
 
+	fn recursive_writer(
+		&self,
+		recursive: &RecursiveQuery<C::Query>,
+		head: ReadHead<'_>,
+		params: PgArguments,
+	) -> SqlWriter {
+		let primary_key = <C::Model as PrimaryKey>::PRIMARY_KEY;
+		let delete_marker = match self.deleted {
+			DeletedRows::Exclude => self.delete_marker_field,
+			DeletedRows::Include | DeletedRows::Only => None,
+		};
+
+		let mut w = self.writer(
+			RecursiveHead::new(tree::TREE_CTE, self.table, primary_key),
+			params,
+		);
+		recursive.write_body(
+			&mut w,
+			self.table,
+			primary_key,
+			delete_marker,
+			C::Model::TENANT_FIELD,
+			self.default_scope.as_ref(),
+		);
+		w.push(" ");
+		w.push(&head.from_cte(tree::TREE_CTE).to_string());
+		w
+	}
+
+	fn distinct_clause(&self) -> Option<DistinctClause> {
+		Some(match self.distinct.as_ref()? {
+			Distinct::Rows => DistinctClause::Rows,
+			Distinct::On(columns) => {
+				DistinctClause::On(resolve_selection_columns(
+					columns,
+					self.table,
+					self.joins.as_deref(),
+				))
+			}
+		})
+	}
+
+	fn write_distinct_total(&self, w: &mut SqlWriter) {
+		let head =
+			ReadHead::new(self.table, self.select_type_for(SelectType::Star))
+				.with_distinct(self.distinct_clause());
+		w.push(r#" CROSS JOIN (SELECT COUNT(*) AS "total_count" FROM ("#);
+		w.push(&head.to_string());
+		if let Some(js) = &self.joins {
+			for path in js {
+				w.push_join_path(path, self.table);
+			}
+		}
+		let has_clause = self.write_filtered_rows(w);
+		if let Some(condition) = self.base_row_condition() {
+			w.push(if has_clause { " AND " } else { " WHERE " });
+			w.push(&condition);
+		}
+		w.push(&format!(
+			r#") AS "__sqlxo_distinct") AS "{DISTINCT_TOTAL}""#
+		));
+	}
+
+	/// Rejects combinations the plan cannot render, whatever it is fetched
+	/// as.
+	fn validate(&self) -> Result<(), String> {
+		if self.deleted == DeletedRows::Only && self.recursive.is_some() {
+			return Err(
+				"only_deleted cannot be combined with recursive reads".into()
+			);
+		}
+
+		let Some(Distinct::On(columns)) = &self.distinct else {
+			return Ok(());
+		};
+		if columns.is_empty() {
+			return Err("distinct_on requires at least one column".into());
+		}
+
+		let Some(sort) = &self.sort_expr else {
+			let implicit_order = self.recursive.is_some() ||
+				self.full_text_search
+					.as_ref()
+					.is_some_and(|fts| fts.include_rank());
+			if implicit_order {
+				return Err("distinct_on requires an explicit order_by when \
+				            the read is ordered by rank or tree path"
+					.into());
+			}
+			return Ok(());
+		};
+
+		let keys: Vec<String> = resolve_selection_columns(
+			columns,
+			self.table,
+			self.joins.as_deref(),
+		)
+		.iter()
+		.map(|col| format!(r#""{}"."{}""#, col.table_alias, col.column))
+		.collect();
+		let sorted: Vec<String> = sort
+			.0
+			.iter()
+			.map(|s| {
+				let clause = s.sort_clause();
+				match clause.rsplit_once(' ') {
+					Some((column, _direction)) => column.to_string(),
+					None => clause,
+				}
+			})
+			.collect();
+
+		let leading =
+			sorted.iter().take_while(|col| keys.contains(col)).count();
+		if leading == sorted.len() ||
+			keys.iter().all(|key| sorted[..leading].contains(key))
+		{
+			return Ok(());
+		}
+		Err(format!(
+			"distinct_on({}) must match the leading order_by columns but the \
+			 read is ordered by {}",
+			keys.join(", "),
+			sorted.join(", "),
+		))
+	}
+
+	/// Facets and distinct totals are counted in subqueries of the plain
+	/// read, which recursive reads do not have.
+	fn check_counted(&self, facets: bool) -> Result<(), Error> {
+		if self.recursive.is_none() {
+			return Ok(());
+		}
+		if facets {
+			return Err(self.unsupported(
+				"facets cannot be combined with descendants_of(...) or \
+				 ancestors_of(...)",
+			));
+		}
+		if self.distinct.is_some() {
+			return Err(self.unsupported(
+				"distinct pages cannot be combined with descendants_of(...) \
+				 or ancestors_of(...)",
+			));
+		}
+		Ok(())
+	}
+
+	fn facet_head(&self, facets: &[Facet]) -> FacetHead<'_> {
+		let primary_key = <C::Model as PrimaryKey>::PRIMARY_KEY;
+		let key = if primary_key.is_empty() {
+			format!(r#""{}".ctid::text"#, self.table)
+		} else {
+			path_key(self.table, primary_key)
+		};
+
+		let aliases = build_alias_lookup(self.joins.as_deref());
+		let mut projections = vec![SelectProjection {
+			expression: key,
+			alias:      Some(FACET_KEY_COLUMN.to_string()),
+		}];
+		for (idx, facet) in facets.iter().enumerate() {
+			let column =
+				resolve_selection_column(&facet.column, self.table, &aliases);
+			projections.push(SelectProjection {
+				expression: format!(
+					r#""{}"."{}""#,
+					column.table_alias, column.column
+				),
+				alias:      Some(facet_column(idx)),
+			});
+		}
+
+		FacetHead::new(
+			FACET_CTE,
+			ReadHead::new(self.table, SelectType::Projection(projections)),
+		)
+	}
+
+	/// Closes the facet CTE with the read's joins and filters, then folds
+	/// every facet into one JSON object keyed by facet name.
+	fn write_facet_body(&self, w: &mut SqlWriter, facets: &[Facet]) {
+		if let Some(js) = &self.joins {
+			for path in js {
+				w.push_join_path(path, self.table);
+			}
+		}
+		self.write_filtered_rows(w);
+
+		w.push(") SELECT json_build_object(");
+		for (idx, facet) in facets.iter().enumerate() {
+			if idx > 0 {
+				w.push(", ");
+			}
+			w.push(&format!(
+				"'{}', {}",
+				facet.name.replace('\'', "''"),
+				format_facet_counts(facet, idx)
+			));
+		}
+		w.push(r#") AS "facets""#);
+	}
+
+	fn facet_query_builder(
+		&self,
+		facets: &[Facet],
+	) -> sqlx::QueryBuilder<'static, Postgres> {
+		let mut w = SqlWriter::new(self.facet_head(facets))
+			.with_tenant(self.tenant.tenant());
+		self.write_facet_body(&mut w, facets);
+		w.into_builder()
+	}
+
+	/// The count projections and the values they compare against, which
+	/// the query binds ahead of its own.
+	fn count_projections(&self) -> (Vec<SelectProjection>, PgArguments) {
+		let mut args = PgArguments::default();
+		if let Some(tenant) = self.count_tenant() {
+			bind_tenant(&mut args, tenant);
+		}
+		if self.selection.is_some() {
+			return (Vec::new(), args);
+		}
+
+		let mut params = sqlx::QueryBuilder::with_arguments("", args);
+		let projections = self
+			.counts
+			.iter()
+			.flatten()
+			.map(|descriptor| {
+				count_projection(
+					self.table,
+					descriptor,
+					&self.tenant,
+					&mut params,
+				)
+			})
+			.collect();
+		let args = params
+			.build()
+			.take_arguments()
+			.expect("count parameters always encode")
+			.unwrap_or_default();
+		(projections, args)
+	}
+
+	/// The tenant the count projections compare against, if any.
+	fn count_tenant(&self) -> Option<&TenantId> {
+		let tenant = self.tenant.tenant()?;
+		let scoped = |descriptor: &JoinDescriptor| {
+			descriptor.tenant_field.is_some() ||
+				descriptor
+					.through
+					.is_some_and(|through| through.tenant_field.is_some())
+		};
+		(self.selection.is_none() && self.counts.iter().flatten().any(scoped))
+			.then_some(tenant)
+	}
+
+	/// A writer starting with `head`, with `params` bound first and
+	/// restricted to the read's tenant.
+	fn writer(&self, head: impl ToHead, params: PgArguments) -> SqlWriter {
+		SqlWriter::with_params(head, params).with_tenant(self.tenant.tenant())
+	}
+
 	fn select_type_for(&self, base: SelectType) -> SelectType {
 		let resolved = match base {
 			SelectType::Star => self
@@ -528,10 +1275,12 @@ where
 	) -> SelectType {
 		let mut has_columns = false;
 		let mut has_aggregates = false;
+		let mut has_windows = false;
 		for entry in selection.entries() {
 			match entry {
 				SelectionEntry::Column(_) => has_columns = true,
 				SelectionEntry::Aggregate(_) => has_aggregates = true,
+				SelectionEntry::Window(_) => has_windows = true,
 			}
 		}
 
@@ -542,7 +1291,11 @@ where
 			);
 		}
 
-		if has_columns && !has_aggregates {
+		if has_columns &&
+			!has_aggregates &&
+			!has_windows &&
+			selection.aliases().is_empty()
+		{
 			let mut cols: SmallVec<[SelectionColumn; 4]> =
 				SmallVec::with_capacity(selection.entries().len());
 			for entry in selection.entries() {
@@ -566,30 +1319,38 @@ where
 		selection: &SelectionList<Row, SelectionEntry>,
 	) -> Vec<SelectProjection> {
 		let aliases = build_alias_lookup(self.joins.as_deref());
+		let names = selection.aliases();
 		selection
 			.entries()
 			.iter()
 			.enumerate()
-			.map(|(idx, entry)| match entry {
-				SelectionEntry::Column(col) => {
-					let qualified =
-						resolve_selection_column(col, self.table, &aliases);
-					SelectProjection {
-						expression: format!(
+			.map(|(idx, entry)| {
+				let generated = || format!(r#"__sqlxo_sel_{}"#, idx);
+				let (expression, alias) = match entry {
+					SelectionEntry::Column(col) => {
+						let qualified =
+							resolve_selection_column(col, self.table, &aliases);
+						let expression = format!(
 							r#""{}"."{}""#,
 							qualified.table_alias, qualified.column
-						),
-						alias:      None,
-					}
-				}
-				SelectionEntry::Aggregate(agg) => {
-					let expr =
-						format_aggregate_expression(agg, self.table, &aliases);
-					let alias = format!(r#"__sqlxo_sel_{}"#, idx);
-					SelectProjection {
-						expression: expr,
-						alias:      Some(alias),
+						);
+						(expression, None)
 					}
+					SelectionEntry::Aggregate(agg) => (
+						format_aggregate_expression(agg, self.table, &aliases),
+						Some(generated()),
+					),
+					SelectionEntry::Window(window) => (
+						format_window_expression(window, self.table, &aliases),
+						Some(generated()),
+					),
+				};
+				SelectProjection {
+					expression,
+					alias: names
+						.get(idx)
+						.map(|name| name.to_string())
+						.or(alias),
 				}
 			})
 			.collect()
@@ -617,18 +1378,33 @@ where
 	}
 
 	fn push_where_clause(&self, w: &mut SqlWriter) {
-		let mut has_clause = false;
+		w.push_tenant_where(self.table, C::Model::TENANT_FIELD);
+		let mut has_clause =
+			w.tenant().is_some() && C::Model::TENANT_FIELD.is_some();
 
-		if !self.include_deleted {
-			if let Some(delete_field) = self.delete_marker_field {
-				let qualified =
-					format!(r#""{}"."{}""#, self.table, delete_field);
-				w.push_where_raw(|writer| {
-					writer.push(&qualified);
-					writer.push(" IS NULL");
-				});
-				has_clause = true;
-			}
+		if let (Some(delete_field), Some(condition)) =
+			(self.delete_marker_field, self.deleted.marker_condition())
+		{
+			let qualified = format!(r#""{}"."{}""#, self.table, delete_field);
+			w.push_where_raw(|writer| {
+				writer.push(&qualified);
+				writer.push(condition);
+			});
+			has_clause = true;
+		}
+
+		if let Some(scope) = &self.default_scope {
+			let wrap = has_clause;
+			w.push_where_raw(|writer| {
+				if wrap {
+					writer.push("(");
+					scope.write(writer);
+					writer.push(")");
+				} else {
+					scope.write(writer);
+				}
+			});
+			has_clause = true;
 		}
 
 		if let Some(e) = &self.where_expr {
@@ -674,37 +1450,31 @@ where
 	}
 
 	fn push_having_clause(&self, w: &mut SqlWriter) {
-		let Some(predicates) = &self.having else {
+		let Some(having) = &self.having else {
 			return;
 		};
-		if predicates.is_empty() {
+		if having.is_empty() {
 			return;
 		}
 		let aliases = build_alias_lookup(self.joins.as_deref());
 		let table = self.table;
 		w.push_having(|writer| {
-			for (idx, predicate) in predicates.iter().enumerate() {
-				if idx > 0 {
-					writer.push(" AND ");
-				}
-				write_having_predicate(predicate, writer, table, &aliases);
-			}
+			write_having_expr(having, writer, table, &aliases);
 		});
 	}
 
+	/// Rows a `RIGHT`/`FULL OUTER` join produced without a base row are
+	/// filtered out before paginating, so they are neither part of `items`
+	/// nor counted in `total`.
 	pub async fn fetch_page<'e, E>(
 		&self,
 		exec: E,
-	) -> Result<Page<C::Model>, sqlx::Error>
+	) -> Result<Page<C::Model>, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
-		#[derive(sqlx::FromRow)]
-		struct RowWithCount<M> {
-			#[sqlx(flatten)]
-			model:       M,
-			total_count: i64,
-		}
+		self.check()?;
+		self.check_counted(false)?;
 
 		let rows: Vec<PgRow> = self
 			.to_query_builder(SelectType::StarAndCount)
@@ -712,6 +1482,67 @@ where
 			.fetch_all(exec)
 			.await?;
 
+		self.collect_page(rows)
+	}
+
+	/// Like [`fetch_page`](Self::fetch_page), with the facets counted over
+	/// every matching row rather than the page. A page past the last row
+	/// reports empty facets, just as it reports a `total` of zero.
+	pub async fn fetch_page_with_facets<'e, E>(
+		&self,
+		exec: E,
+	) -> Result<(Page<C::Model>, Facets), Error>
+	where
+		E: Executor<'e, Database = Postgres>,
+	{
+		self.check()?;
+		self.check_counted(true)?;
+
+		let facets = self.facets.as_deref().unwrap_or_default();
+		let rows: Vec<PgRow> = self
+			.build_query(SelectType::StarAndCount, Some(facets))
+			.build()
+			.fetch_all(exec)
+			.await?;
+
+		let mut raw = None;
+		for row in &rows {
+			raw = row.try_get::<Option<serde_json::Value>, _>(FACET_COLUMN)?;
+			if raw.is_some() {
+				break;
+			}
+		}
+
+		let facets = Facets::decode(facets, raw)?;
+		Ok((self.collect_page(rows)?, facets))
+	}
+
+	/// Counts the facets over every row the read matches, ignoring
+	/// pagination, projections and grouping.
+	pub async fn fetch_facets<'e, E>(&self, exec: E) -> Result<Facets, Error>
+	where
+		E: Executor<'e, Database = Postgres>,
+	{
+		self.check()?;
+		self.check_counted(true)?;
+
+		let facets = self.facets.as_deref().unwrap_or_default();
+		let row = self
+			.facet_query_builder(facets)
+			.build()
+			.fetch_one(exec)
+			.await?;
+		Ok(Facets::decode(facets, row.try_get("facets")?)?)
+	}
+
+	fn collect_page(&self, rows: Vec<PgRow>) -> Result<Page<C::Model>, Error> {
+		#[derive(sqlx::FromRow)]
+		struct RowWithCount<M> {
+			#[sqlx(flatten)]
+			model:       M,
+			total_count: i64,
+		}
+
 		let pagination = self.pagination.unwrap_or_default();
 
 		if rows.is_empty() {
@@ -725,11 +1556,7 @@ where
 		for row in rows {
 			let mut parsed = RowWithCount::<C::Model>::from_row(&row)?;
 			if hydrate {
-				parsed.model.hydrate_navigations(
-					self.joins.as_deref(),
-					&row,
-					"",
-				)?;
+				self.hydrate_model(&mut parsed.model, &row)?;
 			}
 			total = parsed.total_count;
 			items.push(parsed.model);
@@ -745,10 +1572,79 @@ where
 		Ok(Page::new(items, pagination, total))
 	}
 
-	pub async fn exists<'e, E>(&self, exec: E) -> Result<bool, sqlx::Error>
+	/// Fetches the rows of a `descendants_of`/`ancestors_of` read nested
+	/// below the row they were reached from.
+	pub async fn fetch_tree<'e, E>(
+		&self,
+		exec: E,
+	) -> Result<Vec<TreeNode<C::Model>>, Error>
+	where
+		E: Executor<'e, Database = Postgres>,
+	{
+		self.check()?;
+		if self.recursive.is_none() {
+			return Err(self.unsupported(
+				"fetch_tree requires descendants_of(...) or ancestors_of(...)",
+			));
+		}
+
+		let rows: Vec<PgRow> = self
+			.to_query_builder(SelectType::Star)
+			.build()
+			.fetch_all(exec)
+			.await?;
+
+		let hydrate = self.selection.is_none();
+		let skip_missing_base = hydrate && self.base_may_be_missing();
+		let merge =
+			hydrate && C::Model::has_collection_joins(self.joins.as_deref());
+
+		let mut entries: Vec<(Vec<String>, TreeNode<C::Model>)> =
+			Vec::with_capacity(rows.len());
+		for row in rows {
+			if skip_missing_base && base_row_is_null::<C>(&row) {
+				continue;
+			}
+
+			let path: Vec<String> = row.try_get(tree::PATH_COLUMN)?;
+			let depth: i32 = row.try_get(tree::DEPTH_COLUMN)?;
+			let mut model = C::Model::from_row(&row)?;
+			if hydrate {
+				self.hydrate_model(&mut model, &row)?;
+			}
+
+			if merge {
+				if let Some((last_path, last)) = entries.last_mut() {
+					if *last_path == path {
+						let merged =
+							<C::Model as JoinNavigationModel>::merge_collection_rows(
+								vec![last.node.clone(), model],
+								self.joins.as_deref(),
+							);
+						if let Some(node) = merged.into_iter().next() {
+							last.node = node;
+						}
+						continue;
+					}
+				}
+			}
+
+			entries.push((path, TreeNode {
+				node: model,
+				depth,
+				children: Vec::new(),
+			}));
+		}
+
+		Ok(tree::assemble(entries))
+	}
+
+	pub async fn exists<'e, E>(&self, exec: E) -> Result<bool, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.check()?;
+
 		#[derive(sqlx::FromRow)]
 		struct ExistsRow {
 			exists: bool,
@@ -769,12 +1665,62 @@ where
 		self.to_query_builder(build).build().sql().to_string()
 	}
 
+	#[cfg(any(test, feature = "test-utils"))]
+	pub fn facet_sql(&self) -> String {
+		use sqlx::Execute;
+		let facets = self.facets.as_deref().unwrap_or_default();
+		self.facet_query_builder(facets).build().sql().to_string()
+	}
+
+	#[cfg(any(test, feature = "test-utils"))]
+	pub fn page_with_facets_sql(&self) -> String {
+		use sqlx::Execute;
+		let facets = self.facets.as_deref().unwrap_or_default();
+		self.build_query(SelectType::StarAndCount, Some(facets))
+			.build()
+			.sql()
+			.to_string()
+	}
+
 	fn map_pg_row(&self, row: PgRow) -> Result<Row, sqlx::Error>
 	where
 		Row: HydrateRow<C>,
 	{
 		<Row as HydrateRow<C>>::from_pg_row(self, row)
 	}
+
+	fn map_pg_rows(&self, rows: Vec<PgRow>) -> Result<Vec<Row>, sqlx::Error>
+	where
+		Row: HydrateRow<C>,
+	{
+		rows.into_iter()
+			.filter(|row| !<Row as HydrateRow<C>>::is_missing_base(self, row))
+			.map(|row| self.map_pg_row(row))
+			.collect()
+	}
+
+	fn hydrate_model(
+		&self,
+		model: &mut C::Model,
+		row: &PgRow,
+	) -> Result<(), sqlx::Error> {
+		model.hydrate_navigations(self.joins.as_deref(), row, "")?;
+		model.hydrate_counts(row)
+	}
+
+	fn base_may_be_missing(&self) -> bool {
+		self.joins
+			.as_deref()
+			.is_some_and(|paths| paths.iter().any(JoinPath::may_omit_base))
+	}
+
+	/// Matches the rows with a base row when joins may produce rows
+	/// without one.
+	fn base_row_condition(&self) -> Option<String> {
+		let key = <C::Model as PrimaryKey>::PRIMARY_KEY.first()?;
+		(self.selection.is_none() && self.base_may_be_missing())
+			.then(|| format!(r#""{}"."{}" IS NOT NULL"#, self.table, key))
+	}
 }
 
 #[async_trait::async_trait]
@@ -783,23 +1729,27 @@ where
 	C: QueryContext,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
 {
-	async fn fetch_one<'e, E>(&self, exec: E) -> Result<Row, sqlx::Error>
+	async fn fetch_one<'e, E>(&self, exec: E) -> Result<Row, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
-		if <Row as HydrateRow<C>>::requires_collection_merge(self) {
+		self.check()?;
+
+		if <Row as HydrateRow<C>>::requires_collection_merge(self) ||
+			<Row as HydrateRow<C>>::may_skip_rows(self)
+		{
 			let rows = self
 				.to_query_builder(SelectType::Star)
 				.build()
 				.fetch_all(exec)
 				.await?;
-			let mapped = rows
-				.into_iter()
-				.map(|row| self.map_pg_row(row))
-				.collect::<Result<Vec<Row>, _>>()?;
+			let mapped = self.map_pg_rows(rows)?;
 			let merged =
 				<Row as HydrateRow<C>>::merge_collection_rows(mapped, self);
-			return merged.into_iter().next().ok_or(sqlx::Error::RowNotFound);
+			return merged
+				.into_iter()
+				.next()
+				.ok_or(Error::Sqlx(sqlx::Error::RowNotFound));
 		}
 
 		let row = self
@@ -808,44 +1758,41 @@ where
 			.fetch_one(exec)
 			.await?;
 
-		self.map_pg_row(row)
+		Ok(self.map_pg_row(row)?)
 	}
 
-	async fn fetch_all<'e, E>(&self, exec: E) -> Result<Vec<Row>, sqlx::Error>
+	async fn fetch_all<'e, E>(&self, exec: E) -> Result<Vec<Row>, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.check()?;
+
 		let rows = self
 			.to_query_builder(SelectType::Star)
 			.build()
 			.fetch_all(exec)
 			.await?;
 
-		let mapped = rows
-			.into_iter()
-			.map(|row| self.map_pg_row(row))
-			.collect::<Result<Vec<Row>, _>>()?;
+		let mapped = self.map_pg_rows(rows)?;
 
 		Ok(<Row as HydrateRow<C>>::merge_collection_rows(mapped, self))
 	}
 
-	async fn fetch_optional<'e, E>(
-		&self,
-		exec: E,
-	) -> Result<Option<Row>, sqlx::Error>
+	async fn fetch_optional<'e, E>(&self, exec: E) -> Result<Option<Row>, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
-		if <Row as HydrateRow<C>>::requires_collection_merge(self) {
+		self.check()?;
+
+		if <Row as HydrateRow<C>>::requires_collection_merge(self) ||
+			<Row as HydrateRow<C>>::may_skip_rows(self)
+		{
 			let rows = self
 				.to_query_builder(SelectType::Star)
 				.build()
 				.fetch_all(exec)
 				.await?;
-			let mapped = rows
-				.into_iter()
-				.map(|row| self.map_pg_row(row))
-				.collect::<Result<Vec<Row>, _>>()?;
+			let mapped = self.map_pg_rows(rows)?;
 			let merged =
 				<Row as HydrateRow<C>>::merge_collection_rows(mapped, self);
 			return Ok(merged.into_iter().next());
@@ -858,74 +1805,24 @@ where
 			.await?;
 
 		match row {
-			Some(row) => self.map_pg_row(row).map(Some),
+			Some(row) => Ok(Some(self.map_pg_row(row)?)),
 			None => Ok(None),
 		}
 	}
 }
 
-fn push_join_path_inline(
-	qb: &mut sqlx::QueryBuilder<'static, Postgres>,
-	path: &JoinPath,
-	base_table: &str,
-) {
-	if path.is_empty() {
-		return;
-	}
-
-	let mut left_alias = base_table.to_string();
-	let mut alias_prefix = String::new();
-
-	for segment in path.segments() {
-		let join_word = match segment.kind {
-			JoinKind::Inner => " INNER JOIN ",
-			JoinKind::Left => " LEFT JOIN ",
-		};
-
-		if let Some(through) = segment.descriptor.through {
-			let mut through_alias = alias_prefix.clone();
-			through_alias.push_str(through.alias_segment);
-			let clause = format!(
-				r#"{join}{table} AS "{alias}" ON "{left}"."{left_field}" = "{alias}"."{right_field}""#,
-				join = join_word,
-				table = through.table,
-				alias = &through_alias,
-				left = &left_alias,
-				left_field = through.left_field,
-				right_field = through.right_field,
-			);
-			qb.push(clause);
-			left_alias = through_alias;
-		}
-
-		alias_prefix.push_str(segment.descriptor.alias_segment);
-		let right_alias = alias_prefix.clone();
-
-		let clause = format!(
-			r#"{join}{table} AS "{alias}" ON "{left}"."{left_field}" = "{alias}"."{right_field}""#,
-			join = join_word,
-			table = segment.descriptor.right_table,
-			alias = &right_alias,
-			left = &left_alias,
-			left_field = segment.descriptor.left_field,
-			right_field = segment.descriptor.right_field,
-		);
-
-		qb.push(clause);
-		left_alias = right_alias;
-	}
-}
-
 #[async_trait::async_trait]
 impl<'a, C, Row> ExecutablePlan<C> for ReadQueryPlan<'a, C, Row>
 where
 	C: QueryContext,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
 {
-	async fn execute<'e, E>(&self, exec: E) -> Result<u64, sqlx::Error>
+	async fn execute<'e, E>(&self, exec: E) -> Result<u64, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.check()?;
+
 		let rows = self
 			.to_query_builder(SelectType::Star)
 			.build()
@@ -944,6 +1841,16 @@ where
 {
 }
 
+fn base_row_is_null<C: QueryContext>(row: &PgRow) -> bool {
+	let pk_columns = <C::Model as PrimaryKey>::PRIMARY_KEY;
+	!pk_columns.is_empty() &&
+		pk_columns.iter().all(|col| {
+			row.try_get_raw(*col)
+				.map(|value| value.is_null())
+				.unwrap_or(false)
+		})
+}
+
 trait HydrateRow<C: QueryContext>: Sized {
 	fn from_pg_row(
 		plan: &ReadQueryPlan<C, Self>,
@@ -954,6 +1861,18 @@ trait HydrateRow<C: QueryContext>: Sized {
 		false
 	}
 
+	/// Whether rows may be dropped by [`HydrateRow::is_missing_base`], in
+	/// which case single-row fetches have to scan the whole result.
+	fn may_skip_rows(_plan: &ReadQueryPlan<C, Self>) -> bool {
+		false
+	}
+
+	/// `RIGHT` and `FULL OUTER` joins yield rows where the base table is
+	/// absent. Projections keep them, models cannot be built from them.
+	fn is_missing_base(_plan: &ReadQueryPlan<C, Self>, _row: &PgRow) -> bool {
+		false
+	}
+
 	fn merge_collection_rows(
 		rows: Vec<Self>,
 		_plan: &ReadQueryPlan<C, Self>,
@@ -980,6 +1899,17 @@ where
 		false
 	}
 
+	default fn may_skip_rows(_plan: &ReadQueryPlan<C, Self>) -> bool {
+		false
+	}
+
+	default fn is_missing_base(
+		_plan: &ReadQueryPlan<C, Self>,
+		_row: &PgRow,
+	) -> bool {
+		false
+	}
+
 	default fn merge_collection_rows(
 		rows: Vec<Self>,
 		_plan: &ReadQueryPlan<C, Self>,
@@ -999,7 +1929,7 @@ where
 	) -> Result<Self, sqlx::Error> {
 		let mut model = Self::from_row(&row)?;
 		if plan.selection.is_none() {
-			model.hydrate_navigations(plan.joins.as_deref(), &row, "")?;
+			plan.hydrate_model(&mut model, &row)?;
 		}
 		Ok(model)
 	}
@@ -1009,6 +1939,14 @@ where
 			Self::has_collection_joins(plan.joins.as_deref())
 	}
 
+	fn may_skip_rows(plan: &ReadQueryPlan<C, Self>) -> bool {
+		plan.selection.is_none() && plan.base_may_be_missing()
+	}
+
+	fn is_missing_base(plan: &ReadQueryPlan<C, Self>, row: &PgRow) -> bool {
+		Self::may_skip_rows(plan) && base_row_is_null::<C>(row)
+	}
+
 	fn merge_collection_rows(
 		rows: Vec<Self>,
 		plan: &ReadQueryPlan<C, Self>,
@@ -1034,12 +1972,21 @@ pub struct ReadQueryBuilder<
 	pub(crate) where_expr: Option<Expression<C::Query>>,
 	pub(crate) sort_expr: Option<SortOrder<C::Sort>>,
 	pub(crate) pagination: Option<Pagination>,
-	pub(crate) include_deleted: bool,
+	pub(crate) deleted: DeletedRows,
 	pub(crate) delete_marker_field: Option<&'a str>,
 	pub(crate) selection: Option<SelectionList<Row, SelectionEntry>>,
 	pub(crate) group_by: Option<Vec<SelectionColumn>>,
-	pub(crate) having: Option<Vec<HavingPredicate>>,
+	pub(crate) having: Option<HavingExpr>,
 	pub(crate) full_text_search: Option<Box<dyn DynFullTextSearchPlan>>,
+	pub(crate) recursive: Option<RecursiveQuery<C::Query>>,
+	pub(crate) counts: Option<Vec<JoinDescriptor>>,
+	pub(crate) facets: Option<Vec<Facet>>,
+	pub(crate) distinct: Option<Distinct>,
+	pub(crate) key_order: Option<Vec<<C::Model as PrimaryKey>::Key>>,
+	pub(crate) all_tenants: bool,
+	pub(crate) unscoped: bool,
+	pub(crate) max_depth: Option<u32>,
+	pub(crate) hidden_fields: Vec<&'static str>,
 	row: PhantomData<Row>,
 }
 
@@ -1048,7 +1995,28 @@ where
 	C: QueryContext,
 {
 	pub fn include_deleted(mut self) -> Self {
-		self.include_deleted = true;
+		self.deleted = DeletedRows::Include;
+		self
+	}
+
+	/// Reads the rows of every tenant, e.g. for admin queries.
+	pub fn all_tenants(mut self) -> Self {
+		self.all_tenants = true;
+		self
+	}
+
+	/// Skips the model's `#[sqlxo(default_scope = "...")]` filter.
+	pub fn unscoped(mut self) -> Self {
+		self.unscoped = true;
+		self
+	}
+
+	/// Only reads soft-deleted rows, e.g. to list the trash.
+	pub fn only_deleted(mut self) -> Self
+	where
+		C::Model: crate::SoftDeletable,
+	{
+		self.deleted = DeletedRows::Only;
 		self
 	}
 
@@ -1065,6 +2033,86 @@ where
 			Some(Box::new(ModelFullTextSearchPlan::<C::Model>::new(config)));
 		self
 	}
+
+	/// Adds a correlated `COUNT(*)` of the related rows, hydrated into the
+	/// model's `#[sqlxo(count_of = "...")]` field.
+	pub fn with_count(self, relation: C::Join) -> Self {
+		self.with_count_descriptor(relation.descriptor())
+	}
+
+	/// Counts the values of `facets` over the rows the read matches; see
+	/// [`ReadQueryPlan::fetch_facets`].
+	pub fn facets(mut self, facets: impl IntoIterator<Item = Facet>) -> Self {
+		self.facets.get_or_insert_with(Vec::new).extend(facets);
+		self
+	}
+
+	/// Drops duplicate rows.
+	pub fn distinct(mut self) -> Self {
+		self.distinct = Some(Distinct::Rows);
+		self
+	}
+
+	/// Keeps the first row per value of `columns`. With an `order_by`, the
+	/// leading sort keys must be these columns, as Postgres requires.
+	pub fn distinct_on(mut self, columns: impl ColumnList) -> Self {
+		self.distinct = Some(Distinct::On(columns.into_columns()));
+		self
+	}
+
+	pub(crate) fn with_count_descriptor(
+		mut self,
+		descriptor: JoinDescriptor,
+	) -> Self {
+		match &mut self.counts {
+			Some(existing) => existing.push(descriptor),
+			None => self.counts = Some(vec![descriptor]),
+		};
+		self
+	}
+
+	/// Reads the rows matching `anchor` and everything below them along the
+	/// self-referential `relation`.
+	pub fn descendants_of(
+		self,
+		relation: C::Join,
+		anchor: Expression<C::Query>,
+	) -> Self {
+		self.recursive_from(relation, anchor, TreeDirection::Descendants)
+	}
+
+	/// Reads the rows matching `anchor` and every row above them along the
+	/// self-referential `relation`.
+	pub fn ancestors_of(
+		self,
+		relation: C::Join,
+		anchor: Expression<C::Query>,
+	) -> Self {
+		self.recursive_from(relation, anchor, TreeDirection::Ancestors)
+	}
+
+	/// Stops the traversal `depth` levels away from the anchor rows of
+	/// `descendants_of`/`ancestors_of`.
+	pub fn max_depth(mut self, depth: u32) -> Self {
+		self.max_depth = Some(depth);
+		self
+	}
+
+	fn recursive_from(
+		mut self,
+		relation: C::Join,
+		anchor: Expression<C::Query>,
+		direction: TreeDirection,
+	) -> Self {
+		self.recursive = Some(RecursiveQuery::new(
+			self.table,
+			<C::Model as PrimaryKey>::PRIMARY_KEY,
+			relation.descriptor(),
+			direction,
+			anchor,
+		));
+		self
+	}
 }
 
 impl<'a, C, Row> Buildable<C> for ReadQueryBuilder<'a, C, Row>
@@ -1083,32 +2131,75 @@ where
 			where_expr:          None,
 			sort_expr:           None,
 			pagination:          None,
-			include_deleted:     false,
+			deleted:             DeletedRows::Exclude,
 			delete_marker_field: C::Model::delete_marker_field(),
 			selection:           None,
 			group_by:            None,
 			having:              None,
 			full_text_search:    None,
+			recursive:           None,
+			counts:              None,
+			facets:              None,
+			distinct:            None,
+			key_order:           None,
+			all_tenants:         false,
+			unscoped:            false,
+			max_depth:           None,
+			hidden_fields:       Vec::new(),
 			row:                 PhantomData,
 		}
 	}
 
 	fn build(self) -> Self::Plan {
+		let mut recursive = self.recursive;
+		let misplaced_depth = match (recursive.as_mut(), self.max_depth) {
+			(Some(recursive), Some(depth)) => {
+				recursive.max_depth = Some(depth);
+				false
+			}
+			(None, Some(_)) => true,
+			(_, None) => false,
+		};
 		let mut plan = ReadQueryPlan {
-			joins:               self.joins,
-			where_expr:          self.where_expr,
-			sort_expr:           self.sort_expr,
-			pagination:          self.pagination,
-			table:               self.table,
-			include_deleted:     self.include_deleted,
+			joins: self.joins,
+			where_expr: self.where_expr,
+			sort_expr: self.sort_expr,
+			pagination: self.pagination,
+			table: self.table,
+			deleted: self.deleted,
 			delete_marker_field: self.delete_marker_field,
-			selection:           self.selection,
-			group_by:            self.group_by,
-			having:              self.having,
-			full_text_search:    self.full_text_search,
-			aggregate_filter:    None,
-			row:                 PhantomData,
+			selection: self.selection,
+			group_by: self.group_by,
+			having: self.having,
+			full_text_search: self.full_text_search,
+			aggregate_filter: None,
+			recursive,
+			counts: self.counts,
+			facets: self.facets,
+			distinct: self.distinct,
+			key_order: self.key_order,
+			tenant: TenantScope::resolve(self.all_tenants),
+			default_scope: if self.unscoped {
+				None
+			} else {
+				C::Query::get_default_scope()
+			},
+			unsupported: None,
+			hidden_fields: self.hidden_fields,
+			row: PhantomData,
+		};
+		let validated = if misplaced_depth {
+			Err(
+				"max_depth requires descendants_of(...) or ancestors_of(...)"
+					.to_string(),
+			)
+		} else {
+			plan.validate()
 		};
+		plan.unsupported = validated.err().map(|reason| UnsupportedRead {
+			table: plan.table.to_string(),
+			reason,
+		});
 		plan.compute_aggregate_filter();
 		plan
 	}
@@ -1136,12 +2227,21 @@ where
 			where_expr:          self.where_expr,
 			sort_expr:           self.sort_expr,
 			pagination:          self.pagination,
-			include_deleted:     self.include_deleted,
+			deleted:             self.deleted,
 			delete_marker_field: self.delete_marker_field,
 			selection:           Some(selection),
 			group_by:            self.group_by,
 			having:              self.having,
 			full_text_search:    self.full_text_search,
+			recursive:           self.recursive,
+			counts:              self.counts,
+			facets:              self.facets,
+			distinct:            self.distinct,
+			key_order:           self.key_order,
+			all_tenants:         self.all_tenants,
+			unscoped:            self.unscoped,
+			max_depth:           self.max_depth,
+			hidden_fields:       self.hidden_fields,
 			row:                 PhantomData,
 		}
 	}
@@ -1152,8 +2252,15 @@ where
 		self
 	}
 
+	/// ANDs `having` with the conditions of earlier calls.
 	pub fn having(mut self, having: HavingList) -> Self {
-		self.having = Some(having.into_predicates());
+		let having = having.into_expr();
+		self.having = Some(match self.having.take() {
+			Some(existing) if !existing.is_empty() => {
+				HavingExpr::And(vec![existing, having])
+			}
+			_ => having,
+		});
 		self
 	}
 }
@@ -6,7 +6,9 @@ use sqlx::{
 	Type,
 };
 use sqlxo_traits::{
+	JoinPath,
 	QueryModel,
+	Sortable,
 	SqlWrite,
 };
 use std::{
@@ -14,7 +16,10 @@ use std::{
 	sync::Arc,
 };
 
-use crate::blocks::SqlWriter;
+use crate::blocks::{
+	SortOrder,
+	SqlWriter,
+};
 
 /// Marker trait for model columns that can participate in `take!`.
 pub trait Column: Copy {
@@ -36,6 +41,132 @@ impl SelectionColumn {
 	}
 }
 
+impl<C: Column> From<C> for SelectionColumn {
+	fn from(_: C) -> Self {
+		Self::new(C::TABLE, C::NAME)
+	}
+}
+
+/// A column holding values of type `T` for updates of `M`, e.g. the source
+/// of a `{Field}SetColumn` update. Converted from a column of `M` or built
+/// with [`joined`](Self::joined) for a table the update joins.
+pub struct ColumnRef<M, T> {
+	column: SelectionColumn,
+	path:   Option<JoinPath>,
+	ty:     PhantomData<fn() -> (M, T)>,
+}
+
+impl<M, T> ColumnRef<M, T> {
+	/// `column` of the table `path` ends at; the update joins `path`.
+	pub fn joined<C>(path: JoinPath, _: C) -> Self
+	where
+		C: Column<Type = T>,
+	{
+		let table = path
+			.segments()
+			.last()
+			.map(|segment| segment.descriptor.right_table);
+		assert_eq!(
+			table,
+			Some(C::TABLE),
+			"`{}` is not a column of the table the join path ends at",
+			C::NAME
+		);
+		Self {
+			column: SelectionColumn::new(C::TABLE, C::NAME),
+			path:   Some(path),
+			ty:     PhantomData,
+		}
+	}
+
+	pub fn column(&self) -> SelectionColumn {
+		self.column
+	}
+
+	pub fn path(&self) -> Option<&JoinPath> {
+		self.path.as_ref()
+	}
+
+	/// The column qualified with its table or join alias.
+	pub fn qualified(&self) -> String {
+		let table = match &self.path {
+			Some(path) => path.alias(),
+			None => self.column.table.to_string(),
+		};
+		format!(r#""{}"."{}""#, table, self.column.column)
+	}
+}
+
+impl<C: Column> From<C> for ColumnRef<C::Model, C::Type> {
+	fn from(_: C) -> Self {
+		Self {
+			column: SelectionColumn::new(C::TABLE, C::NAME),
+			path:   None,
+			ty:     PhantomData,
+		}
+	}
+}
+
+impl<M, T> Clone for ColumnRef<M, T> {
+	fn clone(&self) -> Self {
+		Self {
+			column: self.column,
+			path:   self.path.clone(),
+			ty:     PhantomData,
+		}
+	}
+}
+
+impl<M, T> std::fmt::Debug for ColumnRef<M, T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ColumnRef")
+			.field("column", &self.column)
+			.field("path", &self.path)
+			.finish()
+	}
+}
+
+impl<M, T> PartialEq for ColumnRef<M, T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.column == other.column && self.path == other.path
+	}
+}
+
+/// A single column or a tuple of columns.
+pub trait ColumnList {
+	fn into_columns(self) -> SmallVec<[SelectionColumn; 4]>;
+}
+
+impl<C: Column> ColumnList for C {
+	fn into_columns(self) -> SmallVec<[SelectionColumn; 4]> {
+		smallvec::smallvec![SelectionColumn::new(C::TABLE, C::NAME)]
+	}
+}
+
+macro_rules! impl_column_list_tuple {
+	($($name:ident),+) => {
+		impl<$($name: Column),+> ColumnList for ($($name,)+) {
+			fn into_columns(self) -> SmallVec<[SelectionColumn; 4]> {
+				smallvec::smallvec![
+					$(SelectionColumn::new($name::TABLE, $name::NAME)),+
+				]
+			}
+		}
+	};
+}
+
+impl_column_list_tuple!(A, B);
+impl_column_list_tuple!(A, B, C);
+impl_column_list_tuple!(A, B, C, D);
+
+/// `SELECT DISTINCT` over the whole row, or `DISTINCT ON` a key keeping the
+/// first row per key in sort order.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Distinct {
+	Rows,
+	On(SmallVec<[SelectionColumn; 4]>),
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum AggregateFunction {
 	Count,
@@ -44,6 +175,13 @@ pub enum AggregateFunction {
 	Avg,
 	Min,
 	Max,
+	StringAgg,
+	ArrayAgg,
+	BoolAnd,
+	BoolOr,
+	StdDev,
+	Variance,
+	PercentileCont,
 }
 
 impl AggregateFunction {
@@ -54,6 +192,65 @@ impl AggregateFunction {
 			Self::Avg => "AVG",
 			Self::Min => "MIN",
 			Self::Max => "MAX",
+			Self::StringAgg => "STRING_AGG",
+			Self::ArrayAgg => "ARRAY_AGG",
+			Self::BoolAnd => "BOOL_AND",
+			Self::BoolOr => "BOOL_OR",
+			Self::StdDev => "STDDEV",
+			Self::Variance => "VARIANCE",
+			Self::PercentileCont => "PERCENTILE_CONT",
+		}
+	}
+}
+
+/// `ORDER BY` inside an ordered aggregate such as `STRING_AGG`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AggregateOrder {
+	pub column:     SelectionColumn,
+	pub descending: bool,
+}
+
+/// A fraction between 0 and 1, stored by its bits so that selections
+/// holding one stay `Eq`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fraction(u64);
+
+impl Fraction {
+	/// Panics unless `value` lies between 0 and 1.
+	pub fn new(value: f64) -> Self {
+		assert!(
+			(0.0..=1.0).contains(&value),
+			"percentile fraction must be between 0 and 1 but got {value}",
+		);
+		// Adding zero turns `-0.0` into `0.0`, so equal values share bits.
+		Self((value + 0.0).to_bits())
+	}
+
+	pub fn get(self) -> f64 {
+		f64::from_bits(self.0)
+	}
+}
+
+impl std::fmt::Debug for Fraction {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		self.get().fmt(f)
+	}
+}
+
+/// Arguments besides the aggregated column.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AggregateOptions {
+	pub separator: Option<&'static str>,
+	pub order:     Option<AggregateOrder>,
+	pub fraction:  Option<Fraction>,
+}
+
+impl AggregateOptions {
+	pub const fn none() -> Self {
+		Self {
+			separator: None,
+			order:     None,
+			fraction:  None,
 		}
 	}
 }
@@ -62,6 +259,7 @@ impl AggregateFunction {
 pub struct AggregateSelection {
 	pub function: AggregateFunction,
 	pub column:   Option<SelectionColumn>,
+	pub options:  AggregateOptions,
 }
 
 impl AggregateSelection {
@@ -69,29 +267,70 @@ impl AggregateSelection {
 		function: AggregateFunction,
 		column: Option<SelectionColumn>,
 	) -> Self {
-		Self { function, column }
+		Self {
+			function,
+			column,
+			options: AggregateOptions::none(),
+		}
 	}
 
 	pub const fn with_column(
 		function: AggregateFunction,
 		column: SelectionColumn,
 	) -> Self {
-		Self {
-			function,
-			column: Some(column),
-		}
+		Self::new(function, Some(column))
+	}
+
+	pub const fn with_options(mut self, options: AggregateOptions) -> Self {
+		self.options = options;
+		self
 	}
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WindowFunction {
+	RowNumber,
+	Rank,
+	DenseRank,
+	Lag(u32),
+	Lead(u32),
+	RunningSum,
+}
+
+impl WindowFunction {
+	pub const fn sql_name(&self) -> &'static str {
+		match self {
+			Self::RowNumber => "ROW_NUMBER",
+			Self::Rank => "RANK",
+			Self::DenseRank => "DENSE_RANK",
+			Self::Lag(_) => "LAG",
+			Self::Lead(_) => "LEAD",
+			Self::RunningSum => "SUM",
+		}
+	}
+}
+
+/// A window function call with its `OVER (...)` clause. `order_by` holds the
+/// rendered `SortOrder` of the base model.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WindowSelection {
+	pub function:     WindowFunction,
+	pub column:       Option<SelectionColumn>,
+	pub partition_by: SmallVec<[SelectionColumn; 2]>,
+	pub order_by:     Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum SelectionEntry {
 	Column(SelectionColumn),
 	Aggregate(AggregateSelection),
+	Window(WindowSelection),
 }
 
 #[derive(Debug, Clone)]
 pub struct SelectionList<Output, Store = SelectionColumn> {
 	pub(crate) entries: SmallVec<[Store; 4]>,
+	aliases:            SmallVec<[&'static str; 4]>,
 	_marker:            PhantomData<Output>,
 }
 
@@ -99,10 +338,30 @@ impl<Output, Store> SelectionList<Output, Store> {
 	pub fn new(entries: SmallVec<[Store; 4]>) -> Self {
 		Self {
 			entries,
+			aliases: SmallVec::new(),
 			_marker: PhantomData,
 		}
 	}
 
+	/// Names the selected expressions, one alias per entry, so rows can be
+	/// decoded by name rather than by position.
+	pub fn with_aliases(
+		mut self,
+		aliases: impl IntoIterator<Item = &'static str>,
+	) -> Self {
+		self.aliases = aliases.into_iter().collect();
+		assert_eq!(
+			self.aliases.len(),
+			self.entries.len(),
+			"expected one alias per selected expression",
+		);
+		self
+	}
+
+	pub fn aliases(&self) -> &[&'static str] {
+		&self.aliases
+	}
+
 	pub fn entries(&self) -> &[Store] {
 		&self.entries
 	}
@@ -138,6 +397,15 @@ impl<Output> SelectionList<Output, SelectionColumn> {
 		table: &str,
 	) {
 		qb.push(" RETURNING ");
+		self.push_columns(qb, table);
+	}
+
+	/// Writes the columns qualified with `table`.
+	pub(crate) fn push_columns(
+		&self,
+		qb: &mut QueryBuilder<'static, Postgres>,
+		table: &str,
+	) {
 		for (idx, col) in self.entries.iter().enumerate() {
 			assert_eq!(
 				col.table, table,
@@ -162,6 +430,9 @@ impl<Output> SelectionList<Output, SelectionEntry> {
 				SelectionEntry::Aggregate(_) => {
 					panic!("aggregates are not supported in this context")
 				}
+				SelectionEntry::Window(_) => {
+					panic!("window functions are not supported in this context")
+				}
 			}
 		}
 		SelectionList::new(cols)
@@ -180,6 +451,22 @@ pub fn push_returning<Output>(
 	}
 }
 
+/// Like [`push_returning`], but `RETURNING *` of a mutation with joined
+/// tables would also return their columns.
+pub(crate) fn push_mutation_returning<Output>(
+	qb: &mut QueryBuilder<'static, Postgres>,
+	table: &str,
+	joined: bool,
+	selection: Option<&SelectionList<Output, SelectionColumn>>,
+) {
+	match selection {
+		None if joined => {
+			qb.push(format!(r#" RETURNING "{table}".*"#));
+		}
+		selection => push_returning(qb, table, selection),
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct GroupByList {
 	columns: SmallVec<[SelectionColumn; 4]>,
@@ -272,22 +559,56 @@ impl HavingPredicate {
 	}
 }
 
+#[derive(Clone)]
+pub enum HavingExpr {
+	And(Vec<HavingExpr>),
+	Or(Vec<HavingExpr>),
+	Not(Box<HavingExpr>),
+	Leaf(HavingPredicate),
+}
+
+impl From<HavingPredicate> for HavingExpr {
+	fn from(predicate: HavingPredicate) -> Self {
+		HavingExpr::Leaf(predicate)
+	}
+}
+
+impl HavingExpr {
+	/// `true` for an empty AND/OR group, which renders no condition at all.
+	pub fn is_empty(&self) -> bool {
+		match self {
+			Self::And(xs) | Self::Or(xs) => xs.is_empty(),
+			Self::Not(_) | Self::Leaf(_) => false,
+		}
+	}
+}
+
 #[derive(Clone)]
 pub struct HavingList {
-	predicates: Vec<HavingPredicate>,
+	expr: HavingExpr,
 }
 
 impl HavingList {
 	pub fn new(predicates: Vec<HavingPredicate>) -> Self {
-		Self { predicates }
+		Self {
+			expr: HavingExpr::And(
+				predicates.into_iter().map(HavingExpr::from).collect(),
+			),
+		}
 	}
 
-	pub fn predicates(&self) -> &[HavingPredicate] {
-		&self.predicates
+	pub fn expr(&self) -> &HavingExpr {
+		&self.expr
 	}
 
-	pub fn into_predicates(self) -> Vec<HavingPredicate> {
-		self.predicates
+	pub fn into_expr(self) -> HavingExpr {
+		self.expr
+	}
+}
+
+impl From<HavingExpr> for HavingList {
+	fn from(expr: HavingExpr) -> Self {
+		Self { expr }
 	}
 }
 
@@ -308,6 +629,80 @@ where
 	}
 }
 
+/// Window function selected through `take!`, decoding as `Output`.
+///
+/// `M` is the base model; `order_by` accepts its `SortOrder` while
+/// `partition_by` takes any selectable column, including joined ones.
+pub struct WindowExpr<M, Output> {
+	selection: WindowSelection,
+	_marker:   PhantomData<fn() -> (M, Output)>,
+}
+
+impl<M, Output> WindowExpr<M, Output> {
+	pub fn new(
+		function: WindowFunction,
+		column: Option<SelectionColumn>,
+	) -> Self {
+		Self {
+			selection: WindowSelection {
+				function,
+				column,
+				partition_by: SmallVec::new(),
+				order_by: None,
+			},
+			_marker:   PhantomData,
+		}
+	}
+
+	pub fn partition_by<C: Column>(mut self, _: C) -> Self {
+		self.selection
+			.partition_by
+			.push(SelectionColumn::new(C::TABLE, C::NAME));
+		self
+	}
+
+	pub fn order_by<S>(mut self, order: SortOrder<S>) -> Self
+	where
+		S: Sortable<Entity = M>,
+	{
+		self.selection.order_by = Some(order.to_sql());
+		self
+	}
+
+	pub fn selection(&self) -> &WindowSelection {
+		&self.selection
+	}
+}
+
+impl<M, Output> SelectionExpr for WindowExpr<M, Output> {
+	type Output = Output;
+
+	fn record(self, entries: &mut SmallVec<[SelectionEntry; 4]>) {
+		entries.push(SelectionEntry::Window(self.selection));
+	}
+}
+
+/// Decodes the wrapped selection as `Option`, for columns that can come back
+/// `NULL` even though the model declares them non-null (e.g. base table
+/// columns under a `RIGHT` or `FULL OUTER` join).
+#[derive(Clone, Copy)]
+pub struct NullableExpr<E>(E);
+
+pub const fn nullable<E: SelectionExpr>(expr: E) -> NullableExpr<E> {
+	NullableExpr(expr)
+}
+
+impl<E> SelectionExpr for NullableExpr<E>
+where
+	E: SelectionExpr,
+{
+	type Output = Option<E::Output>;
+
+	fn record(self, entries: &mut SmallVec<[SelectionEntry; 4]>) {
+		self.0.record(entries);
+	}
+}
+
 #[derive(Clone, Copy)]
 pub struct CountAllExpr;
 
@@ -413,6 +808,161 @@ impl<C: Column> Default for MaxExpr<C> {
 	}
 }
 
+/// `STRING_AGG(column::text, separator)`, optionally ordered.
+#[derive(Clone, Copy)]
+pub struct StringAggExpr<C: Column> {
+	separator: &'static str,
+	order:     Option<AggregateOrder>,
+	_column:   PhantomData<C>,
+}
+
+impl<C: Column> StringAggExpr<C> {
+	pub const fn new(separator: &'static str) -> Self {
+		Self {
+			separator,
+			order: None,
+			_column: PhantomData,
+		}
+	}
+
+	pub const fn order_by_asc<O: Column>(self, _: O) -> Self {
+		self.ordered::<O>(false)
+	}
+
+	pub const fn order_by_desc<O: Column>(self, _: O) -> Self {
+		self.ordered::<O>(true)
+	}
+
+	const fn ordered<O: Column>(mut self, descending: bool) -> Self {
+		self.order = Some(AggregateOrder {
+			column: SelectionColumn::new(O::TABLE, O::NAME),
+			descending,
+		});
+		self
+	}
+}
+
+/// `ARRAY_AGG(column)`, optionally ordered.
+#[derive(Clone, Copy)]
+pub struct ArrayAggExpr<C: Column> {
+	order:   Option<AggregateOrder>,
+	_column: PhantomData<C>,
+}
+
+impl<C: Column> ArrayAggExpr<C> {
+	pub const fn new() -> Self {
+		Self {
+			order:   None,
+			_column: PhantomData,
+		}
+	}
+
+	pub const fn order_by_asc<O: Column>(self, _: O) -> Self {
+		self.ordered::<O>(false)
+	}
+
+	pub const fn order_by_desc<O: Column>(self, _: O) -> Self {
+		self.ordered::<O>(true)
+	}
+
+	const fn ordered<O: Column>(mut self, descending: bool) -> Self {
+		self.order = Some(AggregateOrder {
+			column: SelectionColumn::new(O::TABLE, O::NAME),
+			descending,
+		});
+		self
+	}
+}
+
+impl<C: Column> Default for ArrayAggExpr<C> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[derive(Clone, Copy)]
+pub struct BoolAndExpr<C: Column>(PhantomData<C>);
+
+impl<C: Column> BoolAndExpr<C> {
+	pub const fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<C: Column> Default for BoolAndExpr<C> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[derive(Clone, Copy)]
+pub struct BoolOrExpr<C: Column>(PhantomData<C>);
+
+impl<C: Column> BoolOrExpr<C> {
+	pub const fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<C: Column> Default for BoolOrExpr<C> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Sample standard deviation, returned as `float8`.
+#[derive(Clone, Copy)]
+pub struct StdDevExpr<C: Column>(PhantomData<C>);
+
+impl<C: Column> StdDevExpr<C> {
+	pub const fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<C: Column> Default for StdDevExpr<C> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Sample variance, returned as `float8`.
+#[derive(Clone, Copy)]
+pub struct VarianceExpr<C: Column>(PhantomData<C>);
+
+impl<C: Column> VarianceExpr<C> {
+	pub const fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<C: Column> Default for VarianceExpr<C> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// `PERCENTILE_CONT(fraction) WITHIN GROUP (ORDER BY column)`, returned as
+/// `float8`.
+#[derive(Clone, Copy)]
+pub struct PercentileContExpr<C: Column> {
+	fraction: Fraction,
+	_column:  PhantomData<C>,
+}
+
+impl<C: Column> PercentileContExpr<C> {
+	pub fn new(fraction: f64) -> Self {
+		Self {
+			fraction: Fraction::new(fraction),
+			_column:  PhantomData,
+		}
+	}
+
+	pub fn median() -> Self {
+		Self::new(0.5)
+	}
+}
+
 impl SelectionExpr for CountAllExpr {
 	type Output = i64;
 
@@ -556,6 +1106,171 @@ where
 	}
 }
 
+impl<C> SelectionExpr for StringAggExpr<C>
+where
+	C: Column,
+{
+	type Output = Option<String>;
+
+	fn record(self, entries: &mut SmallVec<[SelectionEntry; 4]>) {
+		entries.push(SelectionEntry::Aggregate(self.selection()));
+	}
+}
+
+impl<C> AggregateSelectionExpr for StringAggExpr<C>
+where
+	C: Column,
+{
+	fn selection(&self) -> AggregateSelection {
+		let column = SelectionColumn::new(C::TABLE, C::NAME);
+		AggregateSelection::with_column(AggregateFunction::StringAgg, column)
+			.with_options(AggregateOptions {
+				separator: Some(self.separator),
+				order:     self.order,
+				fraction:  None,
+			})
+	}
+}
+
+impl<C> SelectionExpr for ArrayAggExpr<C>
+where
+	C: Column,
+{
+	type Output = Option<Vec<C::Type>>;
+
+	fn record(self, entries: &mut SmallVec<[SelectionEntry; 4]>) {
+		entries.push(SelectionEntry::Aggregate(self.selection()));
+	}
+}
+
+impl<C> AggregateSelectionExpr for ArrayAggExpr<C>
+where
+	C: Column,
+{
+	fn selection(&self) -> AggregateSelection {
+		let column = SelectionColumn::new(C::TABLE, C::NAME);
+		AggregateSelection::with_column(AggregateFunction::ArrayAgg, column)
+			.with_options(AggregateOptions {
+				separator: None,
+				order:     self.order,
+				fraction:  None,
+			})
+	}
+}
+
+impl<C> SelectionExpr for BoolAndExpr<C>
+where
+	C: Column,
+{
+	type Output = Option<bool>;
+
+	fn record(self, entries: &mut SmallVec<[SelectionEntry; 4]>) {
+		entries.push(SelectionEntry::Aggregate(self.selection()));
+	}
+}
+
+impl<C> AggregateSelectionExpr for BoolAndExpr<C>
+where
+	C: Column,
+{
+	fn selection(&self) -> AggregateSelection {
+		let column = SelectionColumn::new(C::TABLE, C::NAME);
+		AggregateSelection::with_column(AggregateFunction::BoolAnd, column)
+	}
+}
+
+impl<C> SelectionExpr for BoolOrExpr<C>
+where
+	C: Column,
+{
+	type Output = Option<bool>;
+
+	fn record(self, entries: &mut SmallVec<[SelectionEntry; 4]>) {
+		entries.push(SelectionEntry::Aggregate(self.selection()));
+	}
+}
+
+impl<C> AggregateSelectionExpr for BoolOrExpr<C>
+where
+	C: Column,
+{
+	fn selection(&self) -> AggregateSelection {
+		let column = SelectionColumn::new(C::TABLE, C::NAME);
+		AggregateSelection::with_column(AggregateFunction::BoolOr, column)
+	}
+}
+
+impl<C> SelectionExpr for StdDevExpr<C>
+where
+	C: Column,
+{
+	type Output = Option<f64>;
+
+	fn record(self, entries: &mut SmallVec<[SelectionEntry; 4]>) {
+		entries.push(SelectionEntry::Aggregate(self.selection()));
+	}
+}
+
+impl<C> AggregateSelectionExpr for StdDevExpr<C>
+where
+	C: Column,
+{
+	fn selection(&self) -> AggregateSelection {
+		let column = SelectionColumn::new(C::TABLE, C::NAME);
+		AggregateSelection::with_column(AggregateFunction::StdDev, column)
+	}
+}
+
+impl<C> SelectionExpr for VarianceExpr<C>
+where
+	C: Column,
+{
+	type Output = Option<f64>;
+
+	fn record(self, entries: &mut SmallVec<[SelectionEntry; 4]>) {
+		entries.push(SelectionEntry::Aggregate(self.selection()));
+	}
+}
+
+impl<C> AggregateSelectionExpr for VarianceExpr<C>
+where
+	C: Column,
+{
+	fn selection(&self) -> AggregateSelection {
+		let column = SelectionColumn::new(C::TABLE, C::NAME);
+		AggregateSelection::with_column(AggregateFunction::Variance, column)
+	}
+}
+
+impl<C> SelectionExpr for PercentileContExpr<C>
+where
+	C: Column,
+{
+	type Output = Option<f64>;
+
+	fn record(self, entries: &mut SmallVec<[SelectionEntry; 4]>) {
+		entries.push(SelectionEntry::Aggregate(self.selection()));
+	}
+}
+
+impl<C> AggregateSelectionExpr for PercentileContExpr<C>
+where
+	C: Column,
+{
+	fn selection(&self) -> AggregateSelection {
+		let column = SelectionColumn::new(C::TABLE, C::NAME);
+		AggregateSelection::with_column(
+			AggregateFunction::PercentileCont,
+			column,
+		)
+		.with_options(AggregateOptions {
+			separator: None,
+			order:     None,
+			fraction:  Some(self.fraction),
+		})
+	}
+}
+
 pub trait AggregateSelectionExpr: Copy {
 	fn selection(&self) -> AggregateSelection;
 }
@@ -708,6 +1423,13 @@ impl_aggregate_predicate_methods!(SumExpr<C> where C: Column);
 impl_aggregate_predicate_methods!(AvgExpr<C> where C: Column);
 impl_aggregate_predicate_methods!(MinExpr<C> where C: Column);
 impl_aggregate_predicate_methods!(MaxExpr<C> where C: Column);
+impl_aggregate_predicate_methods!(StringAggExpr<C> where C: Column);
+impl_aggregate_predicate_methods!(ArrayAggExpr<C> where C: Column);
+impl_aggregate_predicate_methods!(BoolAndExpr<C> where C: Column);
+impl_aggregate_predicate_methods!(BoolOrExpr<C> where C: Column);
+impl_aggregate_predicate_methods!(StdDevExpr<C> where C: Column);
+impl_aggregate_predicate_methods!(VarianceExpr<C> where C: Column);
+impl_aggregate_predicate_methods!(PercentileContExpr<C> where C: Column);
 
 #[derive(Clone, Copy)]
 pub struct SelectionOutput<T>(pub PhantomData<T>);
@@ -827,11 +1549,33 @@ macro_rules! group_by {
 #[macro_export]
 macro_rules! having {
 	($first:expr $(, $rest:expr)* $(,)?) => {{
-		let mut __preds = Vec::new();
-		__preds.push($first);
-		$(
-			__preds.push($rest);
-		)*
-		$crate::select::HavingList::new(__preds)
+		$crate::select::HavingList::from($crate::having_and![$first $(, $rest)*])
 	}};
 }
+
+#[macro_export]
+macro_rules! having_and {
+	( $( $e:expr ),* $(,)? ) => {
+		$crate::select::HavingExpr::And(vec![
+			$( $crate::select::HavingExpr::from($e) ),*
+		])
+	};
+}
+
+#[macro_export]
+macro_rules! having_or {
+	( $( $e:expr ),* $(,)? ) => {
+		$crate::select::HavingExpr::Or(vec![
+			$( $crate::select::HavingExpr::from($e) ),*
+		])
+	};
+}
+
+#[macro_export]
+macro_rules! having_not {
+	( $e:expr $(,)? ) => {
+		$crate::select::HavingExpr::Not(::std::boxed::Box::new(
+			$crate::select::HavingExpr::from($e),
+		))
+	};
+}
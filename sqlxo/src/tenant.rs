@@ -0,0 +1,71 @@
+use std::future::Future;
+
+use sqlxo_traits::{
+	Error,
+	MissingTenant,
+	TenantId,
+};
+
+tokio::task_local! {
+	static TENANT: TenantId;
+}
+
+/// Runs `f` with `tenant` as the current tenant. Plans of models with a
+/// `#[sqlxo(tenant)]` column built inside only see and write the rows of
+/// that tenant, joined tenant tables included; built outside they fail with
+/// [`MissingTenant`] unless `.all_tenants()` was called.
+pub async fn scope<F: Future>(tenant: impl Into<TenantId>, f: F) -> F::Output {
+	TENANT.scope(tenant.into(), f).await
+}
+
+/// Runs `f` with `tenant` as the current tenant.
+pub fn sync_scope<R>(tenant: impl Into<TenantId>, f: impl FnOnce() -> R) -> R {
+	TENANT.sync_scope(tenant.into(), f)
+}
+
+/// The tenant of the surrounding [`scope`], if any.
+pub fn current() -> Option<TenantId> {
+	TENANT.try_with(TenantId::clone).ok()
+}
+
+/// The tenant a plan is restricted to, fixed when the plan is built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum TenantScope {
+	Tenant(TenantId),
+	/// `.all_tenants()` lifted the restriction.
+	AllTenants,
+	/// Built outside of a [`scope`].
+	Missing,
+}
+
+impl TenantScope {
+	pub(crate) fn resolve(all_tenants: bool) -> Self {
+		if all_tenants {
+			return Self::AllTenants;
+		}
+		current().map_or(Self::Missing, Self::Tenant)
+	}
+
+	pub(crate) fn tenant(&self) -> Option<&TenantId> {
+		match self {
+			Self::Tenant(tenant) => Some(tenant),
+			Self::AllTenants | Self::Missing => None,
+		}
+	}
+
+	/// Fails when rows of the tenant-scoped `table` would be touched
+	/// without a tenant.
+	pub(crate) fn check(
+		&self,
+		table: &str,
+		tenant_field: Option<&str>,
+	) -> Result<(), Error> {
+		if tenant_field.is_none() || *self != Self::Missing {
+			return Ok(());
+		}
+		Err(MissingTenant {
+			table: table.to_string(),
+		}
+		.into())
+	}
+}
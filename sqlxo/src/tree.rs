@@ -0,0 +1,177 @@
+use sqlxo_traits::{
+	Filterable,
+	JoinDescriptor,
+	SqlWrite,
+};
+
+use crate::blocks::{
+	join_condition,
+	path_key,
+	Expression,
+	SqlWriter,
+};
+
+/// Name of the recursive CTE. The outer query aliases it as the base table.
+pub const TREE_CTE: &str = "__sqlxo_tree";
+/// Distance from the anchor row, `0` for the anchor itself.
+pub const DEPTH_COLUMN: &str = "__sqlxo_depth";
+/// Primary keys from the anchor down to the row, used for ordering, cycle
+/// detection and tree assembly.
+pub const PATH_COLUMN: &str = "__sqlxo_path";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeDirection {
+	Descendants,
+	Ancestors,
+}
+
+pub struct RecursiveQuery<Q: Filterable> {
+	pub(crate) direction:  TreeDirection,
+	pub(crate) anchor:     Expression<Q>,
+	pub(crate) parent_key: &'static [&'static str],
+	pub(crate) child_fk:   &'static [&'static str],
+	pub(crate) max_depth:  Option<u32>,
+}
+
+impl<Q: Filterable> RecursiveQuery<Q> {
+	/// `relation` may point either from a child to its parent (`belongs_to`)
+	/// or from a parent to its children (`has_many`/`has_one`); the side
+	/// holding the primary key decides which one it is.
+	pub(crate) fn new(
+		table: &str,
+		primary_key: &'static [&'static str],
+		relation: JoinDescriptor,
+		direction: TreeDirection,
+		anchor: Expression<Q>,
+	) -> Self {
+		assert!(
+			relation.left_table == table &&
+				relation.right_table == table &&
+				relation.through.is_none(),
+			"recursive queries require a self-referential relation on `{}` \
+			 but got `{}` -> `{}`",
+			table,
+			relation.left_table,
+			relation.right_table,
+		);
+
+		let (parent_key, child_fk) = if relation.left_fields == primary_key {
+			(relation.left_fields, relation.right_fields)
+		} else {
+			(relation.right_fields, relation.left_fields)
+		};
+
+		Self {
+			direction,
+			anchor,
+			parent_key,
+			child_fk,
+			max_depth: None,
+		}
+	}
+
+	/// Writes everything after the anchor's `FROM` up to and including the
+	/// closing parenthesis of the CTE.
+	pub(crate) fn write_body(
+		&self,
+		w: &mut SqlWriter,
+		table: &str,
+		primary_key: &[&str],
+		delete_marker: Option<&str>,
+		tenant_field: Option<&str>,
+		default_scope: Option<&Expression<Q>>,
+	) {
+		let live = delete_marker
+			.map(|field| format!(r#" AND "{}"."{}" IS NULL"#, table, field));
+		let tenant = tenant_field.zip(w.tenant().cloned());
+		let scope = |w: &mut SqlWriter| {
+			if let Some(live) = &live {
+				w.push(live);
+			}
+			if let Some((field, tenant)) = &tenant {
+				w.push(" AND ");
+				w.push_tenant_condition(tenant, table, field);
+			}
+			if let Some(scope) = default_scope {
+				w.push(" AND (");
+				scope.write(w);
+				w.push(")");
+			}
+		};
+
+		w.push(" WHERE (");
+		self.anchor.write(w);
+		w.push(")");
+		scope(w);
+
+		let (next_fields, tree_fields) = match self.direction {
+			TreeDirection::Descendants => (self.child_fk, self.parent_key),
+			TreeDirection::Ancestors => (self.parent_key, self.child_fk),
+		};
+		let key = path_key(table, primary_key);
+
+		w.push(&format!(
+			r#" UNION ALL SELECT "{table}".*, "{cte}"."{depth}" + 1, "{cte}"."{path}" || {key} FROM {table} INNER JOIN "{cte}" ON {on} WHERE {key} <> ALL("{cte}"."{path}")"#,
+			cte = TREE_CTE,
+			depth = DEPTH_COLUMN,
+			path = PATH_COLUMN,
+			on = join_condition(table, next_fields, TREE_CTE, tree_fields),
+		));
+		scope(w);
+		if let Some(max) = self.max_depth {
+			w.push(&format!(r#" AND "{}"."{}" < "#, TREE_CTE, DEPTH_COLUMN));
+			w.bind(i32::try_from(max).unwrap_or(i32::MAX));
+		}
+		w.push(")");
+	}
+}
+
+/// A row of a recursive read together with the rows reached from it.
+///
+/// Nodes nest in traversal order: for `ancestors_of` the anchor is the root
+/// and each parent is the single child of the node before it.
+#[derive(Debug, Clone)]
+pub struct TreeNode<M> {
+	pub node:     M,
+	pub depth:    i32,
+	pub children: Vec<TreeNode<M>>,
+}
+
+/// Nests `(path, node)` pairs below their closest ancestor in the result.
+/// Rows whose ancestors were filtered out become roots.
+pub(crate) fn assemble<M>(
+	mut entries: Vec<(Vec<String>, TreeNode<M>)>,
+) -> Vec<TreeNode<M>> {
+	entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+	let mut roots = Vec::new();
+	let mut stack: Vec<(Vec<String>, TreeNode<M>)> = Vec::new();
+
+	for (path, node) in entries {
+		while let Some((top, _)) = stack.last() {
+			if path.len() > top.len() && path.starts_with(top) {
+				break;
+			}
+			let (_, done) = stack.pop().expect("stack is not empty");
+			attach(&mut stack, &mut roots, done);
+		}
+		stack.push((path, node));
+	}
+
+	while let Some((_, done)) = stack.pop() {
+		attach(&mut stack, &mut roots, done);
+	}
+
+	roots
+}
+
+fn attach<M>(
+	stack: &mut [(Vec<String>, TreeNode<M>)],
+	roots: &mut Vec<TreeNode<M>>,
+	node: TreeNode<M>,
+) {
+	match stack.last_mut() {
+		Some((_, parent)) => parent.children.push(node),
+		None => roots.push(node),
+	}
+}
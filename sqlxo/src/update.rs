@@ -1,27 +1,49 @@
 use std::marker::PhantomData;
 
 use sqlx::{
+	Acquire,
 	Executor,
 	Postgres,
 };
 use sqlxo_traits::{
+	Error,
+	Filterable,
+	JoinKind,
+	JoinPath,
 	QueryContext,
+	SqlWrite,
+	StaleVersion,
+	TenantScoped,
 	Updatable,
 	UpdateModel,
+	UpdateOperation,
+	Versioned,
 };
 
 use crate::{
 	and,
+	audit::{
+		AuditAction,
+		AuditTrail,
+	},
 	blocks::{
+		assert_join_start,
+		is_joined,
+		AuditedHead,
 		BuildableFilter,
+		BuildableJoin,
+		BuildableJoinFilter,
 		Expression,
+		JoinFilter,
 		SqlWriter,
 		UpdateHead,
 	},
+	guard,
 	select::{
 		self,
 		SelectionList,
 	},
+	tenant::TenantScope,
 	Buildable,
 	ExecutablePlan,
 	FetchablePlan,
@@ -30,7 +52,9 @@ use crate::{
 
 #[allow(dead_code)]
 pub trait BuildableUpdateQuery<C, Row = <C as QueryContext>::Model>:
-	Buildable<C, Row = Row, Plan: Planable<C, Row>> + BuildableFilter<C>
+	Buildable<C, Row = Row, Plan: Planable<C, Row>>
+	+ BuildableFilter<C>
+	+ BuildableJoin<C>
 where
 	C: QueryContext,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
@@ -44,10 +68,18 @@ pub struct UpdateQueryPlan<
 > where
 	C::Model: Updatable,
 {
+	pub(crate) joins: Option<Vec<JoinPath>>,
+	pub(crate) join_filters: Vec<JoinFilter>,
 	pub(crate) where_expr: Option<Expression<C::Query>>,
 	pub(crate) table: &'a str,
-	pub(crate) update_model: <C::Model as Updatable>::UpdateModel,
+	pub(crate) update_model: Option<<C::Model as Updatable>::UpdateModel>,
+	pub(crate) update_ops: Vec<<C::Model as Updatable>::UpdateOp>,
 	pub(crate) update_marker_field: Option<&'static str>,
+	pub(crate) expected_version: Option<i64>,
+	pub(crate) all_rows: bool,
+	pub(crate) max_affected_rows: Option<u64>,
+	pub(crate) tenant: TenantScope,
+	pub(crate) audit: Option<AuditTrail>,
 	pub(crate) selection: Option<SelectionList<Row, select::SelectionColumn>>,
 	row: PhantomData<Row>,
 }
@@ -58,8 +90,9 @@ where
 	C::Model: Updatable,
 {
 	fn to_query_builder(&self) -> sqlx::QueryBuilder<'static, Postgres> {
-		let head = UpdateHead::new(self.table);
-		let mut w = SqlWriter::new(head);
+		let head =
+			AuditedHead::new(UpdateHead::new(self.table), self.audit.is_some());
+		let mut w = SqlWriter::new(head).with_tenant(self.tenant.tenant());
 
 		let has_marker = self.update_marker_field.is_some();
 
@@ -69,21 +102,215 @@ where
 			qb.push(" = NOW()");
 		}
 
-		let _ = self
-			.update_model
-			.apply_updates(w.query_builder_mut(), has_marker);
+		let mut has_previous = has_marker;
+		if let Some(version) = <C::Model as Updatable>::VERSION_FIELD {
+			let qb = w.query_builder_mut();
+			if has_previous {
+				qb.push(", ");
+			}
+			// Qualified, since `FROM` may join a table with the same column.
+			qb.push(format!(r#"{version} = "{}"."{version}" + 1"#, self.table));
+			has_previous = true;
+		}
+
+		if let Some(model) = &self.update_model {
+			has_previous |= !model
+				.apply_updates(w.query_builder_mut(), has_previous)
+				.is_empty();
+		}
+
+		for op in &self.update_ops {
+			let qb = w.query_builder_mut();
+			if has_previous {
+				qb.push(", ");
+			}
+			op.write(qb);
+			has_previous = true;
+		}
+
+		if let Some(js) = &self.joins {
+			w.push_mutation_joins(" FROM ", js, &self.join_filters, self.table);
+		}
+		w.push_tenant_where(self.table, C::Model::TENANT_FIELD);
 
 		if let Some(e) = &self.where_expr {
 			w.push_where(e);
 		}
 
+		if let (Some(version), Some(expected)) = (
+			<C::Model as Updatable>::VERSION_FIELD,
+			self.expected_version,
+		) {
+			let qualified = format!(r#""{}"."{}" = "#, self.table, version);
+			w.push_where_raw(|writer| {
+				writer.push(&qualified);
+				writer.bind(expected);
+			});
+		}
+
 		w.into_builder()
 	}
 
+	/// The statement without `RETURNING`.
+	fn to_execute_builder(&self) -> sqlx::QueryBuilder<'static, Postgres> {
+		let mut qb = self.to_query_builder();
+		if let Some(audit) = &self.audit {
+			audit.push_count(&mut qb, self.table);
+		}
+		qb
+	}
+
+	fn push_returning(&self, qb: &mut sqlx::QueryBuilder<'static, Postgres>) {
+		if let Some(audit) = &self.audit {
+			audit.push_returning(qb, self.table, self.selection.as_ref());
+			return;
+		}
+		select::push_mutation_returning(
+			qb,
+			self.table,
+			self.joins.is_some(),
+			self.selection.as_ref(),
+		);
+	}
+
+	fn check_tenant(&self) -> Result<(), Error> {
+		self.tenant.check(self.table, C::Model::TENANT_FIELD)
+	}
+
+	fn check_filtered(&self) -> Result<(), Error> {
+		guard::check_filtered(
+			self.table,
+			self.all_rows ||
+				self.where_expr.is_some() ||
+				!self.join_filters.is_empty(),
+		)
+	}
+
+	/// Turns "no row updated" into [`StaleVersion`] when the update was
+	/// guarded by an expected version.
+	fn check_version(&self, updated: bool) -> Result<(), Error> {
+		match self.expected_version {
+			Some(expected) if !updated => Err(StaleVersion {
+				table: self.table.to_string(),
+				expected,
+			}
+			.into()),
+			_ => Ok(()),
+		}
+	}
+
 	#[cfg(any(test, feature = "test-utils"))]
 	pub fn sql(&self) -> String {
 		use sqlx::Execute;
-		self.to_query_builder().build().sql().to_string()
+		self.to_execute_builder().build().sql().to_string()
+	}
+}
+
+impl<'a, C, Row> UpdateQueryPlan<'a, C, Row>
+where
+	C: QueryContext,
+	C::Model: Updatable,
+	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+{
+	/// Updates the rows. With a `max_affected_rows` the update runs in a
+	/// transaction that is rolled back when it changes more rows.
+	pub async fn execute_atomic<'c, A>(&self, conn: A) -> Result<u64, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		let Some(max) = self.max_affected_rows else {
+			let mut conn = conn.acquire().await?;
+			return ExecutablePlan::execute(self, &mut *conn).await;
+		};
+
+		self.check_tenant()?;
+		self.check_filtered()?;
+		let mut tx = conn.begin().await?;
+		let rows = self
+			.to_execute_builder()
+			.build()
+			.execute(&mut *tx)
+			.await?
+			.rows_affected();
+		guard::check_affected(self.table, max, rows)?;
+		self.check_version(rows > 0)?;
+		tx.commit().await?;
+
+		Ok(rows)
+	}
+
+	/// See [`execute_atomic`](Self::execute_atomic).
+	pub async fn fetch_one_atomic<'c, A>(&self, conn: A) -> Result<Row, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		let Some(max) = self.max_affected_rows else {
+			let mut conn = conn.acquire().await?;
+			return FetchablePlan::fetch_one(self, &mut *conn).await;
+		};
+
+		let rows = self.fetch_capped(conn, max).await?;
+		self.check_version(!rows.is_empty())?;
+		rows.into_iter()
+			.next()
+			.ok_or(Error::Sqlx(sqlx::Error::RowNotFound))
+	}
+
+	/// See [`execute_atomic`](Self::execute_atomic).
+	pub async fn fetch_all_atomic<'c, A>(
+		&self,
+		conn: A,
+	) -> Result<Vec<Row>, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		let Some(max) = self.max_affected_rows else {
+			let mut conn = conn.acquire().await?;
+			return FetchablePlan::fetch_all(self, &mut *conn).await;
+		};
+
+		let rows = self.fetch_capped(conn, max).await?;
+		self.check_version(!rows.is_empty())?;
+		Ok(rows)
+	}
+
+	/// See [`execute_atomic`](Self::execute_atomic).
+	pub async fn fetch_optional_atomic<'c, A>(
+		&self,
+		conn: A,
+	) -> Result<Option<Row>, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		let Some(max) = self.max_affected_rows else {
+			let mut conn = conn.acquire().await?;
+			return FetchablePlan::fetch_optional(self, &mut *conn).await;
+		};
+
+		let rows = self.fetch_capped(conn, max).await?;
+		self.check_version(!rows.is_empty())?;
+		Ok(rows.into_iter().next())
+	}
+
+	/// Returns every updated row so the limit sees all of them.
+	async fn fetch_capped<'c, A>(
+		&self,
+		conn: A,
+		max: u64,
+	) -> Result<Vec<Row>, Error>
+	where
+		A: Acquire<'c, Database = Postgres>,
+	{
+		self.check_tenant()?;
+		self.check_filtered()?;
+		let mut tx = conn.begin().await?;
+		let mut qb = self.to_query_builder();
+		self.push_returning(&mut qb);
+		let rows = qb.build_query_as::<Row>().fetch_all(&mut *tx).await?;
+		guard::check_affected(self.table, max, rows.len() as u64)?;
+		tx.commit().await?;
+
+		Ok(rows)
 	}
 }
 
@@ -94,16 +321,20 @@ where
 	C::Model: Updatable,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
 {
-	async fn execute<'e, E>(&self, exec: E) -> Result<u64, sqlx::Error>
+	async fn execute<'e, E>(&self, exec: E) -> Result<u64, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.check_tenant()?;
+		self.check_filtered()?;
+		guard::single_statement(self.table, self.max_affected_rows)?;
 		let rows = self
-			.to_query_builder()
+			.to_execute_builder()
 			.build()
 			.execute(exec)
 			.await?
 			.rows_affected();
+		self.check_version(rows > 0)?;
 
 		Ok(rows)
 	}
@@ -116,34 +347,50 @@ where
 	C::Model: Updatable,
 	Row: Send + Sync + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
 {
-	async fn fetch_one<'e, E>(&self, exec: E) -> Result<Row, sqlx::Error>
+	async fn fetch_one<'e, E>(&self, exec: E) -> Result<Row, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.check_tenant()?;
+		self.check_filtered()?;
+		guard::single_statement(self.table, self.max_affected_rows)?;
 		let mut qb = self.to_query_builder();
-		select::push_returning(&mut qb, self.table, self.selection.as_ref());
-		qb.build_query_as::<Row>().fetch_one(exec).await
+		self.push_returning(&mut qb);
+		match qb.build_query_as::<Row>().fetch_one(exec).await {
+			Err(sqlx::Error::RowNotFound) => {
+				self.check_version(false)?;
+				Err(sqlx::Error::RowNotFound.into())
+			}
+			result => Ok(result?),
+		}
 	}
 
-	async fn fetch_all<'e, E>(&self, exec: E) -> Result<Vec<Row>, sqlx::Error>
+	async fn fetch_all<'e, E>(&self, exec: E) -> Result<Vec<Row>, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.check_tenant()?;
+		self.check_filtered()?;
+		guard::single_statement(self.table, self.max_affected_rows)?;
 		let mut qb = self.to_query_builder();
-		select::push_returning(&mut qb, self.table, self.selection.as_ref());
-		qb.build_query_as::<Row>().fetch_all(exec).await
+		self.push_returning(&mut qb);
+		let rows = qb.build_query_as::<Row>().fetch_all(exec).await?;
+		self.check_version(!rows.is_empty())?;
+		Ok(rows)
 	}
 
-	async fn fetch_optional<'e, E>(
-		&self,
-		exec: E,
-	) -> Result<Option<Row>, sqlx::Error>
+	async fn fetch_optional<'e, E>(&self, exec: E) -> Result<Option<Row>, Error>
 	where
 		E: Executor<'e, Database = Postgres>,
 	{
+		self.check_tenant()?;
+		self.check_filtered()?;
+		guard::single_statement(self.table, self.max_affected_rows)?;
 		let mut qb = self.to_query_builder();
-		select::push_returning(&mut qb, self.table, self.selection.as_ref());
-		qb.build_query_as::<Row>().fetch_optional(exec).await
+		self.push_returning(&mut qb);
+		let row = qb.build_query_as::<Row>().fetch_optional(exec).await?;
+		self.check_version(row.is_some())?;
+		Ok(row)
 	}
 }
 
@@ -163,9 +410,16 @@ pub struct UpdateQueryBuilder<
 	C::Model: Updatable,
 {
 	pub(crate) table: &'a str,
+	pub(crate) joins: Option<Vec<JoinPath>>,
+	pub(crate) join_filters: Vec<JoinFilter>,
 	pub(crate) where_expr: Option<Expression<C::Query>>,
 	pub(crate) update_model: Option<<C::Model as Updatable>::UpdateModel>,
+	pub(crate) update_ops: Vec<<C::Model as Updatable>::UpdateOp>,
 	pub(crate) update_marker_field: Option<&'static str>,
+	pub(crate) expected_version: Option<i64>,
+	pub(crate) all_rows: bool,
+	pub(crate) max_affected_rows: Option<u64>,
+	pub(crate) all_tenants: bool,
 	pub(crate) selection: Option<SelectionList<Row, select::SelectionColumn>>,
 	row: PhantomData<Row>,
 }
@@ -182,6 +436,62 @@ where
 		self.update_model = Some(model);
 		self
 	}
+
+	/// Adds an assignment computed from the current row, e.g.
+	/// `StockItemUpdateOp::AmountDecrement(1)`. Combine with a `WHERE` guard
+	/// to book stock atomically.
+	/// Assignments reading a joined column join its path unless it already
+	/// is.
+	pub fn set(mut self, op: <C::Model as Updatable>::UpdateOp) -> Self {
+		if let Some(path) = op.join_path() {
+			if !is_joined(self.joins.as_deref(), path) {
+				self = self.join_path(path.clone());
+			}
+		}
+		self.update_ops.push(op);
+		self
+	}
+
+	/// Allows the update to run without a filter, changing every row.
+	pub fn all_rows(mut self) -> Self {
+		self.all_rows = true;
+		self
+	}
+
+	/// Updates the rows of every tenant, e.g. for admin queries.
+	pub fn all_tenants(mut self) -> Self {
+		self.all_tenants = true;
+		self
+	}
+
+	/// Fails and rolls back the update when it would change more than `max`
+	/// rows.
+	pub fn max_affected_rows(mut self, max: u64) -> Self {
+		self.max_affected_rows = Some(max);
+		self
+	}
+
+	/// Only updates rows whose row at the end of `path` matches `e`. The path
+	/// is joined unless it already is.
+	pub fn where_join<F>(self, path: JoinPath, e: Expression<F>) -> Self
+	where
+		F: Filterable + Send + Sync + 'static,
+	{
+		self.join_filter(JoinFilter::new(path, e))
+	}
+}
+
+impl<'a, C, Row> UpdateQueryBuilder<'a, C, Row>
+where
+	C: QueryContext,
+	C::Model: Versioned,
+{
+	/// Only updates rows still at `version`; the plan fails with
+	/// [`StaleVersion`] when none is.
+	pub fn expected_version(mut self, version: i64) -> Self {
+		self.expected_version = Some(version);
+		self
+	}
 }
 
 impl<'a, C, Row> Buildable<C> for UpdateQueryBuilder<'a, C, Row>
@@ -196,26 +506,45 @@ where
 	fn from_ctx() -> Self {
 		Self {
 			table:               C::TABLE,
+			joins:               None,
+			join_filters:        Vec::new(),
 			where_expr:          None,
 			update_model:        None,
+			update_ops:          Vec::new(),
 			update_marker_field: <C::Model as Updatable>::UPDATE_MARKER_FIELD,
+			expected_version:    None,
+			all_rows:            false,
+			max_affected_rows:   None,
+			all_tenants:         false,
 			selection:           None,
 			row:                 PhantomData,
 		}
 	}
 
 	fn build(self) -> Self::Plan {
-		let update_model = self
-			.update_model
-			.expect("update model must be set with .model()");
+		assert!(
+			self.update_model.is_some() || !self.update_ops.is_empty(),
+			"update model must be set with .model() or .set(...)"
+		);
 
 		UpdateQueryPlan {
-			where_expr: self.where_expr,
-			table: self.table,
-			update_model,
+			joins:               self.joins,
+			join_filters:        self.join_filters,
+			where_expr:          self.where_expr,
+			table:               self.table,
+			update_model:        self.update_model,
+			update_ops:          self.update_ops,
 			update_marker_field: self.update_marker_field,
-			selection: self.selection,
-			row: PhantomData,
+			expected_version:    self.expected_version,
+			all_rows:            self.all_rows,
+			max_affected_rows:   self.max_affected_rows,
+			tenant:              TenantScope::resolve(self.all_tenants),
+			audit:               AuditTrail::resolve::<C::Model>(
+				AuditAction::Update,
+				true,
+			),
+			selection:           self.selection,
+			row:                 PhantomData,
 		}
 	}
 }
@@ -238,9 +567,16 @@ where
 	{
 		UpdateQueryBuilder {
 			table:               self.table,
+			joins:               self.joins,
+			join_filters:        self.join_filters,
 			where_expr:          self.where_expr,
 			update_model:        self.update_model,
+			update_ops:          self.update_ops,
 			update_marker_field: self.update_marker_field,
+			expected_version:    self.expected_version,
+			all_rows:            self.all_rows,
+			max_affected_rows:   self.max_affected_rows,
+			all_tenants:         self.all_tenants,
 			selection:           Some(selection.expect_columns()),
 			row:                 PhantomData,
 		}
@@ -262,6 +598,40 @@ where
 	}
 }
 
+impl<'a, C, Row> BuildableJoin<C> for UpdateQueryBuilder<'a, C, Row>
+where
+	C: QueryContext,
+	C::Model: Updatable,
+{
+	fn join(self, join: <C as QueryContext>::Join, kind: JoinKind) -> Self {
+		self.join_path(JoinPath::from_join(join, kind))
+	}
+
+	fn join_path(mut self, path: JoinPath) -> Self {
+		assert_join_start(&path, self.table);
+		match &mut self.joins {
+			Some(existing) => existing.push(path),
+			None => self.joins = Some(vec![path]),
+		};
+
+		self
+	}
+}
+
+impl<'a, C, Row> BuildableJoinFilter<C> for UpdateQueryBuilder<'a, C, Row>
+where
+	C: QueryContext,
+	C::Model: Updatable,
+{
+	fn join_filter(mut self, filter: JoinFilter) -> Self {
+		if !is_joined(self.joins.as_deref(), filter.path()) {
+			self = self.join_path(filter.path().clone());
+		}
+		self.join_filters.push(filter);
+		self
+	}
+}
+
 impl<'a, C, Row> BuildableUpdateQuery<C, Row> for UpdateQueryBuilder<'a, C, Row>
 where
 	C: QueryContext,
@@ -2,26 +2,46 @@ use crate::{
 	blocks::{
 		BuildableFilter,
 		BuildableJoin,
+		BuildableJoinFilter,
 		BuildablePage,
 		BuildableSort,
 		Expression,
 		Pagination,
 		SortOrder,
 	},
-	select::HavingList,
+	facet::Facet,
+	select::{
+		GroupByList,
+		HavingExpr,
+		HavingList,
+		SelectionEntry,
+		SelectionList,
+	},
 	web::{
+		policy::{
+			AllowAll,
+			Authorizer,
+		},
 		AggregateBindable,
 		GenericWebExpression,
+		JoinFilterBindable,
 		JoinPayload,
+		WebAction,
 		WebAggregateExpression,
+		WebAggregateRow,
 		WebDeleteFilter,
 		WebExpression,
+		WebJoinFilter,
+		WebPolicy,
+		WebPurgeFilter,
 		WebQueryError,
 		WebReadFilter,
+		WebRestoreFilter,
 		WebSearchPayload,
 		WebUpdateFilter,
 	},
 	DeleteQueryBuilder,
+	Purging,
 	QueryBuilder,
 	ReadQueryBuilder,
 	UpdateQueryBuilder,
@@ -36,10 +56,12 @@ use sqlxo_traits::{
 	QueryContext,
 	WebJoinGraph,
 	WebJoinPayload,
+	WebLeaf,
 	WebQueryModel,
+	WebSelectField,
 };
 
-fn map_expr<C, D>(e: &WebExpression<D>) -> Expression<C::Query>
+pub(crate) fn map_expr<C, D>(e: &WebExpression<D>) -> Expression<C::Query>
 where
 	C: QueryContext,
 	D: WebQueryModel + Bind<C>,
@@ -57,41 +79,101 @@ where
 	}
 }
 
-fn collect_having_predicates<C, D>(
-	expr: &WebAggregateExpression<D>,
-	out: &mut Vec<crate::select::HavingPredicate>,
+fn collect_leaves<'e, Q>(
+	expr: &'e GenericWebExpression<Q>,
+	out: &mut Vec<&'e Q>,
 ) where
+	Q: WebLeaf + serde::Serialize,
+{
+	match expr {
+		GenericWebExpression::And { and: group } |
+		GenericWebExpression::Or { or: group } => {
+			for expr in group {
+				collect_leaves(expr, out);
+			}
+		}
+		GenericWebExpression::Leaf(leaf) => out.push(leaf),
+	}
+}
+
+/// Keys of the DTO fields `expr` compares.
+fn filter_fields<D>(expr: Option<&WebExpression<D>>) -> Vec<&'static str>
+where
+	D: WebQueryModel,
+{
+	let mut leaves = Vec::new();
+	if let Some(expr) = expr {
+		collect_leaves(expr, &mut leaves);
+	}
+	leaves.into_iter().map(D::leaf_field).collect()
+}
+
+fn map_having_expr<C, D>(
+	expr: &WebAggregateExpression<D>,
+) -> Result<HavingExpr, WebQueryError>
+where
 	C: QueryContext,
 	D: WebQueryModel + AggregateBindable<C>,
 {
 	match expr {
 		GenericWebExpression::And { and } => {
-			for inner in and {
-				collect_having_predicates::<C, D>(inner, out);
-			}
-		}
-		GenericWebExpression::Or { .. } => {
-			panic!("OR is not supported in aggregate filters");
+			map_having_group::<C, D>(and, "and").map(HavingExpr::And)
 		}
-		GenericWebExpression::Leaf(leaf) => {
-			out.push(<D as AggregateBindable<C>>::map_aggregate_leaf(leaf));
+		GenericWebExpression::Or { or } => {
+			map_having_group::<C, D>(or, "or").map(HavingExpr::Or)
 		}
+		GenericWebExpression::Leaf(leaf) => Ok(HavingExpr::Leaf(
+			<D as AggregateBindable<C>>::map_aggregate_leaf(leaf),
+		)),
 	}
 }
 
-fn resolve_web_join<C>(segments: &[String]) -> JoinPath
+fn map_having_group<C, D>(
+	group: &[WebAggregateExpression<D>],
+	operator: &'static str,
+) -> Result<Vec<HavingExpr>, WebQueryError>
+where
+	C: QueryContext,
+	D: WebQueryModel + AggregateBindable<C>,
+{
+	if group.is_empty() {
+		return Err(WebQueryError::EmptyHavingGroup { operator });
+	}
+	group.iter().map(map_having_expr::<C, D>).collect()
+}
+
+fn resolve_web_join<C>(segments: &[String], kind: JoinKind) -> JoinPath
 where
 	C: QueryContext,
 	C::Model: WebJoinGraph,
 {
 	let refs: Vec<&str> = segments.iter().map(String::as_str).collect();
-	<C::Model as WebJoinGraph>::resolve_join_path(&refs, JoinKind::Left)
-		.unwrap_or_else(|| {
+	<C::Model as WebJoinGraph>::resolve_join_path(&refs, kind).unwrap_or_else(
+		|| {
 			panic!(
 				"invalid join path {:?} for model {}",
 				segments,
 				std::any::type_name::<C::Model>()
 			);
+		},
+	)
+}
+
+fn resolve_count_relation<C>(
+	relation: &str,
+) -> Result<sqlxo_traits::JoinDescriptor, WebQueryError>
+where
+	C: QueryContext,
+	C::Model: WebJoinGraph,
+{
+	<C::Model as WebJoinGraph>::resolve_join_path(&[relation], JoinKind::Left)
+		.and_then(|path| match path.segments() {
+			[segment] => Some(segment.descriptor),
+			_ => None,
+		})
+		.ok_or_else(|| WebQueryError::CountRelationInvalid {
+			model:    std::any::type_name::<C::Model>(),
+			relation: relation.to_string(),
 		})
 }
 
@@ -255,7 +337,120 @@ where
 		D: WebQueryModel + Bind<C> + AggregateBindable<C>,
 		C::Model: crate::GetDeleteMarker + sqlxo_traits::JoinNavigationModel,
 	{
-		ParsedWebReadQuery::<C, D>::new(dto).into_read_builder()
+		Self::try_from_web_read_for::<D, _>(dto, &AllowAll, &())
+	}
+
+	/// Like [`try_from_web_read`](Self::try_from_web_read) but lets `policy`
+	/// restrict what `principal` may read. The fields it hides are left out
+	/// of the rows of [`fetch_web_page`](crate::ReadQueryPlan::fetch_web_page).
+	pub fn try_from_web_read_for<D, P>(
+		dto: &WebReadFilter<D>,
+		policy: &P,
+		principal: &P::Principal,
+	) -> Result<ReadQueryBuilder<'a, C>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + AggregateBindable<C>,
+		C::Model: crate::GetDeleteMarker + sqlxo_traits::JoinNavigationModel,
+		P: WebPolicy<C, D>,
+	{
+		if dto.group_by.is_some() {
+			return Err(WebQueryError::GroupedReadRequired {
+				section: "groupBy",
+			});
+		}
+		if dto.aggregates.is_some() {
+			return Err(WebQueryError::GroupedReadRequired {
+				section: "aggregates",
+			});
+		}
+		let auth = Authorizer::new(policy, principal);
+		let mut builder =
+			ParsedWebReadQuery::<C, D>::new(dto)?.into_read_builder(&auth)?;
+		builder.hidden_fields = auth.hidden_fields();
+		Ok(builder)
+	}
+
+	/// Builds a grouped read from the `groupBy` and `aggregates` sections.
+	/// Rows come back as JSON objects keyed by the names used in the payload.
+	pub fn try_from_web_aggregate<D>(
+		dto: &WebReadFilter<D>,
+	) -> Result<ReadQueryBuilder<'a, C, WebAggregateRow>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + AggregateBindable<C>,
+		C::Model: crate::GetDeleteMarker + sqlxo_traits::JoinNavigationModel,
+	{
+		Self::try_from_web_aggregate_for::<D, _>(dto, &AllowAll, &())
+	}
+
+	/// Like [`try_from_web_aggregate`](Self::try_from_web_aggregate) but
+	/// lets `policy` restrict what `principal` may group and aggregate.
+	pub fn try_from_web_aggregate_for<D, P>(
+		dto: &WebReadFilter<D>,
+		policy: &P,
+		principal: &P::Principal,
+	) -> Result<ReadQueryBuilder<'a, C, WebAggregateRow>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + AggregateBindable<C>,
+		C::Model: crate::GetDeleteMarker + sqlxo_traits::JoinNavigationModel,
+		P: WebPolicy<C, D>,
+	{
+		if dto.search.is_some() {
+			return Err(WebQueryError::GroupedReadUnsupported {
+				section: "search",
+			});
+		}
+		if dto.counts.is_some() {
+			return Err(WebQueryError::GroupedReadUnsupported {
+				section: "counts",
+			});
+		}
+		if dto.facets.is_some() {
+			return Err(WebQueryError::GroupedReadUnsupported {
+				section: "facets",
+			});
+		}
+
+		let group_by = dto.group_by.as_deref().unwrap_or_default();
+		let aggregates = dto.aggregates.as_deref().unwrap_or_default();
+		if group_by.is_empty() && aggregates.is_empty() {
+			return Err(WebQueryError::EmptyGroupedRead);
+		}
+
+		let auth = Authorizer::new(policy, principal);
+		for field in group_by.iter().filter_map(WebSelectField::source_field) {
+			auth.read("groupBy", field)?;
+		}
+		for field in aggregates.iter().filter_map(WebSelectField::source_field)
+		{
+			auth.read("aggregates", field)?;
+		}
+
+		let columns: smallvec::SmallVec<_> = group_by
+			.iter()
+			.map(<D as AggregateBindable<C>>::map_group_field)
+			.collect();
+		let mut entries: smallvec::SmallVec<[SelectionEntry; 4]> = columns
+			.iter()
+			.copied()
+			.map(SelectionEntry::Column)
+			.collect();
+		entries.extend(aggregates.iter().map(|field| {
+			SelectionEntry::Aggregate(
+				<D as AggregateBindable<C>>::map_aggregate_field(field),
+			)
+		}));
+		let names = group_by
+			.iter()
+			.map(WebSelectField::web_name)
+			.chain(aggregates.iter().map(WebSelectField::web_name));
+		let selection = SelectionList::new(entries).with_aliases(names);
+
+		let mut builder =
+			ParsedWebReadQuery::<C, D>::new(dto)?.into_read_builder(&auth)?;
+		if !columns.is_empty() {
+			builder = builder.group_by(GroupByList::new(columns));
+		}
+		Ok(builder.take(selection))
 	}
 
 	pub fn from_web_read<D>(dto: &WebReadFilter<D>) -> ReadQueryBuilder<'a, C>
@@ -269,48 +464,363 @@ where
 		)
 	}
 
+	/// Builds the update without validating the payload: one without a
+	/// filter fails with [`UnfilteredMutation`](crate::UnfilteredMutation)
+	/// when executed and a `version` for a model that is not versioned is
+	/// ignored. [`try_from_web_update`](Self::try_from_web_update) rejects
+	/// both up front.
 	pub fn from_web_update<D>(
 		dto: &WebUpdateFilter<D>,
 	) -> UpdateQueryBuilder<'a, C>
 	where
-		D: WebQueryModel + Bind<C>,
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
 		C::Model: crate::Updatable,
 	{
-		apply_mutation_filter::<C, D, UpdateQueryBuilder<'a, C>>(
-			QueryBuilder::<C>::update(),
-			dto.filter.as_ref(),
-		)
+		let mut builder =
+			apply_mutation_filter::<C, D, UpdateQueryBuilder<'a, C>>(
+				QueryBuilder::<C>::update(),
+				dto.joins.as_deref(),
+				dto.join_filter.as_ref(),
+				dto.filter.as_ref(),
+			);
+		if <C::Model as crate::Updatable>::VERSION_FIELD.is_some() {
+			builder.expected_version = dto.version;
+		}
+		builder
+	}
+
+	/// Fails with [`WebQueryError::UnfilteredMutation`] when the payload has
+	/// neither a `filter` nor a `joinFilter` and with
+	/// [`WebQueryError::VersionUnsupported`] when it sends a `version` for a
+	/// model that is not versioned.
+	pub fn try_from_web_update<D>(
+		dto: &WebUpdateFilter<D>,
+	) -> Result<UpdateQueryBuilder<'a, C>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
+		C::Model: crate::Updatable,
+	{
+		Self::try_from_web_update_for::<D, _>(dto, &AllowAll, &())
+	}
+
+	/// Like [`try_from_web_update`](Self::try_from_web_update) but lets
+	/// `policy` restrict which rows `principal` may update.
+	pub fn try_from_web_update_for<D, P>(
+		dto: &WebUpdateFilter<D>,
+		policy: &P,
+		principal: &P::Principal,
+	) -> Result<UpdateQueryBuilder<'a, C>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
+		C::Model: crate::Updatable,
+		P: WebPolicy<C, D>,
+	{
+		require_mutation_filter::<C>(
+			dto.filter.is_some() ||
+				!map_join_filter::<D>(dto.join_filter.as_ref()).is_empty(),
+		)?;
+		if dto.version.is_some() &&
+			<C::Model as crate::Updatable>::VERSION_FIELD.is_none()
+		{
+			return Err(WebQueryError::VersionUnsupported {
+				model: std::any::type_name::<C::Model>(),
+			});
+		}
+		let mut builder =
+			authorize_mutation::<C, D, P, UpdateQueryBuilder<'a, C>>(
+				apply_mutation_filter::<C, D, UpdateQueryBuilder<'a, C>>(
+					QueryBuilder::<C>::update(),
+					dto.joins.as_deref(),
+					dto.join_filter.as_ref(),
+					dto.filter.as_ref(),
+				),
+				&Authorizer::new(policy, principal),
+				WebAction::Update,
+				dto.joins.as_deref(),
+				dto.join_filter.as_ref(),
+				dto.filter.as_ref(),
+			)?;
+		builder.expected_version = dto.version;
+		Ok(builder)
 	}
 
+	/// Builds the delete without validating the payload: one without a
+	/// filter fails with [`UnfilteredMutation`](crate::UnfilteredMutation)
+	/// when executed. [`try_from_web_delete`](Self::try_from_web_delete)
+	/// rejects it up front.
 	pub fn from_web_delete<D>(
 		dto: &WebDeleteFilter<D>,
 	) -> DeleteQueryBuilder<'a, C>
 	where
-		D: WebQueryModel + Bind<C>,
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
 		C::Model: crate::Deletable,
 	{
 		apply_mutation_filter::<C, D, DeleteQueryBuilder<'a, C>>(
 			QueryBuilder::<C>::delete(),
+			dto.joins.as_deref(),
+			dto.join_filter.as_ref(),
+			dto.filter.as_ref(),
+		)
+	}
+
+	/// Fails with [`WebQueryError::UnfilteredMutation`] when the payload has
+	/// neither a `filter` nor a `joinFilter`.
+	pub fn try_from_web_delete<D>(
+		dto: &WebDeleteFilter<D>,
+	) -> Result<DeleteQueryBuilder<'a, C>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
+		C::Model: crate::Deletable,
+	{
+		Self::try_from_web_delete_for::<D, _>(dto, &AllowAll, &())
+	}
+
+	/// Like [`try_from_web_delete`](Self::try_from_web_delete) but lets
+	/// `policy` restrict which rows `principal` may delete.
+	pub fn try_from_web_delete_for<D, P>(
+		dto: &WebDeleteFilter<D>,
+		policy: &P,
+		principal: &P::Principal,
+	) -> Result<DeleteQueryBuilder<'a, C>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
+		C::Model: crate::Deletable,
+		P: WebPolicy<C, D>,
+	{
+		require_mutation_filter::<C>(
+			dto.filter.is_some() ||
+				!map_join_filter::<D>(dto.join_filter.as_ref()).is_empty(),
+		)?;
+		authorize_mutation::<C, D, P, DeleteQueryBuilder<'a, C>>(
+			apply_mutation_filter::<C, D, DeleteQueryBuilder<'a, C>>(
+				QueryBuilder::<C>::delete(),
+				dto.joins.as_deref(),
+				dto.join_filter.as_ref(),
+				dto.filter.as_ref(),
+			),
+			&Authorizer::new(policy, principal),
+			WebAction::Delete,
+			dto.joins.as_deref(),
+			dto.join_filter.as_ref(),
+			dto.filter.as_ref(),
+		)
+	}
+
+	/// Builds the restore without validating the payload: one without a
+	/// filter fails with [`UnfilteredMutation`](crate::UnfilteredMutation)
+	/// when executed. [`try_from_web_restore`](Self::try_from_web_restore)
+	/// rejects it up front.
+	pub fn from_web_restore<D>(
+		dto: &WebRestoreFilter<D>,
+	) -> DeleteQueryBuilder<'a, C>
+	where
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
+		C::Model: crate::SoftDeletable,
+	{
+		apply_mutation_filter::<C, D, DeleteQueryBuilder<'a, C>>(
+			QueryBuilder::<C>::restore(),
+			dto.joins.as_deref(),
+			dto.join_filter.as_ref(),
 			dto.filter.as_ref(),
 		)
 	}
+
+	/// Fails with [`WebQueryError::UnfilteredMutation`] when the payload has
+	/// neither a `filter` nor a `joinFilter`.
+	pub fn try_from_web_restore<D>(
+		dto: &WebRestoreFilter<D>,
+	) -> Result<DeleteQueryBuilder<'a, C>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
+		C::Model: crate::SoftDeletable,
+	{
+		Self::try_from_web_restore_for::<D, _>(dto, &AllowAll, &())
+	}
+
+	/// Like [`try_from_web_restore`](Self::try_from_web_restore) but lets
+	/// `policy` restrict which rows `principal` may restore.
+	pub fn try_from_web_restore_for<D, P>(
+		dto: &WebRestoreFilter<D>,
+		policy: &P,
+		principal: &P::Principal,
+	) -> Result<DeleteQueryBuilder<'a, C>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
+		C::Model: crate::SoftDeletable,
+		P: WebPolicy<C, D>,
+	{
+		require_mutation_filter::<C>(
+			dto.filter.is_some() ||
+				!map_join_filter::<D>(dto.join_filter.as_ref()).is_empty(),
+		)?;
+		authorize_mutation::<C, D, P, DeleteQueryBuilder<'a, C>>(
+			apply_mutation_filter::<C, D, DeleteQueryBuilder<'a, C>>(
+				QueryBuilder::<C>::restore(),
+				dto.joins.as_deref(),
+				dto.join_filter.as_ref(),
+				dto.filter.as_ref(),
+			),
+			&Authorizer::new(policy, principal),
+			WebAction::Restore,
+			dto.joins.as_deref(),
+			dto.join_filter.as_ref(),
+			dto.filter.as_ref(),
+		)
+	}
+
+	/// Builds the purge without validating the payload: one with neither a
+	/// filter nor `deletedBefore` fails with
+	/// [`UnfilteredMutation`](crate::UnfilteredMutation) when executed.
+	/// [`try_from_web_purge`](Self::try_from_web_purge) rejects it up front.
+	pub fn from_web_purge<D>(
+		dto: &WebPurgeFilter<D>,
+	) -> DeleteQueryBuilder<'a, C, C::Model, Purging>
+	where
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
+		C::Model: crate::SoftDeletable,
+	{
+		let builder =
+			apply_mutation_filter::<
+				C,
+				D,
+				DeleteQueryBuilder<'a, C, C::Model, Purging>,
+			>(QueryBuilder::<C>::purge(), None, None, dto.filter.as_ref());
+		match dto.deleted_before {
+			Some(before) => builder.deleted_before(before),
+			None => builder,
+		}
+	}
+
+	/// Fails with [`WebQueryError::UnfilteredMutation`] when the payload has
+	/// neither a `filter` nor `deletedBefore`.
+	pub fn try_from_web_purge<D>(
+		dto: &WebPurgeFilter<D>,
+	) -> Result<DeleteQueryBuilder<'a, C, C::Model, Purging>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
+		C::Model: crate::SoftDeletable,
+	{
+		Self::try_from_web_purge_for::<D, _>(dto, &AllowAll, &())
+	}
+
+	/// Like [`try_from_web_purge`](Self::try_from_web_purge) but lets
+	/// `policy` restrict which rows `principal` may purge.
+	pub fn try_from_web_purge_for<D, P>(
+		dto: &WebPurgeFilter<D>,
+		policy: &P,
+		principal: &P::Principal,
+	) -> Result<DeleteQueryBuilder<'a, C, C::Model, Purging>, WebQueryError>
+	where
+		D: WebQueryModel + Bind<C> + JoinFilterBindable,
+		C::Model: crate::SoftDeletable,
+		P: WebPolicy<C, D>,
+	{
+		require_mutation_filter::<C>(
+			dto.filter.is_some() || dto.deleted_before.is_some(),
+		)?;
+		let builder = authorize_mutation::<
+			C,
+			D,
+			P,
+			DeleteQueryBuilder<'a, C, C::Model, Purging>,
+		>(
+			apply_mutation_filter::<
+				C,
+				D,
+				DeleteQueryBuilder<'a, C, C::Model, Purging>,
+			>(QueryBuilder::<C>::purge(), None, None, dto.filter.as_ref()),
+			&Authorizer::new(policy, principal),
+			WebAction::Purge,
+			None,
+			None,
+			dto.filter.as_ref(),
+		)?;
+		Ok(match dto.deleted_before {
+			Some(before) => builder.deleted_before(before),
+			None => builder,
+		})
+	}
+}
+
+fn require_mutation_filter<C>(filtered: bool) -> Result<(), WebQueryError>
+where
+	C: QueryContext,
+{
+	if filtered {
+		return Ok(());
+	}
+	Err(WebQueryError::UnfilteredMutation {
+		model: std::any::type_name::<C::Model>(),
+	})
+}
+
+fn map_join_filter<D>(filter: Option<&D::JoinFilter>) -> Vec<WebJoinFilter>
+where
+	D: JoinFilterBindable,
+{
+	filter.map(D::map_join_filter).unwrap_or_default()
 }
 
+/// Mutations join inner so the affected rows must have a match.
 fn apply_mutation_filter<C, D, B>(
 	mut builder: B,
+	joins: Option<&[JoinPayload<D::JoinPath>]>,
+	join_filter: Option<&D::JoinFilter>,
 	filter: Option<&WebExpression<D>>,
 ) -> B
 where
 	C: QueryContext,
-	D: WebQueryModel + Bind<C>,
-	B: BuildableFilter<C>,
+	D: WebQueryModel + Bind<C> + JoinFilterBindable,
+	B: BuildableFilter<C> + BuildableJoinFilter<C>,
 {
+	for segments in joins.map(flatten_join_payload).unwrap_or_default() {
+		builder = builder
+			.join_path(resolve_web_join::<C>(&segments, JoinKind::Inner));
+	}
+	for join_filter in map_join_filter::<D>(join_filter) {
+		let path = resolve_web_join::<C>(
+			&[join_filter.relation().to_string()],
+			JoinKind::Inner,
+		);
+		builder = builder.join_filter(join_filter.into_join_filter(path));
+	}
 	if let Some(expr) = filter.map(map_expr::<C, D>) {
 		builder = builder.r#where(expr);
 	}
 	builder
 }
 
+/// Rejects forbidden joins and filter fields and ANDs the policy's row
+/// filter into `builder`.
+fn authorize_mutation<C, D, P, B>(
+	builder: B,
+	auth: &Authorizer<'_, P, C, D>,
+	action: WebAction,
+	joins: Option<&[JoinPayload<D::JoinPath>]>,
+	join_filter: Option<&D::JoinFilter>,
+	filter: Option<&WebExpression<D>>,
+) -> Result<B, WebQueryError>
+where
+	C: QueryContext,
+	D: WebQueryModel + Bind<C> + JoinFilterBindable,
+	P: WebPolicy<C, D>,
+	B: BuildableFilter<C>,
+{
+	for segments in joins.map(flatten_join_payload).unwrap_or_default() {
+		auth.join("joins", &segments)?;
+	}
+	for join_filter in map_join_filter::<D>(join_filter) {
+		auth.join("joinFilter", &[join_filter.relation().to_string()])?;
+	}
+	for field in filter_fields::<D>(filter) {
+		auth.filter("filter", field)?;
+	}
+	Ok(match auth.row_filter(action) {
+		Some(expr) => builder.r#where(expr),
+		None => builder,
+	})
+}
+
 #[derive(Clone)]
 struct ParsedWebReadQuery<C, D>
 where
@@ -320,10 +830,15 @@ where
 	joins:         Option<Vec<JoinPath>>,
 	join_segments: Option<Vec<Vec<String>>>,
 	filter_expr:   Option<Expression<C::Query>>,
+	filter_fields: Vec<&'static str>,
 	sort_expr:     Option<SortOrder<C::Sort>>,
+	sort_fields:   Vec<&'static str>,
 	pagination:    Option<Pagination>,
 	search:        Option<ParsedWebSearch>,
-	having:        Option<Vec<crate::select::HavingPredicate>>,
+	having:        Option<HavingExpr>,
+	having_fields: Vec<&'static str>,
+	counts:        Vec<String>,
+	facets:        Vec<Facet>,
 	_marker:       std::marker::PhantomData<D>,
 }
 
@@ -332,13 +847,13 @@ where
 	C: QueryContext,
 	D: WebQueryModel + Bind<C> + AggregateBindable<C>,
 {
-	fn new(filter: &WebReadFilter<D>) -> Self {
+	fn new(filter: &WebReadFilter<D>) -> Result<Self, WebQueryError> {
 		let (joins, join_segments) = if let Some(nodes) = filter.joins.as_ref()
 		{
 			let flattened = flatten_join_payload(nodes);
 			let resolved = flattened
 				.iter()
-				.map(|segments| resolve_web_join::<C>(segments))
+				.map(|segments| resolve_web_join::<C>(segments, JoinKind::Left))
 				.collect();
 			(Some(resolved), Some(flattened))
 		} else {
@@ -346,6 +861,13 @@ where
 		};
 
 		let filter_expr = filter.filter.as_ref().map(map_expr::<C, D>);
+		let filter_fields = filter_fields::<D>(filter.filter.as_ref());
+		let sort_fields = filter
+			.sort
+			.iter()
+			.flatten()
+			.map(|s| D::sort_field(&s.0))
+			.collect();
 
 		let sort_expr = filter
 			.sort
@@ -364,42 +886,100 @@ where
 			page_size: p.page_size,
 		});
 		let search = filter.search.as_ref().map(ParsedWebSearch::new::<D>);
-		let having = filter.having.as_ref().map(|expr| {
-			let mut preds = Vec::new();
-			collect_having_predicates::<C, D>(expr, &mut preds);
-			preds
-		});
+		let having = filter
+			.having
+			.as_ref()
+			.map(map_having_expr::<C, D>)
+			.transpose()?;
+		let mut having_leaves = Vec::new();
+		if let Some(expr) = &filter.having {
+			collect_leaves(expr, &mut having_leaves);
+		}
+		let having_fields = having_leaves
+			.into_iter()
+			.filter_map(D::aggregate_leaf_field)
+			.collect();
+		let facets = filter
+			.facets
+			.iter()
+			.flatten()
+			.map(|name| {
+				<D as AggregateBindable<C>>::map_facet(name).ok_or_else(|| {
+					WebQueryError::FacetInvalid {
+						model: std::any::type_name::<D>(),
+						facet: name.clone(),
+					}
+				})
+			})
+			.collect::<Result<_, _>>()?;
 
-		Self {
+		Ok(Self {
 			joins,
 			join_segments,
 			filter_expr,
+			filter_fields,
 			sort_expr,
+			sort_fields,
 			pagination,
 			search,
 			having,
+			having_fields,
+			counts: filter.counts.clone().unwrap_or_default(),
+			facets,
 			_marker: std::marker::PhantomData,
-		}
+		})
 	}
 
-	fn into_read_builder<'a>(
+	fn into_read_builder<'a, P>(
 		self,
+		auth: &Authorizer<'_, P, C, D>,
 	) -> Result<ReadQueryBuilder<'a, C>, WebQueryError>
 	where
 		C::Model: crate::GetDeleteMarker + sqlxo_traits::JoinNavigationModel,
+		P: WebPolicy<C, D>,
 	{
 		let ParsedWebReadQuery {
 			joins,
 			join_segments,
 			filter_expr,
+			filter_fields,
 			sort_expr,
+			sort_fields,
 			pagination,
 			search,
 			having,
+			having_fields,
+			counts,
+			facets,
 			..
 		} = self;
 
+		for segments in join_segments.iter().flatten() {
+			auth.join("joins", segments)?;
+		}
+		for field in filter_fields {
+			auth.filter("filter", field)?;
+		}
+		for field in having_fields {
+			auth.filter("having", field)?;
+		}
+		for field in sort_fields {
+			auth.sort(field)?;
+		}
+		for segments in search.iter().flat_map(ParsedWebSearch::join_paths) {
+			auth.join("search", segments)?;
+		}
+		for relation in &counts {
+			auth.join("counts", std::slice::from_ref(relation))?;
+		}
+		for facet in &facets {
+			auth.read("facets", facet.name)?;
+		}
+
 		let mut builder = QueryBuilder::<C>::read();
+		if let Some(expr) = auth.row_filter(WebAction::Read) {
+			builder = builder.r#where(expr);
+		}
 
 		if let Some(joins) = joins {
 			for path in joins {
@@ -416,12 +996,21 @@ where
 			builder = SearchBridge::<C>::apply(builder, &search)?;
 		}
 
-		if let Some(preds) = having {
-			if !preds.is_empty() {
-				builder = builder.having(HavingList::new(preds));
+		if let Some(expr) = having {
+			if !expr.is_empty() {
+				builder = builder.having(HavingList::from(expr));
 			}
 		}
 
+		for relation in &counts {
+			let descriptor = resolve_count_relation::<C>(relation)?;
+			builder = builder.with_count_descriptor(descriptor);
+		}
+
+		if !facets.is_empty() {
+			builder = builder.facets(facets);
+		}
+
 		if let Some(sort) = sort_expr {
 			builder = builder.order_by(sort);
 		}
@@ -3,10 +3,13 @@ use serde::{
 	Serialize,
 };
 use sqlxo_traits::{
+	Bind,
+	JoinPath,
 	QueryContext,
 	WebJoinPayload,
 	WebLeaf,
 	WebQueryModel,
+	WebSelectField,
 	WebSortField,
 };
 use utoipa::{
@@ -14,16 +17,34 @@ use utoipa::{
 	ToSchema,
 };
 
-use crate::select::HavingPredicate;
+use crate::{
+	blocks::JoinFilter,
+	facet::Facet,
+	select::{
+		AggregateSelection,
+		HavingPredicate,
+		SelectionColumn,
+	},
+};
 
 mod builder;
 mod page;
+mod policy;
+mod row;
 pub use page::{
 	WebPage,
 	WebPagination,
 };
+pub use policy::{
+	WebAction,
+	WebPolicy,
+};
+pub use row::{
+	WebAggregateRow,
+	WebNumeric,
+};
 
-/// Errors that may occur while translating a [`WebReadFilter`] into a query.
+/// Errors that may occur while translating a web payload into a query.
 #[derive(Debug, thiserror::Error, PartialEq, Eq)]
 pub enum WebQueryError {
 	/// A `search` payload was provided but the target model does not implement
@@ -44,45 +65,154 @@ pub enum WebQueryError {
 	/// underlying `FullTextSearchable` model.
 	#[error("`{model}` does not expose searchable join `{path}`")]
 	SearchJoinInvalid { model: &'static str, path: String },
+	/// A `having` payload contained an `and`/`or` group without any entries.
+	#[error("`having` contains an empty `{operator}` group")]
+	EmptyHavingGroup { operator: &'static str },
+	/// A `counts` entry referenced a relation the model does not expose.
+	#[error("`{model}` does not expose countable relation `{relation}`")]
+	CountRelationInvalid {
+		model:    &'static str,
+		relation: String,
+	},
+	/// A `facets` entry named a field without `#[sqlxo(facet)]`.
+	#[error("`{model}` does not expose facet `{facet}`")]
+	FacetInvalid { model: &'static str, facet: String },
+	/// `groupBy` or `aggregates` was sent to a read that returns models.
+	#[error(
+		"`{section}` is only supported by grouped reads; use \
+		 `QueryBuilder::try_from_web_aggregate`"
+	)]
+	GroupedReadRequired { section: &'static str },
+	/// A grouped read received a section that only applies to model reads.
+	#[error("`{section}` cannot be combined with `groupBy`/`aggregates`")]
+	GroupedReadUnsupported { section: &'static str },
+	/// A grouped read selected neither `groupBy` fields nor `aggregates`.
+	#[error("grouped reads require `groupBy` or `aggregates`")]
+	EmptyGroupedRead,
+	/// An update or delete payload had no `filter` and would have changed
+	/// every row.
+	#[error("refusing to change every `{model}` row without a `filter`")]
+	UnfilteredMutation { model: &'static str },
+	/// An update payload sent a `version` for a model without a
+	/// `#[sqlxo(version)]` field.
+	#[error("`{model}` is not versioned; omit `version` from the payload")]
+	VersionUnsupported { model: &'static str },
+	/// The [`WebPolicy`] does not let the principal use `name` in `section`.
+	#[error("`{name}` is not allowed in `{section}`")]
+	Forbidden {
+		section: &'static str,
+		name:    String,
+	},
 }
 
 #[derive(Clone, Serialize, Deserialize, ToSchema, Debug, IntoParams)]
 #[serde(bound(deserialize = "Q: WebLeaf + Deserialize<'de>, S: \
                              WebSortField + Deserialize<'de>, A: WebLeaf + \
                              Deserialize<'de>, J: WebJoinPayload + \
+                             Deserialize<'de>, G: WebSelectField + \
+                             Deserialize<'de>, F: WebSelectField + \
                              Deserialize<'de>"))]
 #[into_params(parameter_in = Query)]
-pub struct GenericWebFilter<Q, S, A, J>
+pub struct GenericWebFilter<Q, S, A, J, G, F>
 where
 	Q: WebLeaf + Serialize,
 	S: WebSortField + Serialize,
 	A: WebLeaf + Serialize,
 	J: WebJoinPayload + Serialize,
+	G: WebSelectField,
+	F: WebSelectField,
 {
 	#[schema(nullable)]
-	pub joins:  Option<Vec<JoinPayload<J>>>,
+	pub joins:      Option<Vec<JoinPayload<J>>>,
 	#[schema(no_recursion, nullable)]
-	pub filter: Option<GenericWebExpression<Q>>,
+	pub filter:     Option<GenericWebExpression<Q>>,
 	#[schema(no_recursion, nullable)]
-	pub having: Option<GenericWebExpression<A>>,
+	pub having:     Option<GenericWebExpression<A>>,
 	#[schema(no_recursion, nullable)]
-	pub sort:   Option<Vec<GenericWebSort<S>>>,
+	pub sort:       Option<Vec<GenericWebSort<S>>>,
 	#[schema(no_recursion, nullable)]
-	pub search: Option<GenericWebSearch<J>>,
+	pub search:     Option<GenericWebSearch<J>>,
+	#[schema(nullable)]
+	pub page:       Option<WebPagination>,
+	/// Relations whose row count is projected onto the model.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	#[schema(nullable)]
+	pub counts:     Option<Vec<String>>,
+	/// Fields to group by; only valid for grouped reads.
+	#[serde(
+		rename = "groupBy",
+		default,
+		skip_serializing_if = "Option::is_none"
+	)]
+	#[schema(nullable)]
+	#[param(value_type = Option<Vec<String>>)]
+	pub group_by:   Option<Vec<G>>,
+	/// Aggregates to select; only valid for grouped reads.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	#[schema(nullable)]
+	#[param(value_type = Option<Vec<String>>)]
+	pub aggregates: Option<Vec<F>>,
+	/// Facets to count over the matching rows, declared with
+	/// `#[sqlxo(facet)]` on the DTO.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
 	#[schema(nullable)]
-	pub page:   Option<WebPagination>,
+	pub facets:     Option<Vec<String>>,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema, Debug, IntoParams)]
+#[serde(bound(deserialize = "Q: WebLeaf + Deserialize<'de>, J: \
+                             WebJoinPayload + Deserialize<'de>, F: \
+                             Deserialize<'de>"))]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct GenericWebMutationFilter<Q, J = NoJoins, F = NoJoinFilters>
+where
+	Q: WebLeaf + Serialize,
+	J: WebJoinPayload + Serialize,
+	F: Serialize + ToSchema,
+{
+	/// Relations the affected rows must have a match in, rendered as
+	/// `UPDATE ... FROM` / `DELETE ... USING`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	#[schema(nullable)]
+	pub joins:       Option<Vec<JoinPayload<J>>>,
+	#[schema(no_recursion, nullable)]
+	pub filter:      Option<GenericWebExpression<Q>>,
+	/// Filters on the related rows, keyed by relation. Each relation is
+	/// joined like the `joins` entries.
+	#[serde(
+		rename = "joinFilter",
+		default,
+		skip_serializing_if = "Option::is_none"
+	)]
+	#[schema(nullable)]
+	#[param(value_type = Option<Object>)]
+	pub join_filter: Option<F>,
+	/// Version the client last read; updates of `#[sqlxo(version)]` models
+	/// fail with a stale version error when the row has moved on.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	#[schema(nullable)]
+	pub version:     Option<i64>,
 }
 
 #[derive(Clone, Serialize, Deserialize, ToSchema, Debug, IntoParams)]
 #[serde(bound(deserialize = "Q: WebLeaf + Deserialize<'de>"))]
 #[serde(deny_unknown_fields)]
 #[into_params(parameter_in = Query)]
-pub struct GenericWebMutationFilter<Q>
+pub struct GenericWebPurgeFilter<Q>
 where
 	Q: WebLeaf + Serialize,
 {
 	#[schema(no_recursion, nullable)]
-	pub filter: Option<GenericWebExpression<Q>>,
+	pub filter:         Option<GenericWebExpression<Q>>,
+	/// Only purges rows soft-deleted before this point.
+	#[serde(
+		default,
+		rename = "deletedBefore",
+		skip_serializing_if = "Option::is_none"
+	)]
+	#[schema(nullable)]
+	pub deleted_before: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Clone, Serialize, Deserialize, ToSchema, Debug)]
@@ -140,13 +270,27 @@ pub type WebReadFilter<T> = GenericWebFilter<
 	<T as WebQueryModel>::SortField,
 	<T as WebQueryModel>::AggregateLeaf,
 	<T as WebQueryModel>::JoinPath,
+	<T as WebQueryModel>::GroupField,
+	<T as WebQueryModel>::AggregateField,
 >;
 pub type WebFilter<T> = WebReadFilter<T>;
 pub type WebSearchPayload<T> = GenericWebSearch<<T as WebQueryModel>::JoinPath>;
-pub type WebUpdateFilter<T> =
-	GenericWebMutationFilter<<T as WebQueryModel>::Leaf>;
-pub type WebDeleteFilter<T> =
-	GenericWebMutationFilter<<T as WebQueryModel>::Leaf>;
+pub type WebUpdateFilter<T> = GenericWebMutationFilter<
+	<T as WebQueryModel>::Leaf,
+	<T as WebQueryModel>::JoinPath,
+	<T as WebQueryModel>::JoinFilter,
+>;
+pub type WebDeleteFilter<T> = GenericWebMutationFilter<
+	<T as WebQueryModel>::Leaf,
+	<T as WebQueryModel>::JoinPath,
+	<T as WebQueryModel>::JoinFilter,
+>;
+pub type WebRestoreFilter<T> = GenericWebMutationFilter<
+	<T as WebQueryModel>::Leaf,
+	<T as WebQueryModel>::JoinPath,
+	<T as WebQueryModel>::JoinFilter,
+>;
+pub type WebPurgeFilter<T> = GenericWebPurgeFilter<<T as WebQueryModel>::Leaf>;
 
 pub trait AggregateBindable<C>: WebQueryModel
 where
@@ -155,11 +299,67 @@ where
 	fn map_aggregate_leaf(
 		leaf: &<Self as WebQueryModel>::AggregateLeaf,
 	) -> HavingPredicate;
+
+	fn map_group_field(
+		field: &<Self as WebQueryModel>::GroupField,
+	) -> SelectionColumn;
+
+	fn map_aggregate_field(
+		field: &<Self as WebQueryModel>::AggregateField,
+	) -> AggregateSelection;
+
+	/// Resolves a `facets` entry by its JSON name.
+	fn map_facet(name: &str) -> Option<Facet>;
+}
+
+/// Maps the `joinFilter` of a DTO's mutation payloads to filters on the
+/// joined rows. Implemented by `#[bind]`.
+pub trait JoinFilterBindable: WebQueryModel {
+	/// The context the DTO is bound to.
+	type Context: QueryContext;
+
+	fn map_join_filter(
+		filter: &<Self as WebQueryModel>::JoinFilter,
+	) -> Vec<WebJoinFilter>;
+}
+
+/// One `joinFilter` entry: a filter on the rows of a relation, applied once
+/// the relation is resolved to a join path.
+pub struct WebJoinFilter {
+	relation: &'static str,
+	filter:   Box<dyn FnOnce(JoinPath) -> JoinFilter>,
+}
+
+impl WebJoinFilter {
+	pub fn new<D>(relation: &'static str, expr: &WebExpression<D>) -> Self
+	where
+		D: JoinFilterBindable + Bind<<D as JoinFilterBindable>::Context>,
+		<D::Context as QueryContext>::Query: 'static,
+	{
+		let expr = builder::map_expr::<D::Context, D>(expr);
+		Self {
+			relation,
+			filter: Box::new(move |path| JoinFilter::new(path, expr)),
+		}
+	}
+
+	pub(crate) fn relation(&self) -> &'static str {
+		self.relation
+	}
+
+	pub(crate) fn into_join_filter(self, path: JoinPath) -> JoinFilter {
+		(self.filter)(path)
+	}
 }
 
 #[derive(Clone, Serialize, Deserialize, ToSchema, Debug)]
 pub enum NoJoins {}
 
+/// `joinFilter` of payloads whose model has no relations.
+#[derive(Clone, Serialize, Deserialize, ToSchema, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NoJoinFilters {}
+
 impl WebJoinPayload for NoJoins {
 	fn flatten(&self, _prefix: &mut Vec<String>, _out: &mut Vec<Vec<String>>) {
 		match *self {}
@@ -2,12 +2,26 @@ use serde::{
 	Deserialize,
 	Serialize,
 };
+use serde_json::Value;
+use sqlx::{
+	Executor,
+	Postgres,
+};
+use sqlxo_traits::{
+	Error,
+	JoinNavigationModel,
+	QueryContext,
+};
 use utoipa::{
 	IntoParams,
 	ToSchema,
 };
 
-use crate::blocks::Page;
+use crate::{
+	blocks::Page,
+	facet::Facets,
+	ReadQueryPlan,
+};
 
 /// Standard pagination sent as **query** parameters.
 ///
@@ -68,6 +82,22 @@ pub struct WebPage<T> {
 	pub page:        i64,
 	pub total:       i64,
 	pub total_pages: i64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[schema(nullable)]
+	pub facets:      Option<Facets>,
+}
+
+impl<T> WebPage<T> {
+	pub fn with_facets(mut self, facets: Facets) -> Self {
+		self.facets = Some(facets);
+		self
+	}
+}
+
+impl<T> From<(Page<T>, Facets)> for WebPage<T> {
+	fn from((page, facets): (Page<T>, Facets)) -> Self {
+		Self::from(page).with_facets(facets)
+	}
 }
 
 impl<T> From<Page<T>> for WebPage<T> {
@@ -78,6 +108,55 @@ impl<T> From<Page<T>> for WebPage<T> {
 			page:        value.page,
 			total:       value.total,
 			total_pages: value.total_pages,
+			facets:      None,
+		}
+	}
+}
+
+impl<'a, C> ReadQueryPlan<'a, C>
+where
+	C: QueryContext,
+	C::Model: JoinNavigationModel,
+{
+	/// Fetches the page as the DTOs `D` of the web read, serialized without
+	/// the fields its [`WebPolicy`](super::WebPolicy) hides from the
+	/// principal. Facets are counted along when the payload asked for them.
+	pub async fn fetch_web_page<'e, D, E>(
+		&self,
+		exec: E,
+	) -> Result<WebPage<Value>, Error>
+	where
+		D: From<C::Model> + Serialize,
+		E: Executor<'e, Database = Postgres>,
+	{
+		let (page, facets) = match self.facets {
+			Some(_) => {
+				let (page, facets) = self.fetch_page_with_facets(exec).await?;
+				(page, Some(facets))
+			}
+			None => (self.fetch_page(exec).await?, None),
+		};
+
+		Ok(WebPage {
+			items: page
+				.items
+				.into_iter()
+				.map(|model| self.redact(D::from(model)))
+				.collect::<Result<_, _>>()?,
+			page_size: page.page_size,
+			page: page.page,
+			total: page.total,
+			total_pages: page.total_pages,
+			facets,
+		})
+	}
+
+	fn redact<D: Serialize>(&self, dto: D) -> Result<Value, Error> {
+		let mut value = serde_json::to_value(dto)
+			.map_err(|err| sqlx::Error::Decode(Box::new(err)))?;
+		if let Value::Object(fields) = &mut value {
+			fields.retain(|field, _| !self.hidden_fields.contains(&&**field));
 		}
+		Ok(value)
 	}
 }
@@ -0,0 +1,201 @@
+use serde::Serialize;
+use serde_json::Value;
+use sqlxo_traits::{
+	QueryContext,
+	WebQueryModel,
+};
+
+use crate::{
+	blocks::Expression,
+	web::WebQueryError,
+};
+
+/// What a web payload is translated into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebAction {
+	Read,
+	Update,
+	Delete,
+	/// Clearing the delete marker of soft-deleted rows.
+	Restore,
+	/// Permanently deleting soft-deleted rows.
+	Purge,
+}
+
+/// Decides what a principal may do with the web queries of the DTO `D`.
+///
+/// Passed to the `QueryBuilder::try_from_web_*_for` constructors, which
+/// reject payloads touching forbidden fields or joins with
+/// [`WebQueryError::Forbidden`] and AND [`row_filter`](Self::row_filter)
+/// into the query. Reads built this way leave the fields hidden by
+/// [`may_read`](Self::may_read) out of
+/// [`fetch_web_page`](crate::ReadQueryPlan::fetch_web_page). Field and join
+/// names are the keys used in the payload.
+pub trait WebPolicy<C, D>
+where
+	C: QueryContext,
+	D: WebQueryModel,
+{
+	type Principal;
+
+	/// Filter the affected rows must match, e.g. to restrict the principal
+	/// to its own rows.
+	fn row_filter(
+		&self,
+		_principal: &Self::Principal,
+		_action: WebAction,
+	) -> Option<Expression<C::Query>> {
+		None
+	}
+
+	/// Whether `field` is returned to the principal. Hidden fields can
+	/// neither be filtered, sorted, grouped nor aggregated by default.
+	///
+	/// `field` is a payload key, e.g. `differentName` for a DTO field
+	/// `different_name`, also when called by [`redact`](Self::redact).
+	fn may_read(&self, _principal: &Self::Principal, _field: &str) -> bool {
+		true
+	}
+
+	fn may_filter(&self, principal: &Self::Principal, field: &str) -> bool {
+		self.may_read(principal, field)
+	}
+
+	fn may_sort(&self, principal: &Self::Principal, field: &str) -> bool {
+		self.may_read(principal, field)
+	}
+
+	/// Whether the principal may join `path`, e.g. `["material",
+	/// "supplier"]`. Also checked for `search` joins and `counts`.
+	fn may_join(&self, _principal: &Self::Principal, _path: &[String]) -> bool {
+		true
+	}
+
+	/// Serializes `row` without the fields hidden by
+	/// [`may_read`](Self::may_read). Its keys are checked as payload keys, so
+	/// `row` must serialize like the payload, e.g. a DTO renamed with the
+	/// payload's `rename_all` rule rather than the model.
+	fn redact<T: Serialize>(
+		&self,
+		principal: &Self::Principal,
+		row: &T,
+	) -> serde_json::Result<Value> {
+		let mut value = serde_json::to_value(row)?;
+		if let Value::Object(fields) = &mut value {
+			fields.retain(|field, _| self.may_read(principal, field));
+		}
+		Ok(value)
+	}
+}
+
+/// Policy of the web constructors without a principal.
+pub(crate) struct AllowAll;
+
+impl<C, D> WebPolicy<C, D> for AllowAll
+where
+	C: QueryContext,
+	D: WebQueryModel,
+{
+	type Principal = ();
+}
+
+/// A policy bound to the principal it is evaluated for.
+pub(crate) struct Authorizer<'p, P, C, D>
+where
+	P: WebPolicy<C, D>,
+	C: QueryContext,
+	D: WebQueryModel,
+{
+	policy:    &'p P,
+	principal: &'p P::Principal,
+	_marker:   std::marker::PhantomData<(C, D)>,
+}
+
+impl<'p, P, C, D> Authorizer<'p, P, C, D>
+where
+	P: WebPolicy<C, D>,
+	C: QueryContext,
+	D: WebQueryModel,
+{
+	pub(crate) fn new(policy: &'p P, principal: &'p P::Principal) -> Self {
+		Self {
+			policy,
+			principal,
+			_marker: std::marker::PhantomData,
+		}
+	}
+
+	pub(crate) fn row_filter(
+		&self,
+		action: WebAction,
+	) -> Option<Expression<C::Query>> {
+		self.policy.row_filter(self.principal, action)
+	}
+
+	/// Keys of the DTO fields the principal may not read.
+	pub(crate) fn hidden_fields(&self) -> Vec<&'static str> {
+		D::FIELDS
+			.iter()
+			.copied()
+			.filter(|field| !self.policy.may_read(self.principal, field))
+			.collect()
+	}
+
+	pub(crate) fn read(
+		&self,
+		section: &'static str,
+		field: &str,
+	) -> Result<(), WebQueryError> {
+		forbid_unless(
+			self.policy.may_read(self.principal, field),
+			section,
+			field,
+		)
+	}
+
+	pub(crate) fn filter(
+		&self,
+		section: &'static str,
+		field: &str,
+	) -> Result<(), WebQueryError> {
+		forbid_unless(
+			self.policy.may_filter(self.principal, field),
+			section,
+			field,
+		)
+	}
+
+	pub(crate) fn sort(&self, field: &str) -> Result<(), WebQueryError> {
+		forbid_unless(
+			self.policy.may_sort(self.principal, field),
+			"sort",
+			field,
+		)
+	}
+
+	pub(crate) fn join(
+		&self,
+		section: &'static str,
+		path: &[String],
+	) -> Result<(), WebQueryError> {
+		forbid_unless(
+			self.policy.may_join(self.principal, path),
+			section,
+			&path.join("."),
+		)
+	}
+}
+
+fn forbid_unless(
+	allowed: bool,
+	section: &'static str,
+	name: &str,
+) -> Result<(), WebQueryError> {
+	if allowed {
+		return Ok(());
+	}
+	Err(WebQueryError::Forbidden {
+		section,
+		name: name.to_string(),
+	})
+}
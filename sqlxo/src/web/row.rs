@@ -0,0 +1,372 @@
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use serde_json::{
+	Map,
+	Value,
+};
+use std::{
+	fmt::Write,
+	net::{
+		IpAddr,
+		Ipv4Addr,
+		Ipv6Addr,
+	},
+};
+
+use sqlx::{
+	postgres::{
+		types::PgInterval,
+		PgRow,
+		PgTypeInfo,
+		PgTypeKind,
+		PgValueFormat,
+		PgValueRef,
+	},
+	types::{
+		chrono::{
+			DateTime,
+			NaiveDate,
+			NaiveDateTime,
+			NaiveTime,
+			Utc,
+		},
+		BigDecimal,
+		Uuid,
+	},
+	Column,
+	Decode,
+	Postgres,
+	Row,
+	Type,
+	TypeInfo,
+};
+use utoipa::ToSchema;
+
+/// Row of a grouped web read, keyed by the `groupBy` and `aggregates` names
+/// of the payload.
+///
+/// The shape depends on the request; the `{Dto}AggregateRow` struct generated
+/// by `WebQuery` documents every key a row may contain.
+#[derive(
+	Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(transparent)]
+pub struct WebAggregateRow(pub Map<String, Value>);
+
+impl WebAggregateRow {
+	pub fn get(&self, key: &str) -> Option<&Value> {
+		self.0.get(key)
+	}
+
+	pub fn into_inner(self) -> Map<String, Value> {
+		self.0
+	}
+}
+
+/// A `NUMERIC` aggregate, e.g. the `SUM` of a `bigint` or the `AVG` of an
+/// integer column, kept as its decimal string so that no digits are lost.
+#[derive(
+	Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(transparent)]
+pub struct WebNumeric(pub String);
+
+impl WebNumeric {
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	pub fn to_decimal(&self) -> Option<BigDecimal> {
+		self.0.parse().ok()
+	}
+}
+
+impl<'r> sqlx::FromRow<'r, PgRow> for WebAggregateRow {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let mut values = Map::with_capacity(row.len());
+		for column in row.columns() {
+			let idx = column.ordinal();
+			let value = decode_json(row, idx, column.type_info()).map_err(
+				|source| sqlx::Error::ColumnDecode {
+					index: column.name().to_string(),
+					source,
+				},
+			)?;
+			values.insert(column.name().to_string(), value);
+		}
+		Ok(Self(values))
+	}
+}
+
+type DecodeResult = Result<Value, sqlx::error::BoxDynError>;
+
+/// Decodes a column of any type. Types without a JSON mapping of their own
+/// come back as their text, or as `\x`-prefixed hex when Postgres sends them
+/// in a binary form that is not text.
+fn decode_json(
+	row: &PgRow,
+	idx: usize,
+	type_info: &PgTypeInfo,
+) -> DecodeResult {
+	if let PgTypeKind::Array(element) = type_info.kind() {
+		return match decode_typed::<ArrayOf>(row, idx, element) {
+			Some(result) => result,
+			None => decode_fallback(row, idx),
+		};
+	}
+	if let Some(result) = decode_typed::<Scalar>(row, idx, type_info) {
+		return result;
+	}
+	match type_info.name() {
+		"INTERVAL" => {
+			let value: Option<PgInterval> = decode(row, idx)?;
+			Ok(value.map_or(Value::Null, |value| {
+				Value::String(iso_interval(&value))
+			}))
+		}
+		"BYTEA" => {
+			let value: Option<Vec<u8>> = decode(row, idx)?;
+			Ok(value.map_or(Value::Null, |value| Value::String(hex(&value))))
+		}
+		"INET" | "CIDR" => {
+			let raw = row.try_get_raw(idx)?;
+			if sqlx::ValueRef::is_null(&raw) {
+				return Ok(Value::Null);
+			}
+			match raw.format() {
+				PgValueFormat::Text => Ok(Value::String(raw.as_str()?.into())),
+				PgValueFormat::Binary => {
+					Ok(Value::String(inet(raw.as_bytes()?)?))
+				}
+			}
+		}
+		_ => decode_fallback(row, idx),
+	}
+}
+
+/// Decodes the types with a native JSON mapping, as a value or as an array
+/// of values depending on `S`. `None` for any other type.
+fn decode_typed<S: Shape>(
+	row: &PgRow,
+	idx: usize,
+	type_info: &PgTypeInfo,
+) -> Option<DecodeResult> {
+	let result = match type_info.name() {
+		"BOOL" => S::json::<bool>(row, idx),
+		"INT2" => S::json::<i16>(row, idx),
+		"INT4" => S::json::<i32>(row, idx),
+		"INT8" => S::json::<i64>(row, idx),
+		"FLOAT4" => S::json::<f32>(row, idx),
+		"FLOAT8" => S::json::<f64>(row, idx),
+		"NUMERIC" => S::json::<PlainDecimal>(row, idx),
+		"UUID" => S::json::<Uuid>(row, idx),
+		"TIMESTAMPTZ" => S::json::<DateTime<Utc>>(row, idx),
+		"TIMESTAMP" => S::json::<NaiveDateTime>(row, idx),
+		"DATE" => S::json::<NaiveDate>(row, idx),
+		"TIME" => S::json::<NaiveTime>(row, idx),
+		"JSON" | "JSONB" => S::json::<Value>(row, idx),
+		"TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => S::json::<String>(row, idx),
+		_ if matches!(type_info.kind(), PgTypeKind::Enum(_)) => {
+			S::json::<EnumLabel>(row, idx)
+		}
+		_ => return None,
+	};
+	Some(result)
+}
+
+/// Whether [`decode_typed`] decodes a single value or a one-dimensional
+/// array of them.
+trait Shape {
+	fn json<T>(row: &PgRow, idx: usize) -> DecodeResult
+	where
+		T: for<'r> Decode<'r, Postgres> + Type<Postgres> + Serialize;
+}
+
+struct Scalar;
+
+impl Shape for Scalar {
+	fn json<T>(row: &PgRow, idx: usize) -> DecodeResult
+	where
+		T: for<'r> Decode<'r, Postgres> + Type<Postgres> + Serialize,
+	{
+		json::<T>(row, idx)
+	}
+}
+
+struct ArrayOf;
+
+impl Shape for ArrayOf {
+	fn json<T>(row: &PgRow, idx: usize) -> DecodeResult
+	where
+		T: for<'r> Decode<'r, Postgres> + Type<Postgres> + Serialize,
+	{
+		json::<Vec<Option<T>>>(row, idx)
+	}
+}
+
+/// A `NUMERIC` as its decimal string, see [`WebNumeric`].
+struct PlainDecimal(BigDecimal);
+
+impl Serialize for PlainDecimal {
+	fn serialize<S: serde::Serializer>(
+		&self,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.0.to_plain_string())
+	}
+}
+
+impl Type<Postgres> for PlainDecimal {
+	fn type_info() -> PgTypeInfo {
+		<BigDecimal as Type<Postgres>>::type_info()
+	}
+}
+
+/// sqlx derives the scale from the stored base-10000 digits, so the
+/// display scale Postgres sends in the header is applied on top, keeping
+/// e.g. `1.50` from coming out as `1.5000`.
+impl<'r> Decode<'r, Postgres> for PlainDecimal {
+	fn decode(value: PgValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+		let scale = match value.format() {
+			PgValueFormat::Text => None,
+			PgValueFormat::Binary => value
+				.as_bytes()?
+				.get(6..8)
+				.map(|dscale| u16::from_be_bytes([dscale[0], dscale[1]])),
+		};
+		let decimal = <BigDecimal as Decode<Postgres>>::decode(value)?;
+		Ok(Self(match scale {
+			Some(scale) => decimal.with_scale(scale.into()),
+			None => decimal,
+		}))
+	}
+}
+
+/// An enum value, which Postgres sends as its label in either format.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct EnumLabel(String);
+
+impl Type<Postgres> for EnumLabel {
+	fn type_info() -> PgTypeInfo {
+		<String as Type<Postgres>>::type_info()
+	}
+}
+
+impl<'r> Decode<'r, Postgres> for EnumLabel {
+	fn decode(value: PgValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+		Ok(Self(value.as_str()?.to_owned()))
+	}
+}
+
+fn decode_fallback(row: &PgRow, idx: usize) -> DecodeResult {
+	let raw = row.try_get_raw(idx)?;
+	if sqlx::ValueRef::is_null(&raw) {
+		return Ok(Value::Null);
+	}
+	let bytes = raw.as_bytes()?;
+	Ok(Value::String(match std::str::from_utf8(bytes) {
+		Ok(text) => text.to_owned(),
+		Err(_) => hex(bytes),
+	}))
+}
+
+fn json<T>(row: &PgRow, idx: usize) -> DecodeResult
+where
+	T: for<'r> Decode<'r, Postgres> + Serialize,
+{
+	let value: Option<T> = decode(row, idx)?;
+	Ok(serde_json::to_value(value)?)
+}
+
+/// Renders `bytes` the way Postgres prints a `bytea`.
+fn hex(bytes: &[u8]) -> String {
+	let mut out = String::with_capacity(2 + bytes.len() * 2);
+	out.push_str("\\x");
+	for byte in bytes {
+		let _ = write!(out, "{byte:02x}");
+	}
+	out
+}
+
+/// Renders `interval` as an ISO 8601 duration, e.g. `P1Y2M3DT4H5M6.5S`.
+fn iso_interval(interval: &PgInterval) -> String {
+	let mut out = String::from("P");
+	let (years, months) = (interval.months / 12, interval.months % 12);
+	for (value, unit) in [(years, 'Y'), (months, 'M'), (interval.days, 'D')] {
+		if value != 0 {
+			let _ = write!(out, "{value}{unit}");
+		}
+	}
+
+	let micros = interval.microseconds;
+	if micros != 0 {
+		out.push('T');
+		let hours = micros / 3_600_000_000;
+		let minutes = micros % 3_600_000_000 / 60_000_000;
+		let seconds = micros % 60_000_000;
+		if hours != 0 {
+			let _ = write!(out, "{hours}H");
+		}
+		if minutes != 0 {
+			let _ = write!(out, "{minutes}M");
+		}
+		if seconds != 0 {
+			let sign = if seconds < 0 { "-" } else { "" };
+			let (whole, fraction) =
+				(seconds.abs() / 1_000_000, seconds.abs() % 1_000_000);
+			let _ = write!(out, "{sign}{whole}");
+			if fraction != 0 {
+				let fraction = format!("{fraction:06}");
+				let _ = write!(out, ".{}", fraction.trim_end_matches('0'));
+			}
+			out.push('S');
+		}
+	} else if out.len() == 1 {
+		out.push_str("T0S");
+	}
+	out
+}
+
+/// Renders the binary form of an `inet`/`cidr`: family, prefix bits, the
+/// `cidr` flag, address length and the address.
+fn inet(bytes: &[u8]) -> Result<String, sqlx::error::BoxDynError> {
+	let [family, bits, is_cidr, len, address @ ..] = bytes else {
+		return Err("truncated inet value".into());
+	};
+	if address.len() != usize::from(*len) {
+		return Err("truncated inet value".into());
+	}
+	let (address, full) = match (family, address) {
+		(2, &[a, b, c, d]) => (IpAddr::V4(Ipv4Addr::new(a, b, c, d)), 32),
+		(3, address) if address.len() == 16 => {
+			let mut octets = [0u8; 16];
+			octets.copy_from_slice(address);
+			(IpAddr::V6(Ipv6Addr::from(octets)), 128)
+		}
+		_ => return Err(format!("unknown inet family {family}").into()),
+	};
+	Ok(if *is_cidr != 0 || *bits != full {
+		format!("{address}/{bits}")
+	} else {
+		address.to_string()
+	})
+}
+
+/// Decodes without the type check `Row::try_get` performs; the caller already
+/// matched the column type by name.
+fn decode<T>(
+	row: &PgRow,
+	idx: usize,
+) -> Result<Option<T>, sqlx::error::BoxDynError>
+where
+	T: for<'r> Decode<'r, Postgres>,
+{
+	let raw = row.try_get_raw(idx)?;
+	if sqlx::ValueRef::is_null(&raw) {
+		return Ok(None);
+	}
+	T::decode(raw).map(Some)
+}
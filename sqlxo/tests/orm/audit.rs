@@ -0,0 +1,111 @@
+use sqlxo::{
+	audit,
+	cache::CachedMutation,
+	Buildable,
+	QueryBuilder,
+};
+use uuid::Uuid;
+
+use crate::helpers::{
+	Invoice,
+	InvoiceUpdate,
+	Ledger,
+	NormalizeString,
+};
+
+#[test]
+fn update_records_before_and_after() {
+	let plan = audit::sync_scope("alice", || {
+		QueryBuilder::<Invoice>::update_by_id(Uuid::new_v4())
+			.model(InvoiceUpdate {
+				number: None,
+				amount: Some(2),
+			})
+			.build()
+	});
+
+	assert_eq!(
+		plan.sql().normalize(),
+		r#"WITH "sqlxo_changed" AS (UPDATE invoice SET amount = $1 WHERE "invoice"."id" = ANY($2) RETURNING "invoice".*), "sqlxo_audit_rows" AS (INSERT INTO sqlxo_audit (table_name, action, entity, before, after, actor) SELECT $3, 'update', jsonb_build_object('id', "sqlxo_changed"."id"), to_jsonb("sqlxo_before"), to_jsonb("sqlxo_changed"), $4 FROM "sqlxo_changed" LEFT JOIN invoice AS "sqlxo_before" ON "sqlxo_before"."id" = "sqlxo_changed"."id") SELECT 1 FROM "sqlxo_changed""#
+	);
+}
+
+#[test]
+fn audited_mutations_invalidate_cached_history() {
+	let update = QueryBuilder::<Invoice>::update_by_id(Uuid::new_v4())
+		.model(InvoiceUpdate {
+			number: None,
+			amount: Some(2),
+		})
+		.build();
+	assert_eq!(update.written_tables(), ["invoice", audit::AUDIT_TABLE]);
+
+	let delete = QueryBuilder::<Ledger>::delete_by_id(Uuid::new_v4()).build();
+	assert!(delete.written_tables().contains(&audit::AUDIT_TABLE));
+
+	let unaudited =
+		QueryBuilder::<crate::helpers::Project>::delete_by_id(Uuid::new_v4())
+			.build();
+	assert_eq!(unaudited.written_tables(), ["project"]);
+}
+
+#[test]
+fn delete_records_no_after_image() {
+	let plan = QueryBuilder::<Invoice>::delete_by_id(Uuid::new_v4()).build();
+
+	let sql = plan.sql().normalize();
+	assert!(sql.starts_with(r#"WITH "sqlxo_changed" AS (DELETE FROM invoice"#));
+	assert!(
+		sql.contains(r#"'delete', jsonb_build_object('id', "sqlxo_changed"."id"), to_jsonb("sqlxo_before"), NULL,"#),
+		"{sql}"
+	);
+}
+
+#[test]
+fn unaudited_plans_are_unchanged() {
+	let plan =
+		QueryBuilder::<crate::helpers::Project>::delete_by_id(Uuid::new_v4())
+			.all_tenants()
+			.build();
+
+	assert!(!plan.sql().contains("sqlxo_audit"));
+}
+
+#[test]
+fn cascades_record_audited_children() {
+	let plan = audit::sync_scope("carol", || {
+		QueryBuilder::<Ledger>::delete_by_id(Uuid::new_v4()).build()
+	});
+
+	assert!(!plan.sql().contains("sqlxo_audit"));
+	let cascades: Vec<String> = plan
+		.cascade_sql()
+		.iter()
+		.map(|sql| sql.normalize())
+		.collect();
+	assert_eq!(cascades.len(), 2);
+	assert!(
+		cascades[0].starts_with(
+			r#"WITH "sqlxo_changed" AS (DELETE FROM ledger_entry WHERE"#
+		),
+		"{}",
+		cascades[0]
+	);
+	assert!(
+		cascades[0].contains(r#"'delete', jsonb_build_object('id', "sqlxo_changed"."id"), to_jsonb("sqlxo_before"), NULL,"#),
+		"{}",
+		cascades[0]
+	);
+	assert!(
+		cascades[1].starts_with(
+			r#"WITH "sqlxo_changed" AS (UPDATE ledger_tag SET "ledger_id" = NULL WHERE"#
+		),
+		"{}",
+		cascades[1]
+	);
+	assert!(
+		cascades[1].contains(r#"'update', jsonb_build_object('id', "sqlxo_changed"."id"), to_jsonb("sqlxo_before"), to_jsonb("sqlxo_changed"),"#),
+		"{}",
+		cascades[1]
+	);
+}
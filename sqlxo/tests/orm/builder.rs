@@ -23,6 +23,11 @@ use uuid::Uuid;
 use crate::helpers::{
 	AppUser,
 	AppUserJoin,
+	Author,
+	AuthorJoin,
+	Category,
+	CategoryJoin,
+	CategoryQuery,
 	CreateItem,
 	CreateItemCreation,
 	HardDeleteItem,
@@ -33,6 +38,10 @@ use crate::helpers::{
 	Material,
 	MaterialJoin,
 	NormalizeString,
+	TenantMaterial,
+	TenantMaterialJoin,
+	TenantSupplier,
+	TenantSupplierJoin,
 	UpdateItem,
 	UpdateItemUpdate,
 };
@@ -143,6 +152,132 @@ fn read_builder_allows_mixed_take() {
 	);
 }
 
+#[test]
+fn read_builder_renders_window_functions() {
+	use crate::helpers::{
+		ItemColumn,
+		ItemSort,
+		ItemWindow,
+	};
+
+	let plan = QueryBuilder::<Item>::read()
+		.take(sqlxo::take!(
+			ItemColumn::Name,
+			ItemWindow::DenseRank()
+				.partition_by(ItemColumn::MaterialId)
+				.order_by(order_by![
+					ItemSort::ByPriceDesc,
+					ItemSort::ByNameAsc
+				]),
+			ItemWindow::Lag(ItemColumn::Price, 2)
+				.order_by(order_by![ItemSort::ByDueDateAsc]),
+			ItemWindow::RunningSum(ItemColumn::Amount)
+				.order_by(order_by![ItemSort::ByDueDateAsc])
+		))
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT "item"."name",
+            DENSE_RANK() OVER (PARTITION BY "item"."material_id" ORDER BY "item"."price" DESC, "item"."name" ASC) AS "__sqlxo_sel_1",
+            LAG("item"."price", 2) OVER (ORDER BY "item"."due_date" ASC) AS "__sqlxo_sel_2",
+            SUM("item"."amount") OVER (ORDER BY "item"."due_date" ASC ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) AS "__sqlxo_sel_3"
+            FROM item"#
+			.normalize()
+	);
+}
+
+#[test]
+fn read_builder_renders_facets() {
+	use sqlxo::facet::Facet;
+
+	use crate::helpers::ItemColumn;
+
+	let plan = QueryBuilder::<Item>::read()
+		.r#where(ItemQuery::ActiveIsTrue.into())
+		.facets([
+			Facet::values("material", ItemColumn::MaterialId),
+			Facet::buckets("price", ItemColumn::Price, &[10.0, 50.5]),
+		])
+		.build();
+
+	assert_eq!(
+		plan.facet_sql().normalize(),
+		r#"WITH "__sqlxo_facet_base" AS (SELECT ROW("item"."id")::text AS "__sqlxo_key",
+                "item"."material_id" AS "__sqlxo_facet_0",
+                "item"."price" AS "__sqlxo_facet_1"
+            FROM item WHERE ("item"."active" = TRUE))
+            SELECT json_build_object('material', (SELECT COALESCE(json_agg(json_build_object('value', "value", 'count', "count") ORDER BY "count" DESC, "value"), '[]'::json)
+                    FROM (SELECT "__sqlxo_facet_0" AS "value", COUNT(DISTINCT "__sqlxo_key") AS "count"
+                        FROM "__sqlxo_facet_base" GROUP BY 1) AS "__sqlxo_facet"),
+                'price', (SELECT COALESCE(json_agg(json_build_object('bucket', "bucket", 'count', "count")), '[]'::json)
+                    FROM (SELECT width_bucket("__sqlxo_facet_1"::float8, ARRAY[10, 50.5]::float8[]) AS "bucket", COUNT(DISTINCT "__sqlxo_key") AS "count"
+                        FROM "__sqlxo_facet_base" WHERE "__sqlxo_facet_1" IS NOT NULL GROUP BY 1) AS "__sqlxo_facet"))
+            AS "facets""#
+			.normalize()
+	);
+
+	let page_sql = plan.page_with_facets_sql().normalize();
+	assert!(page_sql.starts_with(
+		r#"SELECT "item".*, COUNT(*) OVER() AS total_count,
+            "__sqlxo_facets"."facets" AS "__sqlxo_facets"
+            FROM item CROSS JOIN (WITH "__sqlxo_facet_base" AS ("#
+			.normalize()
+			.as_str()
+	));
+	assert!(page_sql
+		.ends_with(r#") AS "__sqlxo_facets" WHERE "item"."active" = TRUE"#));
+}
+
+#[test]
+fn read_builder_renders_distinct() {
+	use crate::helpers::ItemColumn;
+
+	let plan = QueryBuilder::<Item>::read()
+		.distinct_on(ItemColumn::MaterialId)
+		.order_by(order_by![
+			ItemSort::ByMaterialIdAsc,
+			ItemSort::ByDueDateDesc
+		])
+		.build();
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT DISTINCT ON ("item"."material_id") "item".* FROM item
+            ORDER BY "item"."material_id" ASC, "item"."due_date" DESC"#
+			.normalize()
+	);
+
+	let plan = QueryBuilder::<Item>::read()
+		.r#where(ItemQuery::ActiveIsTrue.into())
+		.distinct_on((ItemColumn::MaterialId, ItemColumn::Active))
+		.paginate(Pagination {
+			page:      0,
+			page_size: 5,
+		})
+		.build();
+	assert_eq!(
+		plan.sql(SelectType::StarAndCount).normalize(),
+		r#"SELECT DISTINCT ON ("item"."material_id", "item"."active") "item".*,
+                "__sqlxo_total"."total_count" AS "total_count"
+            FROM item
+            CROSS JOIN (SELECT COUNT(*) AS "total_count"
+                FROM (SELECT DISTINCT ON ("item"."material_id", "item"."active") "item".*
+                FROM item WHERE ("item"."active" = TRUE)) AS "__sqlxo_distinct") AS "__sqlxo_total"
+            WHERE "item"."active" = TRUE
+            LIMIT $1 OFFSET $2"#
+			.normalize()
+	);
+
+	let plan = QueryBuilder::<Item>::read()
+		.distinct()
+		.take(sqlxo::take!(ItemColumn::MaterialId))
+		.build();
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT DISTINCT "item"."material_id" FROM item"#
+	);
+}
+
 #[test]
 fn has_one_join_builds_sql() {
 	let plan: ReadQueryPlan<AppUser> = QueryBuilder::<AppUser>::read()
@@ -267,6 +402,147 @@ fn read_builder_allows_having_clause() {
 	);
 }
 
+#[test]
+fn read_builder_renders_nested_having_logic() {
+	use crate::helpers::{
+		ItemAgg,
+		ItemColumn,
+	};
+
+	let plan: ReadQueryPlan<Item, (i64,)> = QueryBuilder::<Item>::read()
+		.take(sqlxo::take!(ItemAgg::CountAll()))
+		.having(sqlxo::having!(
+			sqlxo::having_or![
+				ItemAgg::CountAll().gt(5i64),
+				sqlxo::having_and![
+					ItemAgg::Sum(ItemColumn::Amount).ge(10i64),
+					sqlxo::having_not!(ItemAgg::CountAll().eq(0i64)),
+				],
+			],
+			ItemAgg::CountAll().lt(100i64),
+		))
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT COUNT(*) AS "__sqlxo_sel_0" FROM item
+            HAVING (COUNT(*) > $1 OR (SUM("item"."amount") >= $2 AND NOT COUNT(*) = $3))
+            AND COUNT(*) < $4"#
+			.normalize()
+	);
+}
+
+#[test]
+fn read_builder_ands_repeated_having_calls() {
+	use crate::helpers::ItemAgg;
+
+	let plan: ReadQueryPlan<Item, (i64,)> = QueryBuilder::<Item>::read()
+		.take(sqlxo::take!(ItemAgg::CountAll()))
+		.having(sqlxo::having!(sqlxo::having_or![
+			ItemAgg::CountAll().gt(5i64),
+			ItemAgg::CountAll().eq(0i64),
+		]))
+		.having(sqlxo::having!(ItemAgg::CountAll().lt(100i64)))
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT COUNT(*) AS "__sqlxo_sel_0" FROM item
+            HAVING ((COUNT(*) > $1 OR COUNT(*) = $2)) AND (COUNT(*) < $3)"#
+			.normalize()
+	);
+}
+
+#[test]
+fn read_builder_renders_ordered_and_statistical_aggregates() {
+	use crate::helpers::{
+		ItemAgg,
+		ItemColumn,
+	};
+
+	let plan = QueryBuilder::<Item>::read()
+		.take(sqlxo::take!(
+			ItemAgg::StringAgg(ItemColumn::Name, "', ")
+				.order_by_desc(ItemColumn::Price),
+			ItemAgg::ArrayAgg(ItemColumn::Amount)
+				.order_by_asc(ItemColumn::Name),
+			ItemAgg::BoolOr(ItemColumn::Active),
+			ItemAgg::Median(ItemColumn::Price)
+		))
+		.having(sqlxo::having!(
+			ItemAgg::StdDev(ItemColumn::Price).lt(5.0f64),
+			ItemAgg::PercentileCont(ItemColumn::Amount, 0.9).ge(3.0f64),
+			ItemAgg::BoolAnd(ItemColumn::Active).eq(true),
+		))
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT STRING_AGG("item"."name"::text, ''', ' ORDER BY "item"."price" DESC) AS "__sqlxo_sel_0",
+            ARRAY_AGG("item"."amount" ORDER BY "item"."name" ASC) AS "__sqlxo_sel_1",
+            BOOL_OR("item"."active") AS "__sqlxo_sel_2",
+            PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY "item"."price")::float8 AS "__sqlxo_sel_3"
+            FROM item
+            HAVING STDDEV("item"."price")::float8 < $1
+            AND PERCENTILE_CONT(0.9) WITHIN GROUP (ORDER BY "item"."amount")::float8 >= $2
+            AND BOOL_AND("item"."active") = $3"#
+			.normalize()
+	);
+}
+
+#[test]
+#[should_panic(expected = "percentile fraction must be between 0 and 1")]
+fn percentile_rejects_out_of_range_fraction() {
+	use crate::helpers::{
+		ItemAgg,
+		ItemColumn,
+	};
+
+	let _ = ItemAgg::PercentileCont(ItemColumn::Price, 1.5);
+}
+
+#[test]
+fn percentile_selections_compare_by_fraction() {
+	use crate::helpers::{
+		ItemAgg,
+		ItemColumn,
+	};
+	use sqlxo::select::AggregateSelectionExpr;
+
+	let median = ItemAgg::Median(ItemColumn::Price).selection();
+	assert_eq!(
+		median,
+		ItemAgg::PercentileCont(ItemColumn::Price, 0.5).selection()
+	);
+	assert_ne!(
+		median,
+		ItemAgg::PercentileCont(ItemColumn::Price, 0.9).selection()
+	);
+}
+
+#[test]
+fn aggregate_filter_renders_or_having() {
+	use crate::helpers::ItemAgg;
+
+	let plan: ReadQueryPlan<Item> = QueryBuilder::<Item>::read()
+		.having(sqlxo::select::HavingList::from(sqlxo::having_or![
+			ItemAgg::CountAll().gt(5i64),
+			ItemAgg::CountAll().lt(2i64),
+		]))
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start().normalize(),
+		r#"
+            SELECT "item".* FROM item
+            WHERE "item"."id" IN (SELECT "item"."id" FROM item
+                GROUP BY "item"."id"
+                HAVING COUNT(*) > $1 OR COUNT(*) < $2)
+        "#
+		.normalize()
+	);
+}
+
 #[test]
 fn delete_builder_allows_custom_row_type() {
 	let plan: DeleteQueryPlan<HardDeleteItem, (Uuid,)> =
@@ -340,3 +616,370 @@ fn read_builder_supports_take_with_join_columns() {
 		.normalize()
 	);
 }
+
+#[test]
+fn right_join_builds_sql() {
+	let plan: ReadQueryPlan<Item> = QueryBuilder::<Item>::read()
+		.join_path(ItemJoin::ItemToMaterialByMaterialId.right())
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start().normalize(),
+		r#"
+            SELECT "item".*, "material__"."id" AS "__sqlxo_material__id",
+                "material__"."name" AS "__sqlxo_material__name",
+                "material__"."long_name" AS "__sqlxo_material__long_name",
+                "material__"."description" AS "__sqlxo_material__description",
+                "material__"."supplier_id" AS "__sqlxo_material__supplier_id"
+            FROM item
+            RIGHT JOIN material AS "material__" ON "item"."material_id" = "material__"."id"
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn right_join_page_keeps_only_rows_with_base_row() {
+	let plan: ReadQueryPlan<Item> = QueryBuilder::<Item>::read()
+		.join_path(ItemJoin::ItemToMaterialByMaterialId.right())
+		.paginate(Pagination {
+			page:      0,
+			page_size: 10,
+		})
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::StarAndCount).trim_start().normalize(),
+		r#"
+            SELECT "item".*, COUNT(*) OVER() AS total_count,
+                "material__"."id" AS "__sqlxo_material__id",
+                "material__"."name" AS "__sqlxo_material__name",
+                "material__"."long_name" AS "__sqlxo_material__long_name",
+                "material__"."description" AS "__sqlxo_material__description",
+                "material__"."supplier_id" AS "__sqlxo_material__supplier_id"
+            FROM item
+            RIGHT JOIN material AS "material__" ON "item"."material_id" = "material__"."id"
+            WHERE "item"."id" IS NOT NULL
+            LIMIT $1 OFFSET $2
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn full_outer_join_builds_sql_with_take() {
+	let plan: ReadQueryPlan<Item, (Option<Uuid>, Option<Uuid>)> =
+		QueryBuilder::<Item>::read()
+			.join(ItemJoin::ItemToTagByTags, JoinKind::Full)
+			.take(sqlxo::take!(
+				sqlxo::select::nullable(crate::helpers::ItemColumn::Id),
+				sqlxo::select::nullable(crate::helpers::TagColumn::Id)
+			))
+			.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start().normalize(),
+		r#"
+            SELECT "item"."id", "tags__"."id"
+            FROM item
+            FULL OUTER JOIN item_tag AS "tags__pivot__"
+                ON "item"."id" = "tags__pivot__"."item_id"
+            FULL OUTER JOIN tag AS "tags__"
+                ON "tags__pivot__"."tag_id" = "tags__"."id"
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn cross_join_builds_sql_without_condition() {
+	let plan: ReadQueryPlan<Item, (Uuid, Uuid)> = QueryBuilder::<Item>::read()
+		.join(ItemJoin::ItemToMaterialByMaterialId, JoinKind::Cross)
+		.take(sqlxo::take!(
+			crate::helpers::ItemColumn::Id,
+			crate::helpers::MaterialColumn::Id
+		))
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start().normalize(),
+		r#"
+            SELECT "item"."id", "material__"."id"
+            FROM item
+            CROSS JOIN material AS "material__"
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn composite_foreign_key_join_builds_sql() {
+	let plan: ReadQueryPlan<TenantMaterial> =
+		QueryBuilder::<TenantMaterial>::read()
+			.join(
+				TenantMaterialJoin::TenantMaterialToTenantSupplierBySupplierId,
+				JoinKind::Left,
+			)
+			.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start().normalize(),
+		r#"
+            SELECT "tenant_material".*,
+                "supplier__"."tenant_id" AS "__sqlxo_supplier__tenant_id",
+                "supplier__"."id" AS "__sqlxo_supplier__id",
+                "supplier__"."name" AS "__sqlxo_supplier__name"
+            FROM tenant_material
+            LEFT JOIN tenant_supplier AS "supplier__"
+                ON "tenant_material"."supplier_id" = "supplier__"."id"
+                AND "tenant_material"."tenant_id" = "supplier__"."tenant_id"
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn composite_primary_key_has_many_join_builds_sql() {
+	let plan: ReadQueryPlan<TenantSupplier> =
+		QueryBuilder::<TenantSupplier>::read()
+			.join(
+				TenantSupplierJoin::TenantSupplierToTenantMaterialByMaterials,
+				JoinKind::Left,
+			)
+			.having(sqlxo::having!(
+				crate::helpers::TenantSupplierAgg::CountAll().gt(1i64)
+			))
+			.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start().normalize(),
+		r#"
+            SELECT "tenant_supplier".*,
+                "materials__"."tenant_id" AS "__sqlxo_materials__tenant_id",
+                "materials__"."id" AS "__sqlxo_materials__id",
+                "materials__"."name" AS "__sqlxo_materials__name",
+                "materials__"."supplier_id" AS "__sqlxo_materials__supplier_id"
+            FROM tenant_supplier
+            LEFT JOIN tenant_material AS "materials__"
+                ON "tenant_supplier"."tenant_id" = "materials__"."tenant_id"
+                AND "tenant_supplier"."id" = "materials__"."supplier_id"
+            WHERE ("tenant_supplier"."tenant_id", "tenant_supplier"."id") IN (SELECT "tenant_supplier"."tenant_id", "tenant_supplier"."id"
+                FROM tenant_supplier
+                LEFT JOIN tenant_material AS "materials__"
+                    ON "tenant_supplier"."tenant_id" = "materials__"."tenant_id"
+                    AND "tenant_supplier"."id" = "materials__"."supplier_id"
+                GROUP BY "tenant_supplier"."tenant_id", "tenant_supplier"."id"
+                HAVING COUNT(*) > $1)
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn descendants_of_builds_recursive_cte() {
+	let plan: ReadQueryPlan<Category> = QueryBuilder::<Category>::read()
+		.descendants_of(
+			CategoryJoin::CategoryToCategoryByParentId,
+			CategoryQuery::NameEq("root".into()).into(),
+		)
+		.max_depth(2)
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start().normalize(),
+		r#"
+            WITH RECURSIVE "__sqlxo_tree" AS (SELECT "category".*, 0 AS "__sqlxo_depth",
+                    ARRAY[ROW("category"."id")::text] AS "__sqlxo_path"
+                FROM category
+                WHERE ("category"."name" = $1)
+                UNION ALL
+                SELECT "category".*, "__sqlxo_tree"."__sqlxo_depth" + 1,
+                    "__sqlxo_tree"."__sqlxo_path" || ROW("category"."id")::text
+                FROM category
+                INNER JOIN "__sqlxo_tree"
+                    ON "category"."parent_id" = "__sqlxo_tree"."id"
+                WHERE ROW("category"."id")::text <> ALL("__sqlxo_tree"."__sqlxo_path")
+                    AND "__sqlxo_tree"."__sqlxo_depth" < $2)
+            SELECT "category".* FROM "__sqlxo_tree" AS "category"
+            ORDER BY "category"."__sqlxo_path"
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn ancestors_of_accepts_parent_to_children_relation() {
+	let plan: ReadQueryPlan<Category> = QueryBuilder::<Category>::read()
+		.ancestors_of(
+			CategoryJoin::CategoryToCategoryByChildren,
+			CategoryQuery::NameEq("leaf".into()).into(),
+		)
+		.r#where(CategoryQuery::NameNeq("root".into()).into())
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start().normalize(),
+		r#"
+            WITH RECURSIVE "__sqlxo_tree" AS (SELECT "category".*, 0 AS "__sqlxo_depth",
+                    ARRAY[ROW("category"."id")::text] AS "__sqlxo_path"
+                FROM category
+                WHERE ("category"."name" = $1)
+                UNION ALL
+                SELECT "category".*, "__sqlxo_tree"."__sqlxo_depth" + 1,
+                    "__sqlxo_tree"."__sqlxo_path" || ROW("category"."id")::text
+                FROM category
+                INNER JOIN "__sqlxo_tree"
+                    ON "category"."id" = "__sqlxo_tree"."parent_id"
+                WHERE ROW("category"."id")::text <> ALL("__sqlxo_tree"."__sqlxo_path"))
+            SELECT "category".* FROM "__sqlxo_tree" AS "category"
+            WHERE "category"."name" <> $2
+            ORDER BY "category"."__sqlxo_path"
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn with_count_projects_correlated_subquery() {
+	let plan: ReadQueryPlan<Category> = QueryBuilder::<Category>::read()
+		.with_count(CategoryJoin::CategoryToCategoryByChildren)
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start().normalize(),
+		r#"
+            SELECT "category".*,
+                (SELECT COUNT(*) FROM category AS "children__count"
+                    WHERE "category"."id" = "children__count"."parent_id")
+                    AS "__sqlxo_count_children"
+            FROM category
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn with_count_skips_soft_deleted_children() {
+	let plan: ReadQueryPlan<Author> = QueryBuilder::<Author>::read()
+		.with_count(AuthorJoin::AuthorToPostByPosts)
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start().normalize(),
+		r#"
+            SELECT "author".*,
+                (SELECT COUNT(*) FROM post AS "posts__count"
+                    WHERE "author"."id" = "posts__count"."author_id"
+                    AND "posts__count"."deleted_at" IS NULL)
+                    AS "__sqlxo_count_posts"
+            FROM author
+            WHERE "author"."deleted_at" IS NULL
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn with_count_through_table_counts_links() {
+	let plan: ReadQueryPlan<Item> = QueryBuilder::<Item>::read()
+		.with_count(ItemJoin::ItemToTagByTags)
+		.paginate(Pagination {
+			page:      0,
+			page_size: 10,
+		})
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::StarAndCount).trim_start().normalize(),
+		r#"
+            SELECT "item".*, COUNT(*) OVER() AS total_count,
+                (SELECT COUNT(*) FROM item_tag AS "tags__count"
+                    WHERE "item"."id" = "tags__count"."item_id")
+                    AS "__sqlxo_count_tags"
+            FROM item
+            LIMIT $1 OFFSET $2
+        "#
+		.normalize()
+	);
+}
+
+#[test]
+fn find_filters_on_primary_key() {
+	let plan: ReadQueryPlan<Item> = QueryBuilder::<Item>::find(Uuid::new_v4())
+		.join(ItemJoin::ItemToMaterialByMaterialId, JoinKind::Left)
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).trim_start(),
+		r#"
+            SELECT "item".*, "material__"."id" AS "__sqlxo_material__id",
+                "material__"."name" AS "__sqlxo_material__name",
+                "material__"."long_name" AS "__sqlxo_material__long_name",
+                "material__"."description" AS "__sqlxo_material__description",
+                "material__"."supplier_id" AS "__sqlxo_material__supplier_id"
+            FROM item
+            LEFT JOIN material AS "material__" ON "item"."material_id" = "material__"."id"
+            WHERE "item"."id" = ANY($1)
+        "#
+		.normalize()
+	)
+}
+
+#[test]
+fn find_many_keeps_key_order() {
+	let keys = [Uuid::new_v4(), Uuid::new_v4()];
+	let plan: ReadQueryPlan<Item> =
+		QueryBuilder::<Item>::find_many(&keys).build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"
+            SELECT "item".* FROM item
+            WHERE "item"."id" = ANY($1)
+            ORDER BY array_position($2, "item"."id")
+        "#
+		.normalize()
+	);
+
+	let sorted: ReadQueryPlan<Item> = QueryBuilder::<Item>::find_many(&keys)
+		.order_by(order_by![ItemSort::ByNameAsc])
+		.build();
+	assert!(!sorted.sql(SelectType::Star).contains("CASE"));
+
+	let many: Vec<Uuid> = (0..70_000).map(|_| Uuid::new_v4()).collect();
+	let plan: ReadQueryPlan<Item> =
+		QueryBuilder::<Item>::find_many(&many).build();
+	let sql = plan.sql(SelectType::Star);
+	assert!(sql.contains("$2") && !sql.contains("$3"));
+
+	let empty: ReadQueryPlan<Item> =
+		QueryBuilder::<Item>::find_many(&[]).build();
+	assert_eq!(
+		empty.sql(SelectType::Star).normalize(),
+		r#"SELECT "item".* FROM item WHERE FALSE"#
+	);
+}
+
+#[test]
+fn find_many_with_composite_key() {
+	let keys = [
+		(Uuid::new_v4(), Uuid::new_v4()),
+		(Uuid::new_v4(), Uuid::new_v4()),
+	];
+	let plan: ReadQueryPlan<TenantSupplier> =
+		QueryBuilder::<TenantSupplier>::find_many(&keys).build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"
+            SELECT "tenant_supplier".* FROM tenant_supplier
+            WHERE ("tenant_supplier"."tenant_id", "tenant_supplier"."id")
+                IN (SELECT * FROM UNNEST($1, $2))
+            ORDER BY (SELECT "__sqlxo_key"."position"
+                FROM UNNEST($3, $4) WITH ORDINALITY AS "__sqlxo_key"("key_0", "key_1", "position")
+                WHERE ("tenant_supplier"."tenant_id", "tenant_supplier"."id")
+                    = ("__sqlxo_key"."key_0", "__sqlxo_key"."key_1"))
+        "#
+		.normalize()
+	);
+}
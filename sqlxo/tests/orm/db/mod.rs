@@ -28,24 +28,39 @@ use sqlxo::order_by;
 use sqlxo::Buildable;
 use sqlxo::ExecutablePlan;
 use sqlxo::FetchablePlan;
+use sqlxo::NeedsTransaction;
 use sqlxo::QueryBuilder;
+use sqlxo::StaleVersion;
+use sqlxo::UnsupportedInsert;
+use sqlxo::UnsupportedRead;
 use sqlxo::{
-	web::WebReadFilter,
+	web::{
+		WebAggregateRow,
+		WebPage,
+		WebPolicy,
+		WebReadFilter,
+	},
 	JoinKind,
 	JoinValue,
 };
 use uuid::Uuid;
 
 use crate::helpers::{
+	Category,
+	CategoryJoin,
+	CategoryQuery,
 	Item,
+	ItemAgg,
 	ItemColumn,
 	ItemDto,
+	ItemDtoAggregateRow,
 	ItemFullTextSearchConfig,
 	ItemFullTextSearchJoin,
 	ItemJoin,
 	ItemQuery,
 	ItemSort,
 	ItemTag,
+	ItemWindow,
 	Material,
 	MaterialColumn,
 	MaterialFullTextSearchJoin,
@@ -56,6 +71,10 @@ use crate::helpers::{
 	Tag,
 	TagJoin,
 	TagQuery,
+	TenantMaterial,
+	TenantMaterialJoin,
+	TenantSupplier,
+	TenantSupplierJoin,
 };
 
 #[derive(Debug, Clone)]
@@ -571,6 +590,402 @@ async fn web_query_payload_executes_full_stack() {
 	assert_eq!(rows[0].id, matching.id);
 }
 
+#[tokio::test]
+async fn web_grouped_read_returns_json_rows() {
+	let pool = get_connection_pool().await;
+
+	for (amount, price, active) in
+		[(1, 2.0f32, true), (1, 4.0, false), (3, 10.0, true)]
+	{
+		let item = Item {
+			amount,
+			price,
+			active,
+			..Default::default()
+		};
+		insert_item(&item, &pool).await.unwrap();
+	}
+
+	let filter: WebReadFilter<ItemDto> = serde_json::from_value(json!({
+		"groupBy": ["amount"],
+		"aggregates": ["count", "priceAvg", "activeBoolAnd"],
+		"sort": [{ "amount": "asc" }]
+	}))
+	.unwrap();
+
+	let rows = QueryBuilder::<Item>::try_from_web_aggregate::<ItemDto>(&filter)
+		.unwrap()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	let rows: Vec<serde_json::Value> = rows
+		.into_iter()
+		.map(|row| serde_json::Value::Object(row.into_inner()))
+		.collect();
+	assert_eq!(rows, vec![
+		json!({ "amount": 1, "count": 2, "priceAvg": 3.0, "activeBoolAnd": false }),
+		json!({ "amount": 3, "count": 1, "priceAvg": 10.0, "activeBoolAnd": true }),
+	]);
+
+	let typed: ItemDtoAggregateRow =
+		serde_json::from_value(rows[0].clone()).unwrap();
+	assert_eq!(typed.amount, Some(1));
+	assert_eq!(typed.count, Some(2));
+	assert_eq!(typed.different_name, None);
+}
+
+#[tokio::test]
+async fn web_grouped_read_keeps_numeric_precision() {
+	let pool = get_connection_pool().await;
+
+	for amount in [i32::MAX, i32::MAX, i32::MAX - 1] {
+		let item = Item {
+			amount,
+			..Default::default()
+		};
+		insert_item(&item, &pool).await.unwrap();
+	}
+
+	let filter: WebReadFilter<ItemDto> = serde_json::from_value(json!({
+		"aggregates": ["amountSum", "amountAvg"]
+	}))
+	.unwrap();
+
+	let rows = QueryBuilder::<Item>::try_from_web_aggregate::<ItemDto>(&filter)
+		.unwrap()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	let row = serde_json::Value::Object(rows[0].clone().into_inner());
+	assert_eq!(row["amountSum"], json!(6442450940i64));
+	// An `f64` would round this to 2147483646.6666667.
+	assert_eq!(row["amountAvg"], json!("2147483646.66666667"));
+
+	let typed: ItemDtoAggregateRow = serde_json::from_value(row).unwrap();
+	assert_eq!(typed.amount_sum, Some(6442450940));
+	assert_eq!(
+		typed.amount_avg.and_then(|avg| avg.to_decimal()),
+		"2147483646.66666667".parse().ok()
+	);
+}
+
+#[tokio::test]
+async fn web_aggregate_row_decodes_arrays_and_other_types() {
+	let pool = get_connection_pool().await;
+
+	sqlx::query("CREATE TYPE mood AS ENUM ('happy', 'sad')")
+		.execute(&pool)
+		.await
+		.unwrap();
+
+	let row: WebAggregateRow = sqlx::query_as(
+		r#"
+            SELECT ARRAY_AGG(x ORDER BY x) AS "ints",
+                ARRAY['a', NULL]::text[] AS "texts",
+                ARRAY[1.50]::numeric[] AS "numerics",
+                0.5::numeric AS "half",
+                12345.123456::numeric AS "scaled",
+                SUM(x)::numeric AS "sum",
+                'sad'::mood AS "mood",
+                ARRAY['happy']::mood[] AS "moods",
+                INTERVAL '1 year 2 days 3 hours 4.5 seconds' AS "interval",
+                '\xdead'::bytea AS "bytes",
+                '10.0.0.1'::inet AS "inet",
+                '10.0.0.0/8'::cidr AS "cidr",
+                '(1,2)'::point AS "point"
+            FROM generate_series(1, 3) AS x
+        "#,
+	)
+	.fetch_one(&pool)
+	.await
+	.unwrap();
+
+	assert_eq!(
+		serde_json::Value::Object(row.into_inner()),
+		json!({
+			"ints": [1, 2, 3],
+			"texts": ["a", null],
+			"numerics": ["1.50"],
+			"half": "0.5",
+			"scaled": "12345.123456",
+			"sum": "6",
+			"mood": "sad",
+			"moods": ["happy"],
+			"interval": "P1Y2DT3H4.5S",
+			"bytes": "\\xdead",
+			"inet": "10.0.0.1",
+			"cidr": "10.0.0.0/8",
+			"point": "\\x3ff00000000000004000000000000000",
+		})
+	);
+}
+
+#[tokio::test]
+async fn web_read_returns_page_with_facets() {
+	let pool = get_connection_pool().await;
+
+	for (name, price, active) in [
+		("bolt", 2.0f32, true),
+		("nut", 4.0, false),
+		("bolt", 10.0, true),
+		("gear", 60.0, true),
+		("gear", 0.5, true),
+	] {
+		let item = Item {
+			name: name.into(),
+			price,
+			active,
+			..Default::default()
+		};
+		insert_item(&item, &pool).await.unwrap();
+	}
+
+	let filter: WebReadFilter<ItemDto> = serde_json::from_value(json!({
+		"filter": { "price": { "gt": 1.0 } },
+		"facets": ["differentName", "price", "active"],
+		"sort": [{ "price": "asc" }],
+		"page": { "pageNo": 0, "pageSize": 1 }
+	}))
+	.unwrap();
+
+	let plan = QueryBuilder::<Item>::from_web_read::<ItemDto>(&filter).build();
+	let (page, facets) = plan.fetch_page_with_facets(&pool).await.unwrap();
+	assert_eq!(page.total, 4);
+	assert_eq!(page.items.len(), 1);
+	assert_eq!(page.items[0].price, 2.0);
+	assert_eq!(facets, plan.fetch_facets(&pool).await.unwrap());
+
+	let page = WebPage::from((page, facets));
+	assert_eq!(
+		serde_json::to_value(page.facets).unwrap(),
+		json!({
+			"differentName": [
+				{ "value": "bolt", "count": 2 },
+				{ "value": "gear", "count": 1 },
+				{ "value": "nut", "count": 1 }
+			],
+			"price": [
+				{ "from": null, "to": 10.0, "count": 2 },
+				{ "from": 10.0, "to": 50.0, "count": 1 },
+				{ "from": 50.0, "to": null, "count": 1 }
+			],
+			"active": [
+				{ "value": true, "count": 3 },
+				{ "value": false, "count": 1 }
+			]
+		})
+	);
+}
+
+struct HidePrice;
+
+impl WebPolicy<Item, ItemDto> for HidePrice {
+	type Principal = ();
+
+	fn may_read(&self, _principal: &(), field: &str) -> bool {
+		field != "price"
+	}
+}
+
+#[tokio::test]
+async fn web_read_leaves_out_hidden_fields() {
+	let pool = get_connection_pool().await;
+	let item = Item {
+		amount: 7,
+		..Default::default()
+	};
+	insert_item(&item, &pool).await.unwrap();
+
+	let filter: WebReadFilter<ItemDto> = serde_json::from_value(json!({
+		"filter": { "id": { "eq": item.id } }
+	}))
+	.unwrap();
+
+	let page =
+		QueryBuilder::<Item>::try_from_web_read_for(&filter, &HidePrice, &())
+			.unwrap()
+			.build()
+			.fetch_web_page::<ItemDto, _>(&pool)
+			.await
+			.unwrap();
+	assert_eq!(page.total, 1);
+	assert_eq!(page.items[0]["amount"], json!(7));
+	assert!(page.items[0].get("price").is_none(), "{}", page.items[0]);
+
+	let page = QueryBuilder::<Item>::try_from_web_read::<ItemDto>(&filter)
+		.unwrap()
+		.build()
+		.fetch_web_page::<ItemDto, _>(&pool)
+		.await
+		.unwrap();
+	assert_eq!(page.items[0]["price"], json!(item.price));
+}
+
+#[tokio::test]
+async fn web_read_facets_respect_having() {
+	let pool = get_connection_pool().await;
+
+	for (name, price) in [("bolt", 2.0f32), ("bolt", 10.0), ("gear", 60.0)] {
+		let item = Item {
+			name: name.into(),
+			price,
+			..Default::default()
+		};
+		insert_item(&item, &pool).await.unwrap();
+	}
+
+	let filter: WebReadFilter<ItemDto> = serde_json::from_value(json!({
+		"having": { "priceSum": { "gt": 5.0 } },
+		"facets": ["differentName"],
+		"page": { "pageNo": 0, "pageSize": 10 }
+	}))
+	.unwrap();
+
+	let plan = QueryBuilder::<Item>::from_web_read::<ItemDto>(&filter).build();
+	let (page, facets) = plan.fetch_page_with_facets(&pool).await.unwrap();
+	assert_eq!(page.total, 2);
+	assert_eq!(facets, plan.fetch_facets(&pool).await.unwrap());
+	assert_eq!(
+		serde_json::to_value(facets.values("differentName")).unwrap(),
+		json!([
+			{ "value": "bolt", "count": 1 },
+			{ "value": "gear", "count": 1 }
+		])
+	);
+}
+
+#[tokio::test]
+async fn read_distinct_on_returns_first_row_per_key() {
+	let pool = get_connection_pool().await;
+	let steel = Uuid::new_v4();
+	let wood = Uuid::new_v4();
+	for (id, name) in [(steel, "steel"), (wood, "wood")] {
+		sqlx::query(
+			"INSERT INTO material (id, name, long_name) VALUES ($1, $2, $2)",
+		)
+		.bind(id)
+		.bind(name)
+		.execute(&pool)
+		.await
+		.unwrap();
+	}
+
+	for (name, price, material) in [
+		("a", 3.0f32, Some(steel)),
+		("b", 1.0, Some(steel)),
+		("c", 2.0, None),
+		("d", 5.0, Some(steel)),
+		("e", 4.0, Some(wood)),
+	] {
+		let item = Item {
+			name: name.into(),
+			price,
+			material_id: material,
+			..Default::default()
+		};
+		insert_item(&item, &pool).await.unwrap();
+	}
+
+	let page = QueryBuilder::<Item>::read()
+		.distinct_on(ItemColumn::MaterialId)
+		.order_by(order_by![ItemSort::ByMaterialIdAsc, ItemSort::ByPriceDesc])
+		.paginate(Pagination {
+			page:      0,
+			page_size: 2,
+		})
+		.build()
+		.fetch_page(&pool)
+		.await
+		.unwrap();
+	assert_eq!(page.total, 3);
+	assert_eq!(page.items.len(), 2);
+
+	let mut names: Vec<String> = QueryBuilder::<Item>::read()
+		.distinct_on(ItemColumn::MaterialId)
+		.order_by(order_by![ItemSort::ByMaterialIdAsc, ItemSort::ByPriceDesc])
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap()
+		.into_iter()
+		.map(|item| item.name)
+		.collect();
+	names.sort();
+	assert_eq!(names, vec!["c", "d", "e"]);
+
+	let rows = QueryBuilder::<Item>::read()
+		.distinct()
+		.take(sqlxo::take!(ItemColumn::MaterialId, ItemColumn::Active))
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(rows.len(), 3);
+}
+
+#[tokio::test]
+async fn unsupported_reads_fail_with_error() {
+	let pool = get_connection_pool().await;
+
+	let err = QueryBuilder::<Item>::read()
+		.distinct_on(ItemColumn::MaterialId)
+		.order_by(order_by![
+			ItemSort::ByDueDateDesc,
+			ItemSort::ByMaterialIdAsc
+		])
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap_err();
+	let unsupported = UnsupportedRead::from_error(&err).expect("unsupported");
+	assert!(unsupported
+		.reason
+		.contains("must match the leading order_by columns"));
+
+	let err = QueryBuilder::<Category>::read()
+		.max_depth(1)
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap_err();
+	assert!(UnsupportedRead::from_error(&err).is_some());
+
+	let err = QueryBuilder::<Category>::read()
+		.build()
+		.fetch_tree(&pool)
+		.await
+		.unwrap_err();
+	assert!(UnsupportedRead::from_error(&err).is_some());
+
+	let err = QueryBuilder::<Category>::read()
+		.descendants_of(
+			CategoryJoin::CategoryToCategoryByParentId,
+			CategoryQuery::IdEq(Uuid::new_v4()).into(),
+		)
+		.distinct()
+		.build()
+		.fetch_page(&pool)
+		.await
+		.err()
+		.expect("unsupported");
+	assert!(UnsupportedRead::from_error(&err).is_some());
+	let err = QueryBuilder::<Category>::read()
+		.descendants_of(
+			CategoryJoin::CategoryToCategoryByParentId,
+			CategoryQuery::IdEq(Uuid::new_v4()).into(),
+		)
+		.facets([])
+		.build()
+		.fetch_facets(&pool)
+		.await
+		.unwrap_err();
+	assert!(UnsupportedRead::from_error(&err).is_some());
+}
+
 async fn insert_hard_delete_item(
 	item: &HardDeleteItem,
 	pool: &PgPool,
@@ -1067,111 +1482,495 @@ async fn soft_delete_sets_marker() {
 }
 
 #[tokio::test]
-async fn soft_delete_with_returning() {
+async fn self_referential_set_null_keeps_children() {
 	let pool = get_connection_pool().await;
-	let item = SoftDeleteItem::default();
-
-	insert_soft_delete_item(&item, &pool).await.unwrap();
+	let root = Uuid::new_v4();
+	let child = Uuid::new_v4();
+	let grandchild = Uuid::new_v4();
+
+	for (id, name, parent) in [
+		(root, "root", None),
+		(child, "child", Some(root)),
+		(grandchild, "grandchild", Some(child)),
+	] {
+		sqlx::query(
+			"INSERT INTO category (id, name, parent_id) VALUES ($1, $2, $3)",
+		)
+		.bind(id)
+		.bind(name)
+		.bind(parent)
+		.execute(&pool)
+		.await
+		.unwrap();
+	}
 
-	// Delete and get back with deleted_at set
-	let deleted_item: SoftDeleteItem = QueryBuilder::<SoftDeleteItem>::delete()
-		.r#where(Expression::Leaf(SoftDeleteItemQuery::IdEq(item.id)))
+	let deleted = QueryBuilder::<Category>::delete_by_id(root)
 		.build()
-		.fetch_one(&pool)
+		.execute_atomic(&pool)
 		.await
 		.unwrap();
+	assert_eq!(deleted, 1);
 
-	assert_eq!(deleted_item.id, item.id);
-	assert!(deleted_item.deleted_at.is_some());
+	let left: Vec<Category> = QueryBuilder::<Category>::read()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	let parents: Vec<_> = left.iter().map(|c| (c.id, c.parent_id)).collect();
+	assert_eq!(parents.len(), 2);
+	assert!(parents.contains(&(child, None)));
+	assert!(parents.contains(&(grandchild, Some(child))));
 }
 
 #[tokio::test]
-async fn soft_delete_filters_by_default() {
-	let pool = get_connection_pool().await;
-	let active_item = SoftDeleteItem::default();
-	let deleted_item = SoftDeleteItem {
-		deleted_at: Some(chrono::Utc::now()),
-		..Default::default()
+async fn delete_cascades_follow_relation_rules() {
+	use crate::helpers::{
+		Author,
+		AuthorQuery,
+		Post,
 	};
+	use sqlxo::RestrictedDelete;
 
-	insert_soft_delete_item(&active_item, &pool).await.unwrap();
-	insert_soft_delete_item(&deleted_item, &pool).await.unwrap();
-
-	// Default query should only return active
-	let active_items: Vec<SoftDeleteItem> =
-		QueryBuilder::<SoftDeleteItem>::read()
-			.build()
-			.fetch_all(&pool)
+	let pool = get_connection_pool().await;
+	let (ann, bob) = (Uuid::new_v4(), Uuid::new_v4());
+	let (own_post, edited_post) = (Uuid::new_v4(), Uuid::new_v4());
+	for (id, name) in [(ann, "ann"), (bob, "bob")] {
+		sqlx::query("INSERT INTO author (id, name) VALUES ($1, $2)")
+			.bind(id)
+			.bind(name)
+			.execute(&pool)
 			.await
 			.unwrap();
+	}
+	sqlx::query(
+		"INSERT INTO post (id, author_id, editor_id, title) VALUES ($1, $2, \
+		 NULL, 'own'), ($3, $4, $2, 'edited')",
+	)
+	.bind(own_post)
+	.bind(ann)
+	.bind(edited_post)
+	.bind(bob)
+	.execute(&pool)
+	.await
+	.unwrap();
+	sqlx::query(
+		"INSERT INTO comment (id, post_id, body) VALUES ($1, $2, 'first')",
+	)
+	.bind(Uuid::new_v4())
+	.bind(own_post)
+	.execute(&pool)
+	.await
+	.unwrap();
+	sqlx::query(
+		"INSERT INTO award (id, author_id, title) VALUES ($1, $2, 'prize')",
+	)
+	.bind(Uuid::new_v4())
+	.bind(bob)
+	.execute(&pool)
+	.await
+	.unwrap();
 
-	assert_eq!(active_items.len(), 1);
-	assert_eq!(active_items[0].id, active_item.id);
+	let deleted = QueryBuilder::<Author>::delete()
+		.r#where(AuthorQuery::IdEq(ann).into())
+		.build()
+		.execute_atomic(&pool)
+		.await
+		.unwrap();
+	assert_eq!(deleted, 1);
 
-	// With include_deleted, should return both
-	let all_items: Vec<SoftDeleteItem> = QueryBuilder::<SoftDeleteItem>::read()
+	let posts: Vec<Post> = QueryBuilder::<Post>::read()
 		.include_deleted()
 		.build()
 		.fetch_all(&pool)
 		.await
 		.unwrap();
-
-	assert_eq!(all_items.len(), 2);
-}
-
-#[tokio::test]
-async fn soft_delete_with_where_clause() {
-	let pool = get_connection_pool().await;
-	let item1 = SoftDeleteItem {
-		name: "delete me".into(),
-		..Default::default()
-	};
-	let item2 = SoftDeleteItem {
-		name: "keep me".into(),
-		..Default::default()
-	};
-
-	insert_soft_delete_item(&item1, &pool).await.unwrap();
-	insert_soft_delete_item(&item2, &pool).await.unwrap();
-
-	// Soft delete only items matching criteria
-	let deleted = QueryBuilder::<SoftDeleteItem>::delete()
-		.r#where(Expression::Leaf(SoftDeleteItemQuery::NameEq(
-			"delete me".into(),
-		)))
-		.build()
-		.execute(&pool)
+	let own = posts.iter().find(|p| p.id == own_post).unwrap();
+	let edited = posts.iter().find(|p| p.id == edited_post).unwrap();
+	assert!(own.deleted_at.is_some());
+	assert!(edited.deleted_at.is_none());
+	assert_eq!(edited.editor_id, None);
+	let comments: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM comment")
+		.fetch_one(&pool)
 		.await
 		.unwrap();
+	assert_eq!(comments, 0);
 
-	assert_eq!(deleted, 1);
+	let err = QueryBuilder::<Author>::delete()
+		.r#where(AuthorQuery::IdEq(bob).into())
+		.build()
+		.execute_atomic(&pool)
+		.await
+		.unwrap_err();
+	let restricted = RestrictedDelete::from_error(&err).expect("restricted");
+	assert_eq!(restricted.child, "award");
 
-	// Verify only one item remains active
-	let active: Vec<SoftDeleteItem> = QueryBuilder::<SoftDeleteItem>::read()
+	let live_posts: Vec<Post> = QueryBuilder::<Post>::read()
 		.build()
 		.fetch_all(&pool)
 		.await
 		.unwrap();
-
-	assert_eq!(active.len(), 1);
-	assert_eq!(active[0].id, item2.id);
+	assert_eq!(live_posts.len(), 1);
+	assert_eq!(live_posts[0].id, edited_post);
 }
 
 #[tokio::test]
-async fn soft_delete_exists_respects_filter() {
-	let pool = get_connection_pool().await;
-	let deleted_item = SoftDeleteItem {
-		deleted_at: Some(chrono::Utc::now()),
-		..Default::default()
+async fn purge_applies_relation_rules() {
+	use crate::helpers::{
+		Author,
+		AuthorQuery,
 	};
+	use sqlxo::RestrictedDelete;
 
-	insert_soft_delete_item(&deleted_item, &pool).await.unwrap();
-
-	// Should not exist in normal query
-	let exists = QueryBuilder::<SoftDeleteItem>::read()
-		.r#where(Expression::Leaf(SoftDeleteItemQuery::IdEq(deleted_item.id)))
-		.build()
-		.exists(&pool)
+	let pool = get_connection_pool().await;
+	let (ann, bob) = (Uuid::new_v4(), Uuid::new_v4());
+	let post = Uuid::new_v4();
+	for (id, name) in [(ann, "ann"), (bob, "bob")] {
+		sqlx::query("INSERT INTO author (id, name) VALUES ($1, $2)")
+			.bind(id)
+			.bind(name)
+			.execute(&pool)
+			.await
+			.unwrap();
+	}
+	sqlx::query(
+		"INSERT INTO post (id, author_id, title) VALUES ($1, $2, 'own')",
+	)
+	.bind(post)
+	.bind(ann)
+	.execute(&pool)
+	.await
+	.unwrap();
+	sqlx::query(
+		"INSERT INTO comment (id, post_id, body) VALUES ($1, $2, 'first')",
+	)
+	.bind(Uuid::new_v4())
+	.bind(post)
+	.execute(&pool)
+	.await
+	.unwrap();
+	sqlx::query(
+		"INSERT INTO award (id, author_id, title) VALUES ($1, $2, 'prize')",
+	)
+	.bind(Uuid::new_v4())
+	.bind(bob)
+	.execute(&pool)
+	.await
+	.unwrap();
+
+	// Soft deletes the post along with ann.
+	QueryBuilder::<Author>::delete()
+		.r#where(AuthorQuery::IdEq(ann).into())
+		.build()
+		.execute_atomic(&pool)
+		.await
+		.unwrap();
+	sqlx::query("UPDATE author SET deleted_at = NOW() WHERE id = $1")
+		.bind(bob)
+		.execute(&pool)
+		.await
+		.unwrap();
+
+	let err = QueryBuilder::<Author>::purge()
+		.r#where(AuthorQuery::IdEq(bob).into())
+		.build()
+		.execute_atomic(&pool)
+		.await
+		.unwrap_err();
+	let restricted = RestrictedDelete::from_error(&err).expect("restricted");
+	assert_eq!(restricted.child, "award");
+
+	let purged = QueryBuilder::<Author>::purge()
+		.r#where(AuthorQuery::IdEq(ann).into())
+		.build()
+		.execute_atomic(&pool)
+		.await
+		.unwrap();
+	assert_eq!(purged, 1);
+	let (posts, comments): (i64, i64) = sqlx::query_as(
+		"SELECT (SELECT COUNT(*) FROM post), (SELECT COUNT(*) FROM comment)",
+	)
+	.fetch_one(&pool)
+	.await
+	.unwrap();
+	assert_eq!((posts, comments), (0, 0));
+}
+
+#[tokio::test]
+async fn delete_and_update_from_joined_filter() {
+	use crate::helpers::{
+		AuthorQuery,
+		Comment,
+		CommentJoin,
+		Post,
+		PostJoin,
+		PostQuery,
+		PostUpdate,
+	};
+	use sqlxo_traits::JoinPath;
+
+	let pool = get_connection_pool().await;
+	let (ann, bob) = (Uuid::new_v4(), Uuid::new_v4());
+	let (ann_post, bob_post) = (Uuid::new_v4(), Uuid::new_v4());
+	for (id, name) in [(ann, "ann"), (bob, "bob")] {
+		sqlx::query("INSERT INTO author (id, name) VALUES ($1, $2)")
+			.bind(id)
+			.bind(name)
+			.execute(&pool)
+			.await
+			.unwrap();
+	}
+	sqlx::query(
+		"INSERT INTO post (id, author_id, title) VALUES ($1, $2, 'ann'), ($3, \
+		 $4, 'bob')",
+	)
+	.bind(ann_post)
+	.bind(ann)
+	.bind(bob_post)
+	.bind(bob)
+	.execute(&pool)
+	.await
+	.unwrap();
+	sqlx::query(
+		"INSERT INTO comment (id, post_id, body) VALUES ($1, $2, 'a'), ($3, \
+		 $4, 'b')",
+	)
+	.bind(Uuid::new_v4())
+	.bind(ann_post)
+	.bind(Uuid::new_v4())
+	.bind(bob_post)
+	.execute(&pool)
+	.await
+	.unwrap();
+
+	let by_ann = || {
+		JoinPath::from_join(PostJoin::PostToAuthorByAuthorId, JoinKind::Inner)
+	};
+	let renamed: Vec<Post> = QueryBuilder::<Post>::update()
+		.model(PostUpdate {
+			title: Some("renamed".into()),
+			..Default::default()
+		})
+		.where_join(
+			by_ann(),
+			Expression::Leaf(AuthorQuery::NameEq("ann".into())),
+		)
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(renamed.len(), 1);
+	assert_eq!(renamed[0].id, ann_post);
+	assert_eq!(renamed[0].title, "renamed");
+
+	let path = JoinPath::from_join(
+		CommentJoin::CommentToPostByPostId,
+		JoinKind::Inner,
+	)
+	.then(PostJoin::PostToAuthorByAuthorId, JoinKind::Inner);
+	let deleted = QueryBuilder::<Comment>::delete()
+		.where_join(path, Expression::Leaf(AuthorQuery::NameEq("bob".into())))
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+	assert_eq!(deleted, 1);
+
+	let left: Vec<Comment> = QueryBuilder::<Comment>::read()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(left.len(), 1);
+	assert_eq!(left[0].post_id, ann_post);
+
+	let deleted = QueryBuilder::<Post>::delete()
+		.where_join(
+			by_ann(),
+			Expression::Leaf(AuthorQuery::NameEq("ann".into())),
+		)
+		.build()
+		.execute_atomic(&pool)
+		.await
+		.unwrap();
+	assert_eq!(deleted, 1);
+	let live: Vec<Post> = QueryBuilder::<Post>::read()
+		.r#where(PostQuery::IdEq(bob_post).into())
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(live.len(), 1);
+	let comments: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM comment")
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+	assert_eq!(comments, 0);
+}
+
+#[tokio::test]
+async fn soft_delete_trash_restore_and_purge() {
+	let pool = get_connection_pool().await;
+	let day = chrono::Duration::days(1);
+	let old = SoftDeleteItem {
+		deleted_at: Some(chrono::Utc::now() - day * 40),
+		..Default::default()
+	};
+	let recent = SoftDeleteItem {
+		deleted_at: Some(chrono::Utc::now() - day),
+		..Default::default()
+	};
+	let active = SoftDeleteItem::default();
+	for item in [&old, &recent, &active] {
+		insert_soft_delete_item(item, &pool).await.unwrap();
+	}
+
+	let trash: Vec<SoftDeleteItem> = QueryBuilder::<SoftDeleteItem>::read()
+		.only_deleted()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(trash.len(), 2);
+	assert!(trash.iter().all(|i| i.deleted_at.is_some()));
+
+	let purged = QueryBuilder::<SoftDeleteItem>::purge()
+		.older_than(day * 30)
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+	assert_eq!(purged, 1);
+
+	let restored: Vec<SoftDeleteItem> =
+		QueryBuilder::<SoftDeleteItem>::restore()
+			.all_rows()
+			.build()
+			.fetch_all(&pool)
+			.await
+			.unwrap();
+	assert_eq!(restored.len(), 1);
+	assert_eq!(restored[0].id, recent.id);
+	assert!(restored[0].deleted_at.is_none());
+
+	let remaining: Vec<SoftDeleteItem> = QueryBuilder::<SoftDeleteItem>::read()
+		.include_deleted()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	let mut ids: Vec<Uuid> = remaining.iter().map(|i| i.id).collect();
+	ids.sort();
+	let mut expected = vec![recent.id, active.id];
+	expected.sort();
+	assert_eq!(ids, expected);
+}
+
+#[tokio::test]
+async fn soft_delete_with_returning() {
+	let pool = get_connection_pool().await;
+	let item = SoftDeleteItem::default();
+
+	insert_soft_delete_item(&item, &pool).await.unwrap();
+
+	// Delete and get back with deleted_at set
+	let deleted_item: SoftDeleteItem = QueryBuilder::<SoftDeleteItem>::delete()
+		.r#where(Expression::Leaf(SoftDeleteItemQuery::IdEq(item.id)))
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(deleted_item.id, item.id);
+	assert!(deleted_item.deleted_at.is_some());
+}
+
+#[tokio::test]
+async fn soft_delete_filters_by_default() {
+	let pool = get_connection_pool().await;
+	let active_item = SoftDeleteItem::default();
+	let deleted_item = SoftDeleteItem {
+		deleted_at: Some(chrono::Utc::now()),
+		..Default::default()
+	};
+
+	insert_soft_delete_item(&active_item, &pool).await.unwrap();
+	insert_soft_delete_item(&deleted_item, &pool).await.unwrap();
+
+	// Default query should only return active
+	let active_items: Vec<SoftDeleteItem> =
+		QueryBuilder::<SoftDeleteItem>::read()
+			.build()
+			.fetch_all(&pool)
+			.await
+			.unwrap();
+
+	assert_eq!(active_items.len(), 1);
+	assert_eq!(active_items[0].id, active_item.id);
+
+	// With include_deleted, should return both
+	let all_items: Vec<SoftDeleteItem> = QueryBuilder::<SoftDeleteItem>::read()
+		.include_deleted()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(all_items.len(), 2);
+}
+
+#[tokio::test]
+async fn soft_delete_with_where_clause() {
+	let pool = get_connection_pool().await;
+	let item1 = SoftDeleteItem {
+		name: "delete me".into(),
+		..Default::default()
+	};
+	let item2 = SoftDeleteItem {
+		name: "keep me".into(),
+		..Default::default()
+	};
+
+	insert_soft_delete_item(&item1, &pool).await.unwrap();
+	insert_soft_delete_item(&item2, &pool).await.unwrap();
+
+	// Soft delete only items matching criteria
+	let deleted = QueryBuilder::<SoftDeleteItem>::delete()
+		.r#where(Expression::Leaf(SoftDeleteItemQuery::NameEq(
+			"delete me".into(),
+		)))
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(deleted, 1);
+
+	// Verify only one item remains active
+	let active: Vec<SoftDeleteItem> = QueryBuilder::<SoftDeleteItem>::read()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(active.len(), 1);
+	assert_eq!(active[0].id, item2.id);
+}
+
+#[tokio::test]
+async fn soft_delete_exists_respects_filter() {
+	let pool = get_connection_pool().await;
+	let deleted_item = SoftDeleteItem {
+		deleted_at: Some(chrono::Utc::now()),
+		..Default::default()
+	};
+
+	insert_soft_delete_item(&deleted_item, &pool).await.unwrap();
+
+	// Should not exist in normal query
+	let exists = QueryBuilder::<SoftDeleteItem>::read()
+		.r#where(Expression::Leaf(SoftDeleteItemQuery::IdEq(deleted_item.id)))
+		.build()
+		.exists(&pool)
 		.await
 		.unwrap();
 
@@ -1222,6 +2021,12 @@ use crate::helpers::{
 	CreateItemColumn,
 	CreateItemCreation,
 	CreateItemQuery,
+	StockItem,
+	StockItemColumn,
+	StockItemCreation,
+	StockItemQuery,
+	StockItemUpdate,
+	StockItemUpdateOp,
 };
 
 #[tokio::test]
@@ -1305,19 +2110,127 @@ async fn read_item_with_take_returns_tuple() {
 }
 
 #[tokio::test]
-async fn read_item_with_joined_take_returns_tuple() {
+async fn read_item_with_extended_aggregates() {
 	let pool = get_connection_pool().await;
-	let mut item = Item::default();
-	let material_id = Uuid::new_v4();
 
+	for (name, amount, active) in [
+		("b", 1, true),
+		("a", 2, false),
+		("c", 3, true),
+		("d", 6, true),
+	] {
+		let item = Item {
+			name: name.into(),
+			amount,
+			active,
+			..Default::default()
+		};
+		insert_item(&item, &pool).await.unwrap();
+	}
+
+	let (names, amounts, all_active, any_active, median, variance) =
+		QueryBuilder::<Item>::read()
+			.take(sqlxo::take!(
+				ItemAgg::StringAgg(ItemColumn::Name, ", ")
+					.order_by_asc(ItemColumn::Name),
+				ItemAgg::ArrayAgg(ItemColumn::Amount)
+					.order_by_desc(ItemColumn::Amount),
+				ItemAgg::BoolAnd(ItemColumn::Active),
+				ItemAgg::BoolOr(ItemColumn::Active),
+				ItemAgg::Median(ItemColumn::Amount),
+				ItemAgg::Variance(ItemColumn::Amount)
+			))
+			.build()
+			.fetch_one(&pool)
+			.await
+			.unwrap();
+
+	assert_eq!(names.as_deref(), Some("a, b, c, d"));
+	assert_eq!(amounts, Some(vec![6, 3, 2, 1]));
+	assert_eq!(all_active, Some(false));
+	assert_eq!(any_active, Some(true));
+	assert_eq!(median, Some(2.5));
+	assert!((variance.unwrap() - 14.0 / 3.0).abs() < 1e-9);
+
+	let (count,): (i64,) = QueryBuilder::<Item>::read()
+		.take(sqlxo::take!(ItemAgg::CountAll()))
+		.having(sqlxo::having!(
+			ItemAgg::StdDev(ItemColumn::Amount).gt(1.0f64)
+		))
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+	assert_eq!(count, 4);
+}
+
+#[tokio::test]
+async fn read_item_with_window_functions() {
+	let pool = get_connection_pool().await;
+	let material_id = Uuid::new_v4();
 	sqlx::query(
-		r#"
-            INSERT INTO material (id, name, long_name, description, supplier_id)
-            VALUES ($1, $2, $3, $4, $5)
-        "#,
+		"INSERT INTO material (id, name, long_name) VALUES ($1, 'steel', \
+		 'steel')",
 	)
 	.bind(material_id)
-	.bind("joined material")
+	.execute(&pool)
+	.await
+	.unwrap();
+
+	for (name, price, material) in [
+		("a", 3.0f32, Some(material_id)),
+		("b", 1.0, Some(material_id)),
+		("c", 2.0, None),
+		("d", 5.0, Some(material_id)),
+	] {
+		let item = Item {
+			name: name.into(),
+			price,
+			material_id: material,
+			..Default::default()
+		};
+		insert_item(&item, &pool).await.unwrap();
+	}
+
+	let rows = QueryBuilder::<Item>::read()
+		.take(sqlxo::take!(
+			ItemColumn::Name,
+			ItemWindow::RowNumber()
+				.partition_by(ItemColumn::MaterialId)
+				.order_by(order_by![ItemSort::ByPriceDesc]),
+			ItemWindow::Lead(ItemColumn::Name, 1)
+				.order_by(order_by![ItemSort::ByNameAsc]),
+			ItemWindow::RunningSum(ItemColumn::Price)
+				.order_by(order_by![ItemSort::ByNameAsc])
+		))
+		.order_by(order_by![ItemSort::ByNameAsc])
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(rows, vec![
+		("a".to_string(), 2, Some("b".to_string()), Some(3.0)),
+		("b".to_string(), 3, Some("c".to_string()), Some(4.0)),
+		("c".to_string(), 1, Some("d".to_string()), Some(6.0)),
+		("d".to_string(), 1, None, Some(11.0)),
+	]);
+}
+
+#[tokio::test]
+async fn read_item_with_joined_take_returns_tuple() {
+	let pool = get_connection_pool().await;
+	let mut item = Item::default();
+	let material_id = Uuid::new_v4();
+
+	sqlx::query(
+		r#"
+            INSERT INTO material (id, name, long_name, description, supplier_id)
+            VALUES ($1, $2, $3, $4, $5)
+        "#,
+	)
+	.bind(material_id)
+	.bind("joined material")
 	.bind("joined material long name")
 	.bind("joined material desc")
 	.bind(Option::<Uuid>::None)
@@ -1344,6 +2257,135 @@ async fn read_item_with_joined_take_returns_tuple() {
 	assert_eq!(joined_material_id, material_id);
 }
 
+#[tokio::test]
+async fn right_join_take_keeps_rows_without_base() {
+	let pool = get_connection_pool().await;
+	let orphan_id = Uuid::new_v4();
+
+	sqlx::query(
+		r#"
+            INSERT INTO material (id, name, long_name, description, supplier_id)
+            VALUES ($1, $2, $3, $4, $5)
+        "#,
+	)
+	.bind(orphan_id)
+	.bind("orphan material")
+	.bind("orphan material long name")
+	.bind("orphan material desc")
+	.bind(Option::<Uuid>::None)
+	.execute(&pool)
+	.await
+	.unwrap();
+
+	let rows: Vec<(Option<Uuid>, Uuid)> = QueryBuilder::<Item>::read()
+		.join(ItemJoin::ItemToMaterialByMaterialId, JoinKind::Right)
+		.take(sqlxo::take!(
+			sqlxo::select::nullable(ItemColumn::Id),
+			MaterialColumn::Id
+		))
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(rows, vec![(None, orphan_id)]);
+}
+
+#[tokio::test]
+async fn full_join_skips_models_without_base_row() {
+	let pool = get_connection_pool().await;
+	let mut item = Item::default();
+	let material_id = Uuid::new_v4();
+	let orphan_id = Uuid::new_v4();
+
+	for (id, name) in [(material_id, "used"), (orphan_id, "orphan")] {
+		sqlx::query(
+			r#"
+                INSERT INTO material (id, name, long_name, description, supplier_id)
+                VALUES ($1, $2, $3, $4, $5)
+            "#,
+		)
+		.bind(id)
+		.bind(name)
+		.bind(name)
+		.bind(name)
+		.bind(Option::<Uuid>::None)
+		.execute(&pool)
+		.await
+		.unwrap();
+	}
+
+	item.material_id = Some(material_id);
+	insert_item(&item, &pool).await.unwrap();
+
+	let fetched: Vec<Item> = QueryBuilder::<Item>::read()
+		.join(ItemJoin::ItemToMaterialByMaterialId, JoinKind::Full)
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(fetched.len(), 1);
+	assert_eq!(fetched[0].id, item.id);
+	match &fetched[0].material {
+		JoinValue::Loaded(material) => assert_eq!(material.id, material_id),
+		other => panic!("unexpected navigation state: {:?}", other),
+	}
+}
+
+#[tokio::test]
+async fn full_join_page_total_skips_rows_without_base_row() {
+	let pool = get_connection_pool().await;
+	let mut item = Item::default();
+	let material_id = Uuid::new_v4();
+	let orphan_id = Uuid::new_v4();
+
+	for (id, name) in [(material_id, "used"), (orphan_id, "orphan")] {
+		sqlx::query(
+			r#"
+                INSERT INTO material (id, name, long_name, description, supplier_id)
+                VALUES ($1, $2, $3, $4, $5)
+            "#,
+		)
+		.bind(id)
+		.bind(name)
+		.bind(name)
+		.bind(name)
+		.bind(Option::<Uuid>::None)
+		.execute(&pool)
+		.await
+		.unwrap();
+	}
+
+	item.material_id = Some(material_id);
+	insert_item(&item, &pool).await.unwrap();
+
+	let page = QueryBuilder::<Item>::read()
+		.join(ItemJoin::ItemToMaterialByMaterialId, JoinKind::Full)
+		.build()
+		.fetch_page(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(page.items.len(), 1);
+	assert_eq!(page.total, 1);
+
+	let first = QueryBuilder::<Item>::read()
+		.join(ItemJoin::ItemToMaterialByMaterialId, JoinKind::Full)
+		.paginate(Pagination {
+			page:      0,
+			page_size: 1,
+		})
+		.build()
+		.fetch_page(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(first.items.len(), 1);
+	assert_eq!(first.items[0].id, item.id);
+	assert_eq!(first.total, 1);
+}
+
 #[tokio::test]
 async fn navigation_not_loaded_without_join() {
 	let pool = get_connection_pool().await;
@@ -1643,6 +2685,243 @@ async fn has_many_join_dedupes_root_rows() {
 	}
 }
 
+#[tokio::test]
+async fn recursive_reads_walk_category_tree() {
+	let pool = get_connection_pool().await;
+	let root = Uuid::new_v4();
+	let child = Uuid::new_v4();
+	let sibling = Uuid::new_v4();
+	let grandchild = Uuid::new_v4();
+
+	for (id, name, parent) in [
+		(root, "root", None),
+		(child, "child", Some(root)),
+		(sibling, "sibling", Some(root)),
+		(grandchild, "grandchild", Some(child)),
+	] {
+		sqlx::query(
+			"INSERT INTO category (id, name, parent_id) VALUES ($1, $2, $3)",
+		)
+		.bind(id)
+		.bind(name)
+		.bind(parent)
+		.execute(&pool)
+		.await
+		.unwrap();
+	}
+
+	let descendants: Vec<Category> = QueryBuilder::<Category>::read()
+		.descendants_of(
+			CategoryJoin::CategoryToCategoryByParentId,
+			CategoryQuery::IdEq(root).into(),
+		)
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(descendants.len(), 4);
+	assert_eq!(descendants[0].id, root);
+
+	let shallow: Vec<Category> = QueryBuilder::<Category>::read()
+		.descendants_of(
+			CategoryJoin::CategoryToCategoryByParentId,
+			CategoryQuery::IdEq(root).into(),
+		)
+		.max_depth(1)
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(shallow.len(), 3);
+	assert!(shallow.iter().all(|c| c.id != grandchild));
+
+	let tree = QueryBuilder::<Category>::read()
+		.descendants_of(
+			CategoryJoin::CategoryToCategoryByParentId,
+			CategoryQuery::IdEq(root).into(),
+		)
+		.build()
+		.fetch_tree(&pool)
+		.await
+		.unwrap();
+	assert_eq!(tree.len(), 1);
+	assert_eq!(tree[0].node.id, root);
+	assert_eq!(tree[0].depth, 0);
+	assert_eq!(tree[0].children.len(), 2);
+	let child_node = tree[0]
+		.children
+		.iter()
+		.find(|n| n.node.id == child)
+		.expect("child node");
+	assert_eq!(child_node.depth, 1);
+	assert_eq!(child_node.children.len(), 1);
+	assert_eq!(child_node.children[0].node.id, grandchild);
+	assert_eq!(child_node.children[0].depth, 2);
+
+	let ancestors: Vec<Category> = QueryBuilder::<Category>::read()
+		.ancestors_of(
+			CategoryJoin::CategoryToCategoryByParentId,
+			CategoryQuery::IdEq(grandchild).into(),
+		)
+		.join(CategoryJoin::CategoryToCategoryByParentId, JoinKind::Left)
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	let ids: Vec<Uuid> = ancestors.iter().map(|c| c.id).collect();
+	assert_eq!(ids, vec![grandchild, child, root]);
+	match &ancestors[0].parent {
+		JoinValue::Loaded(parent) => assert_eq!(parent.id, child),
+		other => panic!("expected loaded parent, got {:?}", other),
+	}
+	assert!(matches!(ancestors[2].parent, JoinValue::Missing));
+}
+
+#[tokio::test]
+async fn with_count_hydrates_relation_counts() {
+	let pool = get_connection_pool().await;
+	let root = Uuid::new_v4();
+	let child = Uuid::new_v4();
+
+	for (id, name, parent) in [
+		(root, "root", None),
+		(child, "child", Some(root)),
+		(Uuid::new_v4(), "sibling", Some(root)),
+		(Uuid::new_v4(), "grandchild", Some(child)),
+	] {
+		sqlx::query(
+			"INSERT INTO category (id, name, parent_id) VALUES ($1, $2, $3)",
+		)
+		.bind(id)
+		.bind(name)
+		.bind(parent)
+		.execute(&pool)
+		.await
+		.unwrap();
+	}
+
+	let categories: Vec<Category> = QueryBuilder::<Category>::read()
+		.with_count(CategoryJoin::CategoryToCategoryByChildren)
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(categories.len(), 4);
+	for category in &categories {
+		let expected = if category.id == root {
+			2
+		} else if category.id == child {
+			1
+		} else {
+			0
+		};
+		assert_eq!(category.child_count, JoinValue::Loaded(expected));
+	}
+
+	let page = QueryBuilder::<Category>::read()
+		.r#where(CategoryQuery::IdEq(root).into())
+		.with_count(CategoryJoin::CategoryToCategoryByChildren)
+		.paginate(Pagination {
+			page:      0,
+			page_size: 10,
+		})
+		.build()
+		.fetch_page(&pool)
+		.await
+		.unwrap();
+	assert_eq!(page.items[0].child_count, JoinValue::Loaded(2));
+
+	let plain: Category = QueryBuilder::<Category>::read()
+		.r#where(CategoryQuery::IdEq(root).into())
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+	assert_eq!(plain.child_count, JoinValue::NotLoaded);
+}
+
+#[tokio::test]
+async fn composite_keys_hydrate_per_tenant() {
+	let pool = get_connection_pool().await;
+	let supplier_id = Uuid::new_v4();
+	let tenants = [Uuid::new_v4(), Uuid::new_v4()];
+
+	for (idx, tenant_id) in tenants.iter().enumerate() {
+		sqlx::query(
+			"INSERT INTO tenant_supplier (tenant_id, id, name) VALUES ($1, \
+			 $2, $3)",
+		)
+		.bind(tenant_id)
+		.bind(supplier_id)
+		.bind(format!("supplier {idx}"))
+		.execute(&pool)
+		.await
+		.unwrap();
+
+		for material in 0..=idx {
+			sqlx::query(
+				"INSERT INTO tenant_material (tenant_id, id, name, \
+				 supplier_id) VALUES ($1, $2, $3, $4)",
+			)
+			.bind(tenant_id)
+			.bind(Uuid::new_v4())
+			.bind(format!("material {idx}.{material}"))
+			.bind(supplier_id)
+			.execute(&pool)
+			.await
+			.unwrap();
+		}
+	}
+
+	let suppliers: Vec<TenantSupplier> = QueryBuilder::<TenantSupplier>::read()
+		.join(
+			TenantSupplierJoin::TenantSupplierToTenantMaterialByMaterials,
+			JoinKind::Left,
+		)
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(suppliers.len(), 2);
+	for supplier in &suppliers {
+		let expected = if supplier.tenant_id == tenants[0] {
+			1
+		} else {
+			2
+		};
+		match &supplier.materials {
+			JoinValue::Loaded(materials) => {
+				assert_eq!(materials.len(), expected);
+				assert!(materials
+					.iter()
+					.all(|m| m.tenant_id == supplier.tenant_id));
+			}
+			other => panic!("expected loaded materials, got {:?}", other),
+		}
+	}
+
+	let materials: Vec<TenantMaterial> = QueryBuilder::<TenantMaterial>::read()
+		.join(
+			TenantMaterialJoin::TenantMaterialToTenantSupplierBySupplierId,
+			JoinKind::Inner,
+		)
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(materials.len(), 3);
+	for material in &materials {
+		match &material.supplier {
+			JoinValue::Loaded(supplier) => {
+				assert_eq!(supplier.tenant_id, material.tenant_id);
+			}
+			other => panic!("expected loaded supplier, got {:?}", other),
+		}
+	}
+}
+
 #[tokio::test]
 async fn many_to_many_navigation_hydrates_tags() {
 	let pool = get_connection_pool().await;
@@ -2022,48 +3301,430 @@ async fn insert_multiple_items() {
 }
 
 #[tokio::test]
-async fn insert_then_read_and_verify() {
-	let pool = get_connection_pool().await;
-
-	let create = CreateItemCreation {
-		id:          Uuid::new_v4(),
-		name:        "verify item".into(),
-		description: "for verification".into(),
-		price:       77.77,
+async fn insert_graph_propagates_parent_keys() {
+	use crate::helpers::{
+		Author,
+		AuthorCreation,
+		CommentCreation,
+		PostCreation,
 	};
 
-	// Insert
-	let inserted: CreateItem = QueryBuilder::<CreateItem>::insert()
-		.model(create.clone())
+	let pool = get_connection_pool().await;
+	let post = |title: &str, comments: Vec<CommentCreation>| PostCreation {
+		id: Uuid::new_v4(),
+		author_id: Uuid::nil(),
+		editor_id: None,
+		title: title.into(),
+		comments,
+	};
+	let comment = |body: &str| CommentCreation {
+		id:      Uuid::new_v4(),
+		post_id: Uuid::nil(),
+		body:    body.into(),
+	};
+	let author_id = Uuid::new_v4();
+
+	let authors = QueryBuilder::<Author>::insert()
+		.model(AuthorCreation {
+			id:    author_id,
+			name:  "ann".into(),
+			posts: vec![
+				post("first", vec![comment("nice"), comment("thanks")]),
+				post("second", vec![]),
+			],
+		})
 		.build()
-		.fetch_one(&pool)
+		.fetch_graph(&pool)
 		.await
 		.unwrap();
 
-	// Read back
-	let retrieved: CreateItem = QueryBuilder::<CreateItem>::read()
-		.r#where(Expression::Leaf(CreateItemQuery::IdEq(create.id)))
-		.build()
-		.fetch_one(&pool)
-		.await
-		.unwrap();
+	assert_eq!(authors.len(), 1);
+	let JoinValue::Loaded(posts) = &authors[0].posts else {
+		panic!("posts not loaded");
+	};
+	assert_eq!(posts.len(), 2);
+	assert!(posts.iter().all(|p| p.author_id == author_id));
+	let JoinValue::Loaded(comments) = &posts[0].comments else {
+		panic!("comments not loaded");
+	};
+	assert_eq!(comments.len(), 2);
+	assert!(comments.iter().all(|c| c.post_id == posts[0].id));
+	assert_eq!(posts[1].comments, JoinValue::Loaded(vec![]));
 
-	// Should match
-	assert_eq!(retrieved.id, inserted.id);
-	assert_eq!(retrieved.name, inserted.name);
-	assert_eq!(retrieved.description, inserted.description);
-	assert_eq!(retrieved.price, inserted.price);
+	let stored: i64 = sqlx::query_scalar(
+		"SELECT COUNT(*) FROM comment JOIN post ON post.id = comment.post_id \
+		 WHERE post.author_id = $1",
+	)
+	.bind(author_id)
+	.fetch_one(&pool)
+	.await
+	.unwrap();
+	assert_eq!(stored, 2);
 
-	// Timestamps should be very close (both set by DB)
-	let diff = (retrieved.created_at - inserted.created_at)
-		.num_milliseconds()
-		.abs();
-	assert!(diff < 1000); // Within 1 second
+	let err = QueryBuilder::<Author>::insert()
+		.model(AuthorCreation {
+			id:    Uuid::new_v4(),
+			name:  "bob".into(),
+			posts: vec![post("orphan", vec![comment("lost")])],
+		})
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap_err();
+	assert!(UnsupportedInsert::from_error(&err).is_some());
 }
 
 #[tokio::test]
-async fn insert_with_special_characters() {
-	let pool = get_connection_pool().await;
+async fn insert_graph_pairs_children_with_their_parent() {
+	use crate::helpers::{
+		Author,
+		AuthorCreation,
+		PostCreation,
+	};
+
+	let pool = get_connection_pool().await;
+	let author = |name: &str, titles: &[&str]| AuthorCreation {
+		id:    Uuid::new_v4(),
+		name:  name.into(),
+		posts: titles
+			.iter()
+			.map(|title| PostCreation {
+				id:        Uuid::new_v4(),
+				author_id: Uuid::nil(),
+				editor_id: None,
+				title:     (*title).into(),
+				comments:  vec![],
+			})
+			.collect(),
+	};
+	let creations = vec![
+		author("ann", &["ann 1", "ann 2"]),
+		author("bob", &[]),
+		author("cat", &["cat 1"]),
+	];
+
+	let authors = QueryBuilder::<Author>::insert()
+		.models(creations.clone())
+		.build()
+		.fetch_graph(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(
+		authors.iter().map(|a| a.id).collect::<Vec<_>>(),
+		creations.iter().map(|c| c.id).collect::<Vec<_>>()
+	);
+	for author in &authors {
+		let JoinValue::Loaded(posts) = &author.posts else {
+			panic!("posts not loaded");
+		};
+		assert!(posts.iter().all(|p| p.author_id == author.id));
+		assert!(posts.iter().all(|p| p.title.starts_with(&author.name)));
+	}
+}
+
+#[tokio::test]
+async fn insert_models_chunks_below_bind_limit() {
+	let pool = get_connection_pool().await;
+
+	let creates: Vec<CreateItemCreation> = (0..20_000)
+		.map(|idx| CreateItemCreation {
+			id:          Uuid::new_v4(),
+			name:        format!("bulk {idx}"),
+			description: "bulk insert".into(),
+			price:       idx as f32,
+		})
+		.collect();
+
+	let plan = QueryBuilder::<CreateItem>::insert()
+		.models(creates.clone())
+		.build();
+	assert_eq!(plan.statement_count(), 2);
+
+	let err = plan.execute(&pool).await.unwrap_err();
+	assert_eq!(
+		NeedsTransaction::from_error(&err).map(|e| e.table.as_str()),
+		Some("create_item")
+	);
+
+	let inserted: Vec<CreateItem> = plan.fetch_all_atomic(&pool).await.unwrap();
+
+	assert_eq!(inserted.len(), creates.len());
+	let ids: Vec<Uuid> = inserted.iter().map(|i| i.id).collect();
+	assert_eq!(ids, creates.iter().map(|c| c.id).collect::<Vec<_>>());
+
+	let count = QueryBuilder::<CreateItem>::read()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap()
+		.len();
+	assert_eq!(count, 20_000);
+}
+
+#[tokio::test]
+async fn insert_no_models_is_a_no_op() {
+	let pool = get_connection_pool().await;
+
+	let plan = QueryBuilder::<CreateItem>::insert()
+		.models(Vec::<CreateItemCreation>::new())
+		.build();
+	assert_eq!(plan.statement_count(), 0);
+
+	assert_eq!(plan.execute(&pool).await.unwrap(), 0);
+	assert_eq!(plan.execute_atomic(&pool).await.unwrap(), 0);
+	let inserted: Vec<CreateItem> = plan.fetch_all(&pool).await.unwrap();
+	assert!(inserted.is_empty());
+	let inserted: Vec<CreateItem> = plan.fetch_all_atomic(&pool).await.unwrap();
+	assert!(inserted.is_empty());
+}
+
+#[tokio::test]
+async fn upsert_updates_conflicting_rows_and_skips_guarded_ones() {
+	let pool = get_connection_pool().await;
+
+	let first = StockItemCreation {
+		id:           Uuid::new_v4(),
+		sku:          "sku-1".into(),
+		name:         "first".into(),
+		amount:       3,
+		restocked_at: None,
+	};
+	let second = StockItemCreation {
+		id:           Uuid::new_v4(),
+		sku:          "sku-2".into(),
+		name:         "second".into(),
+		amount:       50,
+		restocked_at: None,
+	};
+	QueryBuilder::<StockItem>::insert()
+		.models([first.clone(), second.clone()])
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+
+	let sync = |sku: &str, amount| StockItemCreation {
+		id: Uuid::new_v4(),
+		sku: sku.into(),
+		name: format!("synced {sku}"),
+		amount,
+		restocked_at: None,
+	};
+	let upserted: Vec<StockItem> = QueryBuilder::<StockItem>::insert()
+		.models([sync("sku-1", 10), sync("sku-2", 20), sync("sku-3", 30)])
+		.on_conflict(StockItemColumn::Sku)
+		.do_update_excluded((StockItemColumn::Name, StockItemColumn::Amount))
+		.conflict_where(StockItemQuery::AmountLt(10).into())
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	let skus: Vec<&str> = upserted.iter().map(|i| i.sku.as_str()).collect();
+	assert_eq!(skus, ["sku-1", "sku-3"]);
+	assert_eq!(upserted[0].id, first.id);
+	assert_eq!(upserted[0].amount, 10);
+	assert!(upserted[0].updated_at.is_some());
+	assert!(upserted[1].updated_at.is_none());
+
+	let skipped = QueryBuilder::<StockItem>::insert()
+		.model(sync("sku-2", 0))
+		.on_conflict(StockItemColumn::Sku)
+		.do_nothing()
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+	assert_eq!(skipped, 0);
+
+	let untouched: StockItem = QueryBuilder::<StockItem>::read()
+		.r#where(StockItemQuery::IdEq(second.id).into())
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+	assert_eq!(untouched.amount, 50);
+	assert_eq!(untouched.name, "second");
+}
+
+#[tokio::test]
+async fn incomplete_on_conflict_fails_instead_of_inserting() {
+	let pool = get_connection_pool().await;
+
+	let stock = |sku: &str| StockItemCreation {
+		id:           Uuid::new_v4(),
+		sku:          sku.into(),
+		name:         sku.into(),
+		amount:       1,
+		restocked_at: None,
+	};
+	let err = QueryBuilder::<StockItem>::insert()
+		.model(stock("no-action"))
+		.on_conflict(StockItemColumn::Sku)
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap_err();
+	let unsupported = UnsupportedInsert::from_error(&err).expect("unsupported");
+	assert_eq!(unsupported.table, "stock_item");
+
+	let err = QueryBuilder::<StockItem>::insert()
+		.model(stock("guarded-nothing"))
+		.on_conflict(StockItemColumn::Sku)
+		.do_nothing()
+		.conflict_where(StockItemQuery::AmountLt(10).into())
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap_err();
+	assert!(UnsupportedInsert::from_error(&err).is_some());
+
+	let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM stock_item")
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+	assert_eq!(stored, 0);
+}
+
+#[tokio::test]
+async fn update_ops_book_stock_atomically() {
+	let pool = get_connection_pool().await;
+
+	let item: StockItem = QueryBuilder::<StockItem>::insert()
+		.model(StockItemCreation {
+			id:           Uuid::new_v4(),
+			sku:          "booked".into(),
+			name:         "booked".into(),
+			amount:       5,
+			restocked_at: None,
+		})
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+
+	let bookings: Vec<_> = (0..8)
+		.map(|_| {
+			let pool = pool.clone();
+			tokio::spawn(async move {
+				QueryBuilder::<StockItem>::update()
+					.set(StockItemUpdateOp::AmountDecrement(1))
+					.r#where(and![
+						StockItemQuery::IdEq(item.id),
+						StockItemQuery::AmountGte(1)
+					])
+					.build()
+					.execute(&pool)
+					.await
+					.unwrap()
+			})
+		})
+		.collect();
+	let mut booked = 0;
+	for booking in bookings {
+		booked += booking.await.unwrap();
+	}
+	assert_eq!(booked, 5);
+
+	let restocked: StockItem = QueryBuilder::<StockItem>::update()
+		.set(StockItemUpdateOp::AmountIncrement(10))
+		.set(StockItemUpdateOp::RestockedAtSetNow)
+		.r#where(StockItemQuery::IdEq(item.id).into())
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+	assert_eq!(restocked.amount, 10);
+	assert!(restocked.restocked_at.is_some());
+	assert!(restocked.updated_at.is_some());
+}
+
+#[tokio::test]
+async fn update_with_stale_version_fails() {
+	let pool = get_connection_pool().await;
+
+	let item: StockItem = QueryBuilder::<StockItem>::insert()
+		.model(StockItemCreation {
+			id:           Uuid::new_v4(),
+			sku:          "versioned".into(),
+			name:         "versioned".into(),
+			amount:       1,
+			restocked_at: None,
+		})
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+	assert_eq!(item.version, 0);
+
+	let rename = |name: &str| {
+		QueryBuilder::<StockItem>::update()
+			.model(StockItemUpdate {
+				name: Some(name.into()),
+				..Default::default()
+			})
+			.r#where(StockItemQuery::IdEq(item.id).into())
+			.expected_version(item.version.into())
+			.build()
+	};
+
+	let updated: StockItem = rename("first").fetch_one(&pool).await.unwrap();
+	assert_eq!(updated.version, 1);
+
+	let err = rename("second").execute(&pool).await.unwrap_err();
+	assert_eq!(
+		StaleVersion::from_error(&err),
+		Some(&StaleVersion {
+			table:    "stock_item".into(),
+			expected: 0,
+		})
+	);
+}
+
+#[tokio::test]
+async fn insert_then_read_and_verify() {
+	let pool = get_connection_pool().await;
+
+	let create = CreateItemCreation {
+		id:          Uuid::new_v4(),
+		name:        "verify item".into(),
+		description: "for verification".into(),
+		price:       77.77,
+	};
+
+	// Insert
+	let inserted: CreateItem = QueryBuilder::<CreateItem>::insert()
+		.model(create.clone())
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+
+	// Read back
+	let retrieved: CreateItem = QueryBuilder::<CreateItem>::read()
+		.r#where(Expression::Leaf(CreateItemQuery::IdEq(create.id)))
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+
+	// Should match
+	assert_eq!(retrieved.id, inserted.id);
+	assert_eq!(retrieved.name, inserted.name);
+	assert_eq!(retrieved.description, inserted.description);
+	assert_eq!(retrieved.price, inserted.price);
+
+	// Timestamps should be very close (both set by DB)
+	let diff = (retrieved.created_at - inserted.created_at)
+		.num_milliseconds()
+		.abs();
+	assert!(diff < 1000); // Within 1 second
+}
+
+#[tokio::test]
+async fn insert_with_special_characters() {
+	let pool = get_connection_pool().await;
 
 	let create = CreateItemCreation {
 		id:          Uuid::new_v4(),
@@ -2300,3 +3961,858 @@ async fn insert_preserves_all_field_values() {
 	assert_eq!(created.description, create.description);
 	assert_eq!(created.price, create.price);
 }
+
+#[tokio::test]
+async fn unfiltered_delete_requires_all_rows() {
+	use sqlxo::{
+		web::WebDeleteFilter,
+		TooManyRowsAffected,
+		UnfilteredMutation,
+	};
+
+	use crate::helpers::HardDeleteItemDto;
+
+	let pool = get_connection_pool().await;
+	for price in [10.0, 20.0, 30.0] {
+		let item = HardDeleteItem {
+			price,
+			..Default::default()
+		};
+		insert_hard_delete_item(&item, &pool).await.unwrap();
+	}
+	let count = || async {
+		sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM hard_delete_item")
+			.fetch_one(&pool)
+			.await
+			.unwrap()
+	};
+
+	let err = QueryBuilder::<HardDeleteItem>::delete()
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap_err();
+	assert_eq!(
+		UnfilteredMutation::from_error(&err),
+		Some(&UnfilteredMutation {
+			table: "hard_delete_item".into(),
+		})
+	);
+	assert_eq!(count().await, 3);
+
+	let filter: WebDeleteFilter<HardDeleteItemDto> =
+		serde_json::from_value(json!({})).unwrap();
+	let err = QueryBuilder::<HardDeleteItem>::from_web_delete::<
+		HardDeleteItemDto,
+	>(&filter)
+	.build()
+	.execute(&pool)
+	.await
+	.unwrap_err();
+	assert!(UnfilteredMutation::from_error(&err).is_some());
+	assert_eq!(count().await, 3);
+
+	let err = QueryBuilder::<HardDeleteItem>::delete()
+		.r#where(HardDeleteItemQuery::PriceLt(25.0).into())
+		.max_affected_rows(1)
+		.build()
+		.execute_atomic(&pool)
+		.await
+		.unwrap_err();
+	assert_eq!(
+		TooManyRowsAffected::from_error(&err),
+		Some(&TooManyRowsAffected {
+			table:    "hard_delete_item".into(),
+			max:      1,
+			affected: 2,
+		})
+	);
+	assert_eq!(count().await, 3);
+
+	let deleted = QueryBuilder::<HardDeleteItem>::delete()
+		.all_rows()
+		.max_affected_rows(3)
+		.build()
+		.execute_atomic(&pool)
+		.await
+		.unwrap();
+	assert_eq!(deleted, 3);
+	assert_eq!(count().await, 0);
+}
+
+#[tokio::test]
+async fn update_from_table_sharing_columns() {
+	use crate::helpers::{
+		StockBookingColumn,
+		StockBookingQuery,
+		StockItemJoin,
+	};
+	use sqlxo::select::ColumnRef;
+	use sqlxo::JoinPath;
+
+	let pool = get_connection_pool().await;
+	let (booked, idle) = (Uuid::new_v4(), Uuid::new_v4());
+	QueryBuilder::<StockItem>::insert()
+		.models([(booked, "sku-a"), (idle, "sku-b")].map(|(id, sku)| {
+			StockItemCreation {
+				id,
+				sku: sku.into(),
+				name: sku.into(),
+				amount: 5,
+				restocked_at: None,
+			}
+		}))
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+	sqlx::query(
+		"INSERT INTO stock_booking (id, stock_item_id, name, amount) VALUES \
+		 ($1, $2, 'booked', 2)",
+	)
+	.bind(Uuid::new_v4())
+	.bind(booked)
+	.execute(&pool)
+	.await
+	.unwrap();
+
+	let bookings = JoinPath::from_join(
+		StockItemJoin::StockItemToStockBookingByBookings,
+		JoinKind::Inner,
+	);
+	let updated: Vec<StockItem> = QueryBuilder::<StockItem>::update()
+		.set(StockItemUpdateOp::AmountDecrement(1))
+		.set(StockItemUpdateOp::NameSetColumn(ColumnRef::joined(
+			bookings.clone(),
+			StockBookingColumn::Name,
+		)))
+		.where_join(bookings, Expression::Leaf(StockBookingQuery::AmountGt(0)))
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+
+	assert_eq!(updated.len(), 1);
+	assert_eq!(updated[0].id, booked);
+	assert_eq!(updated[0].amount, 4);
+	assert_eq!(updated[0].name, "booked");
+	assert_eq!(updated[0].version, 1);
+}
+
+#[tokio::test]
+async fn update_max_affected_rows_rolls_back() {
+	use sqlxo::TooManyRowsAffected;
+
+	let pool = get_connection_pool().await;
+	let models = ["sku-a", "sku-b"].map(|sku| StockItemCreation {
+		id:           Uuid::new_v4(),
+		sku:          sku.into(),
+		name:         sku.into(),
+		amount:       5,
+		restocked_at: None,
+	});
+	QueryBuilder::<StockItem>::insert()
+		.models(models)
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+
+	let err = QueryBuilder::<StockItem>::update()
+		.set(StockItemUpdateOp::AmountIncrement(1))
+		.r#where(StockItemQuery::AmountLt(10).into())
+		.max_affected_rows(1)
+		.build()
+		.fetch_all_atomic(&pool)
+		.await
+		.unwrap_err();
+	assert!(TooManyRowsAffected::from_error(&err).is_some());
+
+	let items: Vec<StockItem> = QueryBuilder::<StockItem>::read()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert!(items.iter().all(|item| item.amount == 5));
+
+	let updated = QueryBuilder::<StockItem>::update()
+		.set(StockItemUpdateOp::AmountIncrement(1))
+		.all_rows()
+		.max_affected_rows(2)
+		.build()
+		.execute_atomic(&pool)
+		.await
+		.unwrap();
+	assert_eq!(updated, 2);
+}
+
+#[tokio::test]
+async fn primary_key_lookups() {
+	let pool = get_connection_pool().await;
+	let items: Vec<HardDeleteItem> =
+		(0..3).map(|_| HardDeleteItem::default()).collect();
+	for item in &items {
+		insert_hard_delete_item(item, &pool).await.unwrap();
+	}
+
+	let found: HardDeleteItem =
+		QueryBuilder::<HardDeleteItem>::find(items[1].id)
+			.build()
+			.fetch_one(&pool)
+			.await
+			.unwrap();
+	assert_eq!(found.id, items[1].id);
+
+	let keys = [items[2].id, items[0].id, items[1].id];
+	let many: Vec<HardDeleteItem> =
+		QueryBuilder::<HardDeleteItem>::find_many(&keys)
+			.build()
+			.fetch_all(&pool)
+			.await
+			.unwrap();
+	assert_eq!(many.iter().map(|i| i.id).collect::<Vec<_>>(), keys);
+
+	// More keys than Postgres accepts bind parameters in one statement.
+	let mut keys: Vec<Uuid> = (0..70_000).map(|_| Uuid::new_v4()).collect();
+	keys.push(items[1].id);
+	let many: Vec<HardDeleteItem> =
+		QueryBuilder::<HardDeleteItem>::find_many(&keys)
+			.build()
+			.fetch_all(&pool)
+			.await
+			.unwrap();
+	assert_eq!(many.iter().map(|i| i.id).collect::<Vec<_>>(), [items[1].id]);
+
+	assert!(QueryBuilder::<HardDeleteItem>::exists(items[0].id, &pool)
+		.await
+		.unwrap());
+
+	let deleted = QueryBuilder::<HardDeleteItem>::delete_by_id(items[0].id)
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+	assert_eq!(deleted, 1);
+	assert!(!QueryBuilder::<HardDeleteItem>::exists(items[0].id, &pool)
+		.await
+		.unwrap());
+
+	let item = UpdateItem::default();
+	insert_update_item(&item, &pool).await.unwrap();
+	let updated: UpdateItem = QueryBuilder::<UpdateItem>::update_by_id(item.id)
+		.model(UpdateItemUpdate {
+			name: Some("by id".into()),
+			..Default::default()
+		})
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+	assert_eq!(updated.name, "by id");
+}
+
+#[tokio::test]
+async fn tenant_scope_isolates_rows() {
+	use crate::helpers::{
+		Project,
+		ProjectCreation,
+		ProjectUpdate,
+		Task,
+		TaskCreation,
+		TaskJoin,
+	};
+	use sqlxo::{
+		tenant,
+		MissingTenant,
+	};
+
+	let pool = get_connection_pool().await;
+	let (tenant_a, tenant_b) = (Uuid::new_v4(), Uuid::new_v4());
+	let (project_a, project_b) = (Uuid::new_v4(), Uuid::new_v4());
+
+	for (tenant_id, project_id) in
+		[(tenant_a, project_a), (tenant_b, project_b)]
+	{
+		tenant::scope(tenant_id, async {
+			QueryBuilder::<Project>::insert()
+				.model(ProjectCreation {
+					id:   project_id,
+					name: "project".into(),
+				})
+				.build()
+				.execute(&pool)
+				.await
+				.unwrap();
+			QueryBuilder::<Task>::insert()
+				.model(TaskCreation {
+					id: Uuid::new_v4(),
+					project_id,
+					title: "task".into(),
+				})
+				.build()
+				.execute(&pool)
+				.await
+				.unwrap();
+		})
+		.await;
+	}
+
+	tenant::scope(tenant_a, async {
+		let projects: Vec<Project> = QueryBuilder::<Project>::read()
+			.build()
+			.fetch_all(&pool)
+			.await
+			.unwrap();
+		assert_eq!(projects.len(), 1);
+		assert_eq!(projects[0].tenant_id, tenant_a);
+
+		let tasks: Vec<Task> = QueryBuilder::<Task>::read()
+			.join(TaskJoin::TaskToProjectByProjectId, JoinKind::Left)
+			.build()
+			.fetch_all(&pool)
+			.await
+			.unwrap();
+		assert_eq!(tasks.len(), 1);
+		match &tasks[0].project {
+			JoinValue::Loaded(project) => assert_eq!(project.id, project_a),
+			other => panic!("expected the project to be loaded, got {other:?}"),
+		}
+
+		let updated = QueryBuilder::<Project>::update_by_id(project_b)
+			.model(ProjectUpdate {
+				name: Some("taken".into()),
+			})
+			.build()
+			.execute(&pool)
+			.await
+			.unwrap();
+		assert_eq!(updated, 0);
+	})
+	.await;
+
+	let err = QueryBuilder::<Project>::read()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap_err();
+	assert_eq!(
+		MissingTenant::from_error(&err).map(|e| e.table.as_str()),
+		Some("project")
+	);
+
+	let all: Vec<Project> = QueryBuilder::<Project>::read()
+		.all_tenants()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(all.len(), 2);
+}
+
+#[tokio::test]
+async fn relation_counts_are_scoped_to_tenant() {
+	use crate::helpers::{
+		Label,
+		LabelJoin,
+	};
+	use sqlxo::tenant;
+
+	let pool = get_connection_pool().await;
+	let label = Uuid::new_v4();
+	let (tenant_a, tenant_b) = (Uuid::new_v4(), Uuid::new_v4());
+
+	sqlx::query("INSERT INTO label (id, name) VALUES ($1, 'urgent')")
+		.bind(label)
+		.execute(&pool)
+		.await
+		.unwrap();
+	for tenant_id in [tenant_a, tenant_a, tenant_b] {
+		sqlx::query(
+			"INSERT INTO task_label (id, tenant_id, label_id) VALUES ($1, $2, \
+			 $3)",
+		)
+		.bind(Uuid::new_v4())
+		.bind(tenant_id)
+		.bind(label)
+		.execute(&pool)
+		.await
+		.unwrap();
+	}
+
+	for (tenant_id, expected) in [(tenant_a, 2), (tenant_b, 1)] {
+		let labels: Vec<Label> = tenant::scope(tenant_id, async {
+			QueryBuilder::<Label>::read()
+				.with_count(LabelJoin::LabelToTaskLabelByUses)
+				.build()
+				.fetch_all(&pool)
+				.await
+		})
+		.await
+		.unwrap();
+		assert_eq!(labels.len(), 1);
+		assert_eq!(labels[0].use_count, JoinValue::Loaded(expected));
+	}
+
+	let all: Label = QueryBuilder::<Label>::read()
+		.with_count(LabelJoin::LabelToTaskLabelByUses)
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+	assert_eq!(all.use_count, JoinValue::Loaded(3));
+}
+
+#[tokio::test]
+async fn default_and_named_scopes() {
+	use crate::helpers::{
+		Note,
+		NoteCreation,
+	};
+
+	let pool = get_connection_pool().await;
+	let now = chrono::Utc::now();
+
+	QueryBuilder::<Note>::insert()
+		.models([
+			NoteCreation {
+				id:           Uuid::new_v4(),
+				title:        "published".into(),
+				archived:     false,
+				published_at: Some(now),
+			},
+			NoteCreation {
+				id:           Uuid::new_v4(),
+				title:        "draft".into(),
+				archived:     false,
+				published_at: None,
+			},
+			NoteCreation {
+				id:           Uuid::new_v4(),
+				title:        "archived".into(),
+				archived:     true,
+				published_at: Some(now),
+			},
+		])
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+
+	let titles = |notes: Vec<Note>| {
+		let mut titles: Vec<_> = notes.into_iter().map(|n| n.title).collect();
+		titles.sort();
+		titles
+	};
+
+	let live: Vec<Note> = QueryBuilder::<Note>::read()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(titles(live), ["draft", "published"]);
+
+	let published: Vec<Note> = QueryBuilder::<Note>::read()
+		.r#where(Note::scope_published())
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(titles(published), ["published"]);
+
+	let all_published: Vec<Note> = QueryBuilder::<Note>::read()
+		.r#where(Note::scope_published())
+		.unscoped()
+		.build()
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+	assert_eq!(titles(all_published), ["archived", "published"]);
+}
+
+#[tokio::test]
+async fn relation_counts_apply_the_default_scope() {
+	use crate::helpers::{
+		Notebook,
+		NotebookJoin,
+	};
+
+	let pool = get_connection_pool().await;
+	let notebook = Uuid::new_v4();
+
+	sqlx::query("INSERT INTO notebook (id, name) VALUES ($1, 'journal')")
+		.bind(notebook)
+		.execute(&pool)
+		.await
+		.unwrap();
+	for (title, archived) in [("live", false), ("old", true), ("older", true)] {
+		sqlx::query(
+			"INSERT INTO note (id, title, archived, notebook_id) VALUES ($1, \
+			 $2, $3, $4)",
+		)
+		.bind(Uuid::new_v4())
+		.bind(title)
+		.bind(archived)
+		.bind(notebook)
+		.execute(&pool)
+		.await
+		.unwrap();
+	}
+
+	let notebook: Notebook = QueryBuilder::<Notebook>::read()
+		.with_count(NotebookJoin::NotebookToNoteByNotes)
+		.build()
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+	assert_eq!(notebook.note_count, JoinValue::Loaded(1));
+}
+
+#[tokio::test]
+async fn cached_reads_are_invalidated_by_mutations() {
+	use sqlxo::cache::CachedExecutor;
+
+	use crate::helpers::{
+		Note,
+		NoteCreation,
+		NoteQuery,
+	};
+
+	let pool = get_connection_pool().await;
+	let cache = CachedExecutor::new(pool.clone(), 16);
+	let note = |title: &str| NoteCreation {
+		id:           Uuid::new_v4(),
+		title:        title.into(),
+		archived:     false,
+		published_at: None,
+	};
+	let count = |notes: Vec<Note>| notes.len();
+
+	QueryBuilder::<Note>::insert()
+		.model(note("first"))
+		.build()
+		.execute(cache.pool())
+		.await
+		.unwrap();
+
+	let read = QueryBuilder::<Note>::read().build();
+	assert_eq!(count(cache.fetch_all(&read).await.unwrap()), 1);
+
+	// Written past the executor, so the cached rows are served.
+	QueryBuilder::<Note>::insert()
+		.model(note("second"))
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+	assert_eq!(count(cache.fetch_all(&read).await.unwrap()), 1);
+
+	let bound = QueryBuilder::<Note>::read()
+		.r#where(Expression::Leaf(NoteQuery::TitleEq("second".into())))
+		.build();
+	assert_eq!(count(cache.fetch_all(&bound).await.unwrap()), 1);
+
+	cache
+		.execute(&QueryBuilder::<Note>::insert().model(note("third")).build())
+		.await
+		.unwrap();
+	assert_eq!(count(cache.fetch_all(&read).await.unwrap()), 3);
+
+	cache
+		.execute(
+			&QueryBuilder::<Note>::delete()
+				.r#where(Expression::Leaf(NoteQuery::TitleEq("second".into())))
+				.build(),
+		)
+		.await
+		.unwrap();
+	assert_eq!(count(cache.fetch_all(&read).await.unwrap()), 2);
+	assert_eq!(count(cache.fetch_all(&bound).await.unwrap()), 0);
+}
+
+#[tokio::test]
+async fn cached_aggregates_are_invalidated_by_child_mutations() {
+	use sqlxo::cache::CachedExecutor;
+
+	use crate::helpers::{
+		AuthorCreation,
+		CommentAgg,
+		CommentColumn,
+		CommentCreation,
+		Post,
+		PostCreation,
+		PostJoin,
+	};
+
+	let pool = get_connection_pool().await;
+	let cache = CachedExecutor::new(pool.clone(), 16);
+	let post_id = Uuid::new_v4();
+	let comment = |body: &str| CommentCreation {
+		id:      Uuid::new_v4(),
+		post_id: Uuid::nil(),
+		body:    body.into(),
+	};
+
+	QueryBuilder::<crate::helpers::Author>::insert()
+		.model(AuthorCreation {
+			id:    Uuid::new_v4(),
+			name:  "ann".into(),
+			posts: vec![PostCreation {
+				id:        post_id,
+				author_id: Uuid::nil(),
+				editor_id: None,
+				title:     "first".into(),
+				comments:  vec![comment("nice")],
+			}],
+		})
+		.build()
+		.fetch_graph(&pool)
+		.await
+		.unwrap();
+
+	let discussed = QueryBuilder::<Post>::read()
+		.join(PostJoin::PostToCommentByComments, JoinKind::Left)
+		.having(sqlxo::having!(
+			CommentAgg::CountDistinct(CommentColumn::Id).gt(1i64)
+		))
+		.build();
+	assert!(cache.fetch_all(&discussed).await.unwrap().is_empty());
+
+	cache
+		.execute(
+			&QueryBuilder::<crate::helpers::Comment>::insert()
+				.model(CommentCreation {
+					post_id,
+					..comment("thanks")
+				})
+				.build(),
+		)
+		.await
+		.unwrap();
+	let posts = cache.fetch_all(&discussed).await.unwrap();
+	assert_eq!(posts.iter().map(|p| p.id).collect::<Vec<_>>(), [post_id]);
+}
+
+#[tokio::test]
+async fn cached_reads_expire_after_ttl() {
+	use std::time::Duration;
+
+	use sqlxo::cache::CachedExecutor;
+
+	use crate::helpers::{
+		Note,
+		NoteCreation,
+	};
+
+	let pool = get_connection_pool().await;
+	let cache = CachedExecutor::new(pool.clone(), 16)
+		.with_ttl(Duration::from_millis(50));
+
+	let read = QueryBuilder::<Note>::read().build();
+	assert_eq!(cache.fetch_all(&read).await.unwrap().len(), 0);
+
+	QueryBuilder::<Note>::insert()
+		.model(NoteCreation {
+			id:           Uuid::new_v4(),
+			title:        "late".into(),
+			archived:     false,
+			published_at: None,
+		})
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+	assert_eq!(cache.fetch_all(&read).await.unwrap().len(), 0);
+
+	tokio::time::sleep(Duration::from_millis(60)).await;
+	assert_eq!(cache.fetch_all(&read).await.unwrap().len(), 1);
+	assert_eq!(cache.backend().len(), 1);
+}
+
+#[tokio::test]
+async fn cached_reads_racing_an_invalidation_are_not_stored() {
+	use std::{
+		future::Future,
+		task::{
+			Context,
+			Poll,
+			Waker,
+		},
+	};
+
+	use sqlxo::cache::CachedExecutor;
+
+	use crate::helpers::Note;
+
+	let pool = get_connection_pool().await;
+	let cache = CachedExecutor::new(pool.clone(), 16);
+	let read = QueryBuilder::<Note>::read().build();
+
+	let mut running = Box::pin(cache.fetch_all(&read));
+	assert!(matches!(
+		running
+			.as_mut()
+			.poll(&mut Context::from_waker(Waker::noop())),
+		Poll::Pending
+	));
+	cache.invalidate("note");
+	running.await.unwrap();
+	assert!(cache.backend().is_empty());
+
+	cache.fetch_all(&read).await.unwrap();
+	assert_eq!(cache.backend().len(), 1);
+}
+
+#[tokio::test]
+async fn audited_changes_are_recorded_in_history() {
+	use sqlxo::audit::{
+		self,
+		AuditAction,
+	};
+
+	use crate::helpers::{
+		Invoice,
+		InvoiceColumn,
+		InvoiceCreation,
+		InvoiceUpdate,
+	};
+
+	let pool = get_connection_pool().await;
+	let id = Uuid::new_v4();
+
+	audit::scope("alice", async {
+		let inserted = QueryBuilder::<Invoice>::insert()
+			.model(InvoiceCreation {
+				id,
+				number: "A-1".into(),
+				amount: 100,
+			})
+			.build()
+			.execute(&pool)
+			.await
+			.unwrap();
+		assert_eq!(inserted, 1);
+	})
+	.await;
+
+	audit::scope("bob", async {
+		let updated: Vec<Invoice> = QueryBuilder::<Invoice>::update_by_id(id)
+			.model(InvoiceUpdate {
+				number: None,
+				amount: Some(150),
+			})
+			.build()
+			.fetch_all(&pool)
+			.await
+			.unwrap();
+		assert_eq!(updated[0].amount, 150);
+
+		QueryBuilder::<Invoice>::insert()
+			.model(InvoiceCreation {
+				id,
+				number: "A-2".into(),
+				amount: 150,
+			})
+			.on_conflict(InvoiceColumn::Id)
+			.do_update_excluded(InvoiceColumn::Number)
+			.build()
+			.execute(&pool)
+			.await
+			.unwrap();
+	})
+	.await;
+
+	let deleted = QueryBuilder::<Invoice>::delete_by_id(id)
+		.build()
+		.execute(&pool)
+		.await
+		.unwrap();
+	assert_eq!(deleted, 1);
+
+	let history = audit::history::<Invoice, _>(id, &pool).await.unwrap();
+	let actions: Vec<_> = history.iter().map(|e| e.action).collect();
+	assert_eq!(actions, [
+		AuditAction::Insert,
+		AuditAction::Update,
+		AuditAction::Update,
+		AuditAction::Delete,
+	]);
+	let actors: Vec<_> = history.iter().map(|e| e.actor.as_deref()).collect();
+	assert_eq!(actors, [Some("alice"), Some("bob"), Some("bob"), None]);
+
+	assert_eq!(history[0].before, None);
+	assert_eq!(history[0].after.as_ref().unwrap()["amount"], json!(100));
+	assert_eq!(history[1].before.as_ref().unwrap()["amount"], json!(100));
+	assert_eq!(history[1].after.as_ref().unwrap()["amount"], json!(150));
+	assert_eq!(history[2].after.as_ref().unwrap()["number"], json!("A-2"));
+	assert_eq!(history[3].before.as_ref().unwrap()["number"], json!("A-2"));
+	assert_eq!(history[3].after, None);
+	assert_eq!(history[3].entity, json!({ "id": id }));
+
+	let other = audit::history::<Invoice, _>(Uuid::new_v4(), &pool)
+		.await
+		.unwrap();
+	assert!(other.is_empty());
+}
+
+#[tokio::test]
+async fn delete_cascades_record_audited_children() {
+	use sqlxo::audit::{
+		self,
+		AuditAction,
+	};
+
+	use crate::helpers::{
+		Ledger,
+		LedgerEntry,
+		LedgerTag,
+	};
+
+	let pool = get_connection_pool().await;
+	let (ledger, entry, tag) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+	sqlx::query("INSERT INTO ledger (id, name) VALUES ($1, 'cash')")
+		.bind(ledger)
+		.execute(&pool)
+		.await
+		.unwrap();
+	sqlx::query(
+		"INSERT INTO ledger_entry (id, ledger_id, amount) VALUES ($1, $2, 5)",
+	)
+	.bind(entry)
+	.bind(ledger)
+	.execute(&pool)
+	.await
+	.unwrap();
+	sqlx::query(
+		"INSERT INTO ledger_tag (id, ledger_id, name) VALUES ($1, $2, 'q1')",
+	)
+	.bind(tag)
+	.bind(ledger)
+	.execute(&pool)
+	.await
+	.unwrap();
+
+	let plan = audit::sync_scope("carol", || {
+		QueryBuilder::<Ledger>::delete_by_id(ledger).build()
+	});
+	assert_eq!(plan.execute_atomic(&pool).await.unwrap(), 1);
+
+	let history = audit::history::<LedgerEntry, _>(entry, &pool)
+		.await
+		.unwrap();
+	assert_eq!(history.len(), 1);
+	assert_eq!(history[0].action, AuditAction::Delete);
+	assert_eq!(history[0].actor.as_deref(), Some("carol"));
+	assert_eq!(history[0].before.as_ref().unwrap()["amount"], json!(5));
+	assert_eq!(history[0].after, None);
+
+	let history = audit::history::<LedgerTag, _>(tag, &pool).await.unwrap();
+	assert_eq!(history.len(), 1);
+	assert_eq!(history[0].action, AuditAction::Update);
+	assert_eq!(history[0].actor.as_deref(), Some("carol"));
+	assert_eq!(
+		history[0].before.as_ref().unwrap()["ledger_id"],
+		json!(ledger)
+	);
+	assert_eq!(history[0].after.as_ref().unwrap()["ledger_id"], json!(null));
+}
@@ -197,3 +197,235 @@ fn test_read_soft_delete_without_where() {
 		 \"soft_item\".\"deleted_at\" IS NULL"
 	);
 }
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_read_only_deleted() {
+	use sqlxo::blocks::SelectType;
+
+	let plan = QueryBuilder::<SoftDeleteItem>::read()
+		.only_deleted()
+		.build();
+
+	let sql = plan.sql(SelectType::Star).normalize();
+	assert_eq!(
+		sql,
+		"SELECT \"soft_item\".* FROM soft_item WHERE \
+		 \"soft_item\".\"deleted_at\" IS NOT NULL"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_restore_sql_generation() {
+	use sqlxo::blocks::{
+		BuildableFilter,
+		Expression,
+	};
+
+	let plan = QueryBuilder::<SoftDeleteItem>::restore()
+		.r#where(Expression::Leaf(SoftDeleteItemQuery::NameEq("test".into())))
+		.build();
+
+	let sql = plan.sql().normalize();
+	assert_eq!(
+		sql,
+		"UPDATE soft_item SET deleted_at = NULL WHERE \
+		 \"soft_item\".\"deleted_at\" IS NOT NULL AND \"soft_item\".\"name\" \
+		 = $1"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_purge_sql_generation() {
+	let plan = QueryBuilder::<SoftDeleteItem>::purge()
+		.older_than(chrono::Duration::days(30))
+		.build();
+
+	let sql = plan.sql().normalize();
+	assert_eq!(
+		sql,
+		"DELETE FROM soft_item WHERE \"soft_item\".\"deleted_at\" IS NOT NULL \
+		 AND \"soft_item\".\"deleted_at\" < NOW() - $1"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_delete_cascades_run_children_first() {
+	use crate::helpers::{
+		Author,
+		AuthorQuery,
+	};
+	use sqlxo::blocks::{
+		BuildableFilter,
+		Expression,
+	};
+
+	let plan = QueryBuilder::<Author>::delete()
+		.r#where(Expression::Leaf(AuthorQuery::NameEq("ann".into())))
+		.build();
+
+	let authors =
+		"(SELECT \"author\".\"id\" FROM author WHERE \"author\".\"name\" = $1)";
+	let sql: Vec<String> = plan
+		.cascade_sql()
+		.iter()
+		.map(|sql| sql.normalize())
+		.collect();
+	assert_eq!(sql, vec![
+		format!(
+			"DELETE FROM comment WHERE (\"comment\".\"post_id\") IN (SELECT \
+			 \"post\".\"id\" FROM post WHERE (\"post\".\"author_id\") IN \
+			 {authors} AND \"post\".\"deleted_at\" IS NULL)"
+		),
+		format!(
+			"UPDATE post SET deleted_at = NOW() WHERE \
+			 (\"post\".\"author_id\") IN {authors} AND \
+			 \"post\".\"deleted_at\" IS NULL"
+		),
+		format!(
+			"UPDATE post SET \"editor_id\" = NULL WHERE \
+			 (\"post\".\"editor_id\") IN {authors}"
+		),
+		format!(
+			"SELECT EXISTS(SELECT 1 FROM award WHERE \
+			 (\"award\".\"author_id\") IN {authors})"
+		),
+	]);
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE author SET deleted_at = NOW() WHERE \"author\".\"name\" = $1"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_restore_does_not_cascade() {
+	use crate::helpers::Author;
+
+	let plan = QueryBuilder::<Author>::restore().build();
+	assert!(plan.cascade_sql().is_empty());
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_delete_using_joined_filter() {
+	use crate::helpers::{
+		Comment,
+		CommentJoin,
+		PostQuery,
+	};
+	use sqlxo::blocks::Expression;
+	use sqlxo_traits::{
+		JoinKind,
+		JoinPath,
+	};
+
+	let plan = QueryBuilder::<Comment>::delete()
+		.where_join(
+			JoinPath::from_join(
+				CommentJoin::CommentToPostByPostId,
+				JoinKind::Inner,
+			),
+			Expression::Leaf(PostQuery::TitleEq("spam".into())),
+		)
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"DELETE FROM comment USING post AS \"post__\" WHERE \
+		 \"comment\".\"post_id\" = \"post__\".\"id\" AND \"post__\".\"title\" \
+		 = $1"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_soft_delete_from_joined_path_scopes_cascades() {
+	use crate::helpers::{
+		AuthorQuery,
+		Post,
+		PostJoin,
+	};
+	use sqlxo::blocks::Expression;
+	use sqlxo_traits::{
+		JoinKind,
+		JoinPath,
+	};
+
+	let plan = QueryBuilder::<Post>::delete()
+		.where_join(
+			JoinPath::from_join(
+				PostJoin::PostToAuthorByAuthorId,
+				JoinKind::Inner,
+			),
+			Expression::Leaf(AuthorQuery::NameEq("ann".into())),
+		)
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE post SET deleted_at = NOW() FROM author AS \"author__\" WHERE \
+		 \"post\".\"author_id\" = \"author__\".\"id\" AND \
+		 \"author__\".\"name\" = $1"
+	);
+	let sql: Vec<String> = plan
+		.cascade_sql()
+		.iter()
+		.map(|sql| sql.normalize())
+		.collect();
+	assert_eq!(sql, vec!["DELETE FROM comment WHERE \
+	                      (\"comment\".\"post_id\") IN (SELECT \
+	                      \"post\".\"id\" FROM post, author AS \
+	                      \"author__\" WHERE \"post\".\"author_id\" = \
+	                      \"author__\".\"id\" AND \"author__\".\"name\" \
+	                      = $1)"
+		.to_string(),]);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_delete_using_nested_join_path() {
+	use crate::helpers::{
+		AuthorQuery,
+		Comment,
+		CommentJoin,
+		PostJoin,
+	};
+	use sqlxo::blocks::Expression;
+	use sqlxo_traits::{
+		JoinKind,
+		JoinPath,
+	};
+
+	let path = JoinPath::from_join(
+		CommentJoin::CommentToPostByPostId,
+		JoinKind::Inner,
+	)
+	.then(PostJoin::PostToAuthorByAuthorId, JoinKind::Inner);
+	let plan = QueryBuilder::<Comment>::delete()
+		.where_join(path, Expression::Leaf(AuthorQuery::NameEq("ann".into())))
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"DELETE FROM comment USING post AS \"post__\" INNER JOIN author AS \
+		 \"post__author__\" ON \"post__\".\"author_id\" = \
+		 \"post__author__\".\"id\" WHERE \"comment\".\"post_id\" = \
+		 \"post__\".\"id\" AND \"post__author__\".\"name\" = $1"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_delete_by_id_sql() {
+	let plan =
+		QueryBuilder::<HardDeleteItem>::delete_by_id(Uuid::new_v4()).build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"DELETE FROM hard_item WHERE \"hard_item\".\"id\" = ANY($1)"
+	);
+}
@@ -0,0 +1,19 @@
+#![feature(inherent_associated_types)]
+#![allow(incomplete_features)]
+
+use sqlx::FromRow;
+use sqlxo_macros::{
+	Delete,
+	Query,
+};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Query, Delete)]
+pub struct Child {
+	#[primary_key]
+	pub id:        Uuid,
+	#[foreign_key(to = "parent.id", cascade_type(set_null))]
+	pub parent_id: Uuid,
+}
+
+fn main() {}
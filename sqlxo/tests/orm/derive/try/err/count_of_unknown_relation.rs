@@ -0,0 +1,30 @@
+#![feature(inherent_associated_types)]
+#![allow(incomplete_features)]
+
+use sqlx::FromRow;
+use sqlxo::JoinValue;
+use sqlxo_macros::Query;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Query)]
+pub struct Child {
+	#[primary_key]
+	pub id:        Uuid,
+	pub parent_id: Uuid,
+}
+
+#[derive(Debug, Clone, FromRow, Query)]
+pub struct Parent {
+	#[primary_key]
+	pub id: Uuid,
+
+	#[sqlxo(rel(has_many = "Child", fk = "parent_id"))]
+	#[sqlx(skip)]
+	pub children: JoinValue<Vec<Child>>,
+
+	#[sqlxo(count_of = "kids")]
+	#[sqlx(skip)]
+	pub kid_count: JoinValue<i64>,
+}
+
+fn main() {}
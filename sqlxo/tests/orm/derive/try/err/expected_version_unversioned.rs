@@ -0,0 +1,21 @@
+#![feature(inherent_associated_types)]
+#![allow(incomplete_features)]
+
+use sqlx::FromRow;
+use sqlxo::{
+	QueryBuilder,
+	Update,
+};
+use sqlxo_macros::Query;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Query, Update)]
+pub struct Item {
+	#[primary_key]
+	pub id:   Uuid,
+	pub name: String,
+}
+
+fn main() {
+	let _ = QueryBuilder::<Item>::update().expected_version(1);
+}
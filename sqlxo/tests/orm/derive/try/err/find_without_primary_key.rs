@@ -0,0 +1,26 @@
+#![feature(inherent_associated_types)]
+#![allow(incomplete_features)]
+
+use sqlx::FromRow;
+use sqlxo::{
+	Delete,
+	QueryBuilder,
+	Update,
+};
+use sqlxo_macros::Query;
+
+#[derive(Debug, Clone, FromRow, Query, Update, Delete)]
+pub struct Event {
+	pub name: String,
+}
+
+async fn history(pool: &sqlx::PgPool) {
+	let _ = sqlxo::audit::history::<Event, _>((), pool).await;
+}
+
+fn main() {
+	let _ = QueryBuilder::<Event>::find(());
+	let _ = QueryBuilder::<Event>::find_many(&[()]);
+	let _ = QueryBuilder::<Event>::update_by_id(());
+	let _ = QueryBuilder::<Event>::delete_by_id(());
+}
@@ -0,0 +1,30 @@
+#![feature(inherent_associated_types)]
+#![allow(incomplete_features)]
+
+use sqlx::FromRow;
+use sqlxo::JoinValue;
+use sqlxo_macros::{
+	Delete,
+	Query,
+};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Query, Delete)]
+pub struct Parent {
+	#[primary_key]
+	pub id: Uuid,
+}
+
+#[derive(Debug, Clone, FromRow, Query, Delete)]
+pub struct Child {
+	#[primary_key]
+	pub id:        Uuid,
+	#[foreign_key(to = "parent.id", cascade_type(restrict))]
+	pub parent_id: Uuid,
+
+	#[sqlxo(belongs_to)]
+	#[sqlx(skip)]
+	pub parent: JoinValue<Parent>,
+}
+
+fn main() {}
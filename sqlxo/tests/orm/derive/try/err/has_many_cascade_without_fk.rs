@@ -0,0 +1,30 @@
+#![feature(inherent_associated_types)]
+#![allow(incomplete_features)]
+
+use sqlx::FromRow;
+use sqlxo::JoinValue;
+use sqlxo_macros::{
+	Delete,
+	Query,
+};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Query, Delete)]
+pub struct Child {
+	#[primary_key]
+	pub id:        Uuid,
+	#[foreign_key(to = "parent.id")]
+	pub parent_id: Uuid,
+}
+
+#[derive(Debug, Clone, FromRow, Query, Delete)]
+pub struct Parent {
+	#[primary_key]
+	pub id: Uuid,
+
+	#[sqlxo(rel(has_many = "Child", cascade_type(cascade)))]
+	#[sqlx(skip)]
+	pub children: JoinValue<Vec<Child>>,
+}
+
+fn main() {}
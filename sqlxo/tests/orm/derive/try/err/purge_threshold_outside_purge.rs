@@ -0,0 +1,29 @@
+#![feature(inherent_associated_types)]
+#![allow(incomplete_features)]
+
+use chrono::{
+	DateTime,
+	Duration,
+	Utc,
+};
+use sqlx::FromRow;
+use sqlxo::{
+	QueryBuilder,
+	SoftDelete,
+};
+use sqlxo_macros::Query;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Query, SoftDelete)]
+pub struct Item {
+	#[primary_key]
+	pub id:         Uuid,
+	#[sqlxo(delete_marker)]
+	pub deleted_at: Option<DateTime<Utc>>,
+}
+
+fn main() {
+	let _ = QueryBuilder::<Item>::purge().older_than(Duration::days(30));
+	let _ = QueryBuilder::<Item>::delete().older_than(Duration::days(30));
+	let _ = QueryBuilder::<Item>::restore().deleted_before(Utc::now());
+}
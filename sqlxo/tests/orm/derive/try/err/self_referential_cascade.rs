@@ -0,0 +1,24 @@
+#![feature(inherent_associated_types)]
+#![allow(incomplete_features)]
+
+use sqlx::FromRow;
+use sqlxo::JoinValue;
+use sqlxo_macros::{
+	Delete,
+	Query,
+};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Query, Delete)]
+pub struct Node {
+	#[primary_key]
+	pub id:        Uuid,
+	#[foreign_key(to = "node.id")]
+	pub parent_id: Option<Uuid>,
+
+	#[sqlxo(rel(has_many = "Node", fk = "parent_id", cascade_type(cascade)))]
+	#[sqlx(skip)]
+	pub children: JoinValue<Vec<Node>>,
+}
+
+fn main() {}
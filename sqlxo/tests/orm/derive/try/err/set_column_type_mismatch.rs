@@ -0,0 +1,29 @@
+#![feature(inherent_associated_types)]
+#![allow(incomplete_features)]
+
+use sqlx::FromRow;
+use sqlxo_macros::{
+	Query,
+	Update,
+};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Query, Update)]
+pub struct Stock {
+	#[primary_key]
+	pub id:     Uuid,
+	pub name:   String,
+	pub amount: i32,
+}
+
+#[derive(Debug, Clone, FromRow, Query, Update)]
+pub struct Other {
+	#[primary_key]
+	pub id:   Uuid,
+	pub name: String,
+}
+
+fn main() {
+	let _ = StockUpdateOp::NameSetColumn(StockColumn::Amount.into());
+	let _ = StockUpdateOp::NameSetColumn(OtherColumn::Name.into());
+}
@@ -0,0 +1,22 @@
+#![feature(inherent_associated_types)]
+#![allow(incomplete_features)]
+
+use sqlx::FromRow;
+use sqlxo::{
+	Delete,
+	QueryBuilder,
+};
+use sqlxo_macros::Query;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Query, Delete)]
+pub struct Item {
+	#[primary_key]
+	pub id: Uuid,
+}
+
+fn main() {
+	let _ = QueryBuilder::<Item>::restore();
+	let _ = QueryBuilder::<Item>::purge();
+	let _ = QueryBuilder::<Item>::read().only_deleted();
+}
@@ -14,4 +14,15 @@ fn ui() {
 	t.compile_fail("tests/orm/derive/try/err/unknown_key.rs");
 	t.compile_fail("tests/orm/derive/try/err/duplicate_key.rs");
 	t.compile_fail("tests/orm/derive/try/err/wrong_literal.rs");
+	t.compile_fail("tests/orm/derive/try/err/composite_fk_arity.rs");
+	t.compile_fail("tests/orm/derive/try/err/count_of_unknown_relation.rs");
+	t.compile_fail("tests/orm/derive/try/err/cascade_set_null_non_optional.rs");
+	t.compile_fail("tests/orm/derive/try/err/set_column_type_mismatch.rs");
+	t.compile_fail("tests/orm/derive/try/err/expected_version_unversioned.rs");
+	t.compile_fail("tests/orm/derive/try/err/trash_of_hard_delete.rs");
+	t.compile_fail("tests/orm/derive/try/err/purge_threshold_outside_purge.rs");
+	t.compile_fail("tests/orm/derive/try/err/find_without_primary_key.rs");
+	t.compile_fail("tests/orm/derive/try/err/fk_cascade_without_has_many.rs");
+	t.compile_fail("tests/orm/derive/try/err/self_referential_cascade.rs");
+	t.compile_fail("tests/orm/derive/try/err/has_many_cascade_without_fk.rs");
 }
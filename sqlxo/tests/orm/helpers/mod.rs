@@ -114,11 +114,13 @@ pub struct MaterialDto {
 #[derive(Debug, Clone, WebQuery, Deserialize, Serialize)]
 pub struct ItemDto {
 	pub id:             Uuid,
-	#[sqlxo(field = "name")]
+	#[sqlxo(field = "name", facet)]
 	pub different_name: String,
 	pub description:    String,
+	#[sqlxo(facet(buckets = "10, 50"))]
 	pub price:          f32,
 	pub amount:         i32,
+	#[sqlxo(facet)]
 	pub active:         bool,
 	pub due_date:       sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>,
 
@@ -130,6 +132,22 @@ pub struct ItemDto {
 	pub ignore: Vec<i32>,
 }
 
+impl From<Item> for ItemDto {
+	fn from(item: Item) -> Self {
+		ItemDto {
+			id:             item.id,
+			different_name: item.name,
+			description:    item.description,
+			price:          item.price,
+			amount:         item.amount,
+			active:         item.active,
+			due_date:       item.due_date,
+			material:       None,
+			ignore:         Vec::new(),
+		}
+	}
+}
+
 #[allow(dead_code)]
 #[derive(Debug, FromRow, Clone, Query, FullTextSearchable, PartialEq)]
 pub struct Material {
@@ -263,6 +281,14 @@ pub struct HardDeleteItemDto {
 	pub name: String,
 }
 
+#[allow(dead_code)]
+#[bind(SoftDeleteItem)]
+#[derive(Debug, Clone, WebQuery, Deserialize, Serialize)]
+pub struct SoftDeleteItemDto {
+	pub id:   Uuid,
+	pub name: String,
+}
+
 // Soft delete test model
 #[allow(dead_code)]
 #[derive(Debug, FromRow, Clone, Query, SoftDelete, PartialEq)]
@@ -354,3 +380,346 @@ impl Default for CreateItem {
 		}
 	}
 }
+
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, Create, Update, PartialEq)]
+#[sqlxo(table_name = "stock_item")]
+pub struct StockItem {
+	#[primary_key(manual)]
+	pub id:           Uuid,
+	pub sku:          String,
+	pub name:         String,
+	pub amount:       i32,
+	pub restocked_at: Option<chrono::DateTime<chrono::Utc>>,
+	#[sqlxo(version)]
+	pub version:      i32,
+	#[sqlxo(insert_marker)]
+	pub created_at:   chrono::DateTime<chrono::Utc>,
+	#[sqlxo(update_marker)]
+	pub updated_at:   Option<chrono::DateTime<chrono::Utc>>,
+
+	#[sqlxo(rel(has_many = "StockBooking", fk = "stock_item_id"))]
+	#[sqlx(skip)]
+	pub bookings: JoinValue<Vec<StockBooking>>,
+}
+
+/// Shares `name` and `amount` with `stock_item`.
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, PartialEq)]
+pub struct StockBooking {
+	#[primary_key]
+	pub id:            Uuid,
+	#[foreign_key(to = "stock_item.id")]
+	pub stock_item_id: Uuid,
+	pub name:          String,
+	pub amount:        i32,
+}
+
+#[allow(dead_code)]
+#[bind(StockItem)]
+#[derive(Debug, Clone, WebQuery, Deserialize, Serialize)]
+pub struct StockItemDto {
+	pub id:     Uuid,
+	pub sku:    String,
+	pub name:   String,
+	pub amount: i32,
+}
+
+// Delete cascade test models
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, Create, SoftDelete, PartialEq)]
+pub struct Author {
+	#[primary_key]
+	pub id:         Uuid,
+	pub name:       String,
+	#[sqlxo(delete_marker)]
+	pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+
+	#[sqlxo(rel(has_many = "Post", fk = "author_id", cascade_type(cascade)))]
+	#[sqlxo(nested_create)]
+	#[sqlx(skip)]
+	pub posts: JoinValue<Vec<Post>>,
+
+	#[sqlxo(rel(has_many = "Post", fk = "editor_id", cascade_type(set_null)))]
+	#[sqlx(skip)]
+	pub edited_posts: JoinValue<Vec<Post>>,
+
+	#[sqlxo(rel(has_many = "Award", fk = "author_id"))]
+	#[sqlx(skip)]
+	pub awards: JoinValue<Vec<Award>>,
+}
+
+#[allow(dead_code)]
+#[derive(
+	Debug, FromRow, Clone, Query, Create, Update, SoftDelete, PartialEq,
+)]
+pub struct Post {
+	#[primary_key]
+	pub id:         Uuid,
+	#[foreign_key(to = "author.id")]
+	pub author_id:  Uuid,
+	#[foreign_key(to = "author.id")]
+	pub editor_id:  Option<Uuid>,
+	pub title:      String,
+	#[sqlxo(delete_marker)]
+	pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+
+	#[sqlxo(rel(has_many = "Comment", fk = "post_id"))]
+	#[sqlxo(nested_create)]
+	#[sqlx(skip)]
+	pub comments: JoinValue<Vec<Comment>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, Create, Update, Delete, PartialEq)]
+pub struct Comment {
+	#[primary_key]
+	pub id:      Uuid,
+	#[foreign_key(to = "post.id", cascade_type(cascade))]
+	pub post_id: Uuid,
+	pub body:    String,
+
+	#[sqlxo(belongs_to)]
+	#[sqlx(skip)]
+	pub post: JoinValue<Post>,
+}
+
+// Default and named scope test models
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, Create, Delete, PartialEq)]
+#[sqlxo(default_scope = "NoteQuery::ArchivedIsFalse")]
+#[sqlxo(scope(
+	published = "NoteQuery::PublishedAtIsNotNull",
+	draft = "NoteQuery::PublishedAtIsNull"
+))]
+pub struct Note {
+	#[primary_key]
+	pub id:           Uuid,
+	pub title:        String,
+	pub archived:     bool,
+	pub published_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Counts notes, whose default scope leaves out the archived ones.
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, PartialEq)]
+pub struct Notebook {
+	#[primary_key]
+	pub id:   Uuid,
+	pub name: String,
+
+	#[sqlxo(rel(has_many = "Note", fk = "notebook_id"))]
+	#[sqlx(skip)]
+	pub notes: JoinValue<Vec<Note>>,
+
+	#[sqlxo(count_of = "notes")]
+	#[sqlx(skip)]
+	pub note_count: JoinValue<i64>,
+}
+
+// Audit trail test model
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, Create, Update, Delete, PartialEq)]
+#[sqlxo(audited)]
+pub struct Invoice {
+	#[primary_key]
+	pub id:     Uuid,
+	pub number: String,
+	pub amount: i64,
+}
+
+/// Not audited itself, unlike the children its deletes cascade to.
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, Delete, PartialEq)]
+pub struct Ledger {
+	#[primary_key]
+	pub id:   Uuid,
+	pub name: String,
+
+	#[sqlxo(rel(
+		has_many = "LedgerEntry",
+		fk = "ledger_id",
+		cascade_type(cascade)
+	))]
+	#[sqlx(skip)]
+	pub entries: JoinValue<Vec<LedgerEntry>>,
+
+	#[sqlxo(rel(
+		has_many = "LedgerTag",
+		fk = "ledger_id",
+		cascade_type(set_null)
+	))]
+	#[sqlx(skip)]
+	pub tags: JoinValue<Vec<LedgerTag>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, PartialEq)]
+#[sqlxo(audited)]
+pub struct LedgerEntry {
+	#[primary_key]
+	pub id:        Uuid,
+	#[foreign_key(to = "ledger.id")]
+	pub ledger_id: Uuid,
+	pub amount:    i64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, PartialEq)]
+#[sqlxo(audited)]
+pub struct LedgerTag {
+	#[primary_key]
+	pub id:        Uuid,
+	#[foreign_key(to = "ledger.id")]
+	pub ledger_id: Option<Uuid>,
+	pub name:      String,
+}
+
+// Multi-tenant test models
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, Create, Update, Delete, PartialEq)]
+pub struct Project {
+	#[primary_key]
+	pub id:        Uuid,
+	#[sqlxo(tenant)]
+	pub tenant_id: Uuid,
+	pub name:      String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, Create, Delete, PartialEq)]
+pub struct Task {
+	#[primary_key]
+	pub id:         Uuid,
+	#[sqlxo(tenant)]
+	pub tenant_id:  Uuid,
+	#[foreign_key(to = "project.id")]
+	pub project_id: Uuid,
+	pub title:      String,
+
+	#[sqlxo(belongs_to)]
+	#[sqlx(skip)]
+	pub project: JoinValue<Project>,
+}
+
+/// Shared by every tenant, unlike the uses in `task_label`.
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, PartialEq)]
+pub struct Label {
+	#[primary_key]
+	pub id:   Uuid,
+	pub name: String,
+
+	#[sqlxo(rel(has_many = "TaskLabel", fk = "label_id"))]
+	#[sqlx(skip)]
+	pub uses: JoinValue<Vec<TaskLabel>>,
+
+	#[sqlxo(count_of = "uses")]
+	#[sqlx(skip)]
+	pub use_count: JoinValue<i64>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, PartialEq)]
+pub struct TaskLabel {
+	#[primary_key]
+	pub id:        Uuid,
+	#[sqlxo(tenant)]
+	pub tenant_id: Uuid,
+	#[foreign_key(to = "label.id")]
+	pub label_id:  Uuid,
+}
+
+#[allow(dead_code)]
+#[bind(Post)]
+#[derive(Debug, Clone, WebQuery, Deserialize, Serialize)]
+pub struct PostDto {
+	pub id:        Uuid,
+	pub author_id: Uuid,
+	pub title:     String,
+}
+
+#[allow(dead_code)]
+#[bind(Comment)]
+#[derive(Debug, Clone, WebQuery, Deserialize, Serialize)]
+pub struct CommentDto {
+	pub id:      Uuid,
+	pub post_id: Uuid,
+	pub body:    String,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[sqlxo(webquery_join)]
+	pub post: Option<PostDto>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, Delete, PartialEq)]
+pub struct Award {
+	#[primary_key]
+	pub id:        Uuid,
+	#[foreign_key(to = "author.id", cascade_type(restrict))]
+	pub author_id: Uuid,
+	pub title:     String,
+
+	#[sqlxo(belongs_to)]
+	#[sqlx(skip)]
+	pub author: JoinValue<Author>,
+}
+
+// Composite key test models
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, PartialEq)]
+pub struct TenantSupplier {
+	#[primary_key]
+	pub tenant_id: Uuid,
+	#[primary_key]
+	pub id:        Uuid,
+	pub name:      String,
+
+	#[sqlxo(rel(has_many = "TenantMaterial", fk = "tenant_id, supplier_id"))]
+	#[sqlx(skip)]
+	pub materials: JoinValue<Vec<TenantMaterial>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, PartialEq)]
+pub struct TenantMaterial {
+	#[primary_key]
+	pub tenant_id:   Uuid,
+	#[primary_key]
+	pub id:          Uuid,
+	pub name:        String,
+	#[foreign_key(to = "tenant_supplier.id", on(tenant_id = "tenant_id"))]
+	pub supplier_id: Option<Uuid>,
+
+	#[sqlxo(belongs_to)]
+	#[sqlx(skip)]
+	pub supplier: JoinValue<TenantSupplier>,
+}
+
+// Self-referential test model
+#[allow(dead_code)]
+#[derive(Debug, FromRow, Clone, Query, Delete, PartialEq)]
+pub struct Category {
+	#[primary_key]
+	pub id:        Uuid,
+	pub name:      String,
+	#[foreign_key(to = "category.id")]
+	pub parent_id: Option<Uuid>,
+
+	#[sqlxo(belongs_to)]
+	#[sqlx(skip)]
+	pub parent: JoinValue<Box<Category>>,
+
+	#[sqlxo(rel(
+		has_many = "Category",
+		fk = "parent_id",
+		cascade_type(set_null)
+	))]
+	#[sqlx(skip)]
+	pub children: JoinValue<Vec<Category>>,
+
+	#[sqlxo(count_of = "children")]
+	#[sqlx(skip)]
+	pub child_count: JoinValue<i64>,
+}
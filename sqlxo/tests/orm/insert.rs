@@ -1,7 +1,15 @@
 use crate::helpers::{
+	Author,
+	AuthorCreation,
 	CreateItem,
 	CreateItemCreation,
 	NormalizeString,
+	PostCreation,
+	StockItem,
+	StockItemColumn,
+	StockItemCreation,
+	StockItemQuery,
+	StockItemUpdate,
 };
 use sqlxo::{
 	Buildable,
@@ -49,3 +57,128 @@ fn test_insert_sql_exact_match() {
 		 VALUES ($1, $2, $3, $4, NOW())"
 	);
 }
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_insert_models_renders_multi_row_values() {
+	let creates = (0..2).map(|idx| CreateItemCreation {
+		id:          Uuid::new_v4(),
+		name:        format!("item {idx}"),
+		description: "desc".into(),
+		price:       idx as f32,
+	});
+
+	let plan = QueryBuilder::<CreateItem>::insert().models(creates).build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"INSERT INTO create_item (id, name, description, price, created_at) \
+		 VALUES ($1, $2, $3, $4, NOW()), ($5, $6, $7, $8, NOW())"
+	);
+	assert_eq!(plan.statement_count(), 1);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_insert_no_models_renders_no_statement() {
+	let plan = QueryBuilder::<CreateItem>::insert()
+		.models(Vec::<CreateItemCreation>::new())
+		.build();
+
+	assert_eq!(plan.sql(), "");
+	assert_eq!(plan.statement_count(), 0);
+}
+
+fn stock_creation(sku: &str, amount: i32) -> StockItemCreation {
+	StockItemCreation {
+		id: Uuid::new_v4(),
+		sku: sku.into(),
+		name: format!("stock {sku}"),
+		amount,
+		restocked_at: None,
+	}
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_insert_on_conflict_do_nothing_defaults_to_primary_key() {
+	let plan = QueryBuilder::<CreateItem>::insert()
+		.model(CreateItemCreation {
+			id:          Uuid::new_v4(),
+			name:        "test".into(),
+			description: "desc".into(),
+			price:       1.0,
+		})
+		.do_nothing()
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"INSERT INTO create_item (id, name, description, price, created_at) \
+		 VALUES ($1, $2, $3, $4, NOW()) ON CONFLICT (id) DO NOTHING"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_insert_on_conflict_do_update_sets_update_marker() {
+	let plan = QueryBuilder::<StockItem>::insert()
+		.model(stock_creation("sku-1", 5))
+		.on_conflict(StockItemColumn::Sku)
+		.do_update(StockItemUpdate {
+			amount: Some(5),
+			..Default::default()
+		})
+		.conflict_where(StockItemQuery::AmountLt(5).into())
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"INSERT INTO stock_item (id, sku, name, amount, restocked_at, \
+		 created_at) VALUES ($1, $2, $3, $4, $5, NOW()) ON CONFLICT (sku) DO \
+		 UPDATE SET updated_at = NOW(), version = \"stock_item\".\"version\" \
+		 + 1, amount = $6 WHERE \"stock_item\".\"amount\" < $7"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_insert_on_conflict_do_update_excluded_columns() {
+	let plan = QueryBuilder::<StockItem>::insert()
+		.models([stock_creation("a", 1), stock_creation("b", 2)])
+		.on_conflict_constraint("stock_item_sku_key")
+		.do_update_excluded((StockItemColumn::Name, StockItemColumn::Amount))
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"INSERT INTO stock_item (id, sku, name, amount, restocked_at, \
+		 created_at) VALUES ($1, $2, $3, $4, $5, NOW()), ($6, $7, $8, $9, \
+		 $10, NOW()) ON CONFLICT ON CONSTRAINT stock_item_sku_key DO UPDATE \
+		 SET updated_at = NOW(), version = \"stock_item\".\"version\" + 1, \
+		 name = EXCLUDED.name, amount = EXCLUDED.amount"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_nested_create_inserts_only_parent_columns() {
+	let plan = QueryBuilder::<Author>::insert()
+		.model(AuthorCreation {
+			id:    Uuid::new_v4(),
+			name:  "ann".into(),
+			posts: vec![PostCreation {
+				id:        Uuid::new_v4(),
+				author_id: Uuid::nil(),
+				editor_id: None,
+				title:     "first".into(),
+				comments:  vec![],
+			}],
+		})
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"INSERT INTO author (id, name) VALUES ($1, $2)"
+	);
+}
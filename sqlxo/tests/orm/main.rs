@@ -4,11 +4,14 @@
 #[cfg(not(feature = "ignore-db-tests"))]
 mod db;
 
+mod audit;
 mod blocks;
 mod builder;
 mod delete;
 mod derive;
 mod helpers;
 mod insert;
+mod scope;
+mod tenant;
 mod update;
 mod web;
@@ -0,0 +1,76 @@
+use sqlxo::{
+	and,
+	blocks::{
+		BuildableFilter,
+		Expression,
+		SelectType,
+	},
+	Buildable,
+	QueryBuilder,
+	ReadQueryPlan,
+};
+
+use crate::helpers::{
+	NormalizeString,
+	Note,
+	NoteQuery,
+	Notebook,
+	NotebookJoin,
+};
+
+#[test]
+fn default_scope_is_applied() {
+	let plan: ReadQueryPlan<Note> = QueryBuilder::<Note>::read()
+		.r#where(Expression::Leaf(NoteQuery::TitleEq("a".into())))
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT "note".* FROM note WHERE "note"."archived" = FALSE AND ("note"."title" = $1)"#
+	);
+}
+
+#[test]
+fn unscoped_skips_default_scope() {
+	let plan: ReadQueryPlan<Note> = QueryBuilder::<Note>::read()
+		.r#where(Expression::Leaf(NoteQuery::TitleEq("a".into())))
+		.unscoped()
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT "note".* FROM note WHERE "note"."title" = $1"#
+	);
+}
+
+#[test]
+fn counted_relations_apply_the_default_scope() {
+	let plan: ReadQueryPlan<Notebook> = QueryBuilder::<Notebook>::read()
+		.with_count(NotebookJoin::NotebookToNoteByNotes)
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT "notebook".*, (SELECT COUNT(*) FROM (SELECT * FROM note WHERE "note"."archived" = FALSE) AS "notes__count" WHERE "notebook"."id" = "notes__count"."notebook_id") AS "__sqlxo_count_notes" FROM notebook"#
+	);
+}
+
+#[test]
+fn named_scopes_combine_with_filters() {
+	let plan: ReadQueryPlan<Note> = QueryBuilder::<Note>::read()
+		.r#where(and![
+			Note::scope_published(),
+			NoteQuery::TitleEq("a".into())
+		])
+		.unscoped()
+		.build();
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT "note".* FROM note WHERE ("note"."published_at" IS NOT NULL AND "note"."title" = $1)"#
+	);
+	assert_eq!(
+		Note::scope_draft(),
+		Expression::Leaf(NoteQuery::PublishedAtIsNull)
+	);
+}
@@ -0,0 +1,182 @@
+use sqlxo::{
+	blocks::{
+		BuildableFilter,
+		BuildableJoin,
+		Expression,
+		SelectType,
+	},
+	tenant,
+	Buildable,
+	JoinKind,
+	QueryBuilder,
+	ReadQueryPlan,
+	TenantScoped,
+};
+use uuid::Uuid;
+
+use crate::helpers::{
+	NormalizeString,
+	Project,
+	ProjectCreation,
+	ProjectQuery,
+	ProjectUpdate,
+	Task,
+	TaskJoin,
+};
+
+#[test]
+fn tenant_field_is_derived() {
+	assert_eq!(Project::TENANT_FIELD, Some("tenant_id"));
+	assert_eq!(crate::helpers::Item::TENANT_FIELD, None);
+}
+
+#[test]
+fn read_is_scoped_to_tenant() {
+	let plan: ReadQueryPlan<Project> =
+		tenant::sync_scope(Uuid::new_v4(), || {
+			QueryBuilder::<Project>::read()
+				.r#where(Expression::Leaf(ProjectQuery::NameEq("a".into())))
+				.build()
+		});
+
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT "project".* FROM project WHERE "project"."tenant_id" = $1 AND ("project"."name" = $2)"#
+	);
+}
+
+#[test]
+fn joined_tables_are_scoped_to_tenant() {
+	let plan: ReadQueryPlan<Task> = tenant::sync_scope(Uuid::new_v4(), || {
+		QueryBuilder::<Task>::read()
+			.join(TaskJoin::TaskToProjectByProjectId, JoinKind::Inner)
+			.build()
+	});
+
+	let sql = plan.sql(SelectType::Star).normalize();
+	assert!(sql.contains(
+		r#"INNER JOIN project AS "project__" ON "task"."project_id" = "project__"."id" AND "project__"."tenant_id" = $1"#
+	), "{sql}");
+	assert!(sql.ends_with(r#"WHERE "task"."tenant_id" = $2"#), "{sql}");
+}
+
+#[test]
+fn preserved_right_tables_are_scoped_before_the_join() {
+	let plan: ReadQueryPlan<Task> = tenant::sync_scope(Uuid::new_v4(), || {
+		QueryBuilder::<Task>::read()
+			.join(TaskJoin::TaskToProjectByProjectId, JoinKind::Right)
+			.build()
+	});
+
+	let sql = plan.sql(SelectType::Star).normalize();
+	assert!(sql.contains(
+		r#"RIGHT JOIN (SELECT * FROM project WHERE "project"."tenant_id" = $1) AS "project__" ON "task"."project_id" = "project__"."id""#
+	), "{sql}");
+	assert!(!sql.contains(r#""project__"."tenant_id" ="#), "{sql}");
+}
+
+#[test]
+fn all_tenants_lifts_the_scope() {
+	let plan: ReadQueryPlan<Task> = QueryBuilder::<Task>::read()
+		.join(TaskJoin::TaskToProjectByProjectId, JoinKind::Inner)
+		.all_tenants()
+		.build();
+
+	assert!(!plan.sql(SelectType::Star).contains(r#""tenant_id" ="#));
+}
+
+#[test]
+fn mutations_are_scoped_to_tenant() {
+	let id = Uuid::new_v4();
+	let (update, delete, insert) = tenant::sync_scope(Uuid::new_v4(), || {
+		let update = QueryBuilder::<Project>::update_by_id(id)
+			.model(ProjectUpdate {
+				name: Some("b".into()),
+			})
+			.build();
+		let delete = QueryBuilder::<Project>::delete_by_id(id).build();
+		let insert = QueryBuilder::<Project>::insert()
+			.model(ProjectCreation {
+				id,
+				name: "c".into(),
+			})
+			.build();
+		(update, delete, insert)
+	});
+
+	assert_eq!(
+		update.sql().normalize(),
+		r#"UPDATE project SET name = $1 WHERE "project"."tenant_id" = $2 AND "project"."id" = ANY($3)"#
+	);
+	assert_eq!(
+		delete.sql().normalize(),
+		r#"DELETE FROM project WHERE "project"."tenant_id" = $1 AND "project"."id" = ANY($2)"#
+	);
+	assert_eq!(
+		insert.sql().normalize(),
+		"INSERT INTO project (id, name, tenant_id) VALUES ($1, $2, $3)"
+	);
+}
+
+#[test]
+fn insert_sql_without_a_tenant_leaves_the_tenant_null() {
+	let plan = QueryBuilder::<Project>::insert()
+		.model(ProjectCreation {
+			id:   Uuid::new_v4(),
+			name: "c".into(),
+		})
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"INSERT INTO project (id, name, tenant_id) VALUES ($1, $2, NULL)"
+	);
+}
+
+#[test]
+fn empty_conflict_update_skips_the_row() {
+	let plan = tenant::sync_scope(Uuid::new_v4(), || {
+		QueryBuilder::<Project>::insert()
+			.model(ProjectCreation {
+				id:   Uuid::new_v4(),
+				name: "c".into(),
+			})
+			.do_update(ProjectUpdate::default())
+			.conflict_where(ProjectQuery::NameEq("c".into()).into())
+			.build()
+	});
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"INSERT INTO project (id, name, tenant_id) VALUES ($1, $2, $3) ON \
+		 CONFLICT (id) DO NOTHING"
+	);
+}
+
+#[test]
+fn conflict_binds_shrink_insert_chunks() {
+	// 21_845 rows of three binds fill exactly one statement.
+	let models: Vec<ProjectCreation> = (0..21_845)
+		.map(|_| ProjectCreation {
+			id:   Uuid::new_v4(),
+			name: "p".into(),
+		})
+		.collect();
+
+	let (plain, conflict) = tenant::sync_scope(Uuid::new_v4(), || {
+		let plain = QueryBuilder::<Project>::insert()
+			.models(models.clone())
+			.build();
+		let conflict = QueryBuilder::<Project>::insert()
+			.models(models)
+			.do_update(ProjectUpdate {
+				name: Some("q".into()),
+			})
+			.conflict_where(ProjectQuery::NameEq("p".into()).into())
+			.build();
+		(plain, conflict)
+	});
+
+	assert_eq!(plain.statement_count(), 1);
+	assert_eq!(conflict.statement_count(), 2);
+}
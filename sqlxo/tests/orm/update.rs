@@ -1,5 +1,10 @@
 use crate::helpers::{
 	NormalizeString,
+	StockItem,
+	StockItemColumn,
+	StockItemQuery,
+	StockItemUpdate,
+	StockItemUpdateOp,
 	UpdateItem,
 	UpdateItemQuery,
 	UpdateItemUpdate,
@@ -122,3 +127,159 @@ fn test_update_sql_no_marker_just_fields() {
 		 $2 WHERE \"update_item\".\"id\" = $3"
 	);
 }
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_update_ops_render_after_model_assignments() {
+	let plan = QueryBuilder::<StockItem>::update()
+		.model(StockItemUpdate {
+			name: Some("renamed".into()),
+			..Default::default()
+		})
+		.set(StockItemUpdateOp::AmountDecrement(2))
+		.set(StockItemUpdateOp::RestockedAtSetNow)
+		.r#where(StockItemQuery::AmountGte(2).into())
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE stock_item SET updated_at = NOW(), version = \
+		 \"stock_item\".\"version\" + 1, name = $1, amount = \
+		 \"stock_item\".\"amount\" - $2, restocked_at = NOW() WHERE \
+		 \"stock_item\".\"amount\" >= $3"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_update_ops_without_model() {
+	let plan = QueryBuilder::<StockItem>::update()
+		.set(StockItemUpdateOp::AmountMultiply(3))
+		.set(StockItemUpdateOp::NameSetColumn(
+			StockItemColumn::Sku.into(),
+		))
+		.set(StockItemUpdateOp::RestockedAtSetNull)
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE stock_item SET updated_at = NOW(), version = \
+		 \"stock_item\".\"version\" + 1, amount = \"stock_item\".\"amount\" * \
+		 $1, name = \"stock_item\".\"sku\", restocked_at = NULL"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_update_expected_version_guards_where() {
+	let id = Uuid::new_v4();
+	let plan = QueryBuilder::<StockItem>::update()
+		.model(StockItemUpdate {
+			name: Some("renamed".into()),
+			..Default::default()
+		})
+		.r#where(StockItemQuery::IdEq(id).into())
+		.expected_version(3)
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE stock_item SET updated_at = NOW(), version = \
+		 \"stock_item\".\"version\" + 1, name = $1 WHERE \
+		 \"stock_item\".\"id\" = $2 AND \"stock_item\".\"version\" = $3"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_update_from_joined_path() {
+	use crate::helpers::{
+		AuthorQuery,
+		Post,
+		PostJoin,
+		PostQuery,
+		PostUpdate,
+	};
+	use sqlxo::blocks::BuildableJoin;
+	use sqlxo_traits::{
+		JoinKind,
+		JoinPath,
+	};
+
+	let plan = QueryBuilder::<Post>::update()
+		.model(PostUpdate {
+			title: Some("archived".into()),
+			..Default::default()
+		})
+		.join(PostJoin::PostToAuthorByAuthorId, JoinKind::Inner)
+		.where_join(
+			JoinPath::from_join(
+				PostJoin::PostToAuthorByAuthorId,
+				JoinKind::Inner,
+			),
+			Expression::Leaf(AuthorQuery::NameEq("ann".into())),
+		)
+		.r#where(PostQuery::TitleNeq("archived".into()).into())
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE post SET title = $1 FROM author AS \"author__\" WHERE \
+		 \"post\".\"author_id\" = \"author__\".\"id\" AND \
+		 \"author__\".\"name\" = $2 AND \"post\".\"title\" <> $3"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_update_from_qualifies_shared_columns() {
+	use crate::helpers::{
+		StockBookingColumn,
+		StockBookingQuery,
+		StockItemJoin,
+	};
+	use sqlxo::select::ColumnRef;
+	use sqlxo_traits::{
+		JoinKind,
+		JoinPath,
+	};
+
+	let bookings = JoinPath::from_join(
+		StockItemJoin::StockItemToStockBookingByBookings,
+		JoinKind::Inner,
+	);
+	let plan = QueryBuilder::<StockItem>::update()
+		.set(StockItemUpdateOp::AmountDecrement(1))
+		.set(StockItemUpdateOp::NameSetColumn(ColumnRef::joined(
+			bookings.clone(),
+			StockBookingColumn::Name,
+		)))
+		.where_join(bookings, Expression::Leaf(StockBookingQuery::AmountGt(0)))
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE stock_item SET updated_at = NOW(), version = \
+		 \"stock_item\".\"version\" + 1, amount = \"stock_item\".\"amount\" - \
+		 $1, name = \"bookings__\".\"name\" FROM stock_booking AS \
+		 \"bookings__\" WHERE \"stock_item\".\"id\" = \
+		 \"bookings__\".\"stock_item_id\" AND \"bookings__\".\"amount\" > $2"
+	);
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+#[test]
+fn test_update_by_id_sql() {
+	let plan = QueryBuilder::<UpdateItem>::update_by_id(Uuid::new_v4())
+		.model(UpdateItemUpdate {
+			name: Some("test".into()),
+			..Default::default()
+		})
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE update_item SET updated_at = NOW(), name = $1 WHERE \
+		 \"update_item\".\"id\" = ANY($2)"
+	);
+}
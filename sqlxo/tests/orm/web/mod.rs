@@ -1,2 +1,3 @@
 mod overwrite;
+mod policy;
 mod serialize;
@@ -0,0 +1,327 @@
+use serde_json::json;
+use sqlxo::{
+	blocks::{
+		Expression,
+		SelectType,
+	},
+	web::{
+		WebAction,
+		WebDeleteFilter,
+		WebPolicy,
+		WebPurgeFilter,
+		WebReadFilter,
+		WebRestoreFilter,
+	},
+	Buildable,
+	QueryBuilder,
+	ReadQueryPlan,
+	WebQueryError,
+	WebQueryModel,
+};
+use uuid::Uuid;
+
+use crate::helpers::{
+	Comment,
+	CommentDto,
+	CommentQuery,
+	Item,
+	ItemDto,
+	ItemQuery,
+	NormalizeString,
+	SoftDeleteItem,
+	SoftDeleteItemDto,
+	SoftDeleteItemQuery,
+};
+
+struct Viewer {
+	admin: bool,
+}
+
+struct ItemPolicy;
+
+impl WebPolicy<Item, ItemDto> for ItemPolicy {
+	type Principal = Viewer;
+
+	fn row_filter(
+		&self,
+		viewer: &Viewer,
+		_action: WebAction,
+	) -> Option<Expression<ItemQuery>> {
+		(!viewer.admin).then(|| ItemQuery::ActiveIsTrue.into())
+	}
+
+	fn may_read(&self, viewer: &Viewer, field: &str) -> bool {
+		viewer.admin || field != "price"
+	}
+
+	fn may_join(&self, viewer: &Viewer, path: &[String]) -> bool {
+		viewer.admin || path.first().map(String::as_str) != Some("material")
+	}
+}
+
+struct CommentPolicy {
+	author: Uuid,
+}
+
+impl WebPolicy<Comment, CommentDto> for CommentPolicy {
+	type Principal = ();
+
+	fn row_filter(
+		&self,
+		_principal: &(),
+		action: WebAction,
+	) -> Option<Expression<CommentQuery>> {
+		(action == WebAction::Delete)
+			.then(|| CommentQuery::PostIdEq(self.author).into())
+	}
+
+	fn may_join(&self, _principal: &(), _path: &[String]) -> bool {
+		false
+	}
+}
+
+struct TrashPolicy;
+
+impl WebPolicy<SoftDeleteItem, SoftDeleteItemDto> for TrashPolicy {
+	type Principal = ();
+
+	fn row_filter(
+		&self,
+		_principal: &(),
+		action: WebAction,
+	) -> Option<Expression<SoftDeleteItemQuery>> {
+		match action {
+			WebAction::Restore => {
+				Some(SoftDeleteItemQuery::PriceLt(10.0).into())
+			}
+			WebAction::Purge => {
+				Some(SoftDeleteItemQuery::PriceGt(100.0).into())
+			}
+			_ => None,
+		}
+	}
+
+	fn may_filter(&self, _principal: &(), field: &str) -> bool {
+		field != "name"
+	}
+}
+
+fn read_error(payload: serde_json::Value) -> WebQueryError {
+	let filter: WebReadFilter<ItemDto> =
+		serde_json::from_value(payload).expect("valid ItemDto filter");
+	match QueryBuilder::<Item>::try_from_web_read_for(
+		&filter,
+		&ItemPolicy,
+		&Viewer { admin: false },
+	) {
+		Ok(_) => panic!("expected the policy to reject the payload"),
+		Err(err) => err,
+	}
+}
+
+#[test]
+fn policy_injects_row_filter() {
+	let filter: WebReadFilter<ItemDto> = serde_json::from_value(json!({
+		"filter": { "amount": { "gt": 1 } }
+	}))
+	.unwrap();
+
+	let plan: ReadQueryPlan<Item> =
+		QueryBuilder::<Item>::try_from_web_read_for(
+			&filter,
+			&ItemPolicy,
+			&Viewer { admin: false },
+		)
+		.unwrap()
+		.build();
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT "item".* FROM item WHERE ("item"."active" = TRUE AND "item"."amount" > $1)"#
+	);
+
+	let plan: ReadQueryPlan<Item> =
+		QueryBuilder::<Item>::try_from_web_read_for(
+			&filter,
+			&ItemPolicy,
+			&Viewer { admin: true },
+		)
+		.unwrap()
+		.build();
+	assert_eq!(
+		plan.sql(SelectType::Star).normalize(),
+		r#"SELECT "item".* FROM item WHERE "item"."amount" > $1"#
+	);
+}
+
+#[test]
+fn policy_forbids_hidden_fields_and_joins() {
+	assert_eq!(
+		read_error(json!({
+			"filter": { "or": [
+				{ "amount": { "gt": 1 } },
+				{ "price": { "lt": 5.0 } }
+			]}
+		})),
+		WebQueryError::Forbidden {
+			section: "filter",
+			name:    "price".into(),
+		}
+	);
+	assert_eq!(
+		read_error(json!({ "sort": [{ "price": "desc" }] })),
+		WebQueryError::Forbidden {
+			section: "sort",
+			name:    "price".into(),
+		}
+	);
+	assert_eq!(
+		read_error(json!({ "joins": [{ "material": null }] })),
+		WebQueryError::Forbidden {
+			section: "joins",
+			name:    "material".into(),
+		}
+	);
+	assert_eq!(
+		read_error(json!({ "facets": ["price"] })),
+		WebQueryError::Forbidden {
+			section: "facets",
+			name:    "price".into(),
+		}
+	);
+
+	let grouped: WebReadFilter<ItemDto> = serde_json::from_value(json!({
+		"groupBy": ["amount"],
+		"aggregates": ["count", "priceSum"]
+	}))
+	.unwrap();
+	let err = match QueryBuilder::<Item>::try_from_web_aggregate_for(
+		&grouped,
+		&ItemPolicy,
+		&Viewer { admin: false },
+	) {
+		Ok(_) => panic!("expected the policy to reject `priceSum`"),
+		Err(err) => err,
+	};
+	assert_eq!(err, WebQueryError::Forbidden {
+		section: "aggregates",
+		name:    "price".into(),
+	});
+}
+
+#[test]
+fn dto_fields_are_payload_keys() {
+	assert_eq!(ItemDto::FIELDS, [
+		"id",
+		"differentName",
+		"description",
+		"price",
+		"amount",
+		"active",
+		"dueDate",
+		"material",
+		"ignore",
+	]);
+}
+
+#[test]
+fn policy_redacts_hidden_fields() {
+	let row = json!({ "id": Uuid::nil(), "price": 3.5, "amount": 2 });
+
+	let redacted = WebPolicy::<Item, ItemDto>::redact(
+		&ItemPolicy,
+		&Viewer { admin: false },
+		&row,
+	)
+	.unwrap();
+	assert_eq!(redacted, json!({ "id": Uuid::nil(), "amount": 2 }));
+}
+
+#[test]
+fn policy_applies_to_deletes() {
+	let author = Uuid::new_v4();
+	let policy = CommentPolicy { author };
+
+	let filter: WebDeleteFilter<CommentDto> = serde_json::from_value(json!({
+		"filter": { "body": { "eq": "spam" } }
+	}))
+	.unwrap();
+	let plan =
+		QueryBuilder::<Comment>::try_from_web_delete_for(&filter, &policy, &())
+			.unwrap()
+			.build();
+	assert_eq!(
+		plan.sql().normalize(),
+		r#"DELETE FROM comment WHERE ("comment"."body" = $1 AND "comment"."post_id" = $2)"#
+	);
+
+	let joined: WebDeleteFilter<CommentDto> = serde_json::from_value(json!({
+		"joins": [{ "post": null }],
+		"filter": { "body": { "eq": "spam" } }
+	}))
+	.unwrap();
+	let err = match QueryBuilder::<Comment>::try_from_web_delete_for(
+		&joined,
+		&policy,
+		&(),
+	) {
+		Ok(_) => panic!("expected the policy to reject the join"),
+		Err(err) => err,
+	};
+	assert_eq!(err, WebQueryError::Forbidden {
+		section: "joins",
+		name:    "post".into(),
+	});
+}
+
+#[test]
+fn policy_applies_to_restores_and_purges() {
+	let test_id = Uuid::new_v4();
+	let restore: WebRestoreFilter<SoftDeleteItemDto> = serde_json::from_value(
+		json!({ "filter": { "id": { "eq": test_id } } }),
+	)
+	.unwrap();
+	let plan = QueryBuilder::<SoftDeleteItem>::try_from_web_restore_for(
+		&restore,
+		&TrashPolicy,
+		&(),
+	)
+	.unwrap()
+	.build();
+	assert_eq!(
+		plan.sql().normalize(),
+		r#"UPDATE soft_delete_item SET deleted_at = NULL WHERE "soft_delete_item"."deleted_at" IS NOT NULL AND ("soft_delete_item"."id" = $1 AND "soft_delete_item"."price" < $2)"#
+	);
+
+	let purge: WebPurgeFilter<SoftDeleteItemDto> = serde_json::from_value(
+		json!({ "deletedBefore": "2026-01-01T00:00:00Z" }),
+	)
+	.unwrap();
+	let plan = QueryBuilder::<SoftDeleteItem>::try_from_web_purge_for(
+		&purge,
+		&TrashPolicy,
+		&(),
+	)
+	.unwrap()
+	.build();
+	assert_eq!(
+		plan.sql().normalize(),
+		r#"DELETE FROM soft_delete_item WHERE "soft_delete_item"."deleted_at" IS NOT NULL AND "soft_delete_item"."deleted_at" < $1 AND "soft_delete_item"."price" > $2"#
+	);
+
+	let named: WebPurgeFilter<SoftDeleteItemDto> = serde_json::from_value(
+		json!({ "filter": { "name": { "eq": "obsolete" } } }),
+	)
+	.unwrap();
+	let err = match QueryBuilder::<SoftDeleteItem>::try_from_web_purge_for(
+		&named,
+		&TrashPolicy,
+		&(),
+	) {
+		Ok(_) => panic!("expected the policy to reject the filter"),
+		Err(err) => err,
+	};
+	assert_eq!(err, WebQueryError::Forbidden {
+		section: "filter",
+		name:    "name".into(),
+	});
+}
@@ -10,7 +10,9 @@ use sqlxo::{
 	},
 	web::{
 		WebDeleteFilter,
+		WebPurgeFilter,
 		WebReadFilter,
+		WebRestoreFilter,
 		WebUpdateFilter,
 	},
 	Buildable,
@@ -22,12 +24,20 @@ use sqlxo::{
 use uuid::Uuid;
 
 use crate::helpers::{
+	Comment,
+	CommentDto,
+	CommentUpdate,
 	HardDeleteItem,
 	HardDeleteItemDto,
 	Item,
 	ItemDto,
 	ItemJoin,
 	NormalizeString,
+	SoftDeleteItem,
+	SoftDeleteItemDto,
+	StockItem,
+	StockItemDto,
+	StockItemUpdateOp,
 	UpdateItem,
 	UpdateItemDto,
 	UpdateItemUpdate,
@@ -268,6 +278,94 @@ fn web_query_into_update_builds_sql() {
 	);
 }
 
+#[test]
+fn web_update_payload_passes_expected_version() {
+	let test_id = Uuid::new_v4();
+	let json: Value = json!({
+		"filter": { "id": { "eq": test_id } },
+		"version": 4
+	});
+	let filter: WebUpdateFilter<StockItemDto> =
+		serde_json::from_value(json).expect("valid StockItemDto filter");
+
+	let plan =
+		QueryBuilder::<StockItem>::from_web_update::<StockItemDto>(&filter)
+			.set(StockItemUpdateOp::AmountIncrement(1))
+			.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE stock_item SET updated_at = NOW(), version = \
+		 \"stock_item\".\"version\" + 1, amount = \"stock_item\".\"amount\" + \
+		 $1 WHERE \"stock_item\".\"id\" = $2 AND \"stock_item\".\"version\" = \
+		 $3"
+	);
+}
+
+#[test]
+fn web_update_payload_rejects_version_of_unversioned_model() {
+	let json: Value = json!({
+		"filter": { "id": { "eq": Uuid::new_v4() } },
+		"version": 4
+	});
+	let filter: WebUpdateFilter<UpdateItemDto> =
+		serde_json::from_value(json).expect("valid UpdateItemDto filter");
+
+	let err = QueryBuilder::<UpdateItem>::try_from_web_update::<UpdateItemDto>(
+		&filter,
+	)
+	.err()
+	.expect("version of an unversioned model is rejected");
+	assert!(
+		matches!(err, WebQueryError::VersionUnsupported { .. }),
+		"unexpected error: {err:?}"
+	);
+}
+
+#[test]
+fn web_purge_payload_applies_deleted_before() {
+	let json: Value = json!({
+		"filter": { "name": { "eq": "obsolete" } },
+		"deletedBefore": "2026-01-01T00:00:00Z"
+	});
+	let filter: WebPurgeFilter<SoftDeleteItemDto> =
+		serde_json::from_value(json).expect("valid SoftDeleteItemDto filter");
+	assert_some!(filter.deleted_before);
+
+	let plan = QueryBuilder::<SoftDeleteItem>::from_web_purge::<
+		SoftDeleteItemDto,
+	>(&filter)
+	.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"DELETE FROM soft_delete_item WHERE \
+		 \"soft_delete_item\".\"deleted_at\" IS NOT NULL AND \
+		 \"soft_delete_item\".\"deleted_at\" < $1 AND \
+		 \"soft_delete_item\".\"name\" = $2"
+	);
+}
+
+#[test]
+fn web_restore_payload_builds_sql() {
+	let test_id = Uuid::new_v4();
+	let json: Value = json!({ "filter": { "id": { "eq": test_id } } });
+	let filter: WebRestoreFilter<SoftDeleteItemDto> =
+		serde_json::from_value(json).expect("valid SoftDeleteItemDto filter");
+
+	let plan = QueryBuilder::<SoftDeleteItem>::from_web_restore::<
+		SoftDeleteItemDto,
+	>(&filter)
+	.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE soft_delete_item SET deleted_at = NULL WHERE \
+		 \"soft_delete_item\".\"deleted_at\" IS NOT NULL AND \
+		 \"soft_delete_item\".\"id\" = $1"
+	);
+}
+
 #[test]
 fn web_query_into_delete_builds_sql() {
 	let json: Value = json!({
@@ -287,6 +385,85 @@ fn web_query_into_delete_builds_sql() {
 	);
 }
 
+#[test]
+fn web_delete_joins_render_using() {
+	let json: Value = json!({
+		"joins": [{ "post": null }],
+		"filter": { "body": { "eq": "spam" } }
+	});
+	let filter: WebDeleteFilter<CommentDto> =
+		serde_json::from_value(json).expect("valid CommentDto filter");
+
+	let plan =
+		QueryBuilder::<Comment>::from_web_delete::<CommentDto>(&filter).build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"DELETE FROM comment USING post AS \"post__\" WHERE \
+		 \"comment\".\"post_id\" = \"post__\".\"id\" AND \"comment\".\"body\" \
+		 = $1"
+	);
+}
+
+#[test]
+fn web_update_join_filter_filters_on_joined_rows() {
+	let json: Value = json!({
+		"joinFilter": { "post": { "title": { "eq": "spam" } } }
+	});
+	let filter: WebUpdateFilter<CommentDto> =
+		serde_json::from_value(json).expect("valid CommentDto filter");
+
+	let plan = QueryBuilder::<Comment>::from_web_update::<CommentDto>(&filter)
+		.model(CommentUpdate {
+			body: Some("hidden".into()),
+			..Default::default()
+		})
+		.build();
+
+	assert_eq!(
+		plan.sql().normalize(),
+		"UPDATE comment SET body = $1 FROM post AS \"post__\" WHERE \
+		 \"comment\".\"post_id\" = \"post__\".\"id\" AND \"post__\".\"title\" \
+		 = $2"
+	);
+}
+
+#[test]
+fn web_mutations_require_filter() {
+	let filter: WebDeleteFilter<HardDeleteItemDto> =
+		serde_json::from_value(json!({}))
+			.expect("valid HardDeleteItemDto filter");
+	let err = QueryBuilder::<HardDeleteItem>::try_from_web_delete::<
+		HardDeleteItemDto,
+	>(&filter)
+	.err()
+	.expect("unfiltered delete is rejected");
+	assert!(
+		matches!(err, WebQueryError::UnfilteredMutation { .. }),
+		"unexpected error: {err:?}"
+	);
+
+	let filter: WebUpdateFilter<UpdateItemDto> =
+		serde_json::from_value(json!({ "version": 1 }))
+			.expect("valid UpdateItemDto filter");
+	assert!(
+		QueryBuilder::<UpdateItem>::try_from_web_update::<UpdateItemDto>(
+			&filter
+		)
+		.is_err()
+	);
+
+	let filter: WebPurgeFilter<SoftDeleteItemDto> =
+		serde_json::from_value(json!({
+			"deletedBefore": "2026-01-01T00:00:00Z"
+		}))
+		.expect("valid SoftDeleteItemDto filter");
+	assert!(QueryBuilder::<SoftDeleteItem>::try_from_web_purge::<
+		SoftDeleteItemDto,
+	>(&filter)
+	.is_ok());
+}
+
 #[test]
 fn web_query_update_rejects_having() {
 	let json: Value = json!({
@@ -316,3 +493,267 @@ fn web_query_search_requires_full_text_support() {
 		"expected SearchUnsupported error but got {err:?}"
 	);
 }
+
+#[test]
+fn web_payload_requests_relation_counts() {
+	let json: Value = json!({
+		"counts": ["tags"]
+	});
+	let filter: WebReadFilter<ItemDto> =
+		serde_json::from_value(json).expect("valid ItemDtoFilter");
+
+	let sql = QueryBuilder::<Item>::from_web_read::<ItemDto>(&filter)
+		.build()
+		.sql(SelectType::Star)
+		.trim_start()
+		.normalize();
+
+	assert_eq!(
+		sql,
+		r#"
+        SELECT "item".*,
+            (SELECT COUNT(*) FROM item_tag AS "tags__count"
+                WHERE "item"."id" = "tags__count"."item_id")
+                AS "__sqlxo_count_tags"
+        FROM item
+    "#
+		.normalize()
+	);
+}
+
+#[test]
+fn web_payload_rejects_unknown_count_relation() {
+	let json: Value = json!({
+		"counts": ["suppliers"]
+	});
+	let filter: WebReadFilter<ItemDto> =
+		serde_json::from_value(json).expect("valid ItemDtoFilter");
+
+	let result = QueryBuilder::<Item>::try_from_web_read::<ItemDto>(&filter);
+	let err = match result {
+		Ok(_) => panic!("expected unknown count relation to be rejected"),
+		Err(err) => err,
+	};
+	assert!(
+		matches!(
+			err,
+			WebQueryError::CountRelationInvalid { ref relation, .. }
+				if relation == "suppliers"
+		),
+		"unexpected error: {err:?}"
+	);
+}
+
+#[test]
+fn web_payload_supports_or_in_having() {
+	let json: Value = json!({
+		"having": {
+			"or": [
+				{ "count": { "gt": 5 } },
+				{ "and": [
+					{ "priceSum": { "gt": 25.0 } },
+					{ "count": { "lt": 2 } }
+				]}
+			]
+		}
+	});
+
+	let f: WebReadFilter<ItemDto> =
+		serde_json::from_value(json).expect("valid ItemDtoFilter");
+
+	let sql = QueryBuilder::<Item>::from_web_read::<ItemDto>(&f)
+		.build()
+		.sql(SelectType::Star)
+		.trim_start()
+		.normalize();
+
+	assert_eq!(
+		sql,
+		r#"
+        SELECT "item".* FROM item
+        WHERE "item"."id" IN (SELECT "item"."id" FROM item
+            GROUP BY "item"."id"
+            HAVING COUNT(*) > $1 OR (SUM("item"."price") > $2 AND COUNT(*) < $3))
+    "#
+		.normalize()
+	);
+}
+
+#[test]
+fn web_payload_supports_statistical_and_bool_having() {
+	let json: Value = json!({
+		"having": {
+			"and": [
+				{ "priceMedian": { "gte": 10.0 } },
+				{ "amountStdDev": { "lt": 3.0 } },
+				{ "activeBoolOr": { "eq": true } }
+			]
+		}
+	});
+
+	let f: WebReadFilter<ItemDto> =
+		serde_json::from_value(json).expect("valid ItemDtoFilter");
+
+	let sql = QueryBuilder::<Item>::from_web_read::<ItemDto>(&f)
+		.build()
+		.sql(SelectType::Star)
+		.trim_start()
+		.normalize();
+
+	assert_eq!(
+		sql,
+		r#"
+        SELECT "item".* FROM item
+        WHERE "item"."id" IN (SELECT "item"."id" FROM item
+            GROUP BY "item"."id"
+            HAVING PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY "item"."price")::float8 >= $1
+            AND STDDEV("item"."amount")::float8 < $2
+            AND BOOL_OR("item"."active") = $3)
+    "#
+		.normalize()
+	);
+}
+
+#[test]
+fn web_payload_builds_grouped_read() {
+	let json: Value = json!({
+		"filter": { "active": { "is_true": true } },
+		"groupBy": ["amount"],
+		"aggregates": ["count", "priceSum", "priceMedian"],
+		"having": { "count": { "gt": 1 } },
+		"sort": [{ "amount": "asc" }]
+	});
+	let f: WebReadFilter<ItemDto> =
+		serde_json::from_value(json).expect("valid ItemDtoFilter");
+
+	let sql = QueryBuilder::<Item>::try_from_web_aggregate::<ItemDto>(&f)
+		.unwrap()
+		.build()
+		.sql(SelectType::Star)
+		.trim_start()
+		.normalize();
+
+	assert_eq!(
+		sql,
+		r#"
+        SELECT "item"."amount" AS "amount",
+            COUNT(*) AS "count",
+            SUM("item"."price") AS "priceSum",
+            PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY "item"."price")::float8 AS "priceMedian"
+        FROM item
+        WHERE "item"."active" = TRUE
+        GROUP BY "item"."amount"
+        HAVING COUNT(*) > $1
+        ORDER BY "item"."amount" ASC
+    "#
+		.normalize()
+	);
+}
+
+#[test]
+fn web_grouped_read_sections_are_validated() {
+	let grouped: WebReadFilter<ItemDto> =
+		serde_json::from_value(json!({ "groupBy": ["amount"] })).unwrap();
+	let err = match QueryBuilder::<Item>::try_from_web_read::<ItemDto>(&grouped)
+	{
+		Ok(_) => panic!("expected model reads to reject `groupBy`"),
+		Err(err) => err,
+	};
+	assert_eq!(err, WebQueryError::GroupedReadRequired {
+		section: "groupBy",
+	});
+
+	let empty: WebReadFilter<ItemDto> =
+		serde_json::from_value(json!({ "aggregates": [] })).unwrap();
+	let err =
+		match QueryBuilder::<Item>::try_from_web_aggregate::<ItemDto>(&empty) {
+			Ok(_) => panic!("expected an empty grouped read to be rejected"),
+			Err(err) => err,
+		};
+	assert_eq!(err, WebQueryError::EmptyGroupedRead);
+
+	let counted: WebReadFilter<ItemDto> = serde_json::from_value(json!({
+		"aggregates": ["count"],
+		"counts": ["tags"]
+	}))
+	.unwrap();
+	let err =
+		match QueryBuilder::<Item>::try_from_web_aggregate::<ItemDto>(&counted)
+		{
+			Ok(_) => panic!("expected `counts` to be rejected"),
+			Err(err) => err,
+		};
+	assert_eq!(err, WebQueryError::GroupedReadUnsupported {
+		section: "counts",
+	});
+
+	assert!(serde_json::from_value::<WebReadFilter<ItemDto>>(
+		json!({ "groupBy": ["unknown"] })
+	)
+	.is_err());
+}
+
+#[test]
+fn web_payload_requests_facets() {
+	let f: WebReadFilter<ItemDto> = serde_json::from_value(json!({
+		"filter": { "amount": { "gt": 1 } },
+		"facets": ["differentName", "price"]
+	}))
+	.unwrap();
+
+	let plan: ReadQueryPlan<Item> =
+		QueryBuilder::<Item>::try_from_web_read::<ItemDto>(&f)
+			.unwrap()
+			.build();
+	let sql = plan.facet_sql().normalize();
+	assert!(sql.contains(
+		r#""item"."name" AS "__sqlxo_facet_0", "item"."price" AS "__sqlxo_facet_1" FROM item WHERE ("item"."amount" > $1))"#
+	));
+	assert!(
+		sql.contains(r#"SELECT json_build_object('differentName', (SELECT"#)
+	);
+	assert!(sql.contains(r#"ARRAY[10, 50]::float8[]"#));
+
+	let unknown: WebReadFilter<ItemDto> =
+		serde_json::from_value(json!({ "facets": ["amount"] })).unwrap();
+	let err = match QueryBuilder::<Item>::try_from_web_read::<ItemDto>(&unknown)
+	{
+		Ok(_) => panic!("expected undeclared facets to be rejected"),
+		Err(err) => err,
+	};
+	assert_eq!(err, WebQueryError::FacetInvalid {
+		model: std::any::type_name::<ItemDto>(),
+		facet: "amount".into(),
+	});
+
+	let grouped: WebReadFilter<ItemDto> = serde_json::from_value(json!({
+		"groupBy": ["active"],
+		"facets": ["active"]
+	}))
+	.unwrap();
+	let err =
+		match QueryBuilder::<Item>::try_from_web_aggregate::<ItemDto>(&grouped)
+		{
+			Ok(_) => panic!("expected grouped reads to reject `facets`"),
+			Err(err) => err,
+		};
+	assert_eq!(err, WebQueryError::GroupedReadUnsupported {
+		section: "facets",
+	});
+}
+
+#[test]
+fn web_payload_rejects_empty_having_group() {
+	let json: Value = json!({
+		"having": { "or": [] }
+	});
+	let f: WebReadFilter<ItemDto> =
+		serde_json::from_value(json).expect("valid ItemDtoFilter");
+
+	let result = QueryBuilder::<Item>::try_from_web_read::<ItemDto>(&f);
+	let err = match result {
+		Ok(_) => panic!("expected empty having group to be rejected"),
+		Err(err) => err,
+	};
+	assert_eq!(err, WebQueryError::EmptyHavingGroup { operator: "or" });
+}
@@ -44,18 +44,32 @@ enum Kind {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(dead_code)]
 enum CascadeType {
 	Cascade,
 	Restrict,
 	SetNull,
 }
 
+impl CascadeType {
+	fn to_tokens(
+		self,
+		root: &proc_macro2::TokenStream,
+	) -> proc_macro2::TokenStream {
+		match self {
+			Self::Cascade => quote! { #root::CascadeType::Cascade },
+			Self::Restrict => quote! { #root::CascadeType::Restrict },
+			Self::SetNull => quote! { #root::CascadeType::SetNull },
+		}
+	}
+}
+
 #[derive(Debug, Clone)]
 struct MarkerFields {
 	delete_marker: Option<String>,
 	update_marker: Option<String>,
 	insert_marker: Option<String>,
+	version:       Option<String>,
+	tenant:        Option<String>,
 }
 
 struct JoinFieldInfo {
@@ -65,6 +79,7 @@ struct JoinFieldInfo {
 	json_name:          syn::LitStr,
 	friendly_name:      syn::LitStr,
 	child_join_path_ty: syn::Type,
+	child_leaf_ty:      syn::Type,
 }
 
 impl MarkerFields {
@@ -73,6 +88,8 @@ impl MarkerFields {
 			delete_marker: None,
 			update_marker: None,
 			insert_marker: None,
+			version:       None,
+			tenant:        None,
 		}
 	}
 }
@@ -171,8 +188,30 @@ fn validate_language(value: &str, span: proc_macro2::Span) -> syn::Result<()> {
 	}
 }
 
-fn extract_table_name(input: &DeriveInput) -> syn::Result<Option<String>> {
-	let mut value: Option<String> = None;
+struct ContainerAttrs {
+	table_name:    Option<String>,
+	default_scope: Option<syn::Expr>,
+	scopes:        Vec<(Ident, syn::Expr)>,
+	audited:       bool,
+}
+
+fn parse_scope_expr(lit: &Lit, example: &str) -> syn::Result<syn::Expr> {
+	match lit {
+		Lit::Str(s) => s.parse(),
+		other => Err(syn::Error::new_spanned(
+			other,
+			format!("expected string literal: #[sqlxo({example})]"),
+		)),
+	}
+}
+
+fn extract_container_attrs(input: &DeriveInput) -> syn::Result<ContainerAttrs> {
+	let mut attrs = ContainerAttrs {
+		table_name:    None,
+		default_scope: None,
+		scopes:        Vec::new(),
+		audited:       false,
+	};
 
 	for attr in &input.attrs {
 		if !attr.path.is_ident("sqlxo") {
@@ -200,13 +239,13 @@ fn extract_table_name(input: &DeriveInput) -> syn::Result<Option<String>> {
 				{
 					match nv.lit {
 						Lit::Str(ref s) => {
-							if value.is_some() {
+							if attrs.table_name.is_some() {
 								return Err(syn::Error::new_spanned(
 									nv,
 									"duplicate key `table_name`",
 								));
 							}
-							value = Some(s.value());
+							attrs.table_name = Some(s.value());
 						}
 						other => {
 							return Err(syn::Error::new_spanned(
@@ -217,6 +256,59 @@ fn extract_table_name(input: &DeriveInput) -> syn::Result<Option<String>> {
 						}
 					}
 				}
+				NestedMeta::Meta(Meta::NameValue(nv))
+					if nv.path.is_ident("default_scope") =>
+				{
+					if attrs.default_scope.is_some() {
+						return Err(syn::Error::new_spanned(
+							nv,
+							"duplicate key `default_scope`",
+						));
+					}
+					attrs.default_scope = Some(parse_scope_expr(
+						&nv.lit,
+						"default_scope = \"ItemQuery::ArchivedIsFalse\"",
+					)?);
+				}
+				NestedMeta::Meta(Meta::List(scopes))
+					if scopes.path.is_ident("scope") =>
+				{
+					for scope in scopes.nested {
+						let nv = match scope {
+							NestedMeta::Meta(Meta::NameValue(nv)) => nv,
+							other => {
+								return Err(syn::Error::new_spanned(
+									other,
+									"expected #[sqlxo(scope(active = \
+									 \"...\"))]",
+								));
+							}
+						};
+						let name =
+							nv.path.get_ident().cloned().ok_or_else(|| {
+								syn::Error::new_spanned(
+									&nv.path,
+									"expected scope name",
+								)
+							})?;
+						if attrs.scopes.iter().any(|(n, _)| *n == name) {
+							return Err(syn::Error::new_spanned(
+								nv,
+								format!("duplicate scope `{name}`"),
+							));
+						}
+						let expr = parse_scope_expr(
+							&nv.lit,
+							"scope(active = \"ItemQuery::ArchivedIsFalse\")",
+						)?;
+						attrs.scopes.push((name, expr));
+					}
+				}
+				NestedMeta::Meta(Meta::Path(path))
+					if path.is_ident("audited") =>
+				{
+					attrs.audited = true;
+				}
 				NestedMeta::Meta(Meta::NameValue(nv)) => {
 					return Err(syn::Error::new_spanned(
 						nv,
@@ -233,7 +325,7 @@ fn extract_table_name(input: &DeriveInput) -> syn::Result<Option<String>> {
 		}
 	}
 
-	Ok(value)
+	Ok(attrs)
 }
 
 fn extract_marker_fields(
@@ -294,6 +386,37 @@ fn extract_marker_fields(
 						}
 						markers.insert_marker = Some(field_name.clone());
 					}
+					NestedMeta::Meta(Meta::Path(path))
+						if path.is_ident("version") =>
+					{
+						if markers.version.is_some() {
+							return Err(syn::Error::new_spanned(
+								attr,
+								"duplicate #[sqlxo(version)]",
+							));
+						}
+						if !matches!(classify_type(&field.ty), Kind::Number) ||
+							is_option_type(&field.ty)
+						{
+							return Err(syn::Error::new_spanned(
+								&field.ty,
+								"#[sqlxo(version)] requires a non-optional \
+								 integer field",
+							));
+						}
+						markers.version = Some(field_name.clone());
+					}
+					NestedMeta::Meta(Meta::Path(path))
+						if path.is_ident("tenant") =>
+					{
+						if markers.tenant.is_some() {
+							return Err(syn::Error::new_spanned(
+								attr,
+								"duplicate #[sqlxo(tenant)]",
+							));
+						}
+						markers.tenant = Some(field_name.clone());
+					}
 					_ => {}
 				}
 			}
@@ -305,30 +428,40 @@ fn extract_marker_fields(
 
 struct FkSpec {
 	fk_field_snake: String,
+	left_fields:    Vec<String>,
 	right_table:    String,
-	right_pk:       String,
+	right_fields:   Vec<String>,
 	alias_segment:  String,
 	variant_ident:  Ident,
 	friendly_name:  String,
 	related_ty:     Option<syn::Type>,
 }
 
+struct ForeignKeyAttr {
+	right_table: String,
+	right_pk:    String,
+	/// Further `(local, remote)` column pairs of a composite foreign key.
+	extra_pairs: Vec<(String, String)>,
+	cascade:     Option<CascadeType>,
+}
+
 struct ManualJoinSpec {
 	variant_ident: Ident,
 	friendly_name: String,
 	alias_segment: String,
-	left_field:    String,
+	left_fields:   Vec<String>,
 	right_table:   String,
-	right_field:   String,
+	right_fields:  Vec<String>,
 	related_ty:    Option<syn::Type>,
 	through:       Option<ThroughSpec>,
 }
 
 struct ThroughSpec {
+	ty:            syn::Type,
 	table:         String,
 	alias_segment: String,
-	left_field:    String,
-	right_field:   String,
+	left_fields:   Vec<String>,
+	right_fields:  Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -349,6 +482,7 @@ struct NavigationAttr {
 	self_fk:         Option<String>,
 	other_fk:        Option<String>,
 	other_pk:        Option<String>,
+	cascade:         Option<CascadeType>,
 }
 
 impl NavigationAttr {
@@ -362,6 +496,7 @@ impl NavigationAttr {
 			self_fk: None,
 			other_fk: None,
 			other_pk: None,
+			cascade: None,
 		}
 	}
 
@@ -377,6 +512,7 @@ struct PendingNavigation {
 	ty:             syn::Type,
 	attr:           NavigationAttr,
 	_is_collection: bool,
+	is_boxed:       bool,
 }
 
 #[derive(Debug, Clone)]
@@ -384,6 +520,13 @@ struct NavigationFieldSpec {
 	field_ident:     Ident,
 	join_identifier: String,
 	related_ty:      syn::Type,
+	is_boxed:        bool,
+}
+
+#[derive(Debug, Clone)]
+struct CountFieldSpec {
+	field_ident: Ident,
+	relation:    String,
 }
 
 #[derive(Debug, Clone)]
@@ -427,26 +570,28 @@ fn build_join_codegen(
 			syn::LitStr::new(&fk.alias_segment, proc_macro2::Span::call_site());
 		let left_table_lit =
 			syn::LitStr::new(left_table, proc_macro2::Span::call_site());
-		let left_field_lit = syn::LitStr::new(
-			&fk.fk_field_snake,
-			proc_macro2::Span::call_site(),
-		);
+		let left_fields_lit = column_list_tokens(&fk.left_fields);
 		let right_table_lit =
 			syn::LitStr::new(&right_table, proc_macro2::Span::call_site());
-		let right_field_lit =
-			syn::LitStr::new(&fk.right_pk, proc_macro2::Span::call_site());
+		let right_fields_lit = column_list_tokens(&fk.right_fields);
 		let identifier_lit =
 			syn::LitStr::new(&fk.friendly_name, proc_macro2::Span::call_site());
+		let tenant_field = tenant_field_tokens(fk.related_ty.as_ref());
+		let delete_marker = delete_marker_tokens(fk.related_ty.as_ref());
+		let default_scope = default_scope_tokens(fk.related_ty.as_ref());
 
 		descriptor_arms.push(quote! {
 			Self::#var => #root::JoinDescriptor {
 				left_table:    #left_table_lit,
-				left_field:    #left_field_lit,
+				left_fields:   #left_fields_lit,
 				right_table:   #right_table_lit,
-				right_field:   #right_field_lit,
+				right_fields:  #right_fields_lit,
 				alias_segment: #alias_segment,
 				identifier:    #identifier_lit,
 				through:       None,
+				tenant_field:  #tenant_field,
+				delete_marker: #delete_marker,
+				default_scope: #default_scope,
 			}
 		});
 	}
@@ -465,12 +610,10 @@ fn build_join_codegen(
 		);
 		let left_table_lit =
 			syn::LitStr::new(left_table, proc_macro2::Span::call_site());
-		let left_field_lit =
-			syn::LitStr::new(&spec.left_field, proc_macro2::Span::call_site());
+		let left_fields_lit = column_list_tokens(&spec.left_fields);
 		let right_table_lit =
 			syn::LitStr::new(&spec.right_table, proc_macro2::Span::call_site());
-		let right_field_lit =
-			syn::LitStr::new(&spec.right_field, proc_macro2::Span::call_site());
+		let right_fields_lit = column_list_tokens(&spec.right_fields);
 		let through_expr = if let Some(through) = &spec.through {
 			let through_table = syn::LitStr::new(
 				&through.table,
@@ -480,35 +623,39 @@ fn build_join_codegen(
 				&through.alias_segment,
 				proc_macro2::Span::call_site(),
 			);
-			let through_left = syn::LitStr::new(
-				&through.left_field,
-				proc_macro2::Span::call_site(),
-			);
-			let through_right = syn::LitStr::new(
-				&through.right_field,
-				proc_macro2::Span::call_site(),
-			);
+			let through_left = column_list_tokens(&through.left_fields);
+			let through_right = column_list_tokens(&through.right_fields);
+			let through_tenant = tenant_field_tokens(Some(&through.ty));
+			let through_marker = delete_marker_tokens(Some(&through.ty));
 			quote! {
 				Some(#root::JoinThroughDescriptor {
 					table:         #through_table,
 					alias_segment: #through_alias,
-					left_field:    #through_left,
-					right_field:   #through_right,
+					left_fields:   #through_left,
+					right_fields:  #through_right,
+					tenant_field:  #through_tenant,
+					delete_marker: #through_marker,
 				})
 			}
 		} else {
 			quote! { None }
 		};
+		let tenant_field = tenant_field_tokens(spec.related_ty.as_ref());
+		let delete_marker = delete_marker_tokens(spec.related_ty.as_ref());
+		let default_scope = default_scope_tokens(spec.related_ty.as_ref());
 
 		descriptor_arms.push(quote! {
 			Self::#var => #root::JoinDescriptor {
 				left_table:    #left_table_lit,
-				left_field:    #left_field_lit,
+				left_fields:   #left_fields_lit,
 				right_table:   #right_table_lit,
-				right_field:   #right_field_lit,
+				right_fields:  #right_fields_lit,
 				alias_segment: #alias_segment,
 				identifier:    #friendly_lit,
 				through:       #through_expr,
+				tenant_field:  #tenant_field,
+				delete_marker: #delete_marker,
+				default_scope: #default_scope,
 			}
 		});
 	}
@@ -516,6 +663,222 @@ fn build_join_codegen(
 	(variants, descriptor_arms)
 }
 
+/// The type a `{Dto}AggregateRow` holds an aggregate of a numeric field as.
+/// Postgres sums small integers into `bigint` but returns `NUMERIC` for the
+/// sum of wider integers and the average of any integer, which
+/// `WebAggregateRow` keeps as a string.
+fn aggregate_row_type(suffix: &str, ty: syn::Type) -> syn::Type {
+	let root = sqlxo_root();
+	let base = extract_single_generic(&ty, "Option")
+		.map_or_else(
+			|| type_ident_string(&ty),
+			|inner| type_ident_string(&inner),
+		)
+		.unwrap_or_default();
+	match (suffix, base.as_str()) {
+		("Sum" | "Avg", "f32" | "f64") => parse_quote!(f64),
+		("Sum", "i8" | "i16" | "i32" | "u8" | "u16") => parse_quote!(i64),
+		("Sum" | "Avg", _) => parse_quote!(#root::web::WebNumeric),
+		_ => ty,
+	}
+}
+
+/// The tenant column of a joined model; joins without a model type aren't
+/// scoped.
+fn tenant_field_tokens(ty: Option<&syn::Type>) -> proc_macro2::TokenStream {
+	let root = sqlxo_root();
+	match ty {
+		Some(ty) => quote! { <#ty as #root::TenantScoped>::TENANT_FIELD },
+		None => quote! { None },
+	}
+}
+
+/// The soft-delete marker of a joined model, if it has one.
+fn delete_marker_tokens(ty: Option<&syn::Type>) -> proc_macro2::TokenStream {
+	let root = sqlxo_root();
+	match ty {
+		Some(ty) => {
+			quote! { <#ty as #root::GetDeleteMarker>::delete_marker_field() }
+		}
+		None => quote! { None },
+	}
+}
+
+/// The default scope of a joined model, if it has one.
+fn default_scope_tokens(ty: Option<&syn::Type>) -> proc_macro2::TokenStream {
+	let root = sqlxo_root();
+	match ty {
+		Some(ty) => {
+			quote! { <#ty as #root::GetDefaultScoped>::default_scope_writer() }
+		}
+		None => quote! { None },
+	}
+}
+
+fn column_list_tokens(columns: &[String]) -> proc_macro2::TokenStream {
+	let lits = columns
+		.iter()
+		.map(|col| syn::LitStr::new(col, proc_macro2::Span::call_site()));
+	quote! { &[#(#lits),*] }
+}
+
+/// Splits `"tenant_id, id"` style column lists used by composite keys.
+fn split_column_list(value: &str) -> Vec<String> {
+	value
+		.split(',')
+		.map(|col| col.trim().to_string())
+		.filter(|col| !col.is_empty())
+		.collect()
+}
+
+fn parse_foreign_key_attr(
+	attr: &syn::Attribute,
+) -> syn::Result<ForeignKeyAttr> {
+	let meta = attr
+		.parse_meta()
+		.map_err(|_| Error::new_spanned(attr, "invalid #[foreign_key]"))?;
+
+	let list = match meta {
+		Meta::List(list) => list,
+		_ => {
+			return Err(Error::new_spanned(
+				attr,
+				r#"expected #[foreign_key(to = "table.pk")]"#,
+			))
+		}
+	};
+
+	let mut to_value: Option<String> = None;
+	let mut extra_pairs: Vec<(String, String)> = Vec::new();
+	let mut cascade: Option<CascadeType> = None;
+
+	for nested in list.nested {
+		match nested {
+			NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("to") => {
+				match nv.lit {
+					Lit::Str(ref s) => {
+						if to_value.is_some() {
+							return Err(Error::new_spanned(
+								nv,
+								"duplicate key `to`",
+							));
+						}
+						to_value = Some(s.value());
+					}
+					other => {
+						return Err(Error::new_spanned(
+							other,
+							r#"expected "table.pk""#,
+						));
+					}
+				}
+			}
+			NestedMeta::Meta(Meta::List(on)) if on.path.is_ident("on") => {
+				for pair in on.nested {
+					match pair {
+						NestedMeta::Meta(Meta::NameValue(nv)) => {
+							let local = nv
+								.path
+								.get_ident()
+								.ok_or_else(|| {
+									Error::new_spanned(
+										&nv.path,
+										"expected a column name",
+									)
+								})?
+								.to_string();
+							let remote = parse_string_lit(
+								&nv.lit,
+								r#"expected `local = "remote"`"#,
+							)?;
+							extra_pairs.push((local, remote));
+						}
+						other => {
+							return Err(Error::new_spanned(
+								other,
+								r#"expected `on(local = "remote", ...)`"#,
+							));
+						}
+					}
+				}
+			}
+			NestedMeta::Meta(Meta::List(list))
+				if list.path.is_ident("cascade_type") =>
+			{
+				if cascade.is_some() {
+					return Err(Error::new_spanned(
+						list,
+						"duplicate key `cascade_type`",
+					));
+				}
+				cascade = Some(parse_cascade_type(&list)?);
+			}
+			other => {
+				return Err(Error::new_spanned(
+					other,
+					r#"unknown key; expected `to = "table.pk"`, `on(...)` or `cascade_type(...)`"#,
+				));
+			}
+		}
+	}
+
+	let to = to_value.ok_or_else(|| {
+		Error::new(attr.span(), r#"missing `to = "table.pk"`"#)
+	})?;
+
+	let mut parts = to.split('.');
+	let right_table = parts
+		.next()
+		.filter(|part| !part.is_empty())
+		.ok_or_else(|| Error::new(attr.span(), "missing table"))?
+		.to_string();
+	let right_pk = parts
+		.next()
+		.ok_or_else(|| Error::new(attr.span(), "missing pk"))?
+		.to_string();
+
+	if parts.next().is_some() {
+		return Err(Error::new(
+			attr.span(),
+			r#"invalid `to` — expected "table.pk""#,
+		));
+	}
+
+	Ok(ForeignKeyAttr {
+		right_table,
+		right_pk,
+		extra_pairs,
+		cascade,
+	})
+}
+
+/// Parses `cascade_type(cascade | restrict | set_null)`.
+fn parse_cascade_type(list: &syn::MetaList) -> syn::Result<CascadeType> {
+	let mut nested = list.nested.iter();
+	let cascade = match (nested.next(), nested.next()) {
+		(Some(NestedMeta::Meta(Meta::Path(path))), None) => {
+			if path.is_ident("cascade") {
+				Some(CascadeType::Cascade)
+			} else if path.is_ident("restrict") {
+				Some(CascadeType::Restrict)
+			} else if path.is_ident("set_null") {
+				Some(CascadeType::SetNull)
+			} else {
+				None
+			}
+		}
+		_ => None,
+	};
+
+	cascade.ok_or_else(|| {
+		Error::new_spanned(
+			list,
+			"expected `cascade_type(cascade)`, `cascade_type(restrict)` or \
+			 `cascade_type(set_null)`",
+		)
+	})
+}
+
 fn derive_alias_segment(field_name: &str) -> String {
 	let mut base = field_name.to_string();
 	if let Some(stripped) = base.strip_suffix("_id") {
@@ -681,9 +1044,73 @@ fn has_sqlx_skip(field: &syn::Field) -> syn::Result<bool> {
 	Ok(false)
 }
 
+fn has_nested_create(field: &syn::Field) -> syn::Result<bool> {
+	for attr in &field.attrs {
+		if !attr.path.is_ident("sqlxo") {
+			continue;
+		}
+
+		let meta = attr.parse_meta().map_err(|_| {
+			syn::Error::new_spanned(attr, "invalid #[sqlxo] attribute")
+		})?;
+
+		let Meta::List(list) = meta else {
+			continue;
+		};
+
+		for nested in list.nested {
+			if let NestedMeta::Meta(Meta::Path(path)) = nested {
+				if path.is_ident("nested_create") {
+					return Ok(true);
+				}
+			}
+		}
+	}
+
+	Ok(false)
+}
+
+fn extract_count_of(field: &syn::Field) -> syn::Result<Option<String>> {
+	let mut relation: Option<String> = None;
+
+	for attr in &field.attrs {
+		if !attr.path.is_ident("sqlxo") {
+			continue;
+		}
+
+		let meta = attr.parse_meta().map_err(|_| {
+			syn::Error::new_spanned(attr, "invalid #[sqlxo] attribute")
+		})?;
+
+		let Meta::List(list) = meta else {
+			continue;
+		};
+
+		for nested in list.nested {
+			if let NestedMeta::Meta(Meta::NameValue(nv)) = nested {
+				if nv.path.is_ident("count_of") {
+					if relation.is_some() {
+						return Err(syn::Error::new_spanned(
+							nv,
+							"duplicate `count_of` option",
+						));
+					}
+					relation = Some(parse_string_lit(
+						&nv.lit,
+						"count_of must be a string",
+					)?);
+				}
+			}
+		}
+	}
+
+	Ok(relation)
+}
+
 struct JoinValueInner {
 	ty:            syn::Type,
 	is_collection: bool,
+	is_boxed:      bool,
 }
 
 fn extract_navigation_attr(
@@ -796,6 +1223,7 @@ fn parse_rel_navigation(list: &syn::MetaList) -> syn::Result<NavigationAttr> {
 	let mut self_fk: Option<String> = None;
 	let mut other_fk: Option<String> = None;
 	let mut other_pk: Option<String> = None;
+	let mut cascade: Option<CascadeType> = None;
 	let mut explicit_target: Option<syn::Type> = None;
 
 	for option in list.nested.iter() {
@@ -927,6 +1355,17 @@ fn parse_rel_navigation(list: &syn::MetaList) -> syn::Result<NavigationAttr> {
 					"other_pk must be a string",
 				)?);
 			}
+			NestedMeta::Meta(Meta::List(inner))
+				if inner.path.is_ident("cascade_type") =>
+			{
+				if cascade.is_some() {
+					return Err(syn::Error::new_spanned(
+						inner,
+						"duplicate `cascade_type` option",
+					));
+				}
+				cascade = Some(parse_cascade_type(inner)?);
+			}
 			_ => {
 				return Err(syn::Error::new_spanned(
 					option,
@@ -942,6 +1381,12 @@ fn parse_rel_navigation(list: &syn::MetaList) -> syn::Result<NavigationAttr> {
 			"#[sqlxo(rel(...))] requires a relation kind",
 		)
 	})?;
+	if cascade.is_some() && relation_kind != RelationKind::HasMany {
+		return Err(syn::Error::new(
+			list.span(),
+			"`cascade_type(...)` only applies to has_many relations",
+		));
+	}
 	let mut base = NavigationAttr::new(relation_kind);
 	base.explicit_target = explicit_target;
 	base.fk = fk;
@@ -950,6 +1395,7 @@ fn parse_rel_navigation(list: &syn::MetaList) -> syn::Result<NavigationAttr> {
 	base.self_fk = self_fk;
 	base.other_fk = other_fk;
 	base.other_pk = other_pk;
+	base.cascade = cascade;
 	Ok(base)
 }
 
@@ -1024,14 +1470,23 @@ fn extract_join_value_inner(ty: &syn::Type) -> Option<JoinValueInner> {
 				if let Some(syn::GenericArgument::Type(inner)) =
 					args.args.first()
 				{
-					return match extract_vec_inner(inner) {
-						Some(vec_inner) => Some(JoinValueInner {
+					if let Some(vec_inner) = extract_vec_inner(inner) {
+						return Some(JoinValueInner {
 							ty:            vec_inner,
 							is_collection: true,
+							is_boxed:      false,
+						});
+					}
+					return match extract_box_inner(inner) {
+						Some(box_inner) => Some(JoinValueInner {
+							ty:            box_inner,
+							is_collection: false,
+							is_boxed:      true,
 						}),
 						None => Some(JoinValueInner {
 							ty:            inner.clone(),
 							is_collection: false,
+							is_boxed:      false,
 						}),
 					};
 				}
@@ -1043,10 +1498,18 @@ fn extract_join_value_inner(ty: &syn::Type) -> Option<JoinValueInner> {
 }
 
 fn extract_vec_inner(ty: &syn::Type) -> Option<syn::Type> {
+	extract_single_generic(ty, "Vec")
+}
+
+fn extract_box_inner(ty: &syn::Type) -> Option<syn::Type> {
+	extract_single_generic(ty, "Box")
+}
+
+fn extract_single_generic(ty: &syn::Type, wrapper: &str) -> Option<syn::Type> {
 	match ty {
 		syn::Type::Path(type_path) => {
 			let segment = type_path.path.segments.last()?;
-			if segment.ident != "Vec" {
+			if segment.ident != wrapper {
 				return None;
 			}
 			if let syn::PathArguments::AngleBracketed(args) = &segment.arguments
@@ -1086,13 +1549,15 @@ fn extract_join_target_type(ty: &syn::Type) -> Option<syn::Type> {
 	}
 }
 
-fn derive_join_path_type(ty: &syn::Type) -> syn::Type {
+/// The type the WebQuery derive generated for the DTO `ty` under the name
+/// `{Dto}{suffix}`, e.g. its `JoinPath`.
+fn derive_join_child_type(ty: &syn::Type, suffix: &str) -> syn::Type {
 	match ty {
 		syn::Type::Path(type_path) => {
 			let mut path = type_path.path.clone();
 			if let Some(last) = path.segments.last_mut() {
 				let new_ident =
-					format_ident!("{}JoinPath", last.ident.to_string());
+					format_ident!("{}{}", last.ident.to_string(), suffix);
 				last.ident = new_ident;
 				syn::Type::Path(syn::TypePath {
 					qself: type_path.qself.clone(),
@@ -1106,6 +1571,133 @@ fn derive_join_path_type(ty: &syn::Type) -> syn::Type {
 	}
 }
 
+/// Key type, key accessor and the `write_key_in`/`write_key_position` bodies
+/// of the generated `PrimaryKey` impl. Models without `#[primary_key]` get
+/// `()` keys that can't be written, and no `HasPrimaryKey` impl.
+fn primary_key_methods(
+	table_name: &str,
+	struct_ident: &Ident,
+	pk_field_specs: &[(Ident, syn::Type)],
+) -> (
+	proc_macro2::TokenStream,
+	proc_macro2::TokenStream,
+	proc_macro2::TokenStream,
+	proc_macro2::TokenStream,
+) {
+	if pk_field_specs.is_empty() {
+		let message = format!("`{struct_ident}` has no #[primary_key]");
+		return (
+			quote! { () },
+			quote! { () },
+			quote! { let _ = (keys, w); panic!(#message) },
+			quote! { let _ = (keys, w); panic!(#message) },
+		);
+	}
+
+	let qualified: Vec<String> = pk_field_specs
+		.iter()
+		.map(|(ident, _)| {
+			format!(
+				r#""{}"."{}""#,
+				table_name,
+				ident.to_string().to_snake_case()
+			)
+		})
+		.collect();
+	let key_idents: Vec<Ident> = (0..pk_field_specs.len())
+		.map(|idx| format_ident!("key_{}", idx))
+		.collect();
+	let idents: Vec<_> =
+		pk_field_specs.iter().map(|(ident, _)| ident).collect();
+	let types: Vec<_> = pk_field_specs.iter().map(|(_, ty)| ty).collect();
+
+	let (key_type, key_expr, destructure, columns) =
+		if pk_field_specs.len() == 1 {
+			let ident = idents[0];
+			let ty = types[0];
+			let key = &key_idents[0];
+			(
+				quote! { #ty },
+				quote! { self.#ident.clone() },
+				quote! { let #key = key.clone(); },
+				qualified[0].clone(),
+			)
+		} else {
+			(
+				quote! { ( #( #types ),* ) },
+				quote! { ( #( self.#idents.clone() ),* ) },
+				quote! { let ( #( #key_idents ),* ) = key.clone(); },
+				format!("({})", qualified.join(", ")),
+			)
+		};
+
+	// Every key column is bound once as an array, so the bind count does
+	// not grow with the number of keys.
+	let arrays: Vec<Ident> = (0..pk_field_specs.len())
+		.map(|idx| format_ident!("keys_{}", idx))
+		.collect();
+	let collect_arrays = quote! {
+		#( let mut #arrays: ::std::vec::Vec<#types> =
+			::std::vec::Vec::with_capacity(keys.len()); )*
+		for key in keys {
+			#destructure
+			#( #arrays.push(#key_idents); )*
+		}
+	};
+	let bind_arrays = arrays.iter().enumerate().map(|(idx, array)| {
+		let sep = if idx > 0 {
+			quote! { w.push(", "); }
+		} else {
+			quote! {}
+		};
+		quote! { #sep w.bind(#array); }
+	});
+	let bind_arrays = quote! { #(#bind_arrays)* };
+
+	let (in_head, in_tail) = if pk_field_specs.len() == 1 {
+		(format!("{columns} = ANY("), ")")
+	} else {
+		(format!("{columns} IN (SELECT * FROM UNNEST("), "))")
+	};
+	let write_key_in = quote! {
+		if keys.is_empty() {
+			w.push("FALSE");
+			return;
+		}
+		#collect_arrays
+		w.push(#in_head);
+		#bind_arrays
+		w.push(#in_tail);
+	};
+
+	let (position_head, position_tail) = if pk_field_specs.len() == 1 {
+		("array_position(".to_string(), format!(", {columns})"))
+	} else {
+		let key_columns: Vec<String> = (0..pk_field_specs.len())
+			.map(|idx| format!(r#""__sqlxo_key"."key_{idx}""#))
+			.collect();
+		let aliases: Vec<String> = (0..pk_field_specs.len())
+			.map(|idx| format!(r#""key_{idx}""#))
+			.collect();
+		(
+			r#"(SELECT "__sqlxo_key"."position" FROM UNNEST("#.to_string(),
+			format!(
+				r#") WITH ORDINALITY AS "__sqlxo_key"({}, "position") WHERE {columns} = ({}))"#,
+				aliases.join(", "),
+				key_columns.join(", "),
+			),
+		)
+	};
+	let write_key_position = quote! {
+		#collect_arrays
+		w.push(#position_head);
+		#bind_arrays
+		w.push(#position_tail);
+	};
+
+	(key_type, key_expr, write_key_in, write_key_position)
+}
+
 #[proc_macro_derive(Query, attributes(sqlxo, primary_key, foreign_key))]
 pub fn derive_query(input: TokenStream) -> TokenStream {
 	let input = parse_macro_input!(input as DeriveInput);
@@ -1120,11 +1712,14 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 		.into();
 	}
 
-	let table_name = match extract_table_name(&input) {
-		Ok(Some(v)) => v,
-		Ok(None) => input.ident.to_string().to_snake_case(),
+	let container = match extract_container_attrs(&input) {
+		Ok(v) => v,
 		Err(e) => return e.to_compile_error().into(),
 	};
+	let table_name = container
+		.table_name
+		.clone()
+		.unwrap_or_else(|| input.ident.to_string().to_snake_case());
 	let table_name_lit =
 		syn::LitStr::new(&table_name, proc_macro2::Span::call_site());
 
@@ -1134,6 +1729,7 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 	let sort_ident = format_ident!("{}Sort", struct_ident);
 	let column_mod_ident = format_ident!("{}Column", struct_ident);
 	let agg_struct_ident = format_ident!("{}Agg", struct_ident);
+	let window_struct_ident = format_ident!("{}Window", struct_ident);
 
 	let data = match &input.data {
 		Data::Struct(s) => s,
@@ -1155,16 +1751,56 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 				.into();
 		}
 	};
-
-	let mut query_variants = Vec::new();
-	let mut sort_variants = Vec::new();
-	let mut write_arms = Vec::new();
+	let tenant_field = match extract_marker_fields(fields) {
+		Ok(markers) => markers
+			.tenant
+			.map(|f| quote! { Some(#f) })
+			.unwrap_or_else(|| quote! { None }),
+		Err(e) => return e.to_compile_error().into(),
+	};
+	let scope_fns = container.scopes.iter().map(|(name, expr)| {
+		let fn_ident = format_ident!("scope_{}", name);
+		quote! {
+			pub fn #fn_ident() -> #root::blocks::Expression<#query_ident> {
+				::core::convert::Into::into(#expr)
+			}
+		}
+	});
+	let default_scope_impl = container.default_scope.as_ref().map(|expr| {
+		quote! {
+			impl #root::blocks::DefaultScope for #query_ident {
+				fn default_scope() -> #root::blocks::Expression<Self> {
+					::core::convert::Into::into(#expr)
+				}
+			}
+
+			impl #root::DefaultScoped for #struct_ident {
+				fn write_default_scope(
+					w: &mut sqlx::QueryBuilder<'static, sqlx::Postgres>,
+				) {
+					<#query_ident as #root::blocks::DefaultScope>::default_scope()
+						.write(w);
+				}
+			}
+		}
+	});
+	let audited = container.audited;
+	let scope_impls = quote! {
+		impl #struct_ident {
+			#(#scope_fns)*
+		}
+
+		#default_scope_impl
+	};
+
+	let mut query_variants = Vec::new();
+	let mut sort_variants = Vec::new();
+	let mut write_arms = Vec::new();
 	let mut sort_sql_arms = Vec::new();
 	let mut column_structs = Vec::new();
 	let mut column_type_aliases = Vec::new();
 
 	let mut pk_field: Option<String> = None;
-	let mut pk_field_ident: Option<Ident> = None;
 	let mut pk_field_ty: Option<syn::Type> = None;
 	let mut pk_field_specs: Vec<(Ident, syn::Type)> = Vec::new();
 	let mut pk_columns: Vec<String> = Vec::new();
@@ -1175,6 +1811,7 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 	let mut collection_navigation_fields: Vec<NavigationFieldSpec> = Vec::new();
 	let mut db_fields: Vec<DbFieldSpec> = Vec::new();
 	let mut skip_fields: Vec<SkipFieldSpec> = Vec::new();
+	let mut count_fields: Vec<CountFieldSpec> = Vec::new();
 
 	for field in fields.iter() {
 		let field_ident = field.ident.clone().unwrap();
@@ -1190,6 +1827,49 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 			Ok(val) => val,
 			Err(e) => return e.to_compile_error().into(),
 		};
+		let count_of = match extract_count_of(field) {
+			Ok(val) => val,
+			Err(e) => return e.to_compile_error().into(),
+		};
+
+		if let Some(relation) = count_of {
+			if navigation_attr.is_some() {
+				return Error::new_spanned(
+					field,
+					"`count_of` cannot be combined with a navigation attribute",
+				)
+				.to_compile_error()
+				.into();
+			}
+			if !is_sqlx_skip {
+				return Error::new_spanned(
+					field,
+					"count_of fields must be marked with #[sqlx(skip)]",
+				)
+				.to_compile_error()
+				.into();
+			}
+			if extract_join_value_inner(ty)
+				.is_none_or(|inner| inner.is_collection || inner.is_boxed)
+			{
+				return Error::new_spanned(
+					ty,
+					"count_of fields must use JoinValue<i64>",
+				)
+				.to_compile_error()
+				.into();
+			}
+
+			count_fields.push(CountFieldSpec {
+				field_ident: field_ident.clone(),
+				relation,
+			});
+			skip_fields.push(SkipFieldSpec {
+				field_ident: field_ident.clone(),
+			});
+
+			continue;
+		}
 
 		if let Some(mut attr) = navigation_attr {
 			if !is_sqlx_skip {
@@ -1257,6 +1937,7 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 				ty: inner.ty,
 				attr,
 				_is_collection: inner.is_collection,
+				is_boxed: inner.is_boxed,
 			});
 
 			skip_fields.push(SkipFieldSpec {
@@ -1298,104 +1979,28 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 
 		for attr in &field.attrs {
 			if attr.path.is_ident("primary_key") {
-				if pk_field.is_some() {
+				if pk_columns.contains(&field_name_snake) {
 					return Error::new(attr.span(), "duplicate #[primary_key]")
 						.to_compile_error()
 						.into();
 				}
-				pk_field = Some(field_name_snake.clone());
-				pk_field_ident = Some(field_ident.clone());
-				pk_field_ty = Some(ty.clone());
+				if pk_field.is_none() {
+					pk_field = Some(field_name_snake.clone());
+					pk_field_ty = Some(ty.clone());
+				}
 				pk_field_specs.push((field_ident.clone(), ty.clone()));
 				pk_columns.push(field_name_snake.clone());
 			}
 
 			if attr.path.is_ident("foreign_key") {
-				let meta = attr
-					.parse_meta()
-					.map_err(|_| {
-						Error::new_spanned(attr, "invalid #[foreign_key]")
-					})
-					.unwrap();
-
-				let list = match meta {
-					Meta::List(list) => list,
-					_ => {
-						return Error::new_spanned(
-							attr,
-							r#"expected #[foreign_key(to = "table.pk")]"#,
-						)
-						.to_compile_error()
-						.into()
-					}
+				let fk_attr = match parse_foreign_key_attr(attr) {
+					Ok(fk_attr) => fk_attr,
+					Err(e) => return e.to_compile_error().into(),
 				};
 
-				let mut to_value: Option<String> = None;
-
-				for nested in list.nested {
-					match nested {
-						NestedMeta::Meta(Meta::NameValue(nv))
-							if nv.path.is_ident("to") =>
-						{
-							match nv.lit {
-								Lit::Str(ref s) => {
-									if to_value.is_some() {
-										return Error::new_spanned(
-											nv,
-											"duplicate key `to`",
-										)
-										.to_compile_error()
-										.into();
-									}
-									to_value = Some(s.value());
-								}
-								other => {
-									return Error::new_spanned(
-										other,
-										r#"expected "table.pk""#,
-									)
-									.to_compile_error()
-									.into();
-								}
-							}
-						}
-						other => {
-							return Error::new_spanned(
-								other,
-								r#"unknown key; expected `to = "table.pk"` or `cascade_type(...)`"#,
-							)
-							.to_compile_error()
-							.into();
-						}
-					}
-				}
-
-				let to = to_value.expect(r#"missing `to = "table.pk"`"#);
-
-				let mut parts = to.split('.');
-				let right_table = parts
-					.next()
-					.ok_or_else(|| Error::new(attr.span(), "missing table"))
-					.unwrap()
-					.to_string();
-				let right_pk = parts
-					.next()
-					.ok_or_else(|| Error::new(attr.span(), "missing pk"))
-					.unwrap()
-					.to_string();
-
-				if parts.next().is_some() {
-					return Error::new(
-						attr.span(),
-						r#"invalid `to` — expected "table.pk""#,
-					)
-					.to_compile_error()
-					.into();
-				}
-
 				let alias_segment = derive_alias_segment(&field_name_snake);
 				let friendly_name = derive_join_label(&field_name_snake);
-				let right_pascal = right_table.to_pascal_case();
+				let right_pascal = fk_attr.right_table.to_pascal_case();
 				let variant_ident = format_ident!(
 					"{}To{}By{}",
 					struct_ident,
@@ -1403,10 +2008,18 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 					field_name_pascal
 				);
 
+				let mut left_fields = vec![field_name_snake.clone()];
+				let mut right_fields = vec![fk_attr.right_pk];
+				for (local, remote) in fk_attr.extra_pairs {
+					left_fields.push(local);
+					right_fields.push(remote);
+				}
+
 				fks.push(FkSpec {
 					fk_field_snake: field_name_snake.clone(),
-					right_table,
-					right_pk,
+					left_fields,
+					right_table: fk_attr.right_table,
+					right_fields,
 					alias_segment,
 					variant_ident,
 					friendly_name,
@@ -1689,6 +2302,7 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 					field_ident:     pending.field_ident.clone(),
 					join_identifier: fk.friendly_name.clone(),
 					related_ty:      pending.ty.clone(),
+					is_boxed:        pending.is_boxed,
 				});
 			}
 			RelationKind::HasOne => {
@@ -1704,17 +2318,14 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 						.into()
 					}
 				};
-				let pk_field_name = match pk_columns.first().cloned() {
-					Some(name) => name,
-					None => {
-						return Error::new(
-							struct_ident.span(),
-							"has_one navigation requires a primary key field",
-						)
-						.to_compile_error()
-						.into()
-					}
-				};
+				if pk_columns.is_empty() {
+					return Error::new(
+						struct_ident.span(),
+						"has_one navigation requires a primary key field",
+					)
+					.to_compile_error()
+					.into();
+				}
 				let right_table = match type_to_table_name(&target_ty) {
 					Ok(name) => name,
 					Err(err) => return err.to_compile_error().into(),
@@ -1754,13 +2365,28 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 					}
 				};
 
+				let right_fields = split_column_list(&right_field);
+				if right_fields.len() != pk_columns.len() {
+					return Error::new(
+						pending.field_ident.span(),
+						format!(
+							"`fk` lists {} column(s) but the primary key has \
+							 {}",
+							right_fields.len(),
+							pk_columns.len()
+						),
+					)
+					.to_compile_error()
+					.into();
+				}
+
 				manual_join_specs.push(ManualJoinSpec {
 					variant_ident,
 					friendly_name: friendly_name.clone(),
 					alias_segment,
-					left_field: pk_field_name.clone(),
+					left_fields: pk_columns.clone(),
 					right_table,
-					right_field,
+					right_fields,
 					related_ty: Some(pending.ty.clone()),
 					through: None,
 				});
@@ -1769,6 +2395,7 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 					field_ident:     pending.field_ident.clone(),
 					join_identifier: friendly_name,
 					related_ty:      pending.ty.clone(),
+					is_boxed:        pending.is_boxed,
 				});
 			}
 			RelationKind::HasMany => {
@@ -1784,17 +2411,14 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 						.into()
 					}
 				};
-				let pk_field_name = match pk_columns.first().cloned() {
-					Some(name) => name,
-					None => {
-						return Error::new(
-							struct_ident.span(),
-							"has_many navigation requires a primary key field",
-						)
-						.to_compile_error()
-						.into()
-					}
-				};
+				if pk_columns.is_empty() {
+					return Error::new(
+						struct_ident.span(),
+						"has_many navigation requires a primary key field",
+					)
+					.to_compile_error()
+					.into();
+				}
 				let right_table = match type_to_table_name(&target_ty) {
 					Ok(name) => name,
 					Err(err) => return err.to_compile_error().into(),
@@ -1834,13 +2458,28 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 					}
 				};
 
+				let right_fields = split_column_list(&right_field);
+				if right_fields.len() != pk_columns.len() {
+					return Error::new(
+						pending.field_ident.span(),
+						format!(
+							"`fk` lists {} column(s) but the primary key has \
+							 {}",
+							right_fields.len(),
+							pk_columns.len()
+						),
+					)
+					.to_compile_error()
+					.into();
+				}
+
 				manual_join_specs.push(ManualJoinSpec {
 					variant_ident,
 					friendly_name: friendly_name.clone(),
 					alias_segment,
-					left_field: pk_field_name.clone(),
+					left_fields: pk_columns.clone(),
 					right_table,
-					right_field,
+					right_fields,
 					related_ty: Some(pending.ty.clone()),
 					through: None,
 				});
@@ -1849,6 +2488,7 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 					field_ident:     pending.field_ident.clone(),
 					join_identifier: friendly_name,
 					related_ty:      pending.ty.clone(),
+					is_boxed:        pending.is_boxed,
 				});
 			}
 			RelationKind::ManyToMany => {
@@ -1876,18 +2516,14 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 						.into()
 					}
 				};
-				let pk_field_name = match pk_columns.first().cloned() {
-					Some(name) => name,
-					None => {
-						return Error::new(
-							struct_ident.span(),
-							"many_to_many navigation requires a primary key \
-							 field",
-						)
-						.to_compile_error()
-						.into()
-					}
-				};
+				if pk_columns.is_empty() {
+					return Error::new(
+						struct_ident.span(),
+						"many_to_many navigation requires a primary key field",
+					)
+					.to_compile_error()
+					.into();
+				}
 				let right_table = match type_to_table_name(&target_ty) {
 					Ok(name) => name,
 					Err(err) => return err.to_compile_error().into(),
@@ -1943,11 +2579,36 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 						.into()
 					}
 				};
-				let right_field = pending
+				let self_fks = split_column_list(&self_fk);
+				if self_fks.len() != pk_columns.len() {
+					return Error::new(
+						pending.field_ident.span(),
+						format!(
+							"`self_fk` lists {} column(s) but the primary key \
+							 has {}",
+							self_fks.len(),
+							pk_columns.len()
+						),
+					)
+					.to_compile_error()
+					.into();
+				}
+				let other_fks = split_column_list(&other_fk);
+				let right_fields = pending
 					.attr
 					.other_pk
-					.clone()
-					.unwrap_or_else(|| "id".to_string());
+					.as_deref()
+					.map(split_column_list)
+					.unwrap_or_else(|| vec!["id".to_string()]);
+				if other_fks.len() != right_fields.len() {
+					return Error::new(
+						pending.field_ident.span(),
+						"`other_fk` and `other_pk` must list the same number \
+						 of columns",
+					)
+					.to_compile_error()
+					.into();
+				}
 
 				let through_alias = format!("{}__pivot__", pending.field_name);
 
@@ -1955,15 +2616,16 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 					variant_ident,
 					friendly_name: friendly_name.clone(),
 					alias_segment,
-					left_field: other_fk,
+					left_fields: other_fks,
 					right_table,
-					right_field,
+					right_fields,
 					related_ty: Some(pending.ty.clone()),
 					through: Some(ThroughSpec {
+						ty:            through_ty,
 						table:         through_table,
 						alias_segment: through_alias,
-						left_field:    pk_field_name.clone(),
-						right_field:   self_fk,
+						left_fields:   pk_columns.clone(),
+						right_fields:  self_fks,
 					}),
 				});
 
@@ -1971,6 +2633,7 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 					field_ident:     pending.field_ident.clone(),
 					join_identifier: friendly_name,
 					related_ty:      pending.ty.clone(),
+					is_boxed:        pending.is_boxed,
 				});
 			}
 		}
@@ -2016,15 +2679,15 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 	if pk_columns.is_empty() {
 		pk_columns.push(presence_field_name.clone());
 	}
-	if !collection_navigation_fields.is_empty() && pk_field_ident.is_none() {
+	if container.audited && pk_field_specs.is_empty() {
 		return Error::new(
 			struct_ident.span(),
-			"collection navigation requires #[primary_key]",
+			"#[sqlxo(audited)] requires #[primary_key]",
 		)
 		.to_compile_error()
 		.into();
 	}
-	if !collection_navigation_fields.is_empty() && pk_field_ty.is_none() {
+	if !collection_navigation_fields.is_empty() && pk_field_specs.is_empty() {
 		return Error::new(
 			struct_ident.span(),
 			"collection navigation requires #[primary_key]",
@@ -2032,10 +2695,34 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 		.to_compile_error()
 		.into();
 	}
+	for count in &count_fields {
+		if !collection_navigation_fields
+			.iter()
+			.any(|nav| nav.join_identifier == count.relation)
+		{
+			return Error::new(
+				count.field_ident.span(),
+				format!(
+					"`count_of = \"{}\"` must name a has_many or many_to_many \
+					 navigation",
+					count.relation
+				),
+			)
+			.to_compile_error()
+			.into();
+		}
+	}
 	let pk_column_lits: Vec<_> = pk_columns
 		.iter()
 		.map(|col| syn::LitStr::new(col, proc_macro2::Span::call_site()))
 		.collect();
+	let (pk_key_type, pk_key_expr, write_key_in, write_key_position) =
+		primary_key_methods(&table_name, struct_ident, &pk_field_specs);
+	let has_primary_key_impl = if pk_field_specs.is_empty() {
+		quote! {}
+	} else {
+		quote! { impl #root::HasPrimaryKey for #struct_ident {} }
+	};
 	let (join_key_type, join_key_expr) = if !pk_field_specs.is_empty() {
 		if pk_field_specs.len() == 1 {
 			let (ident, ty) = &pk_field_specs[0];
@@ -2259,6 +2946,11 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 				);
 				let related_ty = &nav.related_ty;
 				let field_ident = &nav.field_ident;
+				let loaded = if nav.is_boxed {
+					quote! { Box::new(v) }
+				} else {
+					quote! { v }
+				};
 				quote! {
 					#identifier => {
 						if #flag {
@@ -2278,7 +2970,7 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 										alias.as_str(),
 									)?;
 								}
-								#root::JoinValue::Loaded(v)
+								#root::JoinValue::Loaded(#loaded)
 							},
 							None => #root::JoinValue::Missing,
 						};
@@ -2327,6 +3019,23 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 			}
 		})
 		.collect();
+	let hydrate_count_fields: Vec<proc_macro2::TokenStream> = count_fields
+		.iter()
+		.map(|count| {
+			let field_ident = &count.field_ident;
+			let column = syn::LitStr::new(
+				&format!("__sqlxo_count_{}", count.relation),
+				proc_macro2::Span::call_site(),
+			);
+			quote! {
+				match sqlx::Row::try_get::<i64, _>(row, #column) {
+					Ok(count) => self.#field_ident = #root::JoinValue::Loaded(count),
+					Err(sqlx::Error::ColumnNotFound(_)) => {}
+					Err(e) => return Err(e),
+				}
+			}
+		})
+		.collect();
 	let nav_flag_collect_defs = nav_flags.clone();
 	let nav_flag_hydrate_defs = nav_flags.clone();
 	let collection_nav_flag_collect_defs = collection_nav_flags.clone();
@@ -2347,8 +3056,6 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 			}
 		}
 	} else {
-		let pk_ident = pk_field_ident.clone().unwrap();
-		let pk_ty = pk_field_ty.clone().unwrap();
 		let collection_identifier_match = &collection_identifier_match;
 		let merge_collection_flag_match = &merge_collection_flag_match;
 		let merge_collection_apply = &merge_collection_apply;
@@ -2400,11 +3107,12 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 
 				let mut merged: ::std::vec::Vec<Self> =
 					::std::vec::Vec::new();
-				let mut keys: ::std::vec::Vec<#pk_ty> =
-					::std::vec::Vec::new();
+				let mut keys: ::std::vec::Vec<
+					<Self as #root::JoinIdentifiable>::Key,
+				> = ::std::vec::Vec::new();
 
 				for mut row in rows {
-					let key = row.#pk_ident.clone();
+					let key = #root::JoinIdentifiable::join_key(&row);
 					if let Some(idx) = keys.iter().position(|existing| existing == &key) {
 						let existing = &mut merged[idx];
 						#(#merge_collection_apply)*
@@ -2502,27 +3210,66 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 			type Query = #query_ident;
 			type Sort  = #sort_ident;
 			type Join  = #join_ident;
+
+			fn primary_key_in(
+				keys: ::std::vec::Vec<<#struct_ident as #root::PrimaryKey>::Key>,
+			) -> Self::Query {
+				#query_ident::PrimaryKeyIn(keys)
+			}
 		}
 
 		impl #root::PrimaryKey for #struct_ident {
 			const PRIMARY_KEY: &'static [&'static str] =
 				&[#(#pk_column_lits),*];
+
+			type Key = #pk_key_type;
+
+			fn primary_key(&self) -> Self::Key {
+				#pk_key_expr
+			}
+
+			fn write_key_in<W: #root::SqlWrite>(keys: &[Self::Key], w: &mut W) {
+				#write_key_in
+			}
+
+			fn write_key_position<W: #root::SqlWrite>(
+				keys: &[Self::Key],
+				w: &mut W,
+			) {
+				#write_key_position
+			}
+		}
+
+		#has_primary_key_impl
+
+		impl #root::TenantScoped for #struct_ident {
+			const TENANT_FIELD: Option<&'static str> = #tenant_field;
 		}
 
+		impl #root::Audited for #struct_ident {
+			const AUDITED: bool = #audited;
+		}
+
+		#scope_impls
+
 
 		#[derive(Debug, Clone, PartialEq)]
 		pub enum #query_ident {
-			#(#query_variants),*
+			#(#query_variants,)*
+			/// Matches rows whose primary key is one of the keys.
+			PrimaryKeyIn(::std::vec::Vec<<#struct_ident as #root::PrimaryKey>::Key>)
 		}
 
 
 		#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+		#[allow(clippy::enum_variant_names)]
 		pub enum #sort_ident {
 			#(#sort_variants),*
 		}
 
 
 		#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+		#[allow(clippy::enum_variant_names)]
 		pub enum #join_ident {
 			#(#join_variants),*
 		}
@@ -2548,6 +3295,18 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 			pub fn inner(self) -> #root::JoinPath {
 				self.path(#root::JoinKind::Inner)
 			}
+
+			pub fn right(self) -> #root::JoinPath {
+				self.path(#root::JoinKind::Right)
+			}
+
+			pub fn full(self) -> #root::JoinPath {
+				self.path(#root::JoinKind::Full)
+			}
+
+			pub fn cross(self) -> #root::JoinPath {
+				self.path(#root::JoinKind::Cross)
+			}
 		}
 
 
@@ -2556,7 +3315,10 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 
 			fn write<W: #root::SqlWrite>(&self, w: &mut W) {
 				match self {
-					#(#write_arms),*
+					#(#write_arms,)*
+					Self::PrimaryKeyIn(keys) => {
+						<#struct_ident as #root::PrimaryKey>::write_key_in(keys, w)
+					}
 				}
 			}
 		}
@@ -2678,6 +3440,14 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 				Ok(())
 			}
 
+			fn hydrate_counts(
+				&mut self,
+				row: &sqlx::postgres::PgRow,
+			) -> Result<(), sqlx::Error> {
+				#(#hydrate_count_fields)*
+				Ok(())
+			}
+
 			#collection_helper_impl
 		}
 
@@ -2752,21 +3522,137 @@ pub fn derive_query(input: TokenStream) -> TokenStream {
 			{
 				#root::select::MaxExpr::new()
 			}
-		}
-	};
 
-	out.into()
-}
+			pub const fn StringAgg<C>(
+				_: C,
+				separator: &'static str,
+			) -> #root::select::StringAggExpr<C>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::StringAggExpr::new(separator)
+			}
 
-#[proc_macro]
-pub fn context(input: TokenStream) -> TokenStream {
-	let parser = syn::punctuated::Punctuated::<syn::Type, syn::Token![,]>::parse_terminated;
-	let args = parse_macro_input!(input with parser);
-	let root = sqlxo_root();
+			pub const fn ArrayAgg<C>(_: C) -> #root::select::ArrayAggExpr<C>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::ArrayAggExpr::new()
+			}
 
-	if args.is_empty() {
-		return Error::new(
-			proc_macro2::Span::call_site(),
+			pub const fn BoolAnd<C>(_: C) -> #root::select::BoolAndExpr<C>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::BoolAndExpr::new()
+			}
+
+			pub const fn BoolOr<C>(_: C) -> #root::select::BoolOrExpr<C>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::BoolOrExpr::new()
+			}
+
+			pub const fn StdDev<C>(_: C) -> #root::select::StdDevExpr<C>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::StdDevExpr::new()
+			}
+
+			pub const fn Variance<C>(_: C) -> #root::select::VarianceExpr<C>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::VarianceExpr::new()
+			}
+
+			pub fn PercentileCont<C>(
+				_: C,
+				fraction: f64,
+			) -> #root::select::PercentileContExpr<C>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::PercentileContExpr::new(fraction)
+			}
+
+			pub fn Median<C>(_: C) -> #root::select::PercentileContExpr<C>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::PercentileContExpr::median()
+			}
+		}
+
+		pub struct #window_struct_ident;
+
+		impl #window_struct_ident {
+			pub fn RowNumber() -> #root::select::WindowExpr<#struct_ident, i64> {
+				#root::select::WindowExpr::new(#root::select::WindowFunction::RowNumber, None)
+			}
+
+			pub fn Rank() -> #root::select::WindowExpr<#struct_ident, i64> {
+				#root::select::WindowExpr::new(#root::select::WindowFunction::Rank, None)
+			}
+
+			pub fn DenseRank() -> #root::select::WindowExpr<#struct_ident, i64> {
+				#root::select::WindowExpr::new(#root::select::WindowFunction::DenseRank, None)
+			}
+
+			pub fn Lag<C>(
+				_: C,
+				offset: u32,
+			) -> #root::select::WindowExpr<#struct_ident, Option<C::Type>>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::WindowExpr::new(
+					#root::select::WindowFunction::Lag(offset),
+					Some(#root::select::SelectionColumn::new(C::TABLE, C::NAME)),
+				)
+			}
+
+			pub fn Lead<C>(
+				_: C,
+				offset: u32,
+			) -> #root::select::WindowExpr<#struct_ident, Option<C::Type>>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::WindowExpr::new(
+					#root::select::WindowFunction::Lead(offset),
+					Some(#root::select::SelectionColumn::new(C::TABLE, C::NAME)),
+				)
+			}
+
+			pub fn RunningSum<C>(
+				_: C,
+			) -> #root::select::WindowExpr<#struct_ident, Option<C::Type>>
+			where
+				C: #root::select::Column<Model = #struct_ident>,
+			{
+				#root::select::WindowExpr::new(
+					#root::select::WindowFunction::RunningSum,
+					Some(#root::select::SelectionColumn::new(C::TABLE, C::NAME)),
+				)
+			}
+		}
+	};
+
+	out.into()
+}
+
+#[proc_macro]
+pub fn context(input: TokenStream) -> TokenStream {
+	let parser = syn::punctuated::Punctuated::<syn::Type, syn::Token![,]>::parse_terminated;
+	let args = parse_macro_input!(input with parser);
+	let root = sqlxo_root();
+
+	if args.is_empty() {
+		return Error::new(
+			proc_macro2::Span::call_site(),
 			"context!(T1, [T2, ...]) requires at least one type",
 		)
 		.to_compile_error()
@@ -2804,6 +3690,12 @@ pub fn context(input: TokenStream) -> TokenStream {
 				type Query = <#base_ty as #root::QueryContext>::Query;
 				type Sort  = <#base_ty as #root::QueryContext>::Sort;
 				type Join  = <#base_ty as #root::QueryContext>::Join;
+
+				fn primary_key_in(
+					keys: ::std::vec::Vec<<Self::Model as #root::PrimaryKey>::Key>,
+				) -> Self::Query {
+					<#base_ty as #root::QueryContext>::primary_key_in(keys)
+				}
 			}
 
 			pub type Where = <#base_ty as #root::QueryContext>::Query;
@@ -2866,6 +3758,7 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 	let leaf_ident = format_ident!("{}Leaf", struct_ident);
 	let sort_field_ident = format_ident!("{}SortField", struct_ident);
 	let join_path_ident = format_ident!("{}JoinPath", struct_ident);
+	let join_filter_ident = format_ident!("{}JoinFilter", struct_ident);
 
 	let mut op_defs = Vec::new();
 	let mut leaf_structs = Vec::new();
@@ -2876,9 +3769,22 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 	let mut agg_extra_structs = Vec::new();
 	let mut agg_leaf_variants = Vec::new();
 	let mut join_field_infos = Vec::new();
+	let mut group_field_variants = Vec::new();
+	let mut group_field_name_arms = Vec::new();
+	let mut agg_field_variants = Vec::new();
+	let mut agg_field_name_arms = Vec::new();
+	let mut agg_field_source_arms = Vec::new();
+	let mut agg_leaf_field_arms = Vec::new();
+	let mut leaf_field_arms = Vec::new();
+	let mut sort_field_arms = Vec::new();
+	let mut agg_row_fields = Vec::new();
+	let mut field_names = Vec::new();
 	let agg_leaf_ident = format_ident!("{}AggregateLeaf", struct_ident);
 	let agg_count_struct = format_ident!("{}AggregateCount", struct_ident);
 	let agg_count_op_ident = format_ident!("{}AggregateCountOp", struct_ident);
+	let group_field_ident = format_ident!("{}GroupField", struct_ident);
+	let agg_field_ident = format_ident!("{}AggregateField", struct_ident);
+	let agg_row_ident = format_ident!("{}AggregateRow", struct_ident);
 	let count_field_name = rename_rule.apply("count");
 	let count_field_lit =
 		syn::LitStr::new(&count_field_name, proc_macro2::Span::call_site());
@@ -2895,6 +3801,7 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 		let field_json_lit =
 			syn::LitStr::new(&field_json_name, proc_macro2::Span::call_site());
 		let ty = &f.ty;
+		field_names.push(field_json_lit.clone());
 
 		let mut webquery_ignore = false;
 		let mut bool_field: Option<String> = None;
@@ -2969,7 +3876,9 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 			let friendly_lit =
 				syn::LitStr::new(&join_label, proc_macro2::Span::call_site());
 			let field_ident = fname_ident.clone();
-			let child_join_path_ty = derive_join_path_type(&join_ty);
+			let child_join_path_ty =
+				derive_join_child_type(&join_ty, "JoinPath");
+			let child_leaf_ty = derive_join_child_type(&join_ty, "Leaf");
 
 			join_field_infos.push(JoinFieldInfo {
 				variant_ident,
@@ -2978,6 +3887,7 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 				json_name: field_json_lit.clone(),
 				friendly_name: friendly_lit,
 				child_join_path_ty,
+				child_leaf_ty,
 			});
 
 			continue;
@@ -3107,6 +4017,9 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 		leaf_variants.push(quote! {
 			#leaf_variant_ident(#leaf_wrap_ident)
 		});
+		leaf_field_arms.push(quote! {
+			#leaf_ident::#leaf_variant_ident(_) => #field_json_lit
+		});
 
 		sort_structs.push(quote! {
             #[derive(Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema, Debug)]
@@ -3119,8 +4032,24 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 		sort_variants.push(quote! {
 			#sort_variant_ident(#sort_wrap_ident)
 		});
+		sort_field_arms.push(quote! {
+			#sort_field_ident::#sort_variant_ident(_) => #field_json_lit
+		});
+
+		group_field_variants.push(quote! {
+			#[serde(rename = #field_json_lit)]
+			#leaf_variant_ident
+		});
+		group_field_name_arms.push(quote! {
+			Self::#leaf_variant_ident => #field_json_lit
+		});
+		agg_row_fields.push(quote! {
+			#[serde(rename = #field_json_lit, default, skip_serializing_if = "Option::is_none")]
+			pub #fname_ident: Option<#ty>,
+		});
 
 		let field_json_name_clone = field_json_name.clone();
+		let field_json_lit_clone = field_json_lit.clone();
 		let fname_ident_clone = fname_ident.clone();
 		let mut add_aggregate =
 			|suffix_pascal: &str, suffix_snake: &str, value_ty: syn::Type| {
@@ -3171,6 +4100,25 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 				agg_leaf_variants.push(quote! {
 					#variant_ident(#wrap_ident)
 				});
+				agg_leaf_field_arms.push(quote! {
+					#agg_leaf_ident::#variant_ident(_) => Some(#field_json_lit_clone)
+				});
+
+				agg_field_variants.push(quote! {
+					#[serde(rename = #serde_lit)]
+					#variant_ident
+				});
+				agg_field_name_arms.push(quote! {
+					Self::#variant_ident => #serde_lit
+				});
+				agg_field_source_arms.push(quote! {
+					Self::#variant_ident => Some(#field_json_lit_clone)
+				});
+				let row_ty = aggregate_row_type(suffix_pascal, value_ty);
+				agg_row_fields.push(quote! {
+					#[serde(rename = #serde_lit, default, skip_serializing_if = "Option::is_none")]
+					pub #field_ident: Option<#row_ty>,
+				});
 			};
 
 		match classify_type(ty) {
@@ -3184,6 +4132,9 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 					"count_distinct",
 					parse_quote!(i64),
 				);
+				add_aggregate("StdDev", "std_dev", parse_quote!(f64));
+				add_aggregate("Variance", "variance", parse_quote!(f64));
+				add_aggregate("Median", "median", parse_quote!(f64));
 			}
 			Kind::DateTime | Kind::Date | Kind::Time => {
 				add_aggregate("Min", "min", ty.clone());
@@ -3196,7 +4147,10 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 					parse_quote!(i64),
 				);
 			}
-			Kind::Bool => {}
+			Kind::Bool => {
+				add_aggregate("BoolAnd", "bool_and", parse_quote!(bool));
+				add_aggregate("BoolOr", "bool_or", parse_quote!(bool));
+			}
 		}
 	}
 
@@ -3284,6 +4238,17 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 		)
 	};
 
+	let join_filter_fields = join_field_infos.iter().map(|info| {
+		let field_ident = &info.field_ident;
+		let json_name = &info.json_name;
+		let child_leaf_ty = &info.child_leaf_ty;
+		quote! {
+			#[serde(rename = #json_name, default, skip_serializing_if = "Option::is_none")]
+			#[schema(no_recursion, nullable)]
+			pub #field_ident: Option<#root::web::GenericWebExpression<#child_leaf_ty>>,
+		}
+	});
+
 	let out = quote! {
 
 		#(#op_defs)*
@@ -3336,14 +4301,98 @@ pub fn derive_webquery(input: TokenStream) -> TokenStream {
 			#(#sort_variants),*
 		}
 
+		/// Fields a grouped read may group by.
+		#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, utoipa::ToSchema, Debug, PartialEq, Eq)]
+		pub enum #group_field_ident {
+			#(#group_field_variants),*
+		}
+
+		impl #root::WebSelectField for #group_field_ident {
+			fn web_name(&self) -> &'static str {
+				match self {
+					#(#group_field_name_arms),*
+				}
+			}
+
+			fn source_field(&self) -> Option<&'static str> {
+				Some(self.web_name())
+			}
+		}
+
+		/// Aggregates a grouped read may select.
+		#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, utoipa::ToSchema, Debug, PartialEq, Eq)]
+		pub enum #agg_field_ident {
+			#[serde(rename = #count_field_lit)]
+			Count,
+			#(#agg_field_variants),*
+		}
+
+		impl #root::WebSelectField for #agg_field_ident {
+			fn web_name(&self) -> &'static str {
+				match self {
+					Self::Count => #count_field_lit,
+					#(#agg_field_name_arms),*
+				}
+			}
+
+			fn source_field(&self) -> Option<&'static str> {
+				match self {
+					Self::Count => None,
+					#(#agg_field_source_arms),*
+				}
+			}
+		}
+
+		/// Row of a grouped read. Only the requested keys are present.
+		#[derive(Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema, Debug, Default)]
+		pub struct #agg_row_ident {
+			#(#agg_row_fields)*
+			#[serde(rename = #count_field_lit, default, skip_serializing_if = "Option::is_none")]
+			pub count: Option<i64>,
+		}
+
 
 	#join_type_tokens
 
+	/// `joinFilter` of a mutation payload: a filter on the rows of each
+	/// relation.
+	#[derive(Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema, Debug, Default)]
+	#[serde(deny_unknown_fields)]
+	pub struct #join_filter_ident {
+		#(#join_filter_fields)*
+	}
+
 	impl #root::WebQueryModel for #struct_ident {
 		type Leaf      = #leaf_ident;
 		type SortField = #sort_field_ident;
 		type AggregateLeaf = #agg_leaf_ident;
 		type JoinPath = #join_path_ty;
+		type JoinFilter = #join_filter_ident;
+		type GroupField = #group_field_ident;
+		type AggregateField = #agg_field_ident;
+
+		const FIELDS: &'static [&'static str] = &[#(#field_names),*];
+
+		fn leaf_field(leaf: &Self::Leaf) -> &'static str {
+			match *leaf {
+				#(#leaf_field_arms),*
+			}
+		}
+
+		fn sort_field(sort: &Self::SortField) -> &'static str {
+			match *sort {
+				#(#sort_field_arms),*
+			}
+		}
+
+		fn aggregate_leaf_field(
+			leaf: &Self::AggregateLeaf,
+		) -> Option<&'static str> {
+			match *leaf {
+				#agg_leaf_ident::Count(_) => None,
+				#(#agg_leaf_field_arms),*
+			}
+		}
 	}
 
 	};
@@ -3499,10 +4548,19 @@ pub fn derive_create(input: TokenStream) -> TokenStream {
 		}
 	}
 
+	let pk_idents: Vec<_> = fields
+		.iter()
+		.filter_map(|field| field.ident.as_ref())
+		.filter(|ident| pk_fields.contains_key(&ident.to_string()))
+		.collect();
+
 	// Generate create struct fields
 	let mut create_fields = Vec::new();
 	let mut field_names = Vec::new();
 	let mut field_names_snake = Vec::new();
+	let mut nested_fields = Vec::new();
+	let mut nested_targets = Vec::new();
+	let mut nested_inserts = Vec::new();
 
 	for field in fields.iter() {
 		let field_ident = field.ident.as_ref().unwrap();
@@ -3510,6 +4568,87 @@ pub fn derive_create(input: TokenStream) -> TokenStream {
 		let field_name_snake = field_name.to_snake_case();
 		let ty = &field.ty;
 
+		let navigation = match extract_navigation_attr(field) {
+			Ok(nav) => nav,
+			Err(e) => return e.to_compile_error().into(),
+		};
+		let nested_create = match has_nested_create(field) {
+			Ok(nested) => nested,
+			Err(e) => return e.to_compile_error().into(),
+		};
+		if nested_create {
+			let (target, fk) = match navigation {
+				Some(NavigationAttr {
+					kind: RelationKind::HasMany,
+					explicit_target: Some(target),
+					fk: Some(fk),
+					..
+				}) => (target, split_column_list(&fk)),
+				_ => {
+					return Error::new_spanned(
+						field_ident,
+						"#[sqlxo(nested_create)] requires a \
+						 #[sqlxo(rel(has_many = ..., fk = ...))] relation",
+					)
+					.to_compile_error()
+					.into();
+				}
+			};
+			if fk.len() != pk_idents.len() {
+				return Error::new_spanned(
+					field_ident,
+					format!(
+						"`fk` lists {} column(s) but the primary key has {}",
+						fk.len(),
+						pk_idents.len()
+					),
+				)
+				.to_compile_error()
+				.into();
+			}
+			let fk_idents: Vec<_> =
+				fk.iter().map(|col| format_ident!("{}", col)).collect();
+
+			let doc = format!(
+				"Inserted by `fetch_graph` with their `{}` set to the key of \
+				 the new row, whatever value they hold here.",
+				fk.join("`, `"),
+			);
+			create_fields.push(quote! {
+				#[doc = #doc]
+				pub #field_ident:
+					Vec<<#target as #root::Creatable>::CreateModel>
+			});
+			nested_fields.push(field_ident);
+			nested_targets.push(target.clone());
+			nested_inserts.push(quote! {
+				let children: Vec<_> = self
+					.#field_ident
+					.iter()
+					.cloned()
+					.map(|mut child| {
+						#(child.#fk_idents = parent.#pk_idents.clone().into();)*
+						child
+					})
+					.collect();
+				let rows = if children.is_empty() {
+					Vec::new()
+				} else {
+					#root::Buildable::build(
+						#root::QueryBuilder::<#target>::insert().models(children),
+					)
+					.fetch_graph(&mut *conn)
+					.await?
+				};
+				parent.#field_ident = #root::JoinValue::Loaded(rows);
+			});
+			continue;
+		}
+		// Other navigation fields are loaded, never inserted
+		if navigation.is_some() {
+			continue;
+		}
+
 		// Skip generated primary keys
 		if let Some(mode) = pk_fields.get(&field_name) {
 			match mode {
@@ -3523,10 +4662,12 @@ pub fn derive_create(input: TokenStream) -> TokenStream {
 			}
 		}
 
-		// Skip all marker fields
+		// Skip all marker fields; the tenant comes from the plan
 		if Some(&field_name_snake) == markers.delete_marker.as_ref() ||
 			Some(&field_name_snake) == markers.update_marker.as_ref() ||
-			Some(&field_name_snake) == markers.insert_marker.as_ref()
+			Some(&field_name_snake) == markers.insert_marker.as_ref() ||
+			Some(&field_name_snake) == markers.version.as_ref() ||
+			Some(&field_name_snake) == markers.tenant.as_ref()
 		{
 			continue;
 		}
@@ -3542,6 +4683,60 @@ pub fn derive_create(input: TokenStream) -> TokenStream {
 		.insert_marker
 		.map(|f| quote! { Some(#f) })
 		.unwrap_or_else(|| quote! { None });
+	let tenant_column = markers.tenant.iter().collect::<Vec<_>>();
+	let bind_count = field_names.len() + tenant_column.len();
+	let push_tenant = if tenant_column.is_empty() {
+		quote! { let _ = tenant; }
+	} else {
+		quote! {
+			if !first {
+				qb.push(", ");
+			}
+			first = false;
+			// Plans check for a tenant before they run, so only their
+			// `sql()` renders a row without one.
+			match tenant {
+				Some(tenant) => tenant.push_bind(qb),
+				None => {
+					qb.push("NULL");
+				}
+			}
+		}
+	};
+	let nested_methods = if nested_fields.is_empty() {
+		quote! {}
+	} else {
+		quote! {
+			fn has_children(&self) -> bool {
+				#(!self.#nested_fields.is_empty())||*
+			}
+
+			fn child_tables(&self, tables: &mut Vec<&'static str>) {
+				#(
+					if !self.#nested_fields.is_empty() {
+						tables.push(<#nested_targets as #root::QueryContext>::TABLE);
+						if <<#nested_targets as #root::QueryContext>::Model as #root::Audited>::AUDITED {
+							tables.push(#root::audit::AUDIT_TABLE);
+						}
+						for child in &self.#nested_fields {
+							#root::CreateModel::child_tables(child, tables);
+						}
+					}
+				)*
+			}
+
+			fn insert_children<'c>(
+				&'c self,
+				parent: &'c mut Self::Entity,
+				conn: &'c mut sqlx::PgConnection,
+			) -> #root::InsertChildren<'c> {
+				Box::pin(async move {
+					#({ #nested_inserts })*
+					Ok(())
+				})
+			}
+		}
+	};
 
 	let out = quote! {
 		#[derive(Debug, Clone)]
@@ -3557,12 +4752,14 @@ pub fn derive_create(input: TokenStream) -> TokenStream {
 		impl #root::CreateModel for #create_ident {
 			type Entity = #struct_ident;
 
-			fn apply_inserts(
-				&self,
+			const BIND_COUNT: usize = #bind_count;
+
+			#nested_methods
+
+			fn push_columns(
 				qb: &mut sqlx::QueryBuilder<'static, sqlx::Postgres>,
 				insert_marker_field: Option<&'static str>,
 			) {
-				// Build column list
 				qb.push("(");
 				let mut first = true;
 
@@ -3574,6 +4771,14 @@ pub fn derive_create(input: TokenStream) -> TokenStream {
 					qb.push(#field_names_snake);
 				)*
 
+				#(
+					if !first {
+						qb.push(", ");
+					}
+					first = false;
+					qb.push(#tenant_column);
+				)*
+
 				// Add insert marker column if present
 				if let Some(marker) = insert_marker_field {
 					if !first {
@@ -3582,9 +4787,16 @@ pub fn derive_create(input: TokenStream) -> TokenStream {
 					qb.push(marker);
 				}
 
-				qb.push(") VALUES (");
+				qb.push(")");
+			}
 
-				// Build values list with proper bindings
+			fn push_values(
+				&self,
+				qb: &mut sqlx::QueryBuilder<'static, sqlx::Postgres>,
+				insert_marker_field: Option<&'static str>,
+				tenant: Option<&#root::TenantId>,
+			) {
+				qb.push("(");
 				let mut first = true;
 
 				#(
@@ -3595,6 +4807,8 @@ pub fn derive_create(input: TokenStream) -> TokenStream {
 					qb.push_bind(self.#field_names.clone());
 				)*
 
+				#push_tenant
+
 				// Add insert marker value if present
 				if insert_marker_field.is_some() {
 					if !first {
@@ -3634,6 +4848,8 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 	let agg_leaf_ident = format_ident!("{}AggregateLeaf", dto_ident);
 	let agg_count_struct = format_ident!("{}AggregateCount", dto_ident);
 	let agg_count_op_ident = format_ident!("{}AggregateCountOp", dto_ident);
+	let group_field_ident = format_ident!("{}GroupField", dto_ident);
+	let agg_field_ident = format_ident!("{}AggregateField", dto_ident);
 	let entity_struct_ident = match &entity_ty {
 		syn::Type::Path(tp) => tp.path.segments.last().unwrap().ident.clone(),
 		_ => Ident::new("Entity", proc_macro2::Span::call_site()),
@@ -3654,6 +4870,15 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 	let mut leaf_arms = Vec::new();
 	let mut sort_arms = Vec::new();
 	let mut agg_match_arms = Vec::new();
+	let mut group_field_arms = Vec::new();
+	let mut agg_field_arms = Vec::new();
+	let mut facet_arms = Vec::new();
+	let mut join_filter_pushes = Vec::new();
+
+	let rename_rule = match container_rename_rule(&dto.attrs) {
+		Ok(rule) => rule,
+		Err(e) => return e.to_compile_error().into(),
+	};
 
 	for field in data.fields.iter() {
 		let fname_ident = field.ident.as_ref().expect("named field");
@@ -3663,6 +4888,9 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 
 		let mut target_snake = fname_snake.clone();
 		let mut webquery_ignore = false;
+		let mut webquery_join: Option<String> = None;
+		// `Some(None)` counts values, `Some(Some(bounds))` counts buckets.
+		let mut facet: Option<Option<Vec<f64>>> = None;
 
 		for attr in &field.attrs {
 			if attr.path.is_ident("sqlxo") {
@@ -3716,7 +4944,7 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 						NestedMeta::Meta(Meta::Path(p))
 							if p.is_ident("webquery_join") =>
 						{
-							webquery_ignore = true;
+							webquery_join = Some(fname_snake.clone());
 						}
 						// optional: #[sqlxo(webquery_ignore = true)]
 						NestedMeta::Meta(Meta::NameValue(nv))
@@ -3737,7 +4965,22 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 						NestedMeta::Meta(Meta::NameValue(nv))
 							if nv.path.is_ident("webquery_join") =>
 						{
-							webquery_ignore = true;
+							if let Lit::Str(ref s) = nv.lit {
+								webquery_join = Some(s.value());
+							}
+						}
+						NestedMeta::Meta(Meta::Path(p))
+							if p.is_ident("facet") =>
+						{
+							facet = Some(None);
+						}
+						NestedMeta::Meta(Meta::List(list))
+							if list.path.is_ident("facet") =>
+						{
+							match parse_facet_buckets(&list) {
+								Ok(bounds) => facet = Some(Some(bounds)),
+								Err(e) => return e.to_compile_error().into(),
+							}
 						}
 						NestedMeta::Meta(Meta::NameValue(nv)) => {
 							return Error::new_spanned(
@@ -3760,6 +5003,21 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 			}
 		}
 
+		if let Some(join_label) = webquery_join {
+			// The WebQuery derive rejects join fields it can't resolve.
+			if let Some(child_ty) = extract_join_target_type(ty) {
+				let label = syn::LitStr::new(
+					&join_label,
+					proc_macro2::Span::call_site(),
+				);
+				join_filter_pushes.push(quote! {
+					if let Some(expr) = &filter.#fname_ident {
+						filters.push(#root::web::WebJoinFilter::new::<#child_ty>(#label, expr));
+					}
+				});
+			}
+			continue;
+		}
 		if webquery_ignore {
 			continue;
 		}
@@ -3899,6 +5157,48 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 		let column_alias_ident =
 			format_ident!("{}{}", entity_struct_ident, target_pascal);
 		let column_type = quote! { <#entity_ty>::#column_alias_ident };
+		group_field_arms.push(quote! {
+			#group_field_ident::#leaf_variant_ident => #root::select::SelectionColumn::new(
+				<#column_type as #root::select::Column>::TABLE,
+				<#column_type as #root::select::Column>::NAME,
+			)
+		});
+		if let Some(buckets) = facet {
+			let kind = match buckets {
+				None => quote!(#root::facet::FacetKind::Values),
+				Some(_) if !matches!(classify_type(ty), Kind::Number) => {
+					return Error::new_spanned(
+						fname_ident,
+						"facet buckets require a numeric field",
+					)
+					.to_compile_error()
+					.into();
+				}
+				Some(bounds) => {
+					let bounds = bounds
+						.into_iter()
+						.map(proc_macro2::Literal::f64_suffixed);
+					quote!(#root::facet::FacetKind::Buckets(&[#(#bounds),*]))
+				}
+			};
+			let json_name = match field_rename(field) {
+				Ok(Some(name)) => name,
+				Ok(None) => rename_rule.apply(&fname_snake),
+				Err(e) => return e.to_compile_error().into(),
+			};
+			let json_lit =
+				syn::LitStr::new(&json_name, proc_macro2::Span::call_site());
+			facet_arms.push(quote! {
+				#json_lit => Some(#root::facet::Facet::new(
+					#json_lit,
+					#root::select::SelectionColumn::new(
+						<#column_type as #root::select::Column>::TABLE,
+						<#column_type as #root::select::Column>::NAME,
+					),
+					#kind,
+				))
+			});
+		}
 		let mut push_agg =
 			|suffix_pascal: &str,
 			 suffix_snake: &str,
@@ -3931,6 +5231,10 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 						}
 					}
 				});
+				agg_field_arms.push(quote! {
+					#agg_field_ident::#variant_ident =>
+						#root::select::AggregateSelectionExpr::selection(&#expr)
+				});
 			};
 
 		match classify_type(ty) {
@@ -3960,6 +5264,21 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 					"count_distinct",
 					quote!(#root::select::CountDistinctExpr::<#column_type>::new()),
 				);
+				push_agg(
+					"StdDev",
+					"std_dev",
+					quote!(#root::select::StdDevExpr::<#column_type>::new()),
+				);
+				push_agg(
+					"Variance",
+					"variance",
+					quote!(#root::select::VarianceExpr::<#column_type>::new()),
+				);
+				push_agg(
+					"Median",
+					"median",
+					quote!(#root::select::PercentileContExpr::<#column_type>::median()),
+				);
 			}
 			Kind::DateTime | Kind::Date | Kind::Time => {
 				push_agg(
@@ -3980,13 +5299,44 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 					quote!(#root::select::CountDistinctExpr::<#column_type>::new()),
 				);
 			}
-			Kind::Bool => {}
+			Kind::Bool => {
+				push_agg(
+					"BoolAnd",
+					"bool_and",
+					quote!(#root::select::BoolAndExpr::<#column_type>::new()),
+				);
+				push_agg(
+					"BoolOr",
+					"bool_or",
+					quote!(#root::select::BoolOrExpr::<#column_type>::new()),
+				);
+			}
 		}
 	}
 
+	let (join_filter_arg, map_join_filter) = if join_filter_pushes.is_empty() {
+		(quote!(_filter), quote! { Vec::new() })
+	} else {
+		(quote!(filter), quote! {
+			let mut filters = Vec::new();
+			#(#join_filter_pushes)*
+			filters
+		})
+	};
+
 	let out = quote! {
 			#dto
 
+	impl #root::web::JoinFilterBindable for #dto_ident {
+		type Context = #entity_ty;
+
+		fn map_join_filter(
+			#join_filter_arg: &<#dto_ident as #root::WebQueryModel>::JoinFilter
+		) -> Vec<#root::web::WebJoinFilter> {
+			#map_join_filter
+		}
+	}
+
 	impl #root::Bind<#entity_ty> for #dto_ident {
 		fn map_leaf(
 			leaf: &<#dto_ident as #root::WebQueryModel>::Leaf
@@ -4023,24 +5373,286 @@ pub fn bind(attr: TokenStream, item: TokenStream) -> TokenStream {
 				#(, #agg_match_arms)*
 			}
 		}
+
+		fn map_group_field(
+			field: &<#dto_ident as #root::WebQueryModel>::GroupField
+		) -> #root::select::SelectionColumn {
+			match field {
+				#(#group_field_arms),*
+			}
+		}
+
+		fn map_aggregate_field(
+			field: &<#dto_ident as #root::WebQueryModel>::AggregateField
+		) -> #root::select::AggregateSelection {
+			match field {
+				#agg_field_ident::Count => #root::select::AggregateSelectionExpr::selection(
+					&#root::select::CountAllExpr::new(),
+				)
+				#(, #agg_field_arms)*
+			}
+		}
+
+		fn map_facet(name: &str) -> Option<#root::facet::Facet> {
+			match name {
+				#(#facet_arms,)*
+				_ => None,
+			}
+		}
 	}
 		};
 
 	out.into()
 }
 
+/// Parses `facet(buckets = "0, 10, 50")` into strictly ascending bounds.
+fn parse_facet_buckets(list: &syn::MetaList) -> syn::Result<Vec<f64>> {
+	let mut bounds = None;
+	for nested in &list.nested {
+		match nested {
+			NestedMeta::Meta(Meta::NameValue(nv))
+				if nv.path.is_ident("buckets") =>
+			{
+				let Lit::Str(s) = &nv.lit else {
+					return Err(Error::new_spanned(
+						&nv.lit,
+						r#"expected string literal: #[sqlxo(facet(buckets = "0, 10, 50"))]"#,
+					));
+				};
+				let parsed = s
+					.value()
+					.split(',')
+					.map(|bound| bound.trim().parse::<f64>())
+					.collect::<Result<Vec<_>, _>>()
+					.map_err(|_| {
+						Error::new_spanned(s, "facet buckets must be numbers")
+					})?;
+				if parsed.is_empty() ||
+					!parsed.iter().all(|bound| bound.is_finite()) ||
+					!parsed.windows(2).all(|w| w[0] < w[1])
+				{
+					return Err(Error::new_spanned(
+						s,
+						"facet buckets must be finite and strictly ascending",
+					));
+				}
+				bounds = Some(parsed);
+			}
+			other => {
+				return Err(Error::new_spanned(
+					other,
+					"unknown key in #[sqlxo(facet(...))]",
+				));
+			}
+		}
+	}
+
+	bounds.ok_or_else(|| {
+		Error::new_spanned(list, r#"expected #[sqlxo(facet(buckets = "..."))]"#)
+	})
+}
+
+/// Collects the `cascade_type(...)` declared on foreign keys and `has_many`
+/// relations into the `Deletable` cascade consts.
+/// The `Deletable` cascade consts, plus compile-time checks that every
+/// `cascade_type` declared on a foreign key is reachable from the parent's
+/// `has_many`.
+fn delete_cascade_consts(
+	input: &DeriveInput,
+	root: &proc_macro2::TokenStream,
+) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+	let fields = match &input.data {
+		Data::Struct(data) => match &data.fields {
+			Fields::Named(named) => &named.named,
+			_ => return Ok((quote! {}, quote! {})),
+		},
+		_ => return Ok((quote! {}, quote! {})),
+	};
+
+	let struct_ident = &input.ident;
+	let table = extract_container_attrs(input)?
+		.table_name
+		.unwrap_or_else(|| struct_ident.to_string().to_snake_case());
+	let is_self = |ty: &syn::Type| {
+		type_ident_string(ty).is_some_and(|name| *struct_ident == name)
+	};
+
+	let mut pk_columns = Vec::new();
+	let mut foreign_keys = Vec::new();
+	let mut cascading_fks = Vec::new();
+	let mut parents = Vec::new();
+	let mut relations = Vec::new();
+	for field in fields.iter() {
+		let field_ident = field.ident.as_ref().expect("named field");
+		let column = field_ident.to_string().to_snake_case();
+		for attr in &field.attrs {
+			if attr.path.is_ident("primary_key") {
+				pk_columns.push(column.clone());
+			}
+			if !attr.path.is_ident("foreign_key") {
+				continue;
+			}
+			let fk_attr = parse_foreign_key_attr(attr)?;
+			let Some(cascade) = fk_attr.cascade else {
+				continue;
+			};
+			if cascade == CascadeType::SetNull && !is_option_type(&field.ty) {
+				return Err(Error::new_spanned(
+					&field.ty,
+					"`cascade_type(set_null)` requires an optional foreign key",
+				));
+			}
+			if cascade == CascadeType::Cascade && fk_attr.right_table == table {
+				return Err(Error::new_spanned(
+					attr,
+					"`cascade_type(cascade)` is not supported on \
+					 self-referential relations",
+				));
+			}
+			cascading_fks.push((field_ident, column.clone()));
+			let table = fk_attr.right_table;
+			let cascade = cascade.to_tokens(root);
+			foreign_keys.push(quote! {
+				#root::ForeignKeyCascade {
+					column:  #column,
+					table:   #table,
+					cascade: #cascade,
+				}
+			});
+		}
+		if let Some(nav) = extract_navigation_attr(field)? {
+			let target = nav.explicit_target.clone().or_else(|| {
+				extract_join_value_inner(&field.ty).map(|inner| inner.ty)
+			});
+			match nav.kind {
+				RelationKind::HasMany => {
+					relations.push((field_ident, nav, target))
+				}
+				RelationKind::BelongsTo => {
+					let fk = nav.fk.unwrap_or_else(|| format!("{}_id", column));
+					parents.push((fk, target));
+				}
+				_ => {}
+			}
+		}
+	}
+
+	// The parent only finds the children through its `has_many`.
+	let mut checks = Vec::new();
+	for (field_ident, column) in cascading_fks {
+		let Some((_, Some(parent))) =
+			parents.iter().find(|(fk, _)| *fk == column)
+		else {
+			return Err(Error::new(
+				field_ident.span(),
+				"`cascade_type` on a foreign key needs a `belongs_to` \
+				 navigation over it; otherwise declare the cascade on the \
+				 parent's `has_many`",
+			));
+		};
+		let message = format!(
+			"`{struct_ident}::{field_ident}` declares a `cascade_type` but \
+			 its parent has no `has_many` with `fk = \"{column}\"`"
+		);
+		checks.push(quote! {
+			const _: () = assert!(
+				#root::declares_delete_cascade(
+					<#parent as #root::Deletable>::DELETE_CASCADES,
+					<#struct_ident as #root::QueryContext>::TABLE,
+					#column,
+				),
+				#message
+			);
+		});
+	}
+
+	let mut cascades = Vec::new();
+	for (field_ident, nav, target) in relations {
+		let (Some(target), Some(fk)) = (target, nav.fk) else {
+			if nav.cascade.is_some() {
+				return Err(Error::new(
+					field_ident.span(),
+					"`cascade_type` on a has_many navigation requires `fk = \
+					 \"...\"`",
+				));
+			}
+			continue;
+		};
+		if nav.cascade == Some(CascadeType::Cascade) && is_self(&target) {
+			return Err(Error::new(
+				field_ident.span(),
+				"`cascade_type(cascade)` is not supported on self-referential \
+				 relations",
+			));
+		}
+		if pk_columns.is_empty() {
+			return Err(Error::new(
+				field_ident.span(),
+				"has_many navigation requires a primary key field",
+			));
+		}
+		let table = type_to_table_name(&target)?;
+		let fk = column_list_tokens(&split_column_list(&fk));
+		let parent_key = column_list_tokens(&pk_columns);
+		let cascade = match nav.cascade {
+			Some(cascade) => {
+				let cascade = cascade.to_tokens(root);
+				quote! { Some(#cascade) }
+			}
+			None => quote! { None },
+		};
+		cascades.push(quote! {
+			#root::DeleteCascade {
+				table:         #table,
+				fk:            #fk,
+				parent_key:    #parent_key,
+				cascade:       #cascade,
+				delete_marker:
+					<#target as #root::GetDeleteMarker>::delete_marker_field,
+				audited_key:
+					if <#target as #root::Audited>::AUDITED {
+						Some(<#target as #root::PrimaryKey>::PRIMARY_KEY)
+					} else {
+						None
+					},
+				foreign_keys:
+					<#target as #root::GetDeleteCascades>::foreign_key_cascades,
+				children:
+					<#target as #root::GetDeleteCascades>::delete_cascades,
+			}
+		});
+	}
+
+	Ok((
+		quote! {
+			const FOREIGN_KEY_CASCADES: &'static [#root::ForeignKeyCascade] =
+				&[#(#foreign_keys),*];
+			const DELETE_CASCADES: &'static [#root::DeleteCascade] =
+				&[#(#cascades),*];
+		},
+		quote! { #(#checks)* },
+	))
+}
+
 #[proc_macro_derive(Delete, attributes(sqlxo, primary_key, foreign_key))]
 pub fn derive_delete(input: TokenStream) -> TokenStream {
 	let input = parse_macro_input!(input as DeriveInput);
 	let root = sqlxo_root();
 
 	let struct_ident = &input.ident;
+	let (cascades, checks) = match delete_cascade_consts(&input, &root) {
+		Ok(tokens) => tokens,
+		Err(e) => return e.to_compile_error().into(),
+	};
 
 	let out = quote! {
 		impl #root::Deletable for #struct_ident {
 			const IS_SOFT_DELETE: bool = false;
 			const DELETE_MARKER_FIELD: Option<&'static str> = None;
+			#cascades
 		}
+
+		#checks
 	};
 
 	out.into()
@@ -4096,11 +5708,21 @@ pub fn derive_soft_delete(input: TokenStream) -> TokenStream {
 		}
 	};
 
+	let (cascades, checks) = match delete_cascade_consts(&input, &root) {
+		Ok(tokens) => tokens,
+		Err(e) => return e.to_compile_error().into(),
+	};
+
 	let out = quote! {
 		impl #root::Deletable for #struct_ident {
 			const IS_SOFT_DELETE: bool = true;
 			const DELETE_MARKER_FIELD: Option<&'static str> = #delete_marker;
+			#cascades
 		}
+
+		#checks
+
+		impl #root::SoftDeletable for #struct_ident {}
 	};
 
 	out.into()
@@ -4151,9 +5773,14 @@ pub fn derive_update(input: TokenStream) -> TokenStream {
 		}
 	}
 
+	let op_ident = format_ident!("{}UpdateOp", struct_ident);
+
 	// Generate update struct fields
 	let mut update_fields = Vec::new();
 	let mut field_names = Vec::new();
+	let mut op_variants = Vec::new();
+	let mut op_arms = Vec::new();
+	let mut join_path_arms = Vec::new();
 
 	for field in fields.iter() {
 		let field_ident = field.ident.as_ref().unwrap();
@@ -4165,14 +5792,23 @@ pub fn derive_update(input: TokenStream) -> TokenStream {
 			continue;
 		}
 
-		// Skip markers
+		// Skip markers; rows never move to another tenant
 		if Some(&field_name) == markers.delete_marker.as_ref() ||
 			Some(&field_name) == markers.update_marker.as_ref() ||
-			Some(&field_name) == markers.insert_marker.as_ref()
+			Some(&field_name) == markers.insert_marker.as_ref() ||
+			Some(&field_name) == markers.version.as_ref() ||
+			Some(&field_name) == markers.tenant.as_ref()
 		{
 			continue;
 		}
 
+		// Skip relations
+		match extract_navigation_attr(field) {
+			Ok(Some(_)) => continue,
+			Ok(None) => {}
+			Err(e) => return e.to_compile_error().into(),
+		}
+
 		// Skip fields marked as update_ignore
 		let mut update_ignore = false;
 		for attr in &field.attrs {
@@ -4221,12 +5857,79 @@ pub fn derive_update(input: TokenStream) -> TokenStream {
 			pub #field_ident: Option<#ty>
 		});
 		field_names.push(field_ident);
+
+		let field_name_pascal = field_name.to_pascal_case();
+		let inner_ty =
+			extract_single_generic(ty, "Option").unwrap_or_else(|| ty.clone());
+
+		let v_set_column = format_ident!("{}SetColumn", field_name_pascal);
+		op_variants.push(quote! {
+			#v_set_column(#root::select::ColumnRef<#struct_ident, #ty>)
+		});
+		op_arms.push(quote! {
+			Self::#v_set_column(c) => {
+				qb.push(concat!(#field_name, " = "));
+				qb.push(c.qualified());
+			}
+		});
+		join_path_arms.push(quote! {
+			Self::#v_set_column(c) => c.path(),
+		});
+
+		if is_option_type(ty) {
+			let v_set_null = format_ident!("{}SetNull", field_name_pascal);
+			op_variants.push(quote! { #v_set_null });
+			op_arms.push(quote! {
+				Self::#v_set_null => { qb.push(concat!(#field_name, " = NULL")); }
+			});
+		}
+
+		match classify_type(ty) {
+			Kind::Number => {
+				for (suffix, op) in
+					[("Increment", "+"), ("Decrement", "-"), ("Multiply", "*")]
+				{
+					let variant =
+						format_ident!("{}{}", field_name_pascal, suffix);
+					// Qualified, since `UPDATE ... FROM` may join a table
+					// with the same column.
+					let expr = format!(
+						r#"{field_name} = "{{}}"."{field_name}" {op} "#
+					);
+					op_variants.push(quote! { #variant(#inner_ty) });
+					op_arms.push(quote! {
+						Self::#variant(v) => {
+							qb.push(format!(
+								#expr,
+								<#struct_ident as #root::QueryContext>::TABLE
+							));
+							qb.push_bind(*v);
+						}
+					});
+				}
+			}
+			Kind::DateTime | Kind::Date | Kind::Time => {
+				let v_set_now = format_ident!("{}SetNow", field_name_pascal);
+				op_variants.push(quote! { #v_set_now });
+				op_arms.push(quote! {
+					Self::#v_set_now => { qb.push(concat!(#field_name, " = NOW()")); }
+				});
+			}
+			_ => {}
+		}
 	}
 
 	let update_marker_field = markers
 		.update_marker
 		.map(|f| quote! { Some(#f) })
 		.unwrap_or_else(|| quote! { None });
+	let versioned_impl = markers.version.is_some().then(|| {
+		quote! { impl #root::Versioned for #struct_ident {} }
+	});
+	let version_field = markers
+		.version
+		.map(|f| quote! { Some(#f) })
+		.unwrap_or_else(|| quote! { None });
 
 	let out = quote! {
 		#[derive(Debug, Clone, Default)]
@@ -4234,11 +5937,38 @@ pub fn derive_update(input: TokenStream) -> TokenStream {
 			#(#update_fields),*
 		}
 
+		#[derive(Debug, Clone, PartialEq)]
+		pub enum #op_ident {
+			#(#op_variants),*
+		}
+
+		impl #root::UpdateOperation for #op_ident {
+			type Entity = #struct_ident;
+
+			fn write(&self, qb: &mut sqlx::QueryBuilder<'static, sqlx::Postgres>) {
+				match self {
+					#(#op_arms)*
+				}
+			}
+
+			#[allow(unreachable_patterns)]
+			fn join_path(&self) -> Option<&#root::JoinPath> {
+				match self {
+					#(#join_path_arms)*
+					_ => None,
+				}
+			}
+		}
+
 		impl #root::Updatable for #struct_ident {
 			type UpdateModel = #update_ident;
+			type UpdateOp = #op_ident;
 			const UPDATE_MARKER_FIELD: Option<&'static str> = #update_marker_field;
+			const VERSION_FIELD: Option<&'static str> = #version_field;
 		}
 
+		#versioned_impl
+
 		impl #root::UpdateModel for #update_ident {
 			type Entity = #struct_ident;
 
@@ -4262,6 +5992,16 @@ pub fn derive_update(input: TokenStream) -> TokenStream {
 
 				set_fields
 			}
+
+			fn bind_count(&self) -> usize {
+				let mut count = 0;
+				#(
+					if self.#field_names.is_some() {
+						count += 1;
+					}
+				)*
+				count
+			}
 		}
 	};
 
@@ -4507,90 +6247,13 @@ pub fn derive_full_text_searchable(input: TokenStream) -> TokenStream {
 
 		for attr in &field.attrs {
 			if attr.path.is_ident("foreign_key") {
-				let meta = attr
-					.parse_meta()
-					.map_err(|_| {
-						Error::new_spanned(attr, "invalid #[foreign_key]")
-					})
-					.unwrap();
-
-				let list = match meta {
-					Meta::List(list) => list,
-					_ => {
-						return Error::new_spanned(
-							attr,
-							r#"expected #[foreign_key(to = "table.pk")]"#,
-						)
-						.to_compile_error()
-						.into()
-					}
-				};
-
-				let mut to_value: Option<String> = None;
-
-				for nested in list.nested {
-					match nested {
-						NestedMeta::Meta(Meta::NameValue(nv))
-							if nv.path.is_ident("to") =>
-						{
-							match nv.lit {
-								Lit::Str(ref s) => {
-									if to_value.is_some() {
-										return Error::new_spanned(
-											nv,
-											"duplicate key `to`",
-										)
-										.to_compile_error()
-										.into();
-									}
-									to_value = Some(s.value());
-								}
-								other => {
-									return Error::new_spanned(
-										other,
-										r#"expected "table.pk""#,
-									)
-									.to_compile_error()
-									.into();
-								}
-							}
-						}
-						_ => {}
-					}
-				}
-
-				let Some(to) = to_value else {
-					return Error::new(
-						attr.span(),
-						r#"missing `to = "table.pk"`"#,
-					)
-					.to_compile_error()
-					.into();
+				let fk_attr = match parse_foreign_key_attr(attr) {
+					Ok(fk_attr) => fk_attr,
+					Err(e) => return e.to_compile_error().into(),
 				};
 
-				let mut parts = to.split('.');
-				let right_table = parts
-					.next()
-					.ok_or_else(|| Error::new(attr.span(), "missing table"))
-					.unwrap()
-					.to_string();
-				let right_pk = parts
-					.next()
-					.ok_or_else(|| Error::new(attr.span(), "missing pk"))
-					.unwrap()
-					.to_string();
-
-				if parts.next().is_some() {
-					return Error::new(
-						attr.span(),
-						r#"invalid `to` — expected "table.pk""#,
-					)
-					.to_compile_error()
-					.into();
-				}
-
 				let alias_segment = derive_alias_segment(&field_name_snake);
-				let right_pascal = right_table.to_pascal_case();
+				let right_pascal = fk_attr.right_table.to_pascal_case();
 				let variant_ident = format_ident!(
 					"{}To{}By{}",
 					struct_ident,
@@ -4598,10 +6261,18 @@ pub fn derive_full_text_searchable(input: TokenStream) -> TokenStream {
 					field_name_pascal
 				);
 
+				let mut left_fields = vec![field_name_snake.clone()];
+				let mut right_fields = vec![fk_attr.right_pk];
+				for (local, remote) in fk_attr.extra_pairs {
+					left_fields.push(local);
+					right_fields.push(remote);
+				}
+
 				fk_specs.push(FkSpec {
 					fk_field_snake: field_name_snake.clone(),
-					right_table,
-					right_pk,
+					left_fields,
+					right_table: fk_attr.right_table,
+					right_fields,
 					alias_segment,
 					variant_ident,
 					friendly_name: derive_join_label(&field_name_snake),
@@ -27,6 +27,20 @@ pub trait SqlWrite {
 		T: Type<Postgres>;
 }
 
+impl SqlWrite for sqlx::QueryBuilder<'static, Postgres> {
+	fn push(&mut self, s: &str) {
+		sqlx::QueryBuilder::push(self, s);
+	}
+
+	fn bind<T>(&mut self, value: T)
+	where
+		T: sqlx::Encode<'static, Postgres> + Send + 'static,
+		T: Type<Postgres>,
+	{
+		self.push_bind(value);
+	}
+}
+
 pub trait QueryContext: Send + Sync + 'static {
 	const TABLE: &'static str;
 
@@ -35,10 +49,17 @@ pub trait QueryContext: Send + Sync + 'static {
 		+ Sync
 		+ JoinNavigationModel
 		+ WebJoinGraph
-		+ PrimaryKey;
+		+ PrimaryKey
+		+ TenantScoped
+		+ Audited;
 	type Query: FilterQuery + Send + Sync;
 	type Sort: QuerySort + Send + Sync;
 	type Join: SqlJoin + Send + Sync;
+
+	/// Filters on the primary key being one of `keys`.
+	fn primary_key_in(
+		keys: Vec<<Self::Model as PrimaryKey>::Key>,
+	) -> Self::Query;
 }
 
 pub trait Sortable {
@@ -51,25 +72,64 @@ pub trait Sortable {
 pub enum JoinKind {
 	Left,
 	Inner,
+	Right,
+	Full,
+	Cross,
+}
+
+impl JoinKind {
+	pub fn keyword(self) -> &'static str {
+		match self {
+			JoinKind::Left => " LEFT JOIN ",
+			JoinKind::Inner => " INNER JOIN ",
+			JoinKind::Right => " RIGHT JOIN ",
+			JoinKind::Full => " FULL OUTER JOIN ",
+			JoinKind::Cross => " CROSS JOIN ",
+		}
+	}
+
+	/// `CROSS JOIN` is the only kind rendered without an `ON` clause.
+	pub fn has_condition(self) -> bool {
+		!matches!(self, JoinKind::Cross)
+	}
+
+	/// Whether rows of the joined table survive without a matching row on
+	/// the left side, leaving every column to the left `NULL`.
+	pub fn preserves_right(self) -> bool {
+		matches!(self, JoinKind::Right | JoinKind::Full)
+	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct JoinThroughDescriptor {
 	pub table:         &'static str,
 	pub alias_segment: &'static str,
-	pub left_field:    &'static str,
-	pub right_field:   &'static str,
+	pub left_fields:   &'static [&'static str],
+	pub right_fields:  &'static [&'static str],
+	pub tenant_field:  Option<&'static str>,
+	pub delete_marker: Option<&'static str>,
 }
 
+/// `left_fields[i]` is matched against `right_fields[i]`; composite keys list
+/// one pair per key column.
+// Descriptors of one relation share the `default_scope` of one model, so
+// comparing it by address is enough to tell them apart.
+#[allow(unpredictable_function_pointer_comparisons)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct JoinDescriptor {
 	pub left_table:    &'static str,
-	pub left_field:    &'static str,
+	pub left_fields:   &'static [&'static str],
 	pub right_table:   &'static str,
-	pub right_field:   &'static str,
+	pub right_fields:  &'static [&'static str],
 	pub alias_segment: &'static str,
 	pub identifier:    &'static str,
 	pub through:       Option<JoinThroughDescriptor>,
+	/// Tenant column of the right table, see [`TenantScoped`].
+	pub tenant_field:  Option<&'static str>,
+	/// Soft-delete marker of the right table, see [`GetDeleteMarker`].
+	pub delete_marker: Option<&'static str>,
+	/// Default scope of the right table, see [`GetDefaultScoped`].
+	pub default_scope: Option<ScopeWriter>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -168,6 +228,12 @@ impl JoinPath {
 		self.len() == 0
 	}
 
+	/// Returns `true` when a `RIGHT` or `FULL OUTER` hop can produce rows
+	/// without a matching base row.
+	pub fn may_omit_base(&self) -> bool {
+		self.segments().iter().any(|seg| seg.kind.preserves_right())
+	}
+
 	pub fn first_table(&self) -> Option<&'static str> {
 		self.segments().first().map(|seg| seg.descriptor.left_table)
 	}
@@ -304,6 +370,11 @@ pub trait JoinNavigationModel {
 		base_alias: &str,
 	) -> Result<(), sqlx::Error>;
 
+	/// Reads relation counts requested through `with_count`.
+	fn hydrate_counts(&mut self, _row: &PgRow) -> Result<(), sqlx::Error> {
+		Ok(())
+	}
+
 	fn has_collection_joins(_joins: Option<&[JoinPath]>) -> bool {
 		false
 	}
@@ -326,13 +397,218 @@ pub trait WebJoinGraph {
 
 pub trait PrimaryKey {
 	const PRIMARY_KEY: &'static [&'static str];
+
+	/// The key of a row; a tuple for composite keys.
+	type Key: Clone + std::fmt::Debug + PartialEq + Send + Sync + 'static;
+
+	fn primary_key(&self) -> Self::Key;
+
+	/// Writes a condition matching the rows whose key is one of `keys`.
+	fn write_key_in<W: SqlWrite>(keys: &[Self::Key], w: &mut W);
+
+	/// Writes the position of the row's key within `keys`, for ordering.
+	fn write_key_position<W: SqlWrite>(keys: &[Self::Key], w: &mut W);
 }
 
+/// Implemented for models with a `#[primary_key]` field, whose rows can be
+/// looked up by key.
+pub trait HasPrimaryKey: PrimaryKey {}
+
 pub trait Model {}
 
+/// Rows scoped to a tenant by the column marked `#[sqlxo(tenant)]`.
+pub trait TenantScoped {
+	const TENANT_FIELD: Option<&'static str>;
+}
+
+/// Rows whose inserts, updates and deletes are recorded in the audit table,
+/// marked by `#[sqlxo(audited)]`, including the rows nested inserts create
+/// and delete cascades delete or clear.
+pub trait Audited {
+	const AUDITED: bool;
+}
+
+/// The tenant rows are read and written for.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TenantId {
+	Int(i64),
+	Uuid(uuid::Uuid),
+	Text(String),
+}
+
+impl TenantId {
+	pub fn push_bind(&self, qb: &mut sqlx::QueryBuilder<'static, Postgres>) {
+		match self {
+			Self::Int(id) => qb.push_bind(*id),
+			Self::Uuid(id) => qb.push_bind(*id),
+			Self::Text(id) => qb.push_bind(id.clone()),
+		};
+	}
+
+	pub fn write<W: SqlWrite>(&self, w: &mut W) {
+		match self {
+			Self::Int(id) => w.bind(*id),
+			Self::Uuid(id) => w.bind(*id),
+			Self::Text(id) => w.bind(id.clone()),
+		}
+	}
+}
+
+impl From<i64> for TenantId {
+	fn from(id: i64) -> Self {
+		Self::Int(id)
+	}
+}
+
+impl From<i32> for TenantId {
+	fn from(id: i32) -> Self {
+		Self::Int(id.into())
+	}
+}
+
+impl From<uuid::Uuid> for TenantId {
+	fn from(id: uuid::Uuid) -> Self {
+		Self::Uuid(id)
+	}
+}
+
+impl From<String> for TenantId {
+	fn from(id: String) -> Self {
+		Self::Text(id)
+	}
+}
+
+impl From<&str> for TenantId {
+	fn from(id: &str) -> Self {
+		Self::Text(id.to_string())
+	}
+}
+
 pub trait Deletable {
 	const IS_SOFT_DELETE: bool;
 	const DELETE_MARKER_FIELD: Option<&'static str>;
+	/// Foreign keys declaring what happens to the row when its parent is
+	/// deleted.
+	const FOREIGN_KEY_CASCADES: &'static [ForeignKeyCascade] = &[];
+	/// `has_many` relations a delete of the row cascades along.
+	const DELETE_CASCADES: &'static [DeleteCascade] = &[];
+}
+
+/// Implemented by `SoftDelete` models, whose deleted rows can be listed,
+/// restored and purged.
+pub trait SoftDeletable: Deletable {}
+
+/// What a delete of the parent does to its children.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CascadeType {
+	/// Deletes the children along with the parent.
+	Cascade,
+	/// Refuses to delete a parent that still has children.
+	Restrict,
+	/// Clears the foreign key of the children.
+	SetNull,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ForeignKeyCascade {
+	pub column:  &'static str,
+	/// Table the foreign key references.
+	pub table:   &'static str,
+	pub cascade: CascadeType,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DeleteCascade {
+	pub table:         &'static str,
+	pub fk:            &'static [&'static str],
+	/// Columns of the parent the foreign key references.
+	pub parent_key:    &'static [&'static str],
+	/// Declared on the relation; the child's foreign key is consulted
+	/// otherwise.
+	pub cascade:       Option<CascadeType>,
+	pub delete_marker: fn() -> Option<&'static str>,
+	/// Primary key of the child when its changes are audited, see
+	/// [`Audited`].
+	pub audited_key:   Option<&'static [&'static str]>,
+	pub foreign_keys:  fn() -> &'static [ForeignKeyCascade],
+	pub children:      fn() -> &'static [DeleteCascade],
+}
+
+/// Whether `relations` reach `table` over the foreign key `column`. Derives
+/// assert it at compile time for `cascade_type`s declared on foreign keys.
+pub const fn declares_delete_cascade(
+	relations: &[DeleteCascade],
+	table: &str,
+	column: &str,
+) -> bool {
+	let mut idx = 0;
+	while idx < relations.len() {
+		let relation = &relations[idx];
+		if str_eq(relation.table, table) {
+			let mut fk = 0;
+			while fk < relation.fk.len() {
+				if str_eq(relation.fk[fk], column) {
+					return true;
+				}
+				fk += 1;
+			}
+		}
+		idx += 1;
+	}
+	false
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+	let (a, b) = (a.as_bytes(), b.as_bytes());
+	if a.len() != b.len() {
+		return false;
+	}
+	let mut idx = 0;
+	while idx < a.len() {
+		if a[idx] != b[idx] {
+			return false;
+		}
+		idx += 1;
+	}
+	true
+}
+
+impl DeleteCascade {
+	pub fn cascade_type(&self, parent_table: &str) -> Option<CascadeType> {
+		self.cascade.or_else(|| {
+			(self.foreign_keys)()
+				.iter()
+				.find(|fk| {
+					fk.table == parent_table && self.fk.contains(&fk.column)
+				})
+				.map(|fk| fk.cascade)
+		})
+	}
+}
+
+pub trait GetDeleteCascades {
+	fn foreign_key_cascades() -> &'static [ForeignKeyCascade];
+	fn delete_cascades() -> &'static [DeleteCascade];
+}
+
+impl<T> GetDeleteCascades for T {
+	default fn foreign_key_cascades() -> &'static [ForeignKeyCascade] {
+		&[]
+	}
+
+	default fn delete_cascades() -> &'static [DeleteCascade] {
+		&[]
+	}
+}
+
+impl<T: Deletable> GetDeleteCascades for T {
+	fn foreign_key_cascades() -> &'static [ForeignKeyCascade] {
+		T::FOREIGN_KEY_CASCADES
+	}
+
+	fn delete_cascades() -> &'static [DeleteCascade] {
+		T::DELETE_CASCADES
+	}
 }
 
 pub trait GetDeleteMarker {
@@ -351,9 +627,66 @@ impl<T: Deletable> GetDeleteMarker for T {
 	}
 }
 
+/// Writes the default scope of a model's reads, qualified by its table name.
+pub type ScopeWriter = fn(&mut sqlx::QueryBuilder<'static, Postgres>);
+
+/// Models whose query has a default scope, so relations counting their rows
+/// apply it as well.
+pub trait DefaultScoped {
+	fn write_default_scope(w: &mut sqlx::QueryBuilder<'static, Postgres>);
+}
+
+pub trait GetDefaultScoped {
+	fn default_scope_writer() -> Option<ScopeWriter>;
+}
+
+impl<T> GetDefaultScoped for T {
+	default fn default_scope_writer() -> Option<ScopeWriter> {
+		None
+	}
+}
+
+impl<T: DefaultScoped> GetDefaultScoped for T {
+	fn default_scope_writer() -> Option<ScopeWriter> {
+		Some(T::write_default_scope)
+	}
+}
+
 pub trait Updatable {
 	type UpdateModel: UpdateModel<Entity = Self>;
+	type UpdateOp: UpdateOperation<Entity = Self>;
 	const UPDATE_MARKER_FIELD: Option<&'static str>;
+	/// Integer column bumped by every update and checked against the
+	/// expected version when one is given.
+	const VERSION_FIELD: Option<&'static str>;
+}
+
+/// Implemented for models with a `#[sqlxo(version)]` field.
+pub trait Versioned: Updatable {}
+
+pub trait GetUpdateFields {
+	fn update_marker_field() -> Option<&'static str>;
+	fn version_field() -> Option<&'static str>;
+}
+
+impl<T> GetUpdateFields for T {
+	default fn update_marker_field() -> Option<&'static str> {
+		None
+	}
+
+	default fn version_field() -> Option<&'static str> {
+		None
+	}
+}
+
+impl<T: Updatable> GetUpdateFields for T {
+	fn update_marker_field() -> Option<&'static str> {
+		T::UPDATE_MARKER_FIELD
+	}
+
+	fn version_field() -> Option<&'static str> {
+		T::VERSION_FIELD
+	}
 }
 
 pub trait UpdateModel: Clone + Send + Sync {
@@ -364,6 +697,22 @@ pub trait UpdateModel: Clone + Send + Sync {
 		qb: &mut sqlx::QueryBuilder<'static, sqlx::Postgres>,
 		has_previous: bool,
 	) -> Vec<&'static str>;
+
+	/// Number of binds `apply_updates` adds, one per set field.
+	fn bind_count(&self) -> usize;
+}
+
+/// An assignment computed from the row being updated, e.g. an increment.
+pub trait UpdateOperation: Clone + Send + Sync {
+	type Entity: QueryModel;
+
+	/// Pushes a single `column = expression` assignment.
+	fn write(&self, qb: &mut sqlx::QueryBuilder<'static, sqlx::Postgres>);
+
+	/// The join path the assignment reads from, which the update joins.
+	fn join_path(&self) -> Option<&JoinPath> {
+		None
+	}
 }
 
 pub trait Creatable {
@@ -374,13 +723,61 @@ pub trait Creatable {
 pub trait CreateModel: Clone + Send + Sync {
 	type Entity: QueryModel;
 
-	fn apply_inserts(
+	/// Number of binds `push_values` adds per row.
+	const BIND_COUNT: usize;
+
+	/// Pushes the parenthesized column list.
+	fn push_columns(
+		qb: &mut sqlx::QueryBuilder<'static, sqlx::Postgres>,
+		insert_marker_field: Option<&'static str>,
+	);
+
+	/// Pushes one parenthesized row of values. `tenant` fills the
+	/// `#[sqlxo(tenant)]` column of the entity.
+	fn push_values(
 		&self,
 		qb: &mut sqlx::QueryBuilder<'static, sqlx::Postgres>,
 		insert_marker_field: Option<&'static str>,
+		tenant: Option<&TenantId>,
 	);
+
+	fn apply_inserts(
+		&self,
+		qb: &mut sqlx::QueryBuilder<'static, sqlx::Postgres>,
+		insert_marker_field: Option<&'static str>,
+		tenant: Option<&TenantId>,
+	) {
+		Self::push_columns(qb, insert_marker_field);
+		qb.push(" VALUES ");
+		self.push_values(qb, insert_marker_field, tenant);
+	}
+
+	/// Whether the model has `nested_create` relations to insert along with
+	/// the row.
+	fn has_children(&self) -> bool {
+		false
+	}
+
+	/// Adds the tables the nested children are inserted into to `tables`,
+	/// the audit table included when their changes are audited.
+	fn child_tables(&self, _tables: &mut Vec<&'static str>) {}
+
+	/// Inserts the nested children of `parent`, the row just inserted for
+	/// `self`, and loads them into its navigation fields. The foreign key of
+	/// each child is set to the key of `parent`, whatever the child held.
+	fn insert_children<'c>(
+		&'c self,
+		_parent: &'c mut Self::Entity,
+		_conn: &'c mut sqlx::PgConnection,
+	) -> InsertChildren<'c> {
+		Box::pin(async { Ok(()) })
+	}
 }
 
+pub type InsertChildren<'c> = std::pin::Pin<
+	Box<dyn std::future::Future<Output = Result<(), crate::Error>> + Send + 'c>,
+>;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SearchWeight {
 	#[default]
@@ -0,0 +1,152 @@
+/// Everything a sqlxo plan can fail with: the checks sqlxo runs around the
+/// statements, or an error from sqlx itself.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error(transparent)]
+	Sqlx(#[from] sqlx::Error),
+	#[error(transparent)]
+	UnfilteredMutation(#[from] UnfilteredMutation),
+	#[error(transparent)]
+	TooManyRowsAffected(#[from] TooManyRowsAffected),
+	#[error(transparent)]
+	NeedsTransaction(#[from] NeedsTransaction),
+	#[error(transparent)]
+	MissingTenant(#[from] MissingTenant),
+	#[error(transparent)]
+	RestrictedDelete(#[from] RestrictedDelete),
+	#[error(transparent)]
+	CascadeCycle(#[from] CascadeCycle),
+	#[error(transparent)]
+	StaleVersion(#[from] StaleVersion),
+	#[error(transparent)]
+	UnsupportedRead(#[from] UnsupportedRead),
+	#[error(transparent)]
+	UnsupportedInsert(#[from] UnsupportedInsert),
+}
+
+impl Error {
+	/// Returns the sqlx error behind `self`, if sqlx is what failed.
+	pub fn as_sqlx(&self) -> Option<&sqlx::Error> {
+		match self {
+			Self::Sqlx(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+macro_rules! from_error {
+	($($variant:ident),* $(,)?) => {$(
+		impl $variant {
+			/// Returns this failure if it is what `err` is.
+			pub fn from_error(err: &Error) -> Option<&Self> {
+				match err {
+					Error::$variant(inner) => Some(inner),
+					_ => None,
+				}
+			}
+		}
+	)*};
+}
+
+from_error!(
+	UnfilteredMutation,
+	TooManyRowsAffected,
+	NeedsTransaction,
+	MissingTenant,
+	RestrictedDelete,
+	CascadeCycle,
+	StaleVersion,
+	UnsupportedRead,
+	UnsupportedInsert,
+);
+
+/// Returned when an update or delete without a filter is executed and
+/// `.all_rows()` was not called.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error(
+	"refusing to change every row of `{table}` without a filter; call \
+	 `.all_rows()` to allow it"
+)]
+pub struct UnfilteredMutation {
+	pub table: String,
+}
+
+/// Returned when a mutation changed more rows than its `max_affected_rows`.
+/// The transaction it ran in was rolled back.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error("`{table}` had {affected} rows changed but at most {max} are allowed")]
+pub struct TooManyRowsAffected {
+	pub table:    String,
+	pub max:      u64,
+	pub affected: u64,
+}
+
+/// Returned when a mutation plan runs through `ExecutablePlan` or
+/// `FetchablePlan` on a single executor but needs several statements in one
+/// transaction. The plan's `execute_atomic`/`fetch_*_atomic` methods take a
+/// pool or connection and open that transaction.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error(
+	"changes to `{table}` need a transaction because {reason}; run the plan \
+	 with `execute_atomic`/`fetch_*_atomic`"
+)]
+pub struct NeedsTransaction {
+	pub table:  String,
+	pub reason: String,
+}
+
+/// Returned when a plan of a tenant-scoped model runs without a tenant in
+/// `tenant::scope` and without `.all_tenants()`.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error(
+	"`{table}` is scoped to tenants but no tenant is set; run the query in \
+	 `tenant::scope` or call `.all_tenants()`"
+)]
+pub struct MissingTenant {
+	pub table: String,
+}
+
+/// Returned when a `cascade_type(restrict)` relation still has rows
+/// referencing the rows being deleted.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error("`{table}` is still referenced by rows in `{child}`")]
+pub struct RestrictedDelete {
+	pub table: String,
+	pub child: String,
+}
+
+/// Returned when the `cascade_type(cascade)` relations of a delete lead back
+/// into a table they already delete from.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error("deleting from `{table}` cascades back into `{child}`")]
+pub struct CascadeCycle {
+	pub table: String,
+	pub child: String,
+}
+
+/// Returned when an update with an expected version matched no row, i.e. the
+/// row was changed or removed since that version was read.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error("`{table}` no longer has a row at version {expected}")]
+pub struct StaleVersion {
+	pub table:    String,
+	pub expected: i64,
+}
+
+/// Returned when a read combines options Postgres or sqlxo cannot run
+/// together, e.g. `distinct_on` columns that do not lead the sort order.
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+#[error("unsupported read of `{table}`: {reason}")]
+pub struct UnsupportedRead {
+	pub table:  String,
+	pub reason: String,
+}
+
+/// Returned when an insert combines options sqlxo cannot run together, e.g.
+/// nested children with an `ON CONFLICT` clause.
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+#[error("unsupported insert into `{table}`: {reason}")]
+pub struct UnsupportedInsert {
+	pub table:  String,
+	pub reason: String,
+}
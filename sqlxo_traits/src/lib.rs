@@ -7,6 +7,10 @@ mod core;
 
 pub use core::*;
 
+mod error;
+
+pub use error::*;
+
 mod web;
 
 pub use web::*;
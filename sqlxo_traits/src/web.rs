@@ -45,11 +45,45 @@ pub trait WebJoinPayload:
 	fn flatten(&self, prefix: &mut Vec<String>, out: &mut Vec<Vec<String>>);
 }
 
+/// A field a web payload can select by name, e.g. in `groupBy`.
+pub trait WebSelectField:
+	Clone + Send + Sync + Serialize + ToSchema + PartialSchema
+{
+	/// Key of the field in the payload and in the returned rows.
+	fn web_name(&self) -> &'static str;
+
+	/// Key of the DTO field the value is read from, `None` for `count`.
+	fn source_field(&self) -> Option<&'static str>;
+}
+
 pub trait WebQueryModel {
 	type Leaf: WebLeaf;
 	type SortField: WebSortField;
 	type AggregateLeaf: WebLeaf;
 	type JoinPath: WebJoinPayload + for<'de> Deserialize<'de>;
+	/// `joinFilter` of a mutation payload, a filter per joinable relation.
+	type JoinFilter: Clone
+		+ Send
+		+ Sync
+		+ Serialize
+		+ for<'de> Deserialize<'de>
+		+ ToSchema
+		+ PartialSchema;
+	type GroupField: WebSelectField + for<'de> Deserialize<'de>;
+	type AggregateField: WebSelectField + for<'de> Deserialize<'de>;
+
+	/// Keys of the DTO fields, as in the payload and in serialized rows.
+	const FIELDS: &'static [&'static str];
+
+	/// Key of the DTO field a `filter` leaf compares.
+	fn leaf_field(leaf: &Self::Leaf) -> &'static str;
+
+	/// Key of the DTO field a `sort` entry orders by.
+	fn sort_field(sort: &Self::SortField) -> &'static str;
+
+	/// Key of the DTO field a `having` leaf aggregates, `None` for `count`.
+	fn aggregate_leaf_field(leaf: &Self::AggregateLeaf)
+		-> Option<&'static str>;
 }
 
 pub trait Bind<C>: WebQueryModel